@@ -0,0 +1,337 @@
+//! Canvas annotations: frames, groups and sticky notes
+//!
+//! Annotations give large graphs visual structure. They are saved with the
+//! graph but never executed. Frames are named, coloured areas whose member
+//! nodes move with them; groups are frames that can collapse their members
+//! behind a one-line summary; notes hold free markdown text.
+
+use crate::graph::command::Command;
+use crate::graph::graph::NodeGraph;
+use crate::graph::node::{pos2_serde, GraphNode};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use uuid::Uuid;
+
+/// Default fill colour of frames and groups
+pub const FRAME_COLOR: [u8; 3] = [70, 110, 160];
+
+/// Default fill colour of notes
+pub const NOTE_COLOR: [u8; 3] = [220, 190, 80];
+
+/// Space between a frame's edge and its nodes
+const FRAME_PADDING: f32 = 24.0;
+
+/// Height of the title bar of a frame or group
+pub const TITLE_HEIGHT: f32 = 28.0;
+
+/// Height of a collapsed group (title bar plus summary line)
+const COLLAPSED_HEIGHT: f32 = 56.0;
+
+/// What an annotation is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AnnotationKind {
+    /// Named, coloured area whose nodes move with it
+    Frame,
+    /// Frame that can hide its nodes behind a summary
+    Group { collapsed: bool },
+    /// Free text, rendered as markdown
+    Note { text: String },
+}
+
+/// A frame, group or note on the canvas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: Uuid,
+    pub kind: AnnotationKind,
+    /// Title of a frame or group (unused for notes)
+    pub title: String,
+    /// Top-left corner in canvas coordinates
+    #[serde(with = "pos2_serde")]
+    pub position: egui::Pos2,
+    pub width: f32,
+    pub height: f32,
+    /// Fill colour (RGB)
+    pub color: [u8; 3],
+    /// Nodes that belong to a frame or group (empty for notes)
+    pub node_ids: BTreeSet<Uuid>,
+}
+
+impl Annotation {
+    /// Frame around `node_ids`, or None if none of them exist
+    pub fn frame(title: String, graph: &NodeGraph, node_ids: &[Uuid]) -> Option<Self> {
+        Self::around(AnnotationKind::Frame, title, graph, node_ids)
+    }
+
+    /// Expanded group around `node_ids`, or None if none of them exist
+    pub fn group(title: String, graph: &NodeGraph, node_ids: &[Uuid]) -> Option<Self> {
+        Self::around(AnnotationKind::Group { collapsed: false }, title, graph, node_ids)
+    }
+
+    /// Sticky note with its top-left corner at `position`
+    pub fn note(text: String, position: egui::Pos2) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            kind: AnnotationKind::Note { text },
+            title: String::new(),
+            position,
+            width: 220.0,
+            height: 120.0,
+            color: NOTE_COLOR,
+            node_ids: BTreeSet::new(),
+        }
+    }
+
+    fn around(
+        kind: AnnotationKind,
+        title: String,
+        graph: &NodeGraph,
+        node_ids: &[Uuid],
+    ) -> Option<Self> {
+        let node_ids: BTreeSet<Uuid> =
+            node_ids.iter().copied().filter(|id| graph.nodes.contains_key(id)).collect();
+        let bounds = nodes_rect(graph, &node_ids)?;
+        let rect = egui::Rect::from_min_max(
+            bounds.min - egui::vec2(FRAME_PADDING, FRAME_PADDING + TITLE_HEIGHT),
+            bounds.max + egui::vec2(FRAME_PADDING, FRAME_PADDING),
+        );
+        Some(Self {
+            id: Uuid::new_v4(),
+            kind,
+            title,
+            position: rect.min,
+            width: rect.width(),
+            height: rect.height(),
+            color: FRAME_COLOR,
+            node_ids,
+        })
+    }
+
+    /// Short name of the kind, for labels
+    pub fn kind_name(&self) -> &'static str {
+        match self.kind {
+            AnnotationKind::Frame => "frame",
+            AnnotationKind::Group { .. } => "group",
+            AnnotationKind::Note { .. } => "note",
+        }
+    }
+
+    /// Title, or the first line of a note
+    pub fn label(&self) -> String {
+        match &self.kind {
+            AnnotationKind::Note { text } => text.lines().next().unwrap_or_default().to_string(),
+            _ => self.title.clone(),
+        }
+    }
+
+    /// Whether this is a collapsed group
+    pub fn is_collapsed(&self) -> bool {
+        matches!(self.kind, AnnotationKind::Group { collapsed: true })
+    }
+
+    /// Area the annotation covers on the canvas
+    pub fn rect(&self) -> egui::Rect {
+        let height = if self.is_collapsed() {
+            COLLAPSED_HEIGHT
+        } else {
+            self.height
+        };
+        egui::Rect::from_min_size(self.position, egui::vec2(self.width, height))
+    }
+
+    /// Title bar of a frame or group (the whole area of a note)
+    pub fn title_rect(&self) -> egui::Rect {
+        match self.kind {
+            AnnotationKind::Note { .. } => self.rect(),
+            _ => egui::Rect::from_min_size(self.position, egui::vec2(self.width, TITLE_HEIGHT)),
+        }
+    }
+
+    /// Nodes whose centre lies inside the annotation
+    pub fn nodes_inside(&self, graph: &NodeGraph) -> BTreeSet<Uuid> {
+        let rect = self.rect();
+        graph
+            .nodes
+            .values()
+            .filter(|node| rect.contains(estimated_node_rect(node).center()))
+            .map(|node| node.id)
+            .collect()
+    }
+
+    /// One-line description of a collapsed group's contents
+    pub fn summary(&self, graph: &NodeGraph) -> String {
+        let names: Vec<&str> = self
+            .node_ids
+            .iter()
+            .filter_map(|id| graph.nodes.get(id))
+            .map(|node| node.display_name.as_str())
+            .collect();
+        let crossing = graph
+            .connections
+            .iter()
+            .filter(|conn| {
+                self.node_ids.contains(&conn.from_node) != self.node_ids.contains(&conn.to_node)
+            })
+            .count();
+        let mut listed = names.iter().take(3).copied().collect::<Vec<_>>().join(", ");
+        if names.len() > 3 {
+            listed.push_str(", …");
+        }
+        format!("{} node(s): {} · {} wire(s) in/out", names.len(), listed, crossing)
+    }
+
+    /// Undoable move of the annotation and its member nodes
+    pub fn move_command(&self, graph: &NodeGraph, delta: egui::Vec2) -> Command {
+        let mut moved = self.clone();
+        moved.position += delta;
+        let mut commands = vec![Command::UpdateAnnotation {
+            old: self.clone(),
+            new: moved,
+        }];
+        commands.extend(self.node_ids.iter().filter_map(|id| {
+            let node = graph.nodes.get(id)?;
+            Some(Command::MoveNode {
+                node_id: *id,
+                old_position: node.position,
+                new_position: node.position + delta,
+            })
+        }));
+        Command::Batch {
+            label: format!("Move {}", self.kind_name()),
+            commands,
+        }
+    }
+}
+
+/// Approximate canvas area of a node (the canvas sizes nodes by content)
+fn estimated_node_rect(node: &GraphNode) -> egui::Rect {
+    let rows = node.inputs.len().max(node.outputs.len()) as f32;
+    egui::Rect::from_min_size(node.position, egui::vec2(180.0, 40.0 + 22.0 * rows))
+}
+
+/// Bounds of the given nodes, or None if there are none
+pub fn nodes_rect(graph: &NodeGraph, node_ids: &BTreeSet<Uuid>) -> Option<egui::Rect> {
+    node_ids
+        .iter()
+        .filter_map(|id| graph.nodes.get(id))
+        .map(estimated_node_rect)
+        .reduce(|bounds, rect| bounds.union(rect))
+}
+
+impl NodeGraph {
+    /// Add or replace an annotation, returning the one it replaced
+    pub fn set_annotation(&mut self, annotation: Annotation) -> Option<Annotation> {
+        self.metadata.touch();
+        self.annotations.insert(annotation.id, annotation)
+    }
+
+    /// Remove an annotation (its nodes stay in the graph)
+    pub fn remove_annotation(&mut self, annotation_id: Uuid) -> Option<Annotation> {
+        self.metadata.touch();
+        self.annotations.remove(&annotation_id)
+    }
+
+    /// Nodes hidden inside collapsed groups
+    pub fn hidden_nodes(&self) -> HashSet<Uuid> {
+        self.annotations
+            .values()
+            .filter(|annotation| annotation.is_collapsed())
+            .flat_map(|annotation| annotation.node_ids.iter().copied())
+            .filter(|id| self.nodes.contains_key(id))
+            .collect()
+    }
+
+    /// Topmost annotation at a canvas position
+    ///
+    /// Notes are above frames, and smaller frames above the ones around them.
+    pub fn annotation_at(&self, pos: egui::Pos2) -> Option<&Annotation> {
+        self.annotations
+            .values()
+            .filter(|annotation| annotation.rect().contains(pos))
+            .min_by(|a, b| {
+                let is_frame = |annotation: &Annotation| {
+                    !matches!(annotation.kind, AnnotationKind::Note { .. })
+                };
+                let area = |annotation: &Annotation| annotation.rect().area();
+                is_frame(a)
+                    .cmp(&is_frame(b))
+                    .then(area(a).total_cmp(&area(b)))
+            })
+    }
+
+    /// Annotations in drawing order: frames (largest first), then notes
+    pub fn annotations_back_to_front(&self) -> Vec<&Annotation> {
+        let mut annotations: Vec<&Annotation> = self.annotations.values().collect();
+        annotations.sort_by(|a, b| {
+            let is_note = |annotation: &Annotation| {
+                matches!(annotation.kind, AnnotationKind::Note { .. })
+            };
+            is_note(a)
+                .cmp(&is_note(b))
+                .then(b.rect().area().total_cmp(&a.rect().area()))
+        });
+        annotations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::command::CommandHistory;
+    use crate::graph::test_support::constant_spec;
+
+    fn graph_with_nodes(count: usize) -> (NodeGraph, Vec<Uuid>) {
+        let spec = constant_spec();
+        let mut graph = NodeGraph::new("Test".to_string(), "Author".to_string());
+        let ids = (0..count)
+            .map(|i| graph.add_node(spec.create_node(egui::pos2(300.0 * i as f32, 0.0))))
+            .collect();
+        (graph, ids)
+    }
+
+    #[test]
+    fn test_frame_moves_its_nodes_as_one_step() {
+        let (mut graph, ids) = graph_with_nodes(3);
+        let frame = Annotation::frame("Inputs".to_string(), &graph, &ids[..2]).unwrap();
+        assert_eq!(frame.node_ids.len(), 2);
+        assert!(frame.rect().contains(graph.nodes[&ids[1]].position));
+        assert!(!frame.rect().contains(graph.nodes[&ids[2]].position));
+
+        let mut history = CommandHistory::new();
+        let frame_id = frame.id;
+        history.execute(Command::AddAnnotation { annotation: frame }, &mut graph).unwrap();
+        let cmd = graph.annotations[&frame_id].move_command(&graph, egui::vec2(0.0, 50.0));
+        history.execute(cmd, &mut graph).unwrap();
+        assert_eq!(graph.nodes[&ids[0]].position, egui::pos2(0.0, 50.0));
+        assert_eq!(graph.nodes[&ids[2]].position, egui::pos2(600.0, 0.0));
+
+        history.undo(&mut graph).unwrap();
+        assert_eq!(graph.nodes[&ids[0]].position, egui::pos2(0.0, 0.0));
+        history.undo(&mut graph).unwrap();
+        assert!(graph.annotations.is_empty());
+        // Annotations are not nodes: execution never sees them
+        assert_eq!(graph.execution_order().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_collapsed_group_hides_members() {
+        let (mut graph, ids) = graph_with_nodes(2);
+        let mut group = Annotation::group("Math".to_string(), &graph, &ids).unwrap();
+        let note = Annotation::note("# TODO".to_string(), group.position);
+        assert_eq!(note.label(), "# TODO");
+        graph.set_annotation(note.clone());
+        graph.set_annotation(group.clone());
+        assert!(graph.hidden_nodes().is_empty());
+        // The note sits above the group it overlaps
+        assert_eq!(graph.annotation_at(group.position + egui::vec2(5.0, 5.0)), Some(&note));
+        assert_eq!(graph.annotations_back_to_front()[1].id, note.id);
+
+        group.kind = AnnotationKind::Group { collapsed: true };
+        graph.set_annotation(group.clone());
+        assert_eq!(graph.hidden_nodes(), ids.iter().copied().collect::<HashSet<_>>());
+        assert!(group.rect().height() < group.height);
+        assert!(group.summary(&graph).starts_with("2 node(s): F32 Constant, F32 Constant"));
+
+        let restored = NodeGraph::from_bytes(&graph.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.annotations, graph.annotations);
+    }
+}
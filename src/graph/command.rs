@@ -1,12 +1,24 @@
 //! Command pattern for undo/redo functionality
+//!
+//! Every graph mutation the UI performs goes through a `Command`. Multi-step
+//! operations are wrapped in `Command::Batch` so they undo as one step, and the
+//! history of a saved graph is kept next to the user's data so it survives
+//! closing and reopening the file.
 
+use super::annotation::Annotation;
 use super::connection::Connection;
 use super::graph::NodeGraph;
-use super::node::{GraphNode, NodeValue};
+use super::node::{pos2_serde, GraphNode, Language, NodeValue, WasmCreatorNodeData};
+use super::serialization::{atomic_write, crc64};
+use crate::runtime::capabilities::CapabilityGrant;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// A command that can be executed and undone
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Command {
     /// Add a node to the graph
     AddNode {
@@ -21,7 +33,9 @@ pub enum Command {
     /// Move a node
     MoveNode {
         node_id: Uuid,
+        #[serde(with = "pos2_serde")]
         old_position: egui::Pos2,
+        #[serde(with = "pos2_serde")]
         new_position: egui::Pos2,
     },
     /// Add a connection
@@ -43,6 +57,207 @@ pub enum Command {
         old_value: NodeValue,
         new_value: NodeValue,
     },
+    /// Add pasted or duplicated nodes together with their connections
+    PasteNodes {
+        nodes: Vec<GraphNode>,
+        connections: Vec<Connection>,
+    },
+    /// Remove several nodes at once (e.g. cutting a selection)
+    RemoveNodes {
+        node_ids: Vec<Uuid>,
+        nodes: Vec<GraphNode>,
+        connections: Vec<Connection>,
+    },
+    /// Grant, change or revoke a node's capabilities (`None` means no grant)
+    SetCapabilityGrant {
+        node_id: Uuid,
+        old: Option<CapabilityGrant>,
+        new: Option<CapabilityGrant>,
+    },
+    /// Edit a node's name, values, configuration or creator code
+    EditNode {
+        node_id: Uuid,
+        before: NodeEdit,
+        after: NodeEdit,
+    },
+    /// Edit the graph's name, author or description
+    EditGraphDetails {
+        before: GraphDetails,
+        after: GraphDetails,
+    },
+    /// Several commands applied and undone as one step
+    Batch {
+        label: String,
+        commands: Vec<Command>,
+    },
+    /// Swap a node for another version of itself (e.g. relinked to a new
+    /// component), dropping the connections whose ports did not survive
+    ReplaceNode {
+        old: GraphNode,
+        new: GraphNode,
+        dropped_connections: Vec<Connection>,
+    },
+    /// Add a frame, group or note to the canvas
+    AddAnnotation {
+        annotation: Annotation,
+    },
+    /// Remove a frame, group or note (its nodes stay)
+    RemoveAnnotation {
+        annotation: Annotation,
+    },
+    /// Move, resize, retitle, collapse or change the members of an annotation
+    UpdateAnnotation {
+        old: Annotation,
+        new: Annotation,
+    },
+}
+
+/// User-editable state of a single node
+///
+/// Captured before and after an edit so the change can be undone without
+/// touching execution results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeEdit {
+    pub display_name: String,
+    /// Values of unconnected inputs, by port name
+    pub input_values: BTreeMap<String, Option<NodeValue>>,
+    /// Output values of constant nodes, which the user edits directly
+    pub constant_values: Vec<Option<NodeValue>>,
+    pub config: BTreeMap<String, NodeValue>,
+    pub creator: Option<CreatorEdit>,
+}
+
+/// Editable part of a WASM creator node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreatorEdit {
+    pub component_name: String,
+    pub source_code: String,
+    pub save_code: bool,
+    pub language: Language,
+}
+
+impl From<&WasmCreatorNodeData> for CreatorEdit {
+    fn from(data: &WasmCreatorNodeData) -> Self {
+        Self {
+            component_name: data.component_name.clone(),
+            source_code: data.source_code.clone(),
+            save_code: data.save_code,
+            language: data.language,
+        }
+    }
+}
+
+impl NodeEdit {
+    /// Capture the editable state of a node
+    pub fn capture(graph: &NodeGraph, node_id: Uuid) -> Option<Self> {
+        let node = graph.nodes.get(&node_id)?;
+        // Connected inputs hold propagated values, not user edits
+        let connected: HashSet<Uuid> = graph
+            .incoming_connections(node_id)
+            .iter()
+            .map(|conn| conn.to_port)
+            .collect();
+
+        Some(Self {
+            display_name: node.display_name.clone(),
+            input_values: node
+                .inputs
+                .iter()
+                .filter(|port| !connected.contains(&port.id))
+                .map(|port| (port.name.clone(), port.current_value.clone()))
+                .collect(),
+            constant_values: if node.component_id.starts_with("builtin:constant:") {
+                node.outputs.iter().map(|port| port.current_value.clone()).collect()
+            } else {
+                Vec::new()
+            },
+            config: node.config.clone(),
+            creator: node.creator_data.as_ref().map(CreatorEdit::from),
+        })
+    }
+
+    /// Whether `other` differs by a user edit
+    ///
+    /// Inputs present in only one snapshot were connected or disconnected in
+    /// between, which is a connection change rather than an edit. Inputs going
+    /// from no value to a value are widgets filling in their initial value.
+    pub fn differs_from(&self, other: &NodeEdit) -> bool {
+        self.display_name != other.display_name
+            || self.constant_values != other.constant_values
+            || self.config != other.config
+            || self.creator != other.creator
+            || self.input_values.iter().any(|(name, value)| {
+                matches!(
+                    (value, other.input_values.get(name)),
+                    (Some(value), Some(Some(other_value))) if value != other_value
+                )
+            })
+    }
+
+    /// Write this state back into the node
+    pub fn apply(&self, graph: &mut NodeGraph, node_id: Uuid) -> Result<(), String> {
+        let node = graph
+            .nodes
+            .get_mut(&node_id)
+            .ok_or_else(|| "Node not found".to_string())?;
+
+        node.display_name = self.display_name.clone();
+        for (name, value) in &self.input_values {
+            if let Some(port) = node.inputs.iter_mut().find(|port| &port.name == name) {
+                port.current_value = value.clone();
+            }
+        }
+        for (port, value) in node.outputs.iter_mut().zip(&self.constant_values) {
+            port.current_value = value.clone();
+        }
+        node.config = self.config.clone();
+        if let (Some(data), Some(creator)) = (node.creator_data.as_mut(), &self.creator) {
+            data.component_name = creator.component_name.clone();
+            data.source_code = creator.source_code.clone();
+            data.save_code = creator.save_code;
+            data.language = creator.language;
+        }
+        node.dirty = true;
+        graph.metadata.touch();
+        Ok(())
+    }
+}
+
+/// Graph-level details edited in the metadata dialog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphDetails {
+    pub name: String,
+    pub author: String,
+    pub description: String,
+}
+
+impl GraphDetails {
+    /// Capture the current details of a graph
+    pub fn capture(graph: &NodeGraph) -> Self {
+        Self {
+            name: graph.name.clone(),
+            author: graph.metadata.author.clone(),
+            description: graph.metadata.description.clone(),
+        }
+    }
+
+    /// Write these details back into the graph
+    pub fn apply(&self, graph: &mut NodeGraph) {
+        graph.name = self.name.clone();
+        graph.metadata.author = self.author.clone();
+        graph.metadata.description = self.description.clone();
+        graph.metadata.touch();
+    }
+}
+
+/// Replace a node's grant, or revoke it for `None`
+fn apply_grant(graph: &mut NodeGraph, node_id: Uuid, grant: &Option<CapabilityGrant>) {
+    match grant {
+        Some(grant) => graph.grant_capability(grant.clone()),
+        None => {
+            graph.revoke_capability(node_id);
+        }
+    }
 }
 
 impl Command {
@@ -84,9 +299,18 @@ impl Command {
                 to_port,
                 connection_id,
             } => {
-                let conn_id = graph
-                    .add_connection(*from_node, *from_port, *to_node, *to_port)
-                    .map_err(|e| e.to_string())?;
+                let conn_id = match connection_id {
+                    // Redo: reuse the ID later commands refer to
+                    Some(id) => graph.restore_connection(&Connection {
+                        id: *id,
+                        from_node: *from_node,
+                        from_port: *from_port,
+                        to_node: *to_node,
+                        to_port: *to_port,
+                    }),
+                    None => graph.add_connection(*from_node, *from_port, *to_node, *to_port),
+                }
+                .map_err(|e| e.to_string())?;
                 *connection_id = Some(conn_id);
                 Ok(())
             }
@@ -113,6 +337,82 @@ impl Command {
                     Err("Node not found".to_string())
                 }
             }
+            Command::PasteNodes { nodes, connections } => {
+                for node in nodes.iter() {
+                    graph.add_node(node.clone());
+                }
+                for conn in connections.iter() {
+                    graph.restore_connection(conn).map_err(|e| e.to_string())?;
+                }
+                Ok(())
+            }
+            Command::RemoveNodes {
+                node_ids,
+                nodes,
+                connections,
+            } => {
+                // Store the nodes and every connection touching them before removal
+                *nodes = node_ids
+                    .iter()
+                    .filter_map(|id| graph.nodes.get(id).cloned())
+                    .collect();
+                *connections = graph
+                    .connections
+                    .iter()
+                    .filter(|conn| node_ids.iter().any(|id| conn.involves_node(*id)))
+                    .cloned()
+                    .collect();
+                for node_id in node_ids.iter() {
+                    graph.remove_node(*node_id).map_err(|e| e.to_string())?;
+                }
+                Ok(())
+            }
+            Command::SetCapabilityGrant { node_id, new, .. } => {
+                apply_grant(graph, *node_id, new);
+                Ok(())
+            }
+            Command::EditNode { node_id, after, .. } => after.apply(graph, *node_id),
+            Command::EditGraphDetails { after, .. } => {
+                after.apply(graph);
+                Ok(())
+            }
+            Command::Batch { commands, .. } => {
+                let mut executed = 0;
+                let result: Result<(), String> = commands.iter_mut().try_for_each(|command| {
+                    command.execute(graph)?;
+                    executed += 1;
+                    Ok(())
+                });
+                if result.is_err() {
+                    // Roll back the steps that already ran so the batch stays atomic
+                    for command in commands[..executed].iter().rev() {
+                        let _ = command.undo(graph);
+                    }
+                }
+                result
+            }
+            Command::ReplaceNode {
+                new,
+                dropped_connections,
+                ..
+            } => {
+                for conn in dropped_connections.iter() {
+                    graph.remove_connection(conn.id).map_err(|e| e.to_string())?;
+                }
+                graph.replace_node(new.clone()).map(|_| ()).map_err(|e| e.to_string())
+            }
+            Command::AddAnnotation { annotation } => {
+                graph.set_annotation(annotation.clone());
+                Ok(())
+            }
+            Command::RemoveAnnotation { annotation } => graph
+                .remove_annotation(annotation.id)
+                .map(|_| ())
+                .ok_or_else(|| "Annotation not found".to_string()),
+            Command::UpdateAnnotation { new, .. } => {
+                graph.set_annotation(new.clone());
+                Ok(())
+            }
         }
     }
 
@@ -130,12 +430,7 @@ impl Command {
                 graph.add_node(node.clone());
                 // Restore connections
                 for conn in connections {
-                    let _ = graph.add_connection(
-                        conn.from_node,
-                        conn.from_port,
-                        conn.to_node,
-                        conn.to_port,
-                    );
+                    let _ = graph.restore_connection(conn);
                 }
                 Ok(())
             }
@@ -159,13 +454,9 @@ impl Command {
                 }
             }
             Command::RemoveConnection { connection } => {
+                // Keep the original ID so a later redo can find it again
                 graph
-                    .add_connection(
-                        connection.from_node,
-                        connection.from_port,
-                        connection.to_node,
-                        connection.to_port,
-                    )
+                    .restore_connection(connection)
                     .map(|_| ())
                     .map_err(|e| e.to_string())
             }
@@ -186,6 +477,90 @@ impl Command {
                     Err("Node not found".to_string())
                 }
             }
+            Command::PasteNodes { nodes, .. } => {
+                for node in nodes {
+                    graph.remove_node(node.id).map_err(|e| e.to_string())?;
+                }
+                Ok(())
+            }
+            Command::RemoveNodes {
+                nodes,
+                connections,
+                ..
+            } => {
+                for node in nodes {
+                    graph.add_node(node.clone());
+                }
+                for conn in connections {
+                    let _ = graph.restore_connection(conn);
+                }
+                Ok(())
+            }
+            Command::SetCapabilityGrant { node_id, old, .. } => {
+                apply_grant(graph, *node_id, old);
+                Ok(())
+            }
+            Command::EditNode {
+                node_id, before, ..
+            } => before.apply(graph, *node_id),
+            Command::EditGraphDetails { before, .. } => {
+                before.apply(graph);
+                Ok(())
+            }
+            Command::Batch { commands, .. } => {
+                for command in commands.iter().rev() {
+                    command.undo(graph)?;
+                }
+                Ok(())
+            }
+            Command::ReplaceNode {
+                old,
+                dropped_connections,
+                ..
+            } => {
+                graph.replace_node(old.clone()).map_err(|e| e.to_string())?;
+                for conn in dropped_connections {
+                    let _ = graph.restore_connection(conn);
+                }
+                Ok(())
+            }
+            Command::AddAnnotation { annotation } => graph
+                .remove_annotation(annotation.id)
+                .map(|_| ())
+                .ok_or_else(|| "Annotation not found".to_string()),
+            Command::RemoveAnnotation { annotation } => {
+                graph.set_annotation(annotation.clone());
+                Ok(())
+            }
+            Command::UpdateAnnotation { old, .. } => {
+                graph.set_annotation(old.clone());
+                Ok(())
+            }
+        }
+    }
+
+    /// Short description for status messages
+    pub fn label(&self) -> String {
+        match self {
+            Command::AddNode { node } => format!("Add {}", node.display_name),
+            Command::RemoveNode { node, .. } => format!("Delete {}", node.display_name),
+            Command::MoveNode { .. } => "Move node".to_string(),
+            Command::AddConnection { .. } => "Connect".to_string(),
+            Command::RemoveConnection { .. } => "Disconnect".to_string(),
+            Command::ChangeConstantValue { .. } => "Change value".to_string(),
+            Command::PasteNodes { nodes, .. } => format!("Paste {} node(s)", nodes.len()),
+            Command::RemoveNodes { node_ids, .. } => format!("Delete {} node(s)", node_ids.len()),
+            Command::SetCapabilityGrant { new: Some(_), .. } => "Grant permissions".to_string(),
+            Command::SetCapabilityGrant { new: None, .. } => "Revoke permissions".to_string(),
+            Command::EditNode { after, .. } => format!("Edit {}", after.display_name),
+            Command::EditGraphDetails { .. } => "Edit graph details".to_string(),
+            Command::Batch { label, .. } => label.clone(),
+            Command::ReplaceNode { new, .. } => format!("Update {}", new.display_name),
+            Command::AddAnnotation { annotation } => format!("Add {}", annotation.kind_name()),
+            Command::RemoveAnnotation { annotation } => {
+                format!("Delete {}", annotation.kind_name())
+            }
+            Command::UpdateAnnotation { new, .. } => format!("Edit {}", new.kind_name()),
         }
     }
 }
@@ -199,6 +574,8 @@ pub struct CommandHistory {
     redo_stack: Vec<Command>,
     /// Maximum history size
     max_size: usize,
+    /// Bumped on every change, so callers can tell when the graph moved under them
+    revision: u64,
 }
 
 impl CommandHistory {
@@ -208,6 +585,7 @@ impl CommandHistory {
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             max_size: 100,
+            revision: 0,
         }
     }
 
@@ -217,12 +595,23 @@ impl CommandHistory {
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             max_size,
+            revision: 0,
         }
     }
 
     /// Execute a command and add it to history
     pub fn execute(&mut self, mut command: Command, graph: &mut NodeGraph) -> Result<(), String> {
         command.execute(graph)?;
+        self.record(command);
+        Ok(())
+    }
+
+    /// Add a command that has already been applied to the graph
+    ///
+    /// Used for edits made live by widgets, where the change is only known
+    /// once the user has finished making it.
+    pub fn record(&mut self, command: Command) {
+        self.revision += 1;
 
         // Clear redo stack when new command is executed
         self.redo_stack.clear();
@@ -234,13 +623,12 @@ impl CommandHistory {
         if self.undo_stack.len() > self.max_size {
             self.undo_stack.remove(0);
         }
-
-        Ok(())
     }
 
     /// Undo the last command
     pub fn undo(&mut self, graph: &mut NodeGraph) -> Result<(), String> {
         if let Some(command) = self.undo_stack.pop() {
+            self.revision += 1;
             command.undo(graph)?;
             self.redo_stack.push(command);
             Ok(())
@@ -252,6 +640,7 @@ impl CommandHistory {
     /// Redo the last undone command
     pub fn redo(&mut self, graph: &mut NodeGraph) -> Result<(), String> {
         if let Some(mut command) = self.redo_stack.pop() {
+            self.revision += 1;
             command.execute(graph)?;
             self.undo_stack.push(command);
             Ok(())
@@ -274,13 +663,140 @@ impl CommandHistory {
     pub fn clear(&mut self) {
         self.undo_stack.clear();
         self.redo_stack.clear();
+        self.revision += 1;
     }
+
+    /// Counter that changes whenever the history does
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Label of the command the next undo would revert
+    pub fn undo_label(&self) -> Option<String> {
+        self.undo_stack.last().map(Command::label)
+    }
+
+    /// Label of the command the next redo would apply
+    pub fn redo_label(&self) -> Option<String> {
+        self.redo_stack.last().map(Command::label)
+    }
+
+    /// Where the history of a graph file is kept
+    ///
+    /// Histories live in the user's data directory, keyed by a hash of the
+    /// graph file's path, so they never clutter the project directory.
+    pub fn history_path_for(graph_path: &Path) -> PathBuf {
+        let path = graph_path.canonicalize().unwrap_or_else(|_| graph_path.to_path_buf());
+        let key = crc64(path.to_string_lossy().as_bytes());
+        dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("wasmflow")
+            .join("history")
+            .join(format!("{:016x}.{}", key, HISTORY_EXTENSION))
+    }
+
+    /// Persist the history of a just-saved graph file
+    pub fn save_for(&self, graph_path: &Path, graph_id: Uuid) -> anyhow::Result<()> {
+        self.save_to(&Self::history_path_for(graph_path), graph_path, graph_id)
+    }
+
+    /// Restore the history of a graph file, if it matches the file on disk
+    pub fn load_for(graph_path: &Path, graph_id: Uuid) -> Option<Self> {
+        Self::load_from(&Self::history_path_for(graph_path), graph_path, graph_id)
+    }
+
+    /// Write the history to `history_path`, tied to the current contents of `graph_path`
+    pub fn save_to(
+        &self,
+        history_path: &Path,
+        graph_path: &Path,
+        graph_id: Uuid,
+    ) -> anyhow::Result<()> {
+        let skip = self.undo_stack.len().saturating_sub(self.max_size);
+        let file = HistoryFile {
+            version: HISTORY_VERSION,
+            graph_id,
+            file_checksum: file_checksum(graph_path)?,
+            max_size: self.max_size,
+            undo_stack: self.undo_stack[skip..].to_vec(),
+            redo_stack: self.redo_stack.clone(),
+        };
+        let bytes = bincode::serialize(&file).context("Failed to serialize undo history")?;
+
+        if let Some(dir) = history_path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        atomic_write(history_path, &bytes)
+            .with_context(|| format!("Failed to write {}", history_path.display()))?;
+        Ok(())
+    }
+
+    /// Read a history written by `save_to`
+    ///
+    /// Returns `None` when there is no history, or when the graph file was
+    /// changed outside this app since the history was saved.
+    pub fn load_from(history_path: &Path, graph_path: &Path, graph_id: Uuid) -> Option<Self> {
+        let bytes = std::fs::read(history_path).ok()?;
+        let file: HistoryFile = match bincode::deserialize(&bytes) {
+            Ok(file) => file,
+            Err(e) => {
+                log::warn!("Ignoring unreadable undo history {}: {}", history_path.display(), e);
+                return None;
+            }
+        };
+
+        let checksum = file_checksum(graph_path).ok()?;
+        if file.version != HISTORY_VERSION
+            || file.graph_id != graph_id
+            || file.file_checksum != checksum
+        {
+            log::info!("Undo history for {} is out of date; starting fresh", graph_path.display());
+            return None;
+        }
+
+        Some(Self {
+            undo_stack: file.undo_stack,
+            redo_stack: file.redo_stack,
+            max_size: file.max_size,
+            revision: 0,
+        })
+    }
+}
+
+/// File extension for persisted undo histories
+const HISTORY_EXTENSION: &str = "history";
+
+/// Current history file format version
+///
+/// Bump it whenever the encoding of anything a `Command` holds changes; older
+/// histories are dropped, not migrated. 2: nodes record their component pin.
+const HISTORY_VERSION: u32 = 2;
+
+/// On-disk undo history of one graph file
+#[derive(Serialize, Deserialize)]
+struct HistoryFile {
+    version: u32,
+    /// ID of the graph the history belongs to
+    graph_id: Uuid,
+    /// CRC64 of the graph file as saved, to detect outside changes
+    file_checksum: u64,
+    max_size: usize,
+    undo_stack: Vec<Command>,
+    redo_stack: Vec<Command>,
+}
+
+/// CRC64 of a file's bytes
+fn file_checksum(path: &Path) -> anyhow::Result<u64> {
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(crc64(&bytes))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::graph::node::ComponentSpec;
+    use crate::graph::node::{ComponentSpec, DataType};
+    use crate::graph::test_support::{linked_graph, scale_spec};
 
     #[test]
     fn test_add_remove_node() {
@@ -339,4 +855,207 @@ mod tests {
         history.undo(&mut graph).unwrap();
         assert_eq!(graph.nodes.get(&node_id).unwrap().position, egui::Pos2::new(0.0, 0.0));
     }
+
+    #[test]
+    fn test_paste_and_remove_nodes_are_single_steps() {
+        use crate::graph::clipboard::GraphFragment;
+
+        let spec = scale_spec(DataType::F32);
+        let (mut graph, first_id, second_id) = linked_graph(&spec, &spec);
+        let mut history = CommandHistory::new();
+
+        // Paste a copy of both nodes as one step
+        let pasted = GraphFragment::from_selection(&graph, &[first_id, second_id])
+            .unwrap()
+            .instantiate(egui::vec2(40.0, 40.0));
+        let cmd = Command::PasteNodes {
+            nodes: pasted.nodes,
+            connections: pasted.connections,
+        };
+        history.execute(cmd, &mut graph).unwrap();
+        assert_eq!((graph.nodes.len(), graph.connections.len()), (4, 2));
+
+        history.undo(&mut graph).unwrap();
+        assert_eq!((graph.nodes.len(), graph.connections.len()), (2, 1));
+
+        // Cut the originals as one step
+        let cmd = Command::RemoveNodes {
+            node_ids: vec![first_id, second_id],
+            nodes: Vec::new(),
+            connections: Vec::new(),
+        };
+        history.execute(cmd, &mut graph).unwrap();
+        assert!(graph.nodes.is_empty());
+
+        history.undo(&mut graph).unwrap();
+        assert_eq!((graph.nodes.len(), graph.connections.len()), (2, 1));
+    }
+
+    /// The shared linked pair with its wire taken out again
+    ///
+    /// Returns (graph, first, first output, second, second input).
+    fn unlinked_pair() -> (NodeGraph, Uuid, Uuid, Uuid, Uuid) {
+        let spec = scale_spec(DataType::F32);
+        let (mut graph, first, second) = linked_graph(&spec, &spec);
+        let wire = graph.remove_connection(graph.connections[0].id).unwrap();
+        (graph, first, wire.from_port, second, wire.to_port)
+    }
+
+    #[test]
+    fn test_batch_is_atomic_and_undoes_in_one_step() {
+        let (mut graph, first, first_out, second, second_in) = unlinked_pair();
+        let mut history = CommandHistory::new();
+
+        let connect = Command::AddConnection {
+            from_node: first,
+            from_port: first_out,
+            to_node: second,
+            to_port: second_in,
+            connection_id: None,
+        };
+        let batch = Command::Batch {
+            label: "Connect and move".to_string(),
+            commands: vec![
+                connect.clone(),
+                Command::MoveNode {
+                    node_id: second,
+                    old_position: egui::Pos2::new(200.0, 0.0),
+                    new_position: egui::Pos2::new(200.0, 50.0),
+                },
+            ],
+        };
+        history.execute(batch, &mut graph).unwrap();
+        assert_eq!(graph.connections.len(), 1);
+        assert_eq!(history.undo_label().as_deref(), Some("Connect and move"));
+
+        history.undo(&mut graph).unwrap();
+        assert!(graph.connections.is_empty());
+        assert_eq!(graph.nodes[&second].position, egui::Pos2::new(200.0, 0.0));
+        history.redo(&mut graph).unwrap();
+        assert_eq!(graph.connections.len(), 1);
+
+        // A failing step rolls back the steps before it
+        history.undo(&mut graph).unwrap();
+        let failing = Command::Batch {
+            label: "Broken".to_string(),
+            commands: vec![
+                connect,
+                Command::MoveNode {
+                    node_id: Uuid::new_v4(),
+                    old_position: egui::Pos2::ZERO,
+                    new_position: egui::Pos2::ZERO,
+                },
+            ],
+        };
+        assert!(history.execute(failing, &mut graph).is_err());
+        assert!(graph.connections.is_empty());
+        assert!(history.can_redo());
+    }
+
+    #[test]
+    fn test_grant_and_node_edits_undo() {
+        use crate::runtime::capabilities::CapabilitySet;
+
+        let (mut graph, first, first_out, second, second_in) = unlinked_pair();
+        let mut history = CommandHistory::new();
+
+        let grant = CapabilityGrant::new(first, CapabilitySet::Full);
+        let cmd = Command::SetCapabilityGrant {
+            node_id: first,
+            old: None,
+            new: Some(grant),
+        };
+        history.execute(cmd, &mut graph).unwrap();
+        assert!(graph.has_capability_grant(first));
+        history.undo(&mut graph).unwrap();
+        assert!(!graph.has_capability_grant(first));
+
+        // Rename and change an input value, as the widgets do, then record it
+        graph.nodes.get_mut(&second).unwrap().inputs[0].current_value = Some(NodeValue::F32(1.0));
+        let before = NodeEdit::capture(&graph, second).unwrap();
+        let node = graph.nodes.get_mut(&second).unwrap();
+        node.display_name = "Renamed".to_string();
+        node.inputs[0].current_value = Some(NodeValue::F32(2.5));
+        let after = NodeEdit::capture(&graph, second).unwrap();
+        assert!(before.differs_from(&after));
+        history.record(Command::EditNode {
+            node_id: second,
+            before,
+            after,
+        });
+
+        history.undo(&mut graph).unwrap();
+        assert_eq!(graph.nodes[&second].display_name, "Second");
+        assert_eq!(
+            graph.nodes[&second].inputs[0].current_value,
+            Some(NodeValue::F32(1.0))
+        );
+        history.redo(&mut graph).unwrap();
+        assert_eq!(graph.nodes[&second].display_name, "Renamed");
+
+        // Connecting an input is not an edit of the node
+        let unconnected = NodeEdit::capture(&graph, second).unwrap();
+        graph.add_connection(first, first_out, second, second_in).unwrap();
+        let connected = NodeEdit::capture(&graph, second).unwrap();
+        assert!(!unconnected.differs_from(&connected));
+    }
+
+    #[test]
+    fn test_connection_ids_survive_undo_redo() {
+        let (mut graph, first, first_out, second, second_in) = unlinked_pair();
+        let mut history = CommandHistory::new();
+
+        let cmd = Command::AddConnection {
+            from_node: first,
+            from_port: first_out,
+            to_node: second,
+            to_port: second_in,
+            connection_id: None,
+        };
+        history.execute(cmd, &mut graph).unwrap();
+        let connection = graph.connections[0].clone();
+        history
+            .execute(Command::RemoveConnection { connection }, &mut graph)
+            .unwrap();
+
+        history.undo(&mut graph).unwrap();
+        history.undo(&mut graph).unwrap();
+        history.redo(&mut graph).unwrap();
+        history.redo(&mut graph).unwrap();
+        assert!(graph.connections.is_empty());
+    }
+
+    #[test]
+    fn test_history_persists_for_unchanged_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph_path = dir.path().join("graph.wasmflow");
+        let history_path = dir.path().join("graph.history");
+
+        let (mut graph, first, ..) = unlinked_pair();
+        let mut history = CommandHistory::with_max_size(2);
+        for x in [10.0, 20.0, 30.0] {
+            let old_position = graph.nodes[&first].position;
+            let cmd = Command::MoveNode {
+                node_id: first,
+                old_position,
+                new_position: egui::Pos2::new(x, 0.0),
+            };
+            history.execute(cmd, &mut graph).unwrap();
+        }
+        graph.save_to_file(&graph_path).unwrap();
+        history.save_to(&history_path, &graph_path, graph.id).unwrap();
+
+        // Reopening the same file restores the bounded history
+        let mut loaded = NodeGraph::load_from_file(&graph_path).unwrap();
+        let mut restored = CommandHistory::load_from(&history_path, &graph_path, graph.id).unwrap();
+        restored.undo(&mut loaded).unwrap();
+        restored.undo(&mut loaded).unwrap();
+        assert!(!restored.can_undo());
+        assert_eq!(loaded.nodes[&first].position, egui::Pos2::new(10.0, 0.0));
+
+        // A file changed elsewhere, or a different graph, starts fresh
+        assert!(CommandHistory::load_from(&history_path, &graph_path, Uuid::new_v4()).is_none());
+        std::fs::write(&graph_path, b"changed").unwrap();
+        assert!(CommandHistory::load_from(&history_path, &graph_path, graph.id).is_none());
+    }
 }
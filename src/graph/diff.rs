@@ -0,0 +1,963 @@
+//! Semantic diff and three-way merge of graphs
+//!
+//! Saved graphs are binary, so line-based tools can neither review nor merge
+//! them. Diffing matches nodes by UUID and connections by their endpoints and
+//! reports what changed for a user: nodes added and removed, renames, moves,
+//! component and port changes, input and config values, wiring, grants and
+//! canvas annotations.
+//! Merging compares each of those parts of a node against the common
+//! ancestor separately, so edits to different parts of a node combine
+//! cleanly and only edits to the same part conflict.
+
+use crate::graph::annotation::{Annotation, AnnotationKind};
+use crate::graph::connection::Connection;
+use crate::graph::graph::NodeGraph;
+use crate::graph::node::{GraphNode, NodeValue, Port};
+use crate::runtime::capabilities::{CapabilityGrant, CapabilitySet};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use uuid::Uuid;
+
+/// Part of a node that is compared and merged on its own
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Aspect {
+    Name,
+    Position,
+    /// Component id, pin and port layout
+    Component,
+    Capabilities,
+    /// Value of the input port with this name
+    Value(String),
+    /// Configuration value with this key
+    Config(String),
+    /// Creator code, continuous settings, composition and guest state
+    Data,
+}
+
+impl Aspect {
+    fn label(&self) -> String {
+        match self {
+            Aspect::Name => "name".to_string(),
+            Aspect::Position => "position".to_string(),
+            Aspect::Component => "component".to_string(),
+            Aspect::Capabilities => "capabilities".to_string(),
+            Aspect::Value(port) => format!("input '{}'", port),
+            Aspect::Config(key) => format!("config '{}'", key),
+            Aspect::Data => "stored data".to_string(),
+        }
+    }
+}
+
+/// One difference in a node present in both graphs
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeChange {
+    Renamed { from: String, to: String },
+    Moved { from: egui::Pos2, to: egui::Pos2 },
+    /// Component id or pinned binary changed (as "id vVERSION")
+    Component { from: String, to: String },
+    /// Ports as "name: type"
+    Ports { added: Vec<String>, removed: Vec<String> },
+    Capabilities { from: CapabilitySet, to: CapabilitySet },
+    Value { port: String, from: Option<NodeValue>, to: Option<NodeValue> },
+    Config { key: String, from: Option<NodeValue>, to: Option<NodeValue> },
+    /// Creator code, continuous settings, composition or guest state
+    Data,
+}
+
+impl fmt::Display for NodeChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeChange::Renamed { from, to } => write!(f, "renamed \"{}\" -> \"{}\"", from, to),
+            NodeChange::Moved { from, to } => write!(
+                f,
+                "moved ({:.0}, {:.0}) -> ({:.0}, {:.0})",
+                from.x, from.y, to.x, to.y
+            ),
+            NodeChange::Component { from, to } => write!(f, "component {} -> {}", from, to),
+            NodeChange::Ports { added, removed } => {
+                let mut parts = Vec::new();
+                if !added.is_empty() {
+                    parts.push(format!("added {}", added.join(", ")));
+                }
+                if !removed.is_empty() {
+                    parts.push(format!("removed {}", removed.join(", ")));
+                }
+                write!(f, "ports {}", parts.join("; "))
+            }
+            NodeChange::Capabilities { from, to } => write!(
+                f,
+                "capabilities {} -> {}",
+                from.description(),
+                to.description()
+            ),
+            NodeChange::Value { port, from, to } => {
+                write!(f, "input {}: {} -> {}", port, value_label(from), value_label(to))
+            }
+            NodeChange::Config { key, from, to } => {
+                write!(f, "config {}: {} -> {}", key, value_label(from), value_label(to))
+            }
+            NodeChange::Data => write!(f, "stored data changed"),
+        }
+    }
+}
+
+fn value_label(value: &Option<NodeValue>) -> String {
+    value
+        .as_ref()
+        .map(NodeValue::format_display)
+        .unwrap_or_else(|| "unset".to_string())
+}
+
+/// A node that exists in only one of the graphs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeSummary {
+    pub node_id: Uuid,
+    pub display_name: String,
+    pub component_id: String,
+}
+
+impl NodeSummary {
+    fn new(node: &GraphNode) -> Self {
+        Self {
+            node_id: node.id,
+            display_name: node.display_name.clone(),
+            component_id: node.component_id.clone(),
+        }
+    }
+}
+
+/// Changes to a node present in both graphs
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeDiff {
+    pub node_id: Uuid,
+    /// Name in the newer graph
+    pub display_name: String,
+    pub changes: Vec<NodeChange>,
+}
+
+/// A connection only one of the graphs has
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionChange {
+    pub connection: Connection,
+    /// "node.port -> node.port", named from the graph that has it
+    pub label: String,
+}
+
+/// A capability grant added, removed or changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrantChange {
+    pub node_id: Uuid,
+    pub display_name: String,
+    pub from: Option<CapabilitySet>,
+    pub to: Option<CapabilitySet>,
+}
+
+/// A frame, group or note added, removed or changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotationChange {
+    pub annotation_id: Uuid,
+    pub from: Option<Annotation>,
+    pub to: Option<Annotation>,
+}
+
+impl fmt::Display for AnnotationChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.from, &self.to) {
+            (None, Some(to)) => write!(f, "+ {}", annotation_name(to)),
+            (Some(from), None) => write!(f, "- {}", annotation_name(from)),
+            (Some(from), Some(to)) => {
+                let mut parts = Vec::new();
+                if from.title != to.title {
+                    parts.push(format!("renamed \"{}\" -> \"{}\"", from.title, to.title));
+                }
+                if from.position != to.position {
+                    parts.push("moved".to_string());
+                }
+                if (from.width, from.height) != (to.width, to.height) {
+                    parts.push("resized".to_string());
+                }
+                if from.color != to.color {
+                    parts.push("color changed".to_string());
+                }
+                if from.node_ids != to.node_ids {
+                    parts.push(format!("now holds {} node(s)", to.node_ids.len()));
+                }
+                if from.kind != to.kind {
+                    parts.push(match to.kind {
+                        AnnotationKind::Group { collapsed: true } => "collapsed".to_string(),
+                        AnnotationKind::Group { collapsed: false } => "expanded".to_string(),
+                        _ => "text changed".to_string(),
+                    });
+                }
+                write!(f, "~ {}: {}", annotation_name(to), parts.join(", "))
+            }
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// "frame \"Inputs\"", or a note's first line
+fn annotation_name(annotation: &Annotation) -> String {
+    format!("{} \"{}\"", annotation.kind_name(), annotation.label())
+}
+
+/// Differences between two versions of a graph
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphDiff {
+    /// Graph name (old, new) if it changed
+    pub renamed: Option<(String, String)>,
+    pub added_nodes: Vec<NodeSummary>,
+    pub removed_nodes: Vec<NodeSummary>,
+    pub changed_nodes: Vec<NodeDiff>,
+    pub added_connections: Vec<ConnectionChange>,
+    pub removed_connections: Vec<ConnectionChange>,
+    pub grants: Vec<GrantChange>,
+    pub annotations: Vec<AnnotationChange>,
+}
+
+impl GraphDiff {
+    /// Whether the graphs are equivalent
+    pub fn is_empty(&self) -> bool {
+        self.change_count() == 0
+    }
+
+    /// Number of listed changes
+    pub fn change_count(&self) -> usize {
+        usize::from(self.renamed.is_some())
+            + self.added_nodes.len()
+            + self.removed_nodes.len()
+            + self.changed_nodes.iter().map(|node| node.changes.len()).sum::<usize>()
+            + self.added_connections.len()
+            + self.removed_connections.len()
+            + self.grants.len()
+            + self.annotations.len()
+    }
+
+    /// Changes to one node, if it is in both graphs and changed
+    pub fn node(&self, node_id: Uuid) -> Option<&NodeDiff> {
+        self.changed_nodes.iter().find(|node| node.node_id == node_id)
+    }
+}
+
+impl fmt::Display for GraphDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((from, to)) = &self.renamed {
+            writeln!(f, "~ graph renamed \"{}\" -> \"{}\"", from, to)?;
+        }
+        for node in &self.removed_nodes {
+            writeln!(f, "- node \"{}\" ({})", node.display_name, node.component_id)?;
+        }
+        for node in &self.added_nodes {
+            writeln!(f, "+ node \"{}\" ({})", node.display_name, node.component_id)?;
+        }
+        for node in &self.changed_nodes {
+            for change in &node.changes {
+                writeln!(f, "~ node \"{}\": {}", node.display_name, change)?;
+            }
+        }
+        for conn in &self.removed_connections {
+            writeln!(f, "- connection {}", conn.label)?;
+        }
+        for conn in &self.added_connections {
+            writeln!(f, "+ connection {}", conn.label)?;
+        }
+        for grant in &self.grants {
+            match (&grant.from, &grant.to) {
+                (None, Some(to)) => {
+                    writeln!(f, "+ grant \"{}\": {}", grant.display_name, to.description())?
+                }
+                (Some(from), None) => {
+                    writeln!(f, "- grant \"{}\": {}", grant.display_name, from.description())?
+                }
+                (Some(from), Some(to)) => writeln!(
+                    f,
+                    "~ grant \"{}\": {} -> {}",
+                    grant.display_name,
+                    from.description(),
+                    to.description()
+                )?,
+                (None, None) => {}
+            }
+        }
+        for annotation in &self.annotations {
+            writeln!(f, "{}", annotation)?;
+        }
+        Ok(())
+    }
+}
+
+impl NodeGraph {
+    /// Differences from this graph to `new`
+    ///
+    /// Nodes are matched by id, connections by their endpoints (not their
+    /// own ids), so re-created wires with the same endpoints are unchanged.
+    pub fn diff(&self, new: &NodeGraph) -> GraphDiff {
+        let mut diff = GraphDiff::default();
+        if self.name != new.name {
+            diff.renamed = Some((self.name.clone(), new.name.clone()));
+        }
+
+        for (id, old_node) in &self.nodes {
+            match new.nodes.get(id) {
+                None => diff.removed_nodes.push(NodeSummary::new(old_node)),
+                Some(new_node) => {
+                    let changes = node_changes(old_node, new_node);
+                    if !changes.is_empty() {
+                        diff.changed_nodes.push(NodeDiff {
+                            node_id: *id,
+                            display_name: new_node.display_name.clone(),
+                            changes,
+                        });
+                    }
+                }
+            }
+        }
+        diff.added_nodes = new
+            .nodes
+            .values()
+            .filter(|node| !self.nodes.contains_key(&node.id))
+            .map(NodeSummary::new)
+            .collect();
+
+        let old_keys: HashSet<_> = self.connections.iter().map(endpoints).collect();
+        let new_keys: HashSet<_> = new.connections.iter().map(endpoints).collect();
+        diff.removed_connections = self
+            .connections
+            .iter()
+            .filter(|conn| !new_keys.contains(&endpoints(conn)))
+            .map(|conn| ConnectionChange {
+                connection: conn.clone(),
+                label: connection_label(self, conn),
+            })
+            .collect();
+        diff.added_connections = new
+            .connections
+            .iter()
+            .filter(|conn| !old_keys.contains(&endpoints(conn)))
+            .map(|conn| ConnectionChange {
+                connection: conn.clone(),
+                label: connection_label(new, conn),
+            })
+            .collect();
+
+        let grant_ids: BTreeSet<&Uuid> =
+            self.capability_grants.keys().chain(new.capability_grants.keys()).collect();
+        for id in grant_ids {
+            let from = self.capability_grants.get(id).map(|g| g.capability_set.clone());
+            let to = new.capability_grants.get(id).map(|g| g.capability_set.clone());
+            if from != to {
+                diff.grants.push(GrantChange {
+                    node_id: *id,
+                    display_name: node_name(&[new, self], *id),
+                    from,
+                    to,
+                });
+            }
+        }
+
+        let annotation_ids: BTreeSet<&Uuid> =
+            self.annotations.keys().chain(new.annotations.keys()).collect();
+        for id in annotation_ids {
+            let from = self.annotations.get(id);
+            let to = new.annotations.get(id);
+            if from != to {
+                diff.annotations.push(AnnotationChange {
+                    annotation_id: *id,
+                    from: from.cloned(),
+                    to: to.cloned(),
+                });
+            }
+        }
+        diff
+    }
+}
+
+
+/// Display name of a node from the first graph that has it, or its short id
+fn node_name(graphs: &[&NodeGraph], node_id: Uuid) -> String {
+    graphs
+        .iter()
+        .find_map(|graph| graph.nodes.get(&node_id))
+        .map(|node| node.display_name.clone())
+        .unwrap_or_else(|| node_id.to_string()[..8].to_string())
+}
+
+/// What a connection links, independent of its own id
+fn endpoints(conn: &Connection) -> (Uuid, Uuid, Uuid, Uuid) {
+    (conn.from_node, conn.from_port, conn.to_node, conn.to_port)
+}
+
+/// "node.port -> node.port"
+fn connection_label(graph: &NodeGraph, conn: &Connection) -> String {
+    let end = |node_id: Uuid, port_id: Uuid| {
+        let port = graph
+            .nodes
+            .get(&node_id)
+            .and_then(|node| node.inputs.iter().chain(&node.outputs).find(|p| p.id == port_id))
+            .map(|port| port.name.clone())
+            .unwrap_or_else(|| "?".to_string());
+        format!("{}.{}", node_name(&[graph], node_id), port)
+    };
+    format!("{} -> {}", end(conn.from_node, conn.from_port), end(conn.to_node, conn.to_port))
+}
+
+/// Compare values that do not implement PartialEq by their saved form
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    bincode::serialize(a).ok() == bincode::serialize(b).ok()
+}
+
+fn port_signatures(ports: &[Port]) -> Vec<String> {
+    ports
+        .iter()
+        .map(|port| format!("{}: {}", port.name, port.data_type.name()))
+        .collect()
+}
+
+fn input_value<'a>(node: &'a GraphNode, name: &str) -> Option<&'a NodeValue> {
+    node.inputs
+        .iter()
+        .find(|port| port.name == name)
+        .and_then(|port| port.current_value.as_ref())
+}
+
+fn component_label(node: &GraphNode) -> String {
+    match &node.component_pin {
+        Some(pin) => format!("{} v{} ({})", node.component_id, pin.version, pin.content_hash),
+        None => node.component_id.clone(),
+    }
+}
+
+/// Every aspect either node has
+fn aspects(nodes: &[&GraphNode]) -> BTreeSet<Aspect> {
+    let mut aspects: BTreeSet<Aspect> = [
+        Aspect::Name,
+        Aspect::Position,
+        Aspect::Component,
+        Aspect::Capabilities,
+        Aspect::Data,
+    ]
+    .into_iter()
+    .collect();
+    for node in nodes {
+        aspects.extend(node.inputs.iter().map(|port| Aspect::Value(port.name.clone())));
+        aspects.extend(node.config.keys().map(|key| Aspect::Config(key.clone())));
+    }
+    aspects
+}
+
+fn aspect_eq(a: &GraphNode, b: &GraphNode, aspect: &Aspect) -> bool {
+    match aspect {
+        Aspect::Name => a.display_name == b.display_name,
+        Aspect::Position => a.position == b.position,
+        Aspect::Component => {
+            a.component_id == b.component_id
+                && a.component_pin == b.component_pin
+                && port_signatures(&a.inputs) == port_signatures(&b.inputs)
+                && port_signatures(&a.outputs) == port_signatures(&b.outputs)
+        }
+        Aspect::Capabilities => a.capabilities == b.capabilities,
+        Aspect::Value(port) => input_value(a, port) == input_value(b, port),
+        Aspect::Config(key) => a.config.get(key) == b.config.get(key),
+        Aspect::Data => {
+            a.instance_state == b.instance_state
+                && same(&a.creator_data, &b.creator_data)
+                && same(&a.continuous_config, &b.continuous_config)
+                && same(&a.composition_data, &b.composition_data)
+        }
+    }
+}
+
+/// Changes from `old` to `new` for an aspect that differs
+fn describe(old: &GraphNode, new: &GraphNode, aspect: &Aspect) -> Vec<NodeChange> {
+    match aspect {
+        Aspect::Name => vec![NodeChange::Renamed {
+            from: old.display_name.clone(),
+            to: new.display_name.clone(),
+        }],
+        Aspect::Position => vec![NodeChange::Moved { from: old.position, to: new.position }],
+        Aspect::Component => {
+            let mut changes = Vec::new();
+            if old.component_id != new.component_id || old.component_pin != new.component_pin {
+                changes.push(NodeChange::Component {
+                    from: component_label(old),
+                    to: component_label(new),
+                });
+            }
+            let old_ports = [port_signatures(&old.inputs), port_signatures(&old.outputs)].concat();
+            let new_ports = [port_signatures(&new.inputs), port_signatures(&new.outputs)].concat();
+            let added: Vec<String> =
+                new_ports.iter().filter(|p| !old_ports.contains(p)).cloned().collect();
+            let removed: Vec<String> =
+                old_ports.iter().filter(|p| !new_ports.contains(p)).cloned().collect();
+            if !added.is_empty() || !removed.is_empty() {
+                changes.push(NodeChange::Ports { added, removed });
+            }
+            changes
+        }
+        Aspect::Capabilities => vec![NodeChange::Capabilities {
+            from: old.capabilities.clone(),
+            to: new.capabilities.clone(),
+        }],
+        Aspect::Value(port) => vec![NodeChange::Value {
+            port: port.clone(),
+            from: input_value(old, port).cloned(),
+            to: input_value(new, port).cloned(),
+        }],
+        Aspect::Config(key) => vec![NodeChange::Config {
+            key: key.clone(),
+            from: old.config.get(key).cloned(),
+            to: new.config.get(key).cloned(),
+        }],
+        Aspect::Data => vec![NodeChange::Data],
+    }
+}
+
+/// Changes from `old` to `new` version of a node
+fn node_changes(old: &GraphNode, new: &GraphNode) -> Vec<NodeChange> {
+    aspects(&[old, new])
+        .iter()
+        .filter(|aspect| !aspect_eq(old, new, aspect))
+        .flat_map(|aspect| describe(old, new, aspect))
+        .collect()
+}
+
+/// Take one aspect of `src` into `dst`
+fn copy_aspect(dst: &mut GraphNode, src: &GraphNode, aspect: &Aspect) {
+    match aspect {
+        Aspect::Name => dst.display_name = src.display_name.clone(),
+        Aspect::Position => dst.position = src.position,
+        Aspect::Component => {
+            // Values set on ports that survive are merged separately
+            let mut inputs = src.inputs.clone();
+            for port in &mut inputs {
+                if let Some(kept) = dst
+                    .inputs
+                    .iter()
+                    .find(|old| old.name == port.name && old.data_type == port.data_type)
+                {
+                    port.current_value = kept.current_value.clone();
+                }
+            }
+            dst.inputs = inputs;
+            dst.outputs = src.outputs.clone();
+            dst.component_id = src.component_id.clone();
+            dst.component_pin = src.component_pin.clone();
+            dst.metadata = src.metadata.clone();
+        }
+        Aspect::Capabilities => dst.capabilities = src.capabilities.clone(),
+        Aspect::Value(name) => {
+            if let Some(port) = dst.inputs.iter_mut().find(|port| port.name == *name) {
+                port.current_value = input_value(src, name).cloned();
+            }
+        }
+        Aspect::Config(key) => match src.config.get(key) {
+            Some(value) => {
+                dst.config.insert(key.clone(), value.clone());
+            }
+            None => {
+                dst.config.remove(key);
+            }
+        },
+        Aspect::Data => {
+            dst.instance_state = src.instance_state.clone();
+            dst.creator_data = src.creator_data.clone();
+            dst.continuous_config = src.continuous_config.clone();
+            dst.composition_data = src.composition_data.clone();
+        }
+    }
+    dst.dirty = true;
+}
+
+/// What a grant allows, ignoring when it was given
+fn grant_set(grant: Option<&CapabilityGrant>) -> Option<&CapabilitySet> {
+    grant.map(|grant| &grant.capability_set)
+}
+
+/// Something both sides of a merge changed differently
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// Node the conflict is about, if any
+    pub node_id: Option<Uuid>,
+    pub message: String,
+}
+
+/// Outcome of a three-way merge
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    /// Merged graph; conflicts are resolved in favour of "ours"
+    pub graph: NodeGraph,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeResult {
+    /// Whether the merge needs no manual resolution
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Merge the changes `ours` and `theirs` made to their common ancestor `base`
+///
+/// Nodes are merged aspect by aspect (name, position, component, each input
+/// value, each config key, ...). An aspect both sides changed differently is
+/// a conflict and keeps our version, except positions, which follow ours
+/// silently. A node one side deleted and the other changed is kept.
+/// Connections are merged as sets of endpoints; connections that no longer
+/// fit the merged nodes, or compete for an input, are dropped and reported.
+/// Grants and annotations are merged whole, like nodes deleted on one side.
+pub fn merge(base: &NodeGraph, ours: &NodeGraph, theirs: &NodeGraph) -> MergeResult {
+    let mut conflicts = Vec::new();
+    let mut conflict = |node_id: Option<Uuid>, message: String| {
+        conflicts.push(MergeConflict { node_id, message });
+    };
+
+    let name = if base.name == ours.name {
+        theirs.name.clone()
+    } else {
+        if base.name != theirs.name && ours.name != theirs.name {
+            conflict(
+                None,
+                format!(
+                    "Graph renamed to \"{}\" and \"{}\"; kept \"{}\"",
+                    ours.name, theirs.name, ours.name
+                ),
+            );
+        }
+        ours.name.clone()
+    };
+
+    let ids: BTreeSet<Uuid> = base
+        .nodes
+        .keys()
+        .chain(ours.nodes.keys())
+        .chain(theirs.nodes.keys())
+        .copied()
+        .collect();
+    let mut nodes: BTreeMap<Uuid, GraphNode> = BTreeMap::new();
+    for id in ids {
+        let label = node_name(&[ours, theirs, base], id);
+        let merged = match (base.nodes.get(&id), ours.nodes.get(&id), theirs.nodes.get(&id)) {
+            (Some(b), Some(o), Some(t)) => {
+                let mut merged = o.clone();
+                for aspect in aspects(&[b, o, t]) {
+                    let ours_changed = !aspect_eq(b, o, &aspect);
+                    let theirs_changed = !aspect_eq(b, t, &aspect);
+                    if theirs_changed && !ours_changed {
+                        copy_aspect(&mut merged, t, &aspect);
+                    } else if ours_changed
+                        && theirs_changed
+                        && aspect != Aspect::Position
+                        && !aspect_eq(o, t, &aspect)
+                    {
+                        conflict(
+                            Some(id),
+                            format!(
+                                "\"{}\": {} changed on both sides; kept ours",
+                                label,
+                                aspect.label()
+                            ),
+                        );
+                    }
+                }
+                Some(merged)
+            }
+            (Some(b), Some(o), None) => {
+                if node_changes(b, o).is_empty() {
+                    None
+                } else {
+                    let message =
+                        format!("\"{}\": deleted by theirs, changed by ours; kept", label);
+                    conflict(Some(id), message);
+                    Some(o.clone())
+                }
+            }
+            (Some(b), None, Some(t)) => {
+                if node_changes(b, t).is_empty() {
+                    None
+                } else {
+                    let message =
+                        format!("\"{}\": deleted by ours, changed by theirs; kept", label);
+                    conflict(Some(id), message);
+                    Some(t.clone())
+                }
+            }
+            (None, Some(o), Some(t)) => {
+                if !node_changes(o, t).is_empty() {
+                    let message =
+                        format!("\"{}\": added differently on both sides; kept ours", label);
+                    conflict(Some(id), message);
+                }
+                Some(o.clone())
+            }
+            (None, Some(node), None) | (None, None, Some(node)) => Some(node.clone()),
+            (Some(_), None, None) | (None, None, None) => None,
+        };
+        if let Some(node) = merged {
+            nodes.insert(id, node);
+        }
+    }
+
+    let mut graph = NodeGraph::new(name, ours.metadata.author.clone());
+    graph.id = ours.id;
+    graph.metadata = ours.metadata.clone();
+    for node in nodes.into_values() {
+        graph.add_node(node);
+    }
+
+    // Kept by both, or added by one side; ours first so ours wins an input.
+    // Deleting a node is not a reason to drop wires of a node that was kept.
+    let base_keys: HashSet<_> = base.connections.iter().map(endpoints).collect();
+    let ours_keys: HashSet<_> = ours.connections.iter().map(endpoints).collect();
+    let theirs_keys: HashSet<_> = theirs.connections.iter().map(endpoints).collect();
+    let has_ends = |graph: &NodeGraph, conn: &Connection| {
+        graph.nodes.contains_key(&conn.from_node) && graph.nodes.contains_key(&conn.to_node)
+    };
+    let kept = ours
+        .connections
+        .iter()
+        .filter(|conn| {
+            let key = endpoints(conn);
+            theirs_keys.contains(&key) || !base_keys.contains(&key) || !has_ends(theirs, conn)
+        })
+        .map(|conn| (conn, ours))
+        .chain(
+            theirs
+                .connections
+                .iter()
+                .filter(|conn| {
+                    let key = endpoints(conn);
+                    !ours_keys.contains(&key)
+                        && (!base_keys.contains(&key) || !has_ends(ours, conn))
+                })
+                .map(|conn| (conn, theirs)),
+        );
+    for (conn, source) in kept {
+        if base_keys.contains(&endpoints(conn)) && !has_ends(&graph, conn) {
+            // Went away with a deleted node
+            continue;
+        }
+        if let Err(e) = graph.restore_connection(conn) {
+            conflict(
+                Some(conn.to_node),
+                format!("Connection {} dropped: {}", connection_label(source, conn), e),
+            );
+        }
+    }
+
+    let grant_ids: BTreeSet<Uuid> = base
+        .capability_grants
+        .keys()
+        .chain(ours.capability_grants.keys())
+        .chain(theirs.capability_grants.keys())
+        .copied()
+        .collect();
+    for id in grant_ids {
+        if !graph.nodes.contains_key(&id) {
+            continue;
+        }
+        let (b, o, t) = (
+            base.capability_grants.get(&id),
+            ours.capability_grants.get(&id),
+            theirs.capability_grants.get(&id),
+        );
+        let merged = if grant_set(b) == grant_set(o) {
+            t
+        } else {
+            if grant_set(b) != grant_set(t) && grant_set(o) != grant_set(t) {
+                let message = format!(
+                    "\"{}\": capability grant changed on both sides; kept ours",
+                    node_name(&[&graph], id)
+                );
+                conflict(Some(id), message);
+            }
+            o
+        };
+        if let Some(grant) = merged {
+            graph.grant_capability(grant.clone());
+        }
+    }
+
+    let annotation_ids: BTreeSet<Uuid> = base
+        .annotations
+        .keys()
+        .chain(ours.annotations.keys())
+        .chain(theirs.annotations.keys())
+        .copied()
+        .collect();
+    for id in annotation_ids {
+        let (b, o, t) = (
+            base.annotations.get(&id),
+            ours.annotations.get(&id),
+            theirs.annotations.get(&id),
+        );
+        let merged = if b == o {
+            t
+        } else if b == t || o == t {
+            o
+        } else {
+            match (o, t) {
+                (Some(o), None) => {
+                    let message = format!(
+                        "{}: deleted by theirs, changed by ours; kept",
+                        annotation_name(o)
+                    );
+                    conflict(None, message);
+                    Some(o)
+                }
+                (None, Some(t)) => {
+                    let message = format!(
+                        "{}: deleted by ours, changed by theirs; kept",
+                        annotation_name(t)
+                    );
+                    conflict(None, message);
+                    Some(t)
+                }
+                _ => {
+                    let name = o.map(annotation_name).unwrap_or_default();
+                    conflict(None, format!("{}: changed on both sides; kept ours", name));
+                    o
+                }
+            }
+        };
+        if let Some(annotation) = merged {
+            graph.set_annotation(annotation.clone());
+        }
+    }
+
+    MergeResult { graph, conflicts }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::node::DataType;
+    use crate::graph::test_support::{linked_graph, scale_spec};
+
+    /// Two scale nodes, the first feeding the second's value
+    fn base_graph() -> (NodeGraph, Uuid, Uuid) {
+        let spec = scale_spec(DataType::F32);
+        linked_graph(&spec, &spec)
+    }
+
+    fn set_factor(graph: &mut NodeGraph, node_id: Uuid, factor: f32) {
+        graph.nodes.get_mut(&node_id).unwrap().inputs[1].current_value =
+            Some(NodeValue::F32(factor));
+    }
+
+    #[test]
+    fn test_diff_lists_node_connection_and_grant_changes() {
+        let (old, first_id, second_id) = base_graph();
+        let mut new = old.clone();
+        assert!(old.diff(&new).is_empty());
+
+        new.nodes.get_mut(&first_id).unwrap().position = egui::pos2(10.0, 20.0);
+        set_factor(&mut new, second_id, 2.5);
+        new.connections.clear();
+        let added = scale_spec(DataType::F32).create_node(egui::Pos2::ZERO);
+        let added_id = new.add_node(added);
+        new.grant_capability(CapabilityGrant::new(added_id, CapabilitySet::Full));
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added_nodes.len(), 1);
+        assert!(diff.removed_nodes.is_empty());
+        assert_eq!(diff.removed_connections.len(), 1);
+        assert_eq!(diff.removed_connections[0].label, "Scale.result -> Second.value");
+        assert_eq!(diff.grants.len(), 1);
+        assert_eq!(diff.node(first_id).unwrap().changes.len(), 1);
+        assert_eq!(
+            diff.node(second_id).unwrap().changes,
+            vec![NodeChange::Value {
+                port: "factor".to_string(),
+                from: None,
+                to: Some(NodeValue::F32(2.5)),
+            }]
+        );
+        assert_eq!(diff.change_count(), 5);
+        assert!(diff.to_string().contains("~ node \"Second\": input factor: unset -> 2.50"));
+
+        // Re-creating a wire with a new id is not a change
+        let mut rewired = old.clone();
+        let conn = rewired.connections[0].clone();
+        rewired.connections.clear();
+        rewired
+            .add_connection(conn.from_node, conn.from_port, conn.to_node, conn.to_port)
+            .unwrap();
+        assert!(old.diff(&rewired).is_empty());
+    }
+
+    #[test]
+    fn test_merge_combines_independent_edits() {
+        let (base, first_id, second_id) = base_graph();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+
+        ours.nodes.get_mut(&first_id).unwrap().display_name = "Renamed".to_string();
+        set_factor(&mut theirs, first_id, 4.0);
+        theirs.remove_node(second_id).unwrap();
+        let added_id = ours.add_node(scale_spec(DataType::F32).create_node(egui::Pos2::ZERO));
+
+        let result = merge(&base, &ours, &theirs);
+        assert!(result.is_clean(), "{:?}", result.conflicts);
+        let first = &result.graph.nodes[&first_id];
+        assert_eq!(first.display_name, "Renamed");
+        assert_eq!(first.inputs[1].current_value, Some(NodeValue::F32(4.0)));
+        assert!(!result.graph.nodes.contains_key(&second_id));
+        assert!(result.graph.nodes.contains_key(&added_id));
+        assert!(result.graph.connections.is_empty());
+    }
+
+    #[test]
+    fn test_merge_reports_conflicts_and_keeps_ours() {
+        let (base, first_id, second_id) = base_graph();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+
+        set_factor(&mut ours, first_id, 1.0);
+        set_factor(&mut theirs, first_id, 2.0);
+        // Theirs deletes a node ours edited
+        set_factor(&mut ours, second_id, 3.0);
+        theirs.remove_node(second_id).unwrap();
+
+        let result = merge(&base, &ours, &theirs);
+        assert_eq!(result.conflicts.len(), 2);
+        assert!(result.conflicts.iter().all(|c| c.node_id.is_some()));
+        assert_eq!(
+            result.graph.nodes[&first_id].inputs[1].current_value,
+            Some(NodeValue::F32(1.0))
+        );
+        // The kept node keeps the connection both sides had
+        assert!(result.graph.nodes.contains_key(&second_id));
+        assert_eq!(result.graph.connections.len(), 1);
+    }
+
+    #[test]
+    fn test_annotations_diff_and_merge_whole() {
+        let (mut base, first_id, second_id) = base_graph();
+        let group = Annotation::group("Scales".to_string(), &base, &[first_id, second_id]).unwrap();
+        base.set_annotation(group.clone());
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+
+        let mut collapsed = group.clone();
+        collapsed.kind = AnnotationKind::Group { collapsed: true };
+        ours.set_annotation(collapsed.clone());
+        let note = Annotation::note("Check the factors".to_string(), egui::Pos2::ZERO);
+        theirs.set_annotation(note.clone());
+
+        let diff = base.diff(&ours);
+        assert_eq!(diff.change_count(), 1);
+        assert_eq!(diff.to_string(), "~ group \"Scales\": collapsed\n");
+        assert_eq!(base.diff(&theirs).to_string(), "+ note \"Check the factors\"\n");
+
+        let result = merge(&base, &ours, &theirs);
+        assert!(result.is_clean(), "{:?}", result.conflicts);
+        assert_eq!(result.graph.annotations[&group.id], collapsed);
+        assert_eq!(result.graph.annotations[&note.id], note);
+
+        // Deleting what the other side changed keeps the change
+        theirs.remove_annotation(group.id);
+        let result = merge(&base, &ours, &theirs);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.graph.annotations[&group.id], collapsed);
+    }
+}
@@ -0,0 +1,507 @@
+//! Reusable graph functions
+//!
+//! A graph function is a saved subgraph, builtin and WASM nodes alike, stored
+//! as a file in a library directory. Nodes refer to a function by name
+//! (`function:<name>`) rather than embedding a copy, so saving a new revision
+//! updates every graph that uses it.
+
+use crate::graph::connection::Connection;
+use crate::graph::graph::NodeGraph;
+use crate::graph::node::{ComponentSpec, ComponentType, GraphNode, PortMapping};
+use crate::graph::serialization::atomic_write;
+use crate::runtime::capabilities::CapabilityGrant;
+use crate::FunctionError;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+/// Component id prefix of graph function nodes
+pub const FUNCTION_ID_PREFIX: &str = "function:";
+
+/// File extension of saved graph functions
+pub const FUNCTION_EXTENSION: &str = "wff";
+
+/// Palette category for graph functions
+pub const FUNCTION_CATEGORY: &str = "Functions";
+
+/// Current graph function file format (2: nodes record component pins)
+const FUNCTION_FORMAT_VERSION: u32 = 2;
+
+/// Library shared between the app (which saves functions) and execution threads
+pub type SharedFunctionLibrary = Arc<RwLock<GraphFunctionLibrary>>;
+
+/// Component id of the function called `name`
+pub fn function_id(name: &str) -> String {
+    format!("{}{}", FUNCTION_ID_PREFIX, name)
+}
+
+/// Function name referenced by a component id, if it is a graph function
+pub fn function_name(component_id: &str) -> Option<&str> {
+    component_id.strip_prefix(FUNCTION_ID_PREFIX)
+}
+
+/// Check that `name` is usable as both a file name and a component id
+pub fn validate_name(name: &str) -> Result<(), FunctionError> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(FunctionError::InvalidName(name.to_string()))
+    }
+}
+
+/// Node id of an internal node within one function instance
+///
+/// Derived from the instance node so that two instances of the same function
+/// never share stateful component instances or key-value storage.
+pub fn instance_node_id(instance_id: Uuid, internal_id: Uuid) -> Uuid {
+    Uuid::from_u128(instance_id.as_u128() ^ internal_id.as_u128())
+}
+
+/// A named, versioned subgraph with ports derived from its boundary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphFunction {
+    /// File format version
+    pub format_version: u32,
+    /// Function name, unique within the library
+    pub name: String,
+    /// Description shown in the palette
+    pub description: String,
+    /// Revision, bumped every time the function is saved
+    pub revision: u32,
+    /// When this revision was saved (RFC 3339)
+    pub saved_at: String,
+    /// Internal nodes (BTreeMap for deterministic serialization)
+    pub nodes: BTreeMap<Uuid, GraphNode>,
+    /// Connections between internal nodes
+    pub connections: Vec<Connection>,
+    /// Exposed inputs mapped to internal node inputs
+    pub inputs: BTreeMap<String, PortMapping>,
+    /// Exposed outputs mapped to internal node outputs
+    pub outputs: BTreeMap<String, PortMapping>,
+    /// Capability grants approved for internal nodes
+    pub grants: BTreeMap<Uuid, CapabilityGrant>,
+}
+
+/// Runnable copy of a function for one instance node
+pub struct FunctionInstance {
+    /// Internal graph with instance-specific node ids
+    pub graph: NodeGraph,
+    /// Exposed inputs, remapped onto `graph`
+    pub inputs: BTreeMap<String, PortMapping>,
+    /// Exposed outputs, remapped onto `graph`
+    pub outputs: BTreeMap<String, PortMapping>,
+}
+
+impl GraphFunction {
+    /// Capture `selected` nodes of `graph` as a function
+    ///
+    /// Connections and capability grants internal to the selection are kept;
+    /// `inputs` and `outputs` describe the boundary ports.
+    pub fn from_selection(
+        name: String,
+        description: String,
+        graph: &NodeGraph,
+        selected: &[Uuid],
+        inputs: BTreeMap<String, PortMapping>,
+        outputs: BTreeMap<String, PortMapping>,
+    ) -> Self {
+        let selected_set: HashSet<Uuid> = selected.iter().copied().collect();
+
+        let nodes = selected
+            .iter()
+            .filter_map(|id| graph.nodes.get(id).map(|node| (*id, node.clone())))
+            .map(|(id, mut node)| {
+                node.selected = false;
+                (id, node)
+            })
+            .collect();
+
+        let connections = graph
+            .connections
+            .iter()
+            .filter(|c| selected_set.contains(&c.from_node) && selected_set.contains(&c.to_node))
+            .cloned()
+            .collect();
+
+        let grants = graph
+            .capability_grants
+            .iter()
+            .filter(|(id, _)| selected_set.contains(id))
+            .map(|(id, grant)| (*id, grant.clone()))
+            .collect();
+
+        Self {
+            format_version: FUNCTION_FORMAT_VERSION,
+            name,
+            description,
+            revision: 1,
+            saved_at: chrono::Utc::now().to_rfc3339(),
+            nodes,
+            connections,
+            inputs,
+            outputs,
+            grants,
+        }
+    }
+
+    /// Component id of this function
+    pub fn component_id(&self) -> String {
+        function_id(&self.name)
+    }
+
+    /// Names of the functions this one calls directly
+    pub fn called_functions(&self) -> impl Iterator<Item = &str> {
+        self.nodes
+            .values()
+            .filter_map(|node| function_name(&node.component_id))
+    }
+
+    /// Palette entry for this function, loaded from `path`
+    pub fn to_component_spec(&self, path: PathBuf) -> ComponentSpec {
+        let mut spec = ComponentSpec::new_user_defined(
+            self.component_id(),
+            self.name.clone(),
+            self.description.clone(),
+            Some(FUNCTION_CATEGORY.to_string()),
+            path.clone(),
+        );
+        spec.component_type = ComponentType::GraphFunction(path);
+        spec.version = format!("{}.0.0", self.revision);
+
+        for (external_name, mapping) in &self.inputs {
+            let internal_port = self.nodes.get(&mapping.internal_node_id).and_then(|node| {
+                node.inputs
+                    .iter()
+                    .find(|p| p.name == mapping.internal_port_name)
+            });
+            spec = match internal_port {
+                Some(port) if port.optional => spec.with_optional_input(
+                    external_name.clone(),
+                    mapping.port_type.clone(),
+                    external_name.clone(),
+                    port.default_value.clone(),
+                ),
+                _ => spec.with_input(
+                    external_name.clone(),
+                    mapping.port_type.clone(),
+                    external_name.clone(),
+                ),
+            };
+        }
+
+        for (external_name, mapping) in &self.outputs {
+            spec = spec.with_output(
+                external_name.clone(),
+                mapping.port_type.clone(),
+                external_name.clone(),
+            );
+        }
+
+        spec
+    }
+
+    /// Build the internal graph executed for the instance node `instance_id`
+    ///
+    /// The graph takes the instance id, so internal nodes keep their key-value
+    /// storage across runs of the same instance.
+    pub fn instantiate(&self, instance_id: Uuid) -> FunctionInstance {
+        let mut graph = NodeGraph::new(self.name.clone(), "Graph Function".to_string());
+        graph.id = instance_id;
+
+        for (internal_id, node) in &self.nodes {
+            let mut node = node.clone();
+            node.id = instance_node_id(instance_id, *internal_id);
+            graph.add_node(node);
+        }
+
+        for connection in &self.connections {
+            let _ = graph.add_connection(
+                instance_node_id(instance_id, connection.from_node),
+                connection.from_port,
+                instance_node_id(instance_id, connection.to_node),
+                connection.to_port,
+            );
+        }
+
+        for (internal_id, grant) in &self.grants {
+            let mut grant = grant.clone();
+            grant.node_id = instance_node_id(instance_id, *internal_id);
+            graph.grant_capability(grant);
+        }
+
+        let remap = |mappings: &BTreeMap<String, PortMapping>| {
+            mappings
+                .iter()
+                .map(|(name, mapping)| {
+                    let mut mapping = mapping.clone();
+                    mapping.internal_node_id = instance_node_id(instance_id, mapping.internal_node_id);
+                    (name.clone(), mapping)
+                })
+                .collect()
+        };
+
+        FunctionInstance {
+            inputs: remap(&self.inputs),
+            outputs: remap(&self.outputs),
+            graph,
+        }
+    }
+}
+
+/// Graph functions saved in a library directory
+#[derive(Debug)]
+pub struct GraphFunctionLibrary {
+    root: PathBuf,
+    functions: BTreeMap<String, GraphFunction>,
+}
+
+impl GraphFunctionLibrary {
+    /// Default library location in the user's data directory
+    pub fn default_root() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("wasmflow")
+            .join("functions")
+    }
+
+    /// Create an empty library rooted at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            functions: BTreeMap::new(),
+        }
+    }
+
+    /// Load every function file under `root`
+    ///
+    /// A missing directory yields an empty library. Unreadable files are
+    /// logged and skipped so one bad file doesn't hide the rest.
+    pub fn load(root: impl Into<PathBuf>) -> Result<Self, FunctionError> {
+        let mut library = Self::new(root);
+        if !library.root.is_dir() {
+            return Ok(library);
+        }
+
+        for entry in std::fs::read_dir(&library.root)? {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some(FUNCTION_EXTENSION) {
+                continue;
+            }
+            match Self::read_file(&path) {
+                Ok(function) => {
+                    library.functions.insert(function.name.clone(), function);
+                }
+                Err(e) => log::warn!("Skipping graph function {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(library)
+    }
+
+    /// Library directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// File holding the function called `name`
+    pub fn path_for(&self, name: &str) -> PathBuf {
+        self.root.join(format!("{}.{}", name, FUNCTION_EXTENSION))
+    }
+
+    /// Look up a function by name
+    pub fn get(&self, name: &str) -> Option<&GraphFunction> {
+        self.functions.get(name)
+    }
+
+    /// All functions, sorted by name
+    pub fn functions(&self) -> impl Iterator<Item = &GraphFunction> {
+        self.functions.values()
+    }
+
+    /// Palette entries for every function in the library
+    pub fn component_specs(&self) -> Vec<ComponentSpec> {
+        self.functions
+            .values()
+            .map(|function| function.to_component_spec(self.path_for(&function.name)))
+            .collect()
+    }
+
+    /// Save `function`, replacing any previous revision with the same name
+    ///
+    /// The revision continues from the one being replaced.
+    pub fn save(&mut self, mut function: GraphFunction) -> Result<&GraphFunction, FunctionError> {
+        validate_name(&function.name)?;
+        if self.calls(&function, &function.name) {
+            return Err(FunctionError::Recursive(function.name));
+        }
+
+        function.format_version = FUNCTION_FORMAT_VERSION;
+        function.revision = self
+            .functions
+            .get(&function.name)
+            .map(|previous| previous.revision + 1)
+            .unwrap_or(1);
+        function.saved_at = chrono::Utc::now().to_rfc3339();
+
+        std::fs::create_dir_all(&self.root)?;
+        let path = self.path_for(&function.name);
+        let bytes = bincode::serialize(&function).map_err(|e| FunctionError::Corrupt {
+            path: path.clone(),
+            reason: e.to_string(),
+        })?;
+        atomic_write(&path, &bytes)?;
+
+        let name = function.name.clone();
+        self.functions.insert(name.clone(), function);
+        Ok(&self.functions[&name])
+    }
+
+    /// Whether `function` calls `name`, directly or through other functions
+    fn calls(&self, function: &GraphFunction, name: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending: Vec<&GraphFunction> = vec![function];
+        while let Some(current) = pending.pop() {
+            for callee in current.called_functions() {
+                if callee == name {
+                    return true;
+                }
+                if visited.insert(callee.to_string()) {
+                    if let Some(next) = self.functions.get(callee) {
+                        pending.push(next);
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    fn read_file(path: &Path) -> Result<GraphFunction, FunctionError> {
+        let bytes = std::fs::read(path)?;
+        // The format version leads the file, so it decodes on its own
+        let version: u32 = bincode::deserialize(&bytes).map_err(|e| FunctionError::Corrupt {
+            path: path.to_path_buf(),
+            reason: e.to_string(),
+        })?;
+        if version != FUNCTION_FORMAT_VERSION {
+            return Err(FunctionError::Corrupt {
+                path: path.to_path_buf(),
+                reason: format!("unsupported function version {}", version),
+            });
+        }
+        let function: GraphFunction =
+            bincode::deserialize(&bytes).map_err(|e| FunctionError::Corrupt {
+                path: path.to_path_buf(),
+                reason: e.to_string(),
+            })?;
+        validate_name(&function.name)?;
+        Ok(function)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::node::DataType;
+    use crate::graph::test_support::{linked_graph, scale_spec};
+
+    fn sample_function(name: &str) -> GraphFunction {
+        let spec = scale_spec(DataType::F32);
+        let (graph, first_id, second_id) = linked_graph(&spec, &spec);
+        let mut inputs = BTreeMap::new();
+        inputs.insert(
+            "Scale.value".to_string(),
+            PortMapping {
+                external_name: "Scale.value".to_string(),
+                internal_node_id: first_id,
+                internal_port_name: "value".to_string(),
+                port_type: DataType::F32,
+            },
+        );
+        let mut outputs = BTreeMap::new();
+        outputs.insert(
+            "Second.result".to_string(),
+            PortMapping {
+                external_name: "Second.result".to_string(),
+                internal_node_id: second_id,
+                internal_port_name: "result".to_string(),
+                port_type: DataType::F32,
+            },
+        );
+        GraphFunction::from_selection(
+            name.to_string(),
+            "Scales twice".to_string(),
+            &graph,
+            &[first_id, second_id],
+            inputs,
+            outputs,
+        )
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("add-then-double_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../escape").is_err());
+        assert!(validate_name("has space").is_err());
+    }
+
+    #[test]
+    fn test_save_bumps_revision_and_reloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut library = GraphFunctionLibrary::new(dir.path());
+
+        assert_eq!(library.save(sample_function("scale")).unwrap().revision, 1);
+        assert_eq!(library.save(sample_function("scale")).unwrap().revision, 2);
+
+        let reloaded = GraphFunctionLibrary::load(dir.path()).unwrap();
+        let function = reloaded.get("scale").unwrap();
+        assert_eq!(function.revision, 2);
+        assert_eq!(function.nodes.len(), 2);
+        assert_eq!(function.connections.len(), 1);
+
+        let spec = &reloaded.component_specs()[0];
+        assert_eq!(spec.id, "function:scale");
+        assert_eq!(spec.version, "2.0.0");
+        assert_eq!(spec.input_spec[0].name, "Scale.value");
+        assert_eq!(spec.output_spec[0].name, "Second.result");
+    }
+
+    #[test]
+    fn test_save_rejects_recursion() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut library = GraphFunctionLibrary::new(dir.path());
+        library.save(sample_function("inner")).unwrap();
+
+        let mut outer = sample_function("outer");
+        let call = GraphNode::new(function_id("inner"), "inner".to_string(), egui::Pos2::ZERO);
+        outer.nodes.insert(call.id, call);
+        library.save(outer).unwrap();
+
+        // Re-saving `inner` so that it calls `outer` would loop forever
+        let mut inner = sample_function("inner");
+        let call = GraphNode::new(function_id("outer"), "outer".to_string(), egui::Pos2::ZERO);
+        inner.nodes.insert(call.id, call);
+        assert!(matches!(library.save(inner), Err(FunctionError::Recursive(_))));
+    }
+
+    #[test]
+    fn test_instances_get_distinct_node_ids() {
+        let function = sample_function("scale");
+        let first = function.instantiate(Uuid::new_v4());
+        let second = function.instantiate(Uuid::new_v4());
+
+        assert_eq!(first.graph.connections.len(), 1);
+        let first_ids: HashSet<Uuid> = first.graph.nodes.keys().copied().collect();
+        assert!(second.graph.nodes.keys().all(|id| !first_ids.contains(id)));
+
+        let mapping = &first.inputs["Scale.value"];
+        assert!(first.graph.nodes.contains_key(&mapping.internal_node_id));
+    }
+}
@@ -1,5 +1,6 @@
 //! Node graph data structure and operations
 
+use crate::graph::annotation::Annotation;
 use crate::graph::connection::Connection;
 use crate::graph::node::{DataType, GraphNode, PortDirection};
 use crate::runtime::capabilities::CapabilityGrant;
@@ -54,6 +55,8 @@ pub struct NodeGraph {
     /// T074: Capability grants for nodes (persisted with graph)
     /// Maps node_id to approved capability grant
     pub capability_grants: BTreeMap<Uuid, CapabilityGrant>,
+    /// Frames, groups and notes drawn behind the nodes (never executed)
+    pub annotations: BTreeMap<Uuid, Annotation>,
     /// Cached topological sort result (invalidated on structural changes)
     #[serde(skip)]
     execution_order_cache: Option<Vec<Uuid>>,
@@ -68,8 +71,9 @@ impl NodeGraph {
             nodes: BTreeMap::new(),
             connections: Vec::new(),
             metadata: GraphMetadata::new(author),
-            version: 1,
+            version: crate::graph::serialization::FORMAT_VERSION,
             capability_grants: BTreeMap::new(),
+            annotations: BTreeMap::new(),
             execution_order_cache: None,
         }
     }
@@ -119,6 +123,31 @@ impl NodeGraph {
         }
     }
 
+    /// Replace a node with a new version of itself, keeping its id
+    ///
+    /// Every connection to the node must still have its port on the new
+    /// version; remove the others first. Returns the previous version.
+    pub fn replace_node(&mut self, node: GraphNode) -> Result<GraphNode, GraphError> {
+        if !self.nodes.contains_key(&node.id) {
+            return Err(GraphError::InvalidConnection(format!("Node {} not found", node.id)));
+        }
+        let ports_exist = self.connections.iter().all(|conn| {
+            (conn.to_node != node.id || node.inputs.iter().any(|p| p.id == conn.to_port))
+                && (conn.from_node != node.id
+                    || node.outputs.iter().any(|p| p.id == conn.from_port))
+        });
+        if !ports_exist {
+            return Err(GraphError::InvalidConnection(
+                "Replacement node is missing a connected port".to_string(),
+            ));
+        }
+
+        let previous = self.nodes.insert(node.id, node).expect("checked above");
+        self.invalidate_cache();
+        self.metadata.touch();
+        Ok(previous)
+    }
+
     /// Get a mutable node by ID
     pub fn get_node_mut(&mut self, node_id: Uuid) -> Option<&mut GraphNode> {
         self.nodes.get_mut(&node_id)
@@ -223,6 +252,20 @@ impl NodeGraph {
         Ok(connection)
     }
 
+    /// Re-add a previously removed connection, keeping its original ID
+    pub fn restore_connection(&mut self, connection: &Connection) -> Result<Uuid, GraphError> {
+        let new_id = self.add_connection(
+            connection.from_node,
+            connection.from_port,
+            connection.to_node,
+            connection.to_port,
+        )?;
+        if let Some(restored) = self.connections.iter_mut().find(|conn| conn.id == new_id) {
+            restored.id = connection.id;
+        }
+        Ok(connection.id)
+    }
+
     /// Check if data types are compatible for connection
     pub fn types_compatible(from: &DataType, to: &DataType) -> bool {
         // Any type matches everything
@@ -240,6 +283,16 @@ impl NodeGraph {
             return Self::types_compatible(from_inner, to_inner);
         }
 
+        // Structural record compatibility (width subtyping): the source must
+        // provide every field the target declares, extra fields are allowed
+        if let (DataType::Record(from_fields), DataType::Record(to_fields)) = (from, to) {
+            return to_fields.iter().all(|(name, to_type)| {
+                from_fields.iter().any(|(from_name, from_type)| {
+                    from_name == name && Self::types_compatible(from_type, to_type)
+                })
+            });
+        }
+
         false
     }
 
@@ -469,6 +522,35 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_record_width_subtyping() {
+        let point = DataType::Record(vec![
+            ("x".to_string(), DataType::F32),
+            ("y".to_string(), DataType::F32),
+        ]);
+        let point_3d = DataType::Record(vec![
+            ("x".to_string(), DataType::F32),
+            ("y".to_string(), DataType::F32),
+            ("z".to_string(), DataType::F32),
+        ]);
+        let bad_point = DataType::Record(vec![
+            ("x".to_string(), DataType::String),
+            ("y".to_string(), DataType::F32),
+        ]);
+
+        // Extra fields on the source are allowed
+        assert!(NodeGraph::types_compatible(&point_3d, &point));
+        // Missing fields are not
+        assert!(!NodeGraph::types_compatible(&point, &point_3d));
+        // Field types must be compatible
+        assert!(!NodeGraph::types_compatible(&bad_point, &point));
+        // Structural rules apply inside lists
+        assert!(NodeGraph::types_compatible(
+            &DataType::List(Box::new(point_3d)),
+            &DataType::List(Box::new(point))
+        ));
+    }
+
     #[test]
     fn test_cycle_detection() {
         let mut graph = NodeGraph::new("Test".to_string(), "Author".to_string());
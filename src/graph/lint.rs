@@ -0,0 +1,413 @@
+//! Graph lint pass
+//!
+//! `NodeGraph::validate` rejects graphs that cannot run. Linting looks for
+//! graphs that run but probably not as intended: inputs without a value,
+//! results nobody uses, wiring broken by component updates, leftover grants,
+//! ambiguous node names and continuous nodes that can never start.
+
+use crate::graph::graph::NodeGraph;
+use crate::graph::node::{ComponentRegistry, ComponentType, DataType, GraphNode, PortDirection};
+use crate::runtime::capabilities::CapabilitySet;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintSeverity {
+    /// Worth a look, common in finished graphs
+    Info,
+    /// Probably a mistake; fails a headless check
+    Warning,
+}
+
+/// Lint rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintRule {
+    /// Required input with no connection and no value
+    MissingInput,
+    /// Node whose outputs feed nothing
+    DeadOutputs,
+    /// Connection whose port types no longer match
+    TypeMismatch,
+    /// Capability grant that is no longer requested
+    StaleGrant,
+    /// Several nodes share a display name
+    DuplicateName,
+    /// Continuous node that can never be started
+    UnreachableContinuous,
+}
+
+impl LintRule {
+    /// Short identifier used in headless output
+    pub fn name(self) -> &'static str {
+        match self {
+            LintRule::MissingInput => "missing-input",
+            LintRule::DeadOutputs => "dead-outputs",
+            LintRule::TypeMismatch => "type-mismatch",
+            LintRule::StaleGrant => "stale-grant",
+            LintRule::DuplicateName => "duplicate-name",
+            LintRule::UnreachableContinuous => "unreachable-continuous",
+        }
+    }
+
+    /// Severity of findings from this rule
+    pub fn severity(self) -> LintSeverity {
+        match self {
+            // Terminal nodes show their results on the canvas
+            LintRule::DeadOutputs => LintSeverity::Info,
+            _ => LintSeverity::Warning,
+        }
+    }
+}
+
+/// One problem found by the lint pass
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    /// Node the finding is about (None if it no longer exists)
+    pub node_id: Option<Uuid>,
+    pub message: String,
+}
+
+impl LintFinding {
+    /// Severity of this finding
+    pub fn severity(&self) -> LintSeverity {
+        self.rule.severity()
+    }
+}
+
+/// Result of linting a graph, warnings first
+#[derive(Debug, Clone, Default)]
+pub struct LintReport {
+    pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+    /// Whether nothing was found
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Number of warning-level findings
+    pub fn warning_count(&self) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity() == LintSeverity::Warning)
+            .count()
+    }
+
+    /// Findings about one node
+    pub fn for_node(&self, node_id: Uuid) -> impl Iterator<Item = &LintFinding> {
+        self.findings
+            .iter()
+            .filter(move |f| f.node_id == Some(node_id))
+    }
+
+    fn push(&mut self, rule: LintRule, node_id: Option<Uuid>, message: String) {
+        self.findings.push(LintFinding {
+            rule,
+            node_id,
+            message,
+        });
+    }
+}
+
+impl NodeGraph {
+    /// Run every lint rule
+    ///
+    /// With a `registry`, ports and capabilities are also checked against the
+    /// currently loaded component specs, which catches changes made by
+    /// component upgrades. Components missing from the registry are only
+    /// checked against what the graph stores.
+    pub fn lint(&self, registry: Option<&ComponentRegistry>) -> LintReport {
+        let mut report = LintReport::default();
+
+        self.lint_missing_inputs(&mut report);
+        self.lint_dead_outputs(&mut report);
+        self.lint_type_mismatches(registry, &mut report);
+        self.lint_stale_grants(registry, &mut report);
+        self.lint_duplicate_names(&mut report);
+        self.lint_unreachable_continuous(registry, &mut report);
+
+        // Stable sort keeps rule order within each severity
+        report.findings.sort_by_key(|f| std::cmp::Reverse(f.severity()));
+        report
+    }
+
+    fn lint_missing_inputs(&self, report: &mut LintReport) {
+        for node in self.nodes.values() {
+            for input in node.inputs.iter().filter(|p| !p.optional) {
+                let connected = self
+                    .connections
+                    .iter()
+                    .any(|c| c.to_node == node.id && c.to_port == input.id);
+                if !connected && input.current_value.is_none() && input.default_value.is_none() {
+                    report.push(
+                        LintRule::MissingInput,
+                        Some(node.id),
+                        format!(
+                            "'{}' input '{}' is required but has no connection or value",
+                            node.display_name, input.name
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn lint_dead_outputs(&self, report: &mut LintReport) {
+        for node in self.nodes.values() {
+            let used = self.connections.iter().any(|c| c.from_node == node.id);
+            if !node.outputs.is_empty() && !used {
+                report.push(
+                    LintRule::DeadOutputs,
+                    Some(node.id),
+                    format!("'{}' outputs are not connected to anything", node.display_name),
+                );
+            }
+        }
+    }
+
+    fn lint_type_mismatches(&self, registry: Option<&ComponentRegistry>, report: &mut LintReport) {
+        for connection in &self.connections {
+            let (Some(from_node), Some(to_node)) = (
+                self.nodes.get(&connection.from_node),
+                self.nodes.get(&connection.to_node),
+            ) else {
+                continue; // Dangling connections are reported by validate()
+            };
+            let (Some(from_port), Some(to_port)) = (
+                from_node.outputs.iter().find(|p| p.id == connection.from_port),
+                to_node.inputs.iter().find(|p| p.id == connection.to_port),
+            ) else {
+                continue;
+            };
+
+            let from_type =
+                current_port_type(from_node, &from_port.name, PortDirection::Output, registry)
+                    .unwrap_or_else(|| from_port.data_type.clone());
+            let to_type = current_port_type(to_node, &to_port.name, PortDirection::Input, registry)
+                .unwrap_or_else(|| to_port.data_type.clone());
+
+            if !Self::types_compatible(&from_type, &to_type) {
+                report.push(
+                    LintRule::TypeMismatch,
+                    Some(to_node.id),
+                    format!(
+                        "'{}'.{} ({}) no longer matches '{}'.{} ({})",
+                        from_node.display_name,
+                        from_port.name,
+                        from_type.name(),
+                        to_node.display_name,
+                        to_port.name,
+                        to_type.name()
+                    ),
+                );
+            }
+        }
+    }
+
+    fn lint_stale_grants(&self, registry: Option<&ComponentRegistry>, report: &mut LintReport) {
+        for (node_id, grant) in &self.capability_grants {
+            let Some(node) = self.nodes.get(node_id) else {
+                report.push(
+                    LintRule::StaleGrant,
+                    None,
+                    format!("Permission grant for removed node {}", node_id),
+                );
+                continue;
+            };
+
+            let requests_nothing = registry
+                .and_then(|r| r.get_by_id(&node.component_id))
+                .map(|spec| spec.required_capabilities.is_empty())
+                .unwrap_or(false);
+            if requests_nothing && grant.capability_set != CapabilitySet::None {
+                report.push(
+                    LintRule::StaleGrant,
+                    Some(node.id),
+                    format!(
+                        "'{}' is granted {} but its component no longer requests any",
+                        node.display_name,
+                        grant.capability_set.description()
+                    ),
+                );
+            }
+        }
+    }
+
+    fn lint_duplicate_names(&self, report: &mut LintReport) {
+        let mut by_name: BTreeMap<&str, Vec<Uuid>> = BTreeMap::new();
+        for node in self.nodes.values() {
+            by_name.entry(node.display_name.as_str()).or_default().push(node.id);
+        }
+
+        for (name, ids) in by_name.into_iter().filter(|(_, ids)| ids.len() > 1) {
+            for id in &ids {
+                report.push(
+                    LintRule::DuplicateName,
+                    Some(*id),
+                    format!("{} nodes are named '{}'", ids.len(), name),
+                );
+            }
+        }
+    }
+
+    fn lint_unreachable_continuous(
+        &self,
+        registry: Option<&ComponentRegistry>,
+        report: &mut LintReport,
+    ) {
+        for node in self.nodes.values() {
+            let Some(config) = &node.continuous_config else {
+                continue;
+            };
+
+            // Builtin continuous nodes are recognised by id, so only loaded
+            // components say whether they still export the interface
+            let component_supports = registry
+                .and_then(|r| r.get_by_id(&node.component_id))
+                .filter(|spec| matches!(spec.component_type, ComponentType::UserDefined(_)))
+                .map(|spec| spec.supports_continuous)
+                .unwrap_or(true);
+
+            let reason = if !config.supports_continuous || !component_supports {
+                config
+                    .enabled
+                    .then_some("its component does not support continuous execution")
+            } else if !config.enabled {
+                Some("its play/stop controls are disabled and Run does not start it")
+            } else {
+                None
+            };
+
+            if let Some(reason) = reason {
+                report.push(
+                    LintRule::UnreachableContinuous,
+                    Some(node.id),
+                    format!("'{}' never runs continuously: {}", node.display_name, reason),
+                );
+            }
+        }
+    }
+}
+
+/// Type the loaded component currently declares for a port, if known
+fn current_port_type(
+    node: &GraphNode,
+    port_name: &str,
+    direction: PortDirection,
+    registry: Option<&ComponentRegistry>,
+) -> Option<DataType> {
+    let spec = registry?.get_by_id(&node.component_id)?;
+    let ports = match direction {
+        PortDirection::Input => &spec.input_spec,
+        PortDirection::Output => &spec.output_spec,
+    };
+    ports
+        .iter()
+        .find(|p| p.name == port_name)
+        .map(|p| p.data_type.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::node::NodeValue;
+    use crate::graph::test_support::{linked_graph, scale_spec};
+    use crate::runtime::capabilities::CapabilityGrant;
+
+    fn rules(report: &LintReport) -> Vec<LintRule> {
+        report.findings.iter().map(|f| f.rule).collect()
+    }
+
+    #[test]
+    fn test_missing_inputs_dead_outputs_and_duplicates() {
+        let mut graph = NodeGraph::new("Test".to_string(), "Author".to_string());
+        let spec = scale_spec(DataType::F32);
+        let mut first = spec.create_node(egui::Pos2::ZERO);
+        first.inputs[0].current_value = None;
+        first.inputs[1].current_value = Some(NodeValue::F32(2.0));
+        let first_id = first.id;
+        let mut second = spec.create_node(egui::Pos2::ZERO);
+        second.inputs[0].current_value = Some(NodeValue::F32(1.0));
+        second.inputs[1].current_value = Some(NodeValue::F32(2.0));
+        let (second_id, second_out) = (second.id, second.outputs[0].id);
+        graph.add_node(first);
+        graph.add_node(second);
+
+        let report = graph.lint(None);
+        let missing = report.for_node(first_id).filter(|f| f.rule == LintRule::MissingInput);
+        assert_eq!(missing.count(), 1);
+        assert!(report.for_node(second_id).all(|f| f.rule != LintRule::MissingInput));
+        assert_eq!(rules(&report).iter().filter(|r| **r == LintRule::DuplicateName).count(), 2);
+        assert_eq!(rules(&report).iter().filter(|r| **r == LintRule::DeadOutputs).count(), 2);
+        // Warnings come before info
+        assert_eq!(report.findings.last().unwrap().severity(), LintSeverity::Info);
+        assert_eq!(report.warning_count(), 3);
+
+        // Wiring fills the input and uses the output
+        let in_port = graph.nodes[&first_id].inputs[0].id;
+        graph.add_connection(second_id, second_out, first_id, in_port).unwrap();
+        let report = graph.lint(None);
+        assert!(!rules(&report).contains(&LintRule::MissingInput));
+        assert!(report.for_node(second_id).all(|f| f.rule != LintRule::DeadOutputs));
+    }
+
+    #[test]
+    fn test_upgraded_component_type_mismatch_and_stale_grant() {
+        let spec = scale_spec(DataType::F32);
+        let (mut graph, _, target_id) = linked_graph(&spec, &spec);
+        graph.grant_capability(CapabilityGrant::new(target_id, CapabilitySet::Full));
+
+        // Without a registry nothing looks wrong
+        let report = graph.lint(None);
+        assert!(!rules(&report).contains(&LintRule::TypeMismatch));
+        assert!(!rules(&report).contains(&LintRule::StaleGrant));
+
+        // The loaded component now takes a string and requests nothing
+        let mut registry = ComponentRegistry::new();
+        registry.register_builtin(scale_spec(DataType::String));
+        let report = graph.lint(Some(&registry));
+        let mismatch = report
+            .findings
+            .iter()
+            .find(|f| f.rule == LintRule::TypeMismatch)
+            .unwrap();
+        assert_eq!(mismatch.node_id, Some(target_id));
+        assert!(mismatch.message.contains("string"));
+        assert!(rules(&report).contains(&LintRule::StaleGrant));
+
+        graph.nodes.remove(&target_id);
+        graph.connections.clear();
+        let report = graph.lint(None);
+        let stale = report.findings.iter().find(|f| f.rule == LintRule::StaleGrant).unwrap();
+        assert_eq!(stale.node_id, None);
+    }
+
+    #[test]
+    fn test_continuous_nodes_that_never_start() {
+        let mut graph = NodeGraph::new("Test".to_string(), "Author".to_string());
+        let mut timer = GraphNode::new(
+            "builtin:continuous:timer".to_string(),
+            "Timer".to_string(),
+            egui::Pos2::ZERO,
+        );
+        timer.continuous_config = Some(crate::graph::node::ContinuousNodeConfig {
+            supports_continuous: true,
+            enabled: true,
+            ..Default::default()
+        });
+        let timer_id = timer.id;
+        graph.add_node(timer);
+        assert!(!rules(&graph.lint(None)).contains(&LintRule::UnreachableContinuous));
+
+        let config = graph.nodes.get_mut(&timer_id).unwrap().continuous_config.as_mut().unwrap();
+        config.enabled = false;
+        let report = graph.lint(None);
+        assert_eq!(
+            report.for_node(timer_id).filter(|f| f.rule == LintRule::UnreachableContinuous).count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,618 @@
+//! Graph files written by earlier format versions
+//!
+//! Version 1 is the format of the first release. Its structs are frozen in
+//! `v1` and converted to the current model when a file is loaded, and what
+//! changed is recorded in a `MigrationReport`. Saving always writes
+//! `FORMAT_VERSION`. Versions between 1 and the current one were only ever
+//! written by development builds and are rejected.
+
+use crate::graph::graph::NodeGraph;
+use crate::graph::node::ExecutionState;
+use crate::graph::serialization::{GraphSaveFormat, FORMAT_VERSION, MAGIC_BYTES};
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+/// What to do when a file's stored checksum does not match its contents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumPolicy {
+    /// Refuse to load the file
+    Verify,
+    /// Load it anyway and list the mismatch in the report's warnings
+    Ignore,
+}
+
+/// What happened while bringing a file up to the current format
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MigrationReport {
+    /// Format version declared by the file
+    pub source_version: u32,
+    /// Application version that wrote the file, when the format records it
+    pub saved_by: Option<String>,
+    /// Whether the file used the legacy JSON encoding
+    pub legacy_json: bool,
+    /// Migration steps that ran, oldest first
+    pub steps: Vec<MigrationStep>,
+    /// Problems found that did not stop the file from loading
+    pub warnings: Vec<String>,
+}
+
+/// One upgrade from an older format version
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStep {
+    pub from: u32,
+    pub to: u32,
+    /// Human-readable description of each change made
+    pub changes: Vec<String>,
+}
+
+impl MigrationReport {
+    /// Whether the file was upgraded from an older version or encoding
+    pub fn migrated(&self) -> bool {
+        !self.steps.is_empty() || self.legacy_json
+    }
+
+    /// Whether there is anything worth telling the user
+    pub fn is_empty(&self) -> bool {
+        !self.migrated() && self.warnings.is_empty()
+    }
+
+    /// Multi-line summary for display
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        if self.migrated() {
+            let saved_by = self
+                .saved_by
+                .as_deref()
+                .map(|version| format!(" by WasmFlow {}", version))
+                .unwrap_or_default();
+            lines.push(format!(
+                "Upgraded from format v{}{} to v{}.",
+                self.source_version, saved_by, FORMAT_VERSION
+            ));
+        }
+        if self.legacy_json {
+            lines.push("• Converted from the legacy JSON encoding".to_string());
+        }
+        for step in &self.steps {
+            if step.changes.is_empty() {
+                lines.push(format!("• v{} → v{}: no changes needed", step.from, step.to));
+            }
+            for change in &step.changes {
+                lines.push(format!("• v{} → v{}: {}", step.from, step.to, change));
+            }
+        }
+        for warning in &self.warnings {
+            lines.push(format!("⚠ {}", warning));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Leading fields shared by every format version
+#[derive(Deserialize)]
+struct FormatHeader {
+    magic: Vec<u8>,
+    version: u32,
+}
+
+/// Byte encoding of a save file
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Bincode,
+    /// Written by early builds before files switched to bincode
+    Json,
+}
+
+impl Encoding {
+    fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T> {
+        match self {
+            Encoding::Bincode => bincode::deserialize(bytes).context("Invalid bincode graph file"),
+            Encoding::Json => serde_json::from_slice(bytes).context("Invalid JSON graph file"),
+        }
+    }
+}
+
+/// Work out how a file is encoded and which version it declares
+fn read_header(bytes: &[u8]) -> Result<(Encoding, FormatHeader)> {
+    // Bincode readers ignore trailing bytes, so the header decodes on its own
+    if let Ok(header) = bincode::deserialize::<FormatHeader>(bytes) {
+        if header.magic == MAGIC_BYTES {
+            return Ok((Encoding::Bincode, header));
+        }
+    }
+    if let Ok(header) = serde_json::from_slice::<FormatHeader>(bytes) {
+        if header.magic == MAGIC_BYTES {
+            return Ok((Encoding::Json, header));
+        }
+    }
+    anyhow::bail!("Invalid file format: magic bytes mismatch. Expected WASMFLOW file.")
+}
+
+/// Apply `policy` to the result of a checksum validation
+fn check_checksum(
+    result: Result<()>,
+    policy: ChecksumPolicy,
+    report: &mut MigrationReport,
+) -> Result<()> {
+    match result {
+        Err(e) if policy == ChecksumPolicy::Ignore => {
+            report.warnings.push(format!("{:#}", e));
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// Decode a save file of any supported version into the current format
+///
+/// A checksum mismatch is an error (`SerializationError::ChecksumMismatch`)
+/// unless `checksum` is `ChecksumPolicy::Ignore`.
+pub fn decode(
+    bytes: &[u8],
+    checksum: ChecksumPolicy,
+) -> Result<(GraphSaveFormat, MigrationReport)> {
+    let (encoding, header) = read_header(bytes)?;
+    let mut report = MigrationReport {
+        source_version: header.version,
+        legacy_json: encoding == Encoding::Json,
+        ..Default::default()
+    };
+
+    let format = match header.version {
+        1 => {
+            let file: v1::GraphFileV1 = encoding.decode(bytes)?;
+            check_checksum(file.validate_checksum(), checksum, &mut report)?;
+            log::info!("Upgrading graph file from format v1 to v{}", FORMAT_VERSION);
+            GraphSaveFormat::new(migrate_v1(file.graph, &mut report))
+        }
+        FORMAT_VERSION => {
+            let format: GraphSaveFormat = encoding.decode(bytes)?;
+            check_checksum(format.validate_checksum(), checksum, &mut report)?;
+            report.saved_by = Some(format.saved_by.clone()).filter(|v| !v.is_empty());
+            format
+        }
+        version if version > FORMAT_VERSION => anyhow::bail!(
+            "Incompatible file version: {}. This application supports version {} or earlier.",
+            version,
+            FORMAT_VERSION
+        ),
+        version if version > 1 => anyhow::bail!(
+            "Unsupported file version: {}. It was written by a development build; \
+             re-save it from version 1 or the current release.",
+            version
+        ),
+        version => anyhow::bail!("Unsupported file version: {}", version),
+    };
+    Ok((format, report))
+}
+
+/// Convert a v1 graph to the current model
+///
+/// v1 files could be saved while a node was executing; such nodes reopened
+/// stuck in the running state, so they are reset. Fields added since v1 keep
+/// the defaults of a new graph, node or port.
+pub fn migrate_v1(graph: v1::NodeGraphV1, report: &mut MigrationReport) -> NodeGraph {
+    let mut current = NodeGraph::new(graph.name, graph.metadata.author.clone());
+    current.id = graph.id;
+    current.nodes = graph
+        .nodes
+        .into_iter()
+        .map(|(id, node)| (id, node.into_current()))
+        .collect();
+    current.connections = graph.connections;
+    current.metadata = graph.metadata;
+    current.version = FORMAT_VERSION;
+    current.capability_grants = graph
+        .capability_grants
+        .into_iter()
+        .map(|(id, grant)| (id, grant.into_current()))
+        .collect();
+
+    let mut changes = Vec::new();
+    let mut reset = 0;
+    for node in current.nodes.values_mut() {
+        if node.execution_state == ExecutionState::Running {
+            node.execution_state = ExecutionState::Idle;
+            node.dirty = true;
+            reset += 1;
+        }
+    }
+    if reset > 0 {
+        changes.push(format!("Reset {} node(s) that were saved while running", reset));
+    }
+    // v1 nodes carry no pin; `NodeGraph::pin_unpinned` pins them once the graph is open
+    let unpinned = current
+        .nodes
+        .values()
+        .filter(|node| node.component_id.starts_with("user:"))
+        .count();
+    if unpinned > 0 {
+        changes.push(format!(
+            "{} component node(s) will be pinned to the binaries loaded now",
+            unpinned
+        ));
+    }
+    changes.push("Frames, groups and notes start out empty".to_string());
+    report.steps.push(MigrationStep {
+        from: 1,
+        to: FORMAT_VERSION,
+        changes,
+    });
+    current
+}
+
+/// Frozen schema of format version 1
+///
+/// The structs are copies of what the first release wrote. Types that have
+/// not changed since (values, data types, metadata, connections) are shared
+/// with the live model.
+pub mod v1 {
+    use crate::graph::connection::Connection;
+    use crate::graph::graph::GraphMetadata;
+    use crate::graph::node::{
+        pos2_serde, CompositionData, CompositionMetadata, ContinuousNodeConfig, DataType,
+        ExecutionState, GraphNode, NodeMetadata, NodeValue, Port, PortDirection, PortMapping,
+        WasmCreatorNodeData,
+    };
+    use crate::runtime::capabilities::{CapabilityGrant, CapabilitySet};
+    use crate::SerializationError;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// v1 save file
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct GraphFileV1 {
+        pub magic: Vec<u8>,
+        pub version: u32,
+        pub graph: NodeGraphV1,
+        /// CRC64 of the bincode-encoded graph
+        pub checksum: u64,
+    }
+
+    impl GraphFileV1 {
+        /// Check the stored checksum against the decoded graph
+        pub fn validate_checksum(&self) -> anyhow::Result<()> {
+            let bytes = bincode::serialize(&self.graph)?;
+            let computed = crate::graph::serialization::crc64(&bytes);
+            if computed != self.checksum {
+                return Err(SerializationError::ChecksumMismatch {
+                    expected: self.checksum,
+                    computed,
+                }
+                .into());
+            }
+            Ok(())
+        }
+    }
+
+    /// v1 graph
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct NodeGraphV1 {
+        pub id: Uuid,
+        pub name: String,
+        pub nodes: BTreeMap<Uuid, GraphNodeV1>,
+        pub connections: Vec<Connection>,
+        pub metadata: GraphMetadata,
+        pub version: u32,
+        pub capability_grants: BTreeMap<Uuid, CapabilityGrantV1>,
+    }
+
+    fn default_dirty() -> bool {
+        true
+    }
+
+    /// v1 node
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct GraphNodeV1 {
+        pub id: Uuid,
+        pub component_id: String,
+        pub display_name: String,
+        #[serde(with = "pos2_serde")]
+        pub position: egui::Pos2,
+        pub inputs: Vec<PortV1>,
+        pub outputs: Vec<PortV1>,
+        pub metadata: NodeMetadata,
+        pub capabilities: CapabilitySetV1,
+        #[serde(default)]
+        pub execution_state: ExecutionState,
+        #[serde(default = "default_dirty")]
+        pub dirty: bool,
+        #[serde(default)]
+        pub creator_data: Option<WasmCreatorNodeData>,
+        #[serde(default)]
+        pub needs_component_refresh: bool,
+        #[serde(default)]
+        pub continuous_config: Option<ContinuousNodeConfig>,
+        #[serde(default)]
+        pub composition_data: Option<CompositionDataV1>,
+    }
+
+    impl GraphNodeV1 {
+        pub fn into_current(self) -> GraphNode {
+            let mut node = GraphNode::new(self.component_id, self.display_name, self.position);
+            node.id = self.id;
+            node.inputs = self.inputs.into_iter().map(PortV1::into_current).collect();
+            node.outputs = self.outputs.into_iter().map(PortV1::into_current).collect();
+            node.metadata = self.metadata;
+            node.capabilities = self.capabilities.into_current();
+            node.execution_state = self.execution_state;
+            node.dirty = self.dirty;
+            node.creator_data = self.creator_data;
+            node.needs_component_refresh = self.needs_component_refresh;
+            node.continuous_config = self.continuous_config;
+            node.composition_data = self.composition_data.map(CompositionDataV1::into_current);
+            node
+        }
+    }
+
+    /// v1 port
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct PortV1 {
+        pub id: Uuid,
+        pub name: String,
+        pub data_type: DataType,
+        pub direction: PortDirection,
+        pub optional: bool,
+        pub current_value: Option<NodeValue>,
+    }
+
+    impl PortV1 {
+        pub fn into_current(self) -> Port {
+            Port {
+                id: self.id,
+                name: self.name,
+                data_type: self.data_type,
+                direction: self.direction,
+                optional: self.optional,
+                current_value: self.current_value,
+                // v1 components could not declare defaults
+                default_value: None,
+            }
+        }
+    }
+
+    /// v1 composite node data
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct CompositionDataV1 {
+        pub name: String,
+        pub socket_path: PathBuf,
+        pub plug_paths: Vec<PathBuf>,
+        pub internal_nodes: BTreeMap<Uuid, GraphNodeV1>,
+        pub internal_edges: Vec<Connection>,
+        pub exposed_inputs: BTreeMap<String, PortMapping>,
+        pub exposed_outputs: BTreeMap<String, PortMapping>,
+        pub metadata: CompositionMetadata,
+    }
+
+    impl CompositionDataV1 {
+        pub fn into_current(self) -> CompositionData {
+            CompositionData {
+                name: self.name,
+                socket_path: self.socket_path,
+                plug_paths: self.plug_paths,
+                internal_nodes: self
+                    .internal_nodes
+                    .into_iter()
+                    .map(|(id, node)| (id, node.into_current()))
+                    .collect(),
+                internal_edges: self.internal_edges,
+                exposed_inputs: self.exposed_inputs,
+                exposed_outputs: self.exposed_outputs,
+                metadata: self.metadata,
+                cached_composition: None,
+            }
+        }
+    }
+
+    /// v1 capability set
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub enum CapabilitySetV1 {
+        None,
+        FileRead { paths: Vec<PathBuf> },
+        FileWrite { paths: Vec<PathBuf> },
+        FileReadWrite { paths: Vec<PathBuf> },
+        Network { allowed_hosts: Vec<String> },
+        Full,
+    }
+
+    impl CapabilitySetV1 {
+        pub fn into_current(self) -> CapabilitySet {
+            match self {
+                CapabilitySetV1::None => CapabilitySet::None,
+                CapabilitySetV1::FileRead { paths } => CapabilitySet::FileRead { paths },
+                CapabilitySetV1::FileWrite { paths } => CapabilitySet::FileWrite { paths },
+                CapabilitySetV1::FileReadWrite { paths } => CapabilitySet::FileReadWrite { paths },
+                CapabilitySetV1::Network { allowed_hosts } => {
+                    CapabilitySet::Network { allowed_hosts }
+                }
+                CapabilitySetV1::Full => CapabilitySet::Full,
+            }
+        }
+    }
+
+    /// v1 capability grant
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct CapabilityGrantV1 {
+        pub node_id: Uuid,
+        pub capability_set: CapabilitySetV1,
+        pub granted_at: String,
+        pub scope: String,
+    }
+
+    impl CapabilityGrantV1 {
+        pub fn into_current(self) -> CapabilityGrant {
+            CapabilityGrant {
+                node_id: self.node_id,
+                capability_set: self.capability_set.into_current(),
+                granted_at: self.granted_at,
+                scope: self.scope,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::graph::GraphMetadata;
+    use crate::graph::node::{DataType, ExecutionState, NodeMetadata, NodeValue, PortDirection};
+    use crate::graph::serialization::crc64;
+    use crate::runtime::capabilities::CapabilitySet;
+    use crate::SerializationError;
+    use std::collections::BTreeMap;
+    use uuid::Uuid;
+
+    fn v1_file() -> v1::GraphFileV1 {
+        let port = v1::PortV1 {
+            id: Uuid::new_v4(),
+            name: "value".to_string(),
+            data_type: DataType::F32,
+            direction: PortDirection::Input,
+            optional: true,
+            current_value: Some(NodeValue::F32(2.0)),
+        };
+        let node = v1::GraphNodeV1 {
+            id: Uuid::new_v4(),
+            component_id: "builtin:math:double".to_string(),
+            display_name: "Double".to_string(),
+            position: egui::pos2(10.0, 20.0),
+            inputs: vec![port],
+            outputs: Vec::new(),
+            metadata: NodeMetadata::default(),
+            capabilities: v1::CapabilitySetV1::Network {
+                allowed_hosts: vec!["example.com".to_string()],
+            },
+            execution_state: ExecutionState::Completed,
+            dirty: false,
+            creator_data: None,
+            needs_component_refresh: false,
+            continuous_config: None,
+            composition_data: None,
+        };
+        let graph = v1::NodeGraphV1 {
+            id: Uuid::new_v4(),
+            name: "Old".to_string(),
+            nodes: BTreeMap::from([(node.id, node)]),
+            connections: Vec::new(),
+            metadata: GraphMetadata::new("Author".to_string()),
+            version: 1,
+            capability_grants: BTreeMap::new(),
+        };
+        let checksum = crc64(&bincode::serialize(&graph).unwrap());
+        v1::GraphFileV1 {
+            magic: MAGIC_BYTES.to_vec(),
+            version: 1,
+            graph,
+            checksum,
+        }
+    }
+
+    #[test]
+    fn test_v1_bincode_file_upgrades() {
+        let file = v1_file();
+        let node_id = *file.graph.nodes.keys().next().unwrap();
+        let (format, report) =
+            decode(&bincode::serialize(&file).unwrap(), ChecksumPolicy::Verify).unwrap();
+
+        assert_eq!(format.version, FORMAT_VERSION);
+        assert_eq!(format.graph.version, FORMAT_VERSION);
+        assert_eq!(format.graph.id, file.graph.id);
+        assert_eq!(format.graph.name, "Old");
+        let node = &format.graph.nodes[&node_id];
+        assert_eq!(node.display_name, "Double");
+        assert_eq!(node.position, egui::pos2(10.0, 20.0));
+        assert!(!node.dirty);
+        assert_eq!(
+            node.capabilities,
+            CapabilitySet::Network {
+                allowed_hosts: vec!["example.com".to_string()]
+            }
+        );
+        assert_eq!(node.inputs[0].current_value, Some(NodeValue::F32(2.0)));
+        assert_eq!(node.inputs[0].default_value, None);
+        assert!(node.config.is_empty());
+        assert_eq!(node.instance_state, None);
+        assert_eq!(node.component_pin, None);
+        assert!(format.graph.annotations.is_empty());
+
+        assert_eq!(report.source_version, 1);
+        assert_eq!(report.saved_by, None);
+        assert!(!report.legacy_json);
+        assert_eq!(report.steps.len(), 1);
+        assert_eq!(
+            report.steps[0].changes,
+            vec!["Frames, groups and notes start out empty".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_v1_running_nodes_are_reset() {
+        let mut file = v1_file();
+        for node in file.graph.nodes.values_mut() {
+            node.execution_state = ExecutionState::Running;
+        }
+        file.checksum = crc64(&bincode::serialize(&file.graph).unwrap());
+
+        let (format, report) =
+            decode(&bincode::serialize(&file).unwrap(), ChecksumPolicy::Verify).unwrap();
+        let node = format.graph.nodes.values().next().unwrap();
+        assert_eq!(node.execution_state, ExecutionState::Idle);
+        assert!(node.dirty);
+        assert_eq!(
+            report.steps[0].changes,
+            vec![
+                "Reset 1 node(s) that were saved while running".to_string(),
+                "Frames, groups and notes start out empty".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_v1_json_file_upgrades() {
+        let bytes = serde_json::to_vec(&v1_file()).unwrap();
+        let (format, report) = decode(&bytes, ChecksumPolicy::Verify).unwrap();
+        assert_eq!(format.graph.version, FORMAT_VERSION);
+        assert_eq!(format.graph.nodes.len(), 1);
+        assert!(report.legacy_json);
+        assert!(report.summary().contains("legacy JSON"));
+    }
+
+    #[test]
+    fn test_current_and_unknown_versions() {
+        let graph = NodeGraph::new("New".to_string(), "Author".to_string());
+        let (format, report) = decode(&graph.to_bytes().unwrap(), ChecksumPolicy::Verify).unwrap();
+        assert_eq!(format.graph.id, graph.id);
+        assert!(report.is_empty());
+        assert_eq!(report.saved_by.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+
+        let mut newer = GraphSaveFormat::new(graph);
+        newer.version = FORMAT_VERSION + 1;
+        assert!(decode(&bincode::serialize(&newer).unwrap(), ChecksumPolicy::Verify).is_err());
+
+        // Formats between v1 and the current one only existed in development builds
+        newer.version = FORMAT_VERSION - 1;
+        let err = decode(&bincode::serialize(&newer).unwrap(), ChecksumPolicy::Verify).unwrap_err();
+        assert!(err.to_string().contains("development build"));
+        assert!(decode(b"not a graph", ChecksumPolicy::Verify).is_err());
+    }
+
+    #[test]
+    fn test_checksum_mismatch_needs_ignore_policy() {
+        let mut file = v1_file();
+        file.checksum ^= 1;
+        let bytes = bincode::serialize(&file).unwrap();
+
+        let err = decode(&bytes, ChecksumPolicy::Verify).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<SerializationError>(),
+            Some(SerializationError::ChecksumMismatch { .. })
+        ));
+
+        let (format, report) = decode(&bytes, ChecksumPolicy::Ignore).unwrap();
+        assert_eq!(format.graph.id, file.graph.id);
+        assert_eq!(report.warnings.len(), 1);
+    }
+}
@@ -2,15 +2,26 @@
 //!
 //! This module contains the core graph types: nodes, connections, ports, and values.
 
+pub mod annotation; // Frames, groups and sticky notes on the canvas
+pub mod clipboard; // Copy/paste fragments of node selections
 pub mod command;
+pub mod diff; // Semantic diff and three-way merge of graphs
 pub mod connection;
 pub mod drill_down; // T005: Drill-down view context management
 pub mod execution;
+pub mod function; // Reusable graph functions saved in a library directory
 #[allow(clippy::module_inception)]
 pub mod graph;
+pub mod lint; // Lint pass for likely mistakes in runnable graphs
+pub mod migration; // Upgrades files written by earlier format versions
 pub mod node;
+pub mod pin; // Component versions and binary hashes recorded per node
+pub mod placeholder; // Placeholder nodes for components that are not loaded
+pub mod schema; // Record port schemas and structural validation
 pub mod serialization;
 pub mod state;
+#[cfg(test)]
+pub(crate) mod test_support; // Specs and graphs shared by unit tests
 pub mod validation; // T003: Graph connectivity validation
 
 pub use command::{Command, CommandHistory};
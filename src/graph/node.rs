@@ -82,7 +82,15 @@ impl DataType {
             DataType::Bool => "bool".to_string(),
             DataType::Binary => "binary".to_string(),
             DataType::List(inner) => format!("list<{}>", inner.name()),
-            DataType::Record(_) => "record".to_string(),
+            DataType::Record(fields) if fields.is_empty() => "record".to_string(),
+            DataType::Record(fields) => format!(
+                "record{{{}}}",
+                fields
+                    .iter()
+                    .map(|(name, data_type)| format!("{}: {}", name, data_type.name()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
             DataType::Any => "any".to_string(),
         }
     }
@@ -110,6 +118,9 @@ pub struct Port {
     pub optional: bool,
     /// Current value (present only for outputs after execution)
     pub current_value: Option<NodeValue>,
+    /// Default declared by the component for unconnected inputs
+    #[serde(default)]
+    pub default_value: Option<NodeValue>,
 }
 
 impl Port {
@@ -127,8 +138,19 @@ impl Port {
             direction,
             optional,
             current_value: None,
+            default_value: None,
         }
     }
+
+    /// Whether the current value differs from the component-declared default
+    pub fn is_overridden(&self) -> bool {
+        self.default_value.is_some() && self.current_value != self.default_value
+    }
+
+    /// Restore the component-declared default value
+    pub fn reset_to_default(&mut self) {
+        self.current_value = self.default_value.clone();
+    }
 }
 
 /// Node execution state
@@ -218,6 +240,15 @@ impl Default for ContinuousNodeConfig {
     }
 }
 
+/// Component binary a node was created with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentPin {
+    /// Semantic version of the component
+    pub version: String,
+    /// Content hash of the component binary (see `graph::pin::content_hash`)
+    pub content_hash: String,
+}
+
 /// Node metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeMetadata {
@@ -400,8 +431,7 @@ impl CompositionData {
         component_names: Vec<String>,
         composed_binary: Vec<u8>,
     ) -> Self {
-        let composition_hash = crc::Crc::<u64>::new(&crc::CRC_64_ECMA_182)
-            .checksum(&composed_binary);
+        let composition_hash = crate::graph::serialization::crc64(&composed_binary);
 
         Self {
             name,
@@ -455,6 +485,9 @@ pub struct GraphNode {
     /// Not serialized (UI-only concern, regenerated on load)
     #[serde(skip)]
     pub cached_footer_view: Option<crate::ui::wit_ui_renderer::FooterView>,
+    /// Input on interactive footer elements, waiting to be sent to the component
+    #[serde(skip)]
+    pub pending_ui_events: Vec<crate::ui::wit_ui_renderer::UiEvent>,
     /// Selection state for rectangle selection
     /// Not serialized (UI-only state)
     #[serde(skip, default)]
@@ -466,6 +499,10 @@ pub struct GraphNode {
     /// T059: Flag indicating component was updated and node may need refresh
     #[serde(default)]
     pub needs_component_refresh: bool,
+    /// Component is not loaded; the node is a placeholder until it is relinked
+    /// Not serialized (resolved against the registry after loading)
+    #[serde(skip)]
+    pub missing_component: bool,
     /// Timestamp when execution started (for showing spinner on long-running nodes)
     /// Not serialized as it's runtime-only state
     #[serde(skip)]
@@ -474,6 +511,9 @@ pub struct GraphNode {
     /// Not serialized as it's runtime-only state
     #[serde(skip)]
     pub execution_completed_at: Option<std::time::Instant>,
+    /// Latest progress reported by the running component (runtime-only)
+    #[serde(skip)]
+    pub execution_progress: Option<crate::runtime::progress::ExecutionProgress>,
     /// Continuous execution configuration (if node supports continuous mode)
     #[serde(default)]
     pub continuous_config: Option<ContinuousNodeConfig>,
@@ -481,6 +521,16 @@ pub struct GraphNode {
     /// Stores internal structure for drill-down inspection
     #[serde(default)]
     pub composition_data: Option<CompositionData>,
+    /// Configuration parameter values, passed to the component separately from inputs
+    #[serde(default)]
+    pub config: BTreeMap<String, NodeValue>,
+    /// Guest state snapshot of a stateful component (restored after reload)
+    #[serde(default)]
+    pub instance_state: Option<Vec<u8>>,
+    /// Version and binary hash of the component when the node was created
+    /// (None for builtins and graph functions)
+    #[serde(default)]
+    pub component_pin: Option<ComponentPin>,
 }
 
 /// T084: Default dirty flag to true for new nodes
@@ -505,11 +555,17 @@ impl GraphNode {
             cached_footer_view: None,
             creator_data: None, // T057: Creator data only present for WasmCreatorNode type
             needs_component_refresh: false, // T059: No refresh needed for new nodes
+            missing_component: false,
             execution_started_at: None,
             execution_completed_at: None,
+            execution_progress: None,
+            pending_ui_events: Vec::new(),
             continuous_config: None, // Continuous config set later if node supports it
             selected: false, // T019: New nodes start unselected
             composition_data: None, // T026: Composition data only present for composite nodes
+            config: BTreeMap::new(), // Filled from ComponentSpec parameters
+            instance_state: None,
+            component_pin: None, // Set from the ComponentSpec the node is created from
         }
     }
 
@@ -615,7 +671,7 @@ impl GraphNode {
 }
 
 /// Custom serde for egui::Pos2
-mod pos2_serde {
+pub(crate) mod pos2_serde {
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
     pub fn serialize<S>(pos: &egui::Pos2, serializer: S) -> Result<S::Ok, S::Error>
@@ -648,6 +704,8 @@ pub enum ComponentType {
         /// Plug (dependency) component paths
         plug_paths: Vec<std::path::PathBuf>,
     },
+    /// Reusable graph function saved in the function library
+    GraphFunction(std::path::PathBuf),
 }
 
 /// Node type classification (T018)
@@ -706,6 +764,66 @@ pub struct PortSpec {
     pub data_type: DataType,
     pub optional: bool,
     pub description: String,
+    /// Value used when the input is left unconnected
+    #[serde(default)]
+    pub default_value: Option<NodeValue>,
+}
+
+/// Widget kind for a component configuration parameter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ParameterKind {
+    /// Free-form text
+    Text,
+    /// One of a fixed set of choices
+    Choice(Vec<String>),
+    /// Integer within an inclusive range
+    IntRange { min: i32, max: i32 },
+    /// Number within an inclusive range
+    FloatRange { min: f32, max: f32, step: f32 },
+    /// On/off switch
+    Toggle,
+}
+
+/// Static configuration parameter declared by a component
+///
+/// Parameters are edited in the node body and passed to the component
+/// separately from data inputs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterSpec {
+    pub name: String,
+    pub label: String,
+    pub description: String,
+    pub kind: ParameterKind,
+    pub default_value: NodeValue,
+}
+
+impl ParameterSpec {
+    /// Normalize a value for this parameter
+    ///
+    /// Range values are clamped, numbers are converted to the parameter's
+    /// numeric type, and anything that does not fit falls back to the default.
+    pub fn normalize(&self, value: &NodeValue) -> NodeValue {
+        match (&self.kind, value) {
+            (ParameterKind::Text, NodeValue::String(_)) => value.clone(),
+            (ParameterKind::Choice(choices), NodeValue::String(s)) if choices.contains(s) => {
+                value.clone()
+            }
+            (ParameterKind::IntRange { min, max }, NodeValue::I32(v)) => {
+                NodeValue::I32((*v).clamp(*min, *max))
+            }
+            (ParameterKind::IntRange { min, max }, NodeValue::U32(v)) => {
+                NodeValue::I32(i32::try_from(*v).unwrap_or(i32::MAX).clamp(*min, *max))
+            }
+            (ParameterKind::FloatRange { min, max, .. }, NodeValue::F32(v)) => {
+                NodeValue::F32(v.clamp(*min, *max))
+            }
+            (ParameterKind::FloatRange { min, max, .. }, NodeValue::I32(v)) => {
+                NodeValue::F32((*v as f32).clamp(*min, *max))
+            }
+            (ParameterKind::Toggle, NodeValue::Bool(_)) => value.clone(),
+            _ => self.default_value.clone(),
+        }
+    }
 }
 
 /// Specification of a node's behavior, interface, and system requirements
@@ -731,6 +849,22 @@ pub struct ComponentSpec {
     pub required_capabilities: Vec<String>,
     /// Category for organization (e.g., "Math", "Text", "File I/O")
     pub category: Option<String>,
+    /// Static configuration parameters (rendered in the node body)
+    #[serde(default)]
+    pub parameters: Vec<ParameterSpec>,
+    /// `wasmflow:node` contract version a WASM component was built against
+    /// (None for builtins)
+    #[serde(default)]
+    pub contract_version: Option<crate::runtime::contract::ContractVersion>,
+    /// Component exports the continuous interface (runs with play/stop controls)
+    #[serde(default)]
+    pub supports_continuous: bool,
+    /// Component exports the stateful interface (one live instance per node)
+    #[serde(default)]
+    pub stateful: bool,
+    /// Content hash of the loaded binary (None for builtins)
+    #[serde(default)]
+    pub content_hash: Option<String>,
     /// Optional custom footer view implementation
     ///
     /// Components can provide a custom UI view for displaying in the canvas
@@ -757,6 +891,11 @@ impl std::fmt::Debug for ComponentSpec {
             .field("output_spec", &self.output_spec)
             .field("required_capabilities", &self.required_capabilities)
             .field("category", &self.category)
+            .field("parameters", &self.parameters)
+            .field("contract_version", &self.contract_version)
+            .field("supports_continuous", &self.supports_continuous)
+            .field("stateful", &self.stateful)
+            .field("content_hash", &self.content_hash)
             .field("footer_view", &self.footer_view.as_ref().map(|_| "<view>"))
             .finish()
     }
@@ -781,6 +920,11 @@ impl ComponentSpec {
             output_spec: Vec::new(),
             required_capabilities: Vec::new(),
             category,
+            parameters: Vec::new(),
+            contract_version: None,
+            supports_continuous: false,
+            stateful: false,
+            content_hash: None,
             footer_view: None,
         }
     }
@@ -804,6 +948,11 @@ impl ComponentSpec {
             output_spec: Vec::new(),
             required_capabilities: Vec::new(),
             category,
+            parameters: Vec::new(),
+            contract_version: None,
+            supports_continuous: false,
+            stateful: false,
+            content_hash: None,
             footer_view: None,
         }
     }
@@ -815,6 +964,25 @@ impl ComponentSpec {
             data_type,
             optional: false,
             description,
+            default_value: None,
+        });
+        self
+    }
+
+    /// Add an optional input port specification with a default value
+    pub fn with_optional_input(
+        mut self,
+        name: String,
+        data_type: DataType,
+        description: String,
+        default_value: Option<NodeValue>,
+    ) -> Self {
+        self.input_spec.push(PortSpec {
+            name,
+            data_type,
+            optional: true,
+            description,
+            default_value,
         });
         self
     }
@@ -826,10 +994,24 @@ impl ComponentSpec {
             data_type,
             optional: false,
             description,
+            default_value: None,
         });
         self
     }
 
+    /// Whether the component was built against a deprecated contract version
+    pub fn uses_deprecated_contract(&self) -> bool {
+        self.contract_version
+            .map(|version| version.is_deprecated())
+            .unwrap_or(false)
+    }
+
+    /// Add a configuration parameter specification
+    pub fn with_parameter(mut self, parameter: ParameterSpec) -> Self {
+        self.parameters.push(parameter);
+        self
+    }
+
     /// Set custom footer view for this component
     ///
     /// Enables the component to provide custom UI rendering in the canvas
@@ -867,14 +1049,17 @@ impl ComponentSpec {
     pub fn create_node(&self, position: egui::Pos2) -> GraphNode {
         let mut node = GraphNode::new(self.id.clone(), self.name.clone(), position);
 
-        // Create input ports
+        // Create input ports, pre-filled with component-declared defaults
         for spec in &self.input_spec {
-            node.inputs.push(Port::new(
+            let mut port = Port::new(
                 spec.name.clone(),
                 spec.data_type.clone(),
                 PortDirection::Input,
                 spec.optional,
-            ));
+            );
+            port.default_value = spec.default_value.clone();
+            port.current_value = spec.default_value.clone();
+            node.inputs.push(port);
         }
 
         // Create output ports
@@ -910,11 +1095,21 @@ impl ComponentSpec {
             node.outputs.push(port);
         }
 
+        // Start configuration parameters at their declared defaults
+        for parameter in &self.parameters {
+            node.config
+                .insert(parameter.name.clone(), parameter.default_value.clone());
+        }
+
         node.metadata = NodeMetadata {
             author: self.author.clone(),
             version: self.version.clone(),
             description: self.description.clone(),
         };
+        node.component_pin = self.content_hash.clone().map(|content_hash| ComponentPin {
+            version: self.version.clone(),
+            content_hash,
+        });
 
         // T057: Initialize creator_data for WASM Creator nodes
         if self.id == "builtin:development:wasm-creator" {
@@ -946,6 +1141,15 @@ let result = value * 2.0;
             }
         }
 
+        // WASM components that export the continuous interface
+        if self.supports_continuous {
+            node.continuous_config = Some(ContinuousNodeConfig {
+                supports_continuous: true,
+                enabled: true,
+                runtime_state: ContinuousRuntimeState::default(),
+            });
+        }
+
         // T050: Initialize continuous_config for continuous combiner nodes
         if self.id == "builtin:continuous:combiner" {
             node.continuous_config = Some(ContinuousNodeConfig {
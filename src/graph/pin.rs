@@ -0,0 +1,177 @@
+//! Component pins: which binary each node was built with
+//!
+//! Nodes created from a user component record its version and the hash of
+//! its binary. When the binary under a component id changes, the node can be
+//! upgraded to it or keep running its own copy from the component store,
+//! under a pinned id `<component-id>@<hash>`.
+
+use crate::graph::graph::NodeGraph;
+use crate::graph::node::{ComponentPin, ComponentRegistry};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Separator between a component id and the binary hash it is pinned to
+const PIN_SEPARATOR: char = '@';
+
+/// Content hash of a component binary (SHA-256, as hex)
+///
+/// A replaced binary must not be able to claim another binary's pin.
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Component id that always refers to one binary of `component_id`
+pub fn pinned_id(component_id: &str, content_hash: &str) -> String {
+    format!("{}{}{}", base_id(component_id), PIN_SEPARATOR, content_hash)
+}
+
+/// Split a pinned id into the component id and hash
+pub fn split_pinned_id(component_id: &str) -> (&str, Option<&str>) {
+    match component_id.rsplit_once(PIN_SEPARATOR) {
+        Some((base, hash)) => (base, Some(hash)),
+        None => (component_id, None),
+    }
+}
+
+/// Component id without any pin
+pub fn base_id(component_id: &str) -> &str {
+    split_pinned_id(component_id).0
+}
+
+/// Nodes whose component binary differs from the one they were created with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentChange {
+    pub component_id: String,
+    pub node_ids: Vec<Uuid>,
+    /// What the nodes were created with
+    pub pinned: ComponentPin,
+    /// What is loaded now
+    pub current: ComponentPin,
+}
+
+impl NodeGraph {
+    /// Nodes whose component was replaced by a different binary
+    ///
+    /// Grouped by component and pinned binary, sorted by component id.
+    /// Placeholders and nodes without a pin are skipped.
+    pub fn changed_components(&self, registry: &ComponentRegistry) -> Vec<ComponentChange> {
+        let mut changes: BTreeMap<(&str, &str), ComponentChange> = BTreeMap::new();
+        for node in self.nodes.values().filter(|node| !node.missing_component) {
+            let (Some(pin), Some(spec)) =
+                (&node.component_pin, registry.get_by_id(&node.component_id))
+            else {
+                continue;
+            };
+            let Some(current_hash) = &spec.content_hash else {
+                continue;
+            };
+            if *current_hash == pin.content_hash {
+                continue;
+            }
+            changes
+                .entry((&node.component_id, &pin.content_hash))
+                .or_insert_with(|| ComponentChange {
+                    component_id: node.component_id.clone(),
+                    node_ids: Vec::new(),
+                    pinned: pin.clone(),
+                    current: ComponentPin {
+                        version: spec.version.clone(),
+                        content_hash: current_hash.clone(),
+                    },
+                })
+                .node_ids
+                .push(node.id);
+        }
+        changes.into_values().collect()
+    }
+
+    /// Pin nodes that have no pin yet to the loaded binary
+    ///
+    /// Graphs saved before pins were recorded start out pinned to whatever is
+    /// loaded when they are first opened. Returns the number of nodes pinned.
+    pub fn pin_unpinned(&mut self, registry: &ComponentRegistry) -> usize {
+        let mut pinned = 0;
+        for node in self.nodes.values_mut().filter(|node| node.component_pin.is_none()) {
+            let Some(spec) = registry.get_by_id(&node.component_id) else {
+                continue;
+            };
+            if let Some(content_hash) = &spec.content_hash {
+                node.component_pin = Some(ComponentPin {
+                    version: spec.version.clone(),
+                    content_hash: content_hash.clone(),
+                });
+                pinned += 1;
+            }
+        }
+        pinned
+    }
+
+    /// Binaries referenced by pinned ids, as (component id, hash)
+    pub fn pinned_binaries(&self) -> Vec<(String, String)> {
+        let mut pinned: Vec<(String, String)> = self
+            .nodes
+            .values()
+            .filter_map(|node| match split_pinned_id(&node.component_id) {
+                (base, Some(hash)) => Some((base.to_string(), hash.to_string())),
+                _ => None,
+            })
+            .collect();
+        pinned.sort();
+        pinned.dedup();
+        pinned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::node::{ComponentSpec, DataType};
+    use crate::graph::test_support::{linked_graph, scale_spec};
+
+    fn spec(id: &str, version: &str, bytes: &[u8]) -> ComponentSpec {
+        let mut spec = scale_spec(DataType::F32);
+        spec.id = id.to_string();
+        spec.version = version.to_string();
+        spec.content_hash = Some(content_hash(bytes));
+        spec
+    }
+
+    #[test]
+    fn test_pinned_ids() {
+        let id = pinned_id("user:json_parser", "00ff");
+        assert_eq!(id, "user:json_parser@00ff");
+        assert_eq!(split_pinned_id(&id), ("user:json_parser", Some("00ff")));
+        assert_eq!(split_pinned_id("user:lib:parse"), ("user:lib:parse", None));
+        // Re-pinning replaces the old pin
+        assert_eq!(pinned_id(&id, "abcd"), "user:json_parser@abcd");
+        assert_eq!(
+            content_hash(b"123456789"),
+            "15e2b0d3c33891ebb0f1ef609ec419420c20e320ce94c65fbc8c3312448eb225"
+        );
+    }
+
+    #[test]
+    fn test_replaced_binary_is_reported() {
+        let old = spec("user:json_parser", "1.0.0", b"old");
+        let (mut graph, first_id, _) = linked_graph(&old, &old);
+        let pin = graph.nodes[&first_id].component_pin.as_ref().unwrap();
+        assert_eq!(pin.content_hash, content_hash(b"old"));
+
+        let mut registry = ComponentRegistry::new();
+        registry.register_builtin(old.clone());
+        assert!(graph.changed_components(&registry).is_empty());
+
+        registry.register_builtin(spec("user:json_parser", "1.1.0", b"new"));
+        let changes = graph.changed_components(&registry);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].node_ids.len(), 2);
+        assert_eq!(changes[0].pinned.version, "1.0.0");
+        assert_eq!(changes[0].current.version, "1.1.0");
+
+        // Nodes from older files get pinned to what is loaded, not reported
+        graph.nodes.get_mut(&first_id).unwrap().component_pin = None;
+        assert_eq!(graph.pin_unpinned(&registry), 1);
+        assert_eq!(graph.changed_components(&registry)[0].node_ids.len(), 1);
+    }
+}
@@ -0,0 +1,376 @@
+//! Placeholder nodes for components that are not loaded
+//!
+//! A graph can reference `user:` components that are missing from
+//! `components/bin` (renamed, not built yet, or from another machine). After
+//! loading, such nodes are flagged as placeholders: they keep their ports and
+//! connections but refuse to run until they are relinked to a component that
+//! is available. Relinking keeps every port whose name and type still match.
+//! Refreshing a node after its component is recompiled works the same way.
+//! Relinking to a different component revokes the node's capability grant:
+//! the user approved what the old component asked for, not the new one.
+
+use crate::graph::command::Command;
+use crate::graph::connection::Connection;
+use crate::graph::graph::NodeGraph;
+use crate::graph::node::{ComponentRegistry, ComponentSpec, ExecutionState, GraphNode, Port};
+use crate::graph::pin::base_id;
+use crate::runtime::capabilities::{CapabilityGrant, CapabilitySet};
+use std::collections::{BTreeMap, HashSet};
+use uuid::Uuid;
+
+/// Components whose ids can go missing (builtins and graph functions are
+/// always registered by the application)
+const RESOLVED_PREFIX: &str = "user:";
+
+/// A component the graph references but the registry does not have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingComponent {
+    pub component_id: String,
+    /// Placeholder nodes that reference it
+    pub node_ids: Vec<Uuid>,
+}
+
+impl NodeGraph {
+    /// Flag nodes whose component is not registered as placeholders
+    ///
+    /// Clears the flag on nodes whose component has become available. Call
+    /// after loading a graph and whenever the set of loaded components
+    /// changes. Returns the missing components, sorted by id.
+    pub fn resolve_components(&mut self, registry: &ComponentRegistry) -> Vec<MissingComponent> {
+        for node in self.nodes.values_mut() {
+            node.missing_component = node.component_id.starts_with(RESOLVED_PREFIX)
+                && registry.get_by_id(&node.component_id).is_none();
+        }
+        self.missing_components()
+    }
+
+    /// Components of the nodes currently flagged as placeholders
+    pub fn missing_components(&self) -> Vec<MissingComponent> {
+        let mut missing: BTreeMap<&str, Vec<Uuid>> = BTreeMap::new();
+        for node in self.nodes.values().filter(|node| node.missing_component) {
+            missing.entry(&node.component_id).or_default().push(node.id);
+        }
+        missing
+            .into_iter()
+            .map(|(component_id, node_ids)| MissingComponent {
+                component_id: component_id.to_string(),
+                node_ids,
+            })
+            .collect()
+    }
+}
+
+/// A node rebuilt from a different component, ready to replace the original
+#[derive(Debug, Clone)]
+pub struct Relink {
+    pub old: GraphNode,
+    pub node: GraphNode,
+    /// Connections to ports the new component does not have
+    pub dropped_connections: Vec<Connection>,
+    /// Ports of the old node with no match, as "name: type"
+    pub unmatched_ports: Vec<String>,
+    /// Grant of the old node, revoked because the component changed
+    pub revoked_grant: Option<CapabilityGrant>,
+}
+
+impl Relink {
+    /// Grant to ask the user to approve for the new component, which
+    /// requests `requested`; None unless a grant was revoked
+    pub fn grant_request(&self, requested: &CapabilitySet) -> Option<CapabilityGrant> {
+        let revoked = self.revoked_grant.as_ref()?;
+        if *requested == CapabilitySet::None {
+            return None;
+        }
+        Some(CapabilityGrant {
+            node_id: self.node.id,
+            capability_set: requested.clone(),
+            granted_at: chrono::Utc::now().to_rfc3339(),
+            scope: revoked.scope.clone(),
+        })
+    }
+
+    /// Undoable command that applies the relink
+    pub fn into_command(self) -> Command {
+        let replace = Command::ReplaceNode {
+            old: self.old,
+            new: self.node,
+            dropped_connections: self.dropped_connections,
+        };
+        let Some(grant) = self.revoked_grant else {
+            return replace;
+        };
+        // The grant comes back when the relink is undone
+        let label = replace.label();
+        Command::Batch {
+            label,
+            commands: vec![
+                Command::SetCapabilityGrant {
+                    node_id: grant.node_id,
+                    old: Some(grant),
+                    new: None,
+                },
+                replace,
+            ],
+        }
+    }
+}
+
+/// Rebuild a node from `spec`, keeping what still fits
+///
+/// Ports are matched by name and type; matched ports keep their ids (so
+/// their connections survive) and their values. The display name, position
+/// and configuration values of the same type are kept too, and so are the
+/// capabilities and grant unless the node moves to a different component.
+/// Returns None if the node does not exist.
+pub fn plan_relink(graph: &NodeGraph, node_id: Uuid, spec: &ComponentSpec) -> Option<Relink> {
+    let old = graph.nodes.get(&node_id)?;
+
+    let mut node = spec.create_node(old.position);
+    node.id = old.id;
+    node.display_name = old.display_name.clone();
+    node.creator_data = old.creator_data.clone();
+    let mut kept = HashSet::new();
+    adopt_ports(&mut node.inputs, &old.inputs, &mut kept);
+    adopt_ports(&mut node.outputs, &old.outputs, &mut kept);
+
+    for (key, value) in &old.config {
+        if let Some(current) = node.config.get_mut(key) {
+            if std::mem::discriminant(current) == std::mem::discriminant(value) {
+                *current = value.clone();
+            }
+        }
+    }
+    // Guest state and granted capabilities belong to the component they were for
+    let same_component = base_id(&node.component_id) == base_id(&old.component_id);
+    let mut revoked_grant = None;
+    if same_component {
+        node.instance_state = old.instance_state.clone();
+        node.capabilities = old.capabilities.clone();
+    } else {
+        revoked_grant = graph.get_capability_grant(node_id).cloned();
+    }
+    node.missing_component = false;
+    node.needs_component_refresh = false;
+    node.execution_state = ExecutionState::Idle;
+    node.dirty = true;
+
+    let dropped_connections = graph
+        .node_connections(node_id)
+        .into_iter()
+        .filter(|conn| {
+            (conn.to_node == node_id && !kept.contains(&conn.to_port))
+                || (conn.from_node == node_id && !kept.contains(&conn.from_port))
+        })
+        .cloned()
+        .collect();
+    let unmatched_ports = old
+        .inputs
+        .iter()
+        .chain(&old.outputs)
+        .filter(|port| !kept.contains(&port.id))
+        .map(|port| format!("{}: {}", port.name, port.data_type.name()))
+        .collect();
+
+    Some(Relink {
+        old: old.clone(),
+        node,
+        dropped_connections,
+        unmatched_ports,
+        revoked_grant,
+    })
+}
+
+/// Rebuild a node from the spec now registered under its own component id
+///
+/// Used after a component is recompiled: the node picks up added, removed
+/// or retyped ports, keeping the ones that still match. Returns None if the
+/// node or its component is not available.
+pub fn plan_refresh(
+    graph: &NodeGraph,
+    node_id: Uuid,
+    registry: &ComponentRegistry,
+) -> Option<Relink> {
+    let spec = registry.get_by_id(&graph.nodes.get(&node_id)?.component_id)?;
+    plan_relink(graph, node_id, spec)
+}
+
+impl NodeGraph {
+    /// Nodes flagged as needing a refresh, in a stable order
+    pub fn nodes_needing_refresh(&self) -> Vec<Uuid> {
+        let mut node_ids: Vec<Uuid> = self
+            .nodes
+            .values()
+            .filter(|node| node.needs_component_refresh && !node.missing_component)
+            .map(|node| node.id)
+            .collect();
+        node_ids.sort();
+        node_ids
+    }
+}
+
+/// Give new ports the id and value of the old port with the same name and type
+fn adopt_ports(new_ports: &mut [Port], old_ports: &[Port], kept: &mut HashSet<Uuid>) {
+    for port in new_ports {
+        let matching = old_ports
+            .iter()
+            .find(|old| old.name == port.name && old.data_type == port.data_type);
+        if let Some(old) = matching {
+            port.id = old.id;
+            if old.current_value.is_some() {
+                port.current_value = old.current_value.clone();
+            }
+            kept.insert(old.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::command::CommandHistory;
+    use crate::graph::node::{ComponentType, DataType, NodeValue};
+    use crate::graph::test_support::{constant_spec, linked_graph, scale_spec};
+    use std::path::PathBuf;
+
+    /// The shared scale spec as a user component
+    fn user_spec(id: &str, input_type: DataType) -> ComponentSpec {
+        let mut spec = scale_spec(input_type);
+        spec.id = id.to_string();
+        spec.component_type = ComponentType::UserDefined(PathBuf::from("scale.wasm"));
+        spec
+    }
+
+    /// Constant -> value, constant -> factor; returns (graph, constant, scale)
+    fn wired_graph() -> (NodeGraph, Uuid, Uuid) {
+        let (mut graph, constant_id, scale_id) =
+            linked_graph(&constant_spec(), &user_spec("user:scale", DataType::F32));
+        let out = graph.nodes[&constant_id].outputs[0].id;
+        let scale = graph.nodes.get_mut(&scale_id).unwrap();
+        scale.display_name = "My Scale".to_string();
+        scale.inputs[1].current_value = Some(NodeValue::F32(3.0));
+        let factor_in = scale.inputs[1].id;
+        graph.add_connection(constant_id, out, scale_id, factor_in).unwrap();
+        (graph, constant_id, scale_id)
+    }
+
+    #[test]
+    fn test_resolve_marks_and_clears_placeholders() {
+        let (mut graph, constant_id, scale_id) = wired_graph();
+        let mut registry = ComponentRegistry::new();
+        registry.register_builtin(constant_spec());
+
+        let missing = graph.resolve_components(&registry);
+        assert_eq!(
+            missing,
+            vec![MissingComponent {
+                component_id: "user:scale".to_string(),
+                node_ids: vec![scale_id],
+            }]
+        );
+        assert!(graph.nodes[&scale_id].missing_component);
+        // Builtins are never placeholders, and nothing was disconnected
+        assert!(!graph.nodes[&constant_id].missing_component);
+        assert_eq!(graph.connections.len(), 2);
+
+        registry.register_builtin(user_spec("user:scale", DataType::F32));
+        assert!(graph.resolve_components(&registry).is_empty());
+        assert!(!graph.nodes[&scale_id].missing_component);
+    }
+
+    #[test]
+    fn test_relink_keeps_matching_ports_and_drops_the_rest() {
+        let (mut graph, _, scale_id) = wired_graph();
+        let mut registry = ComponentRegistry::new();
+        graph.resolve_components(&registry);
+        let old_factor = graph.nodes[&scale_id].inputs[1].id;
+
+        // "value" changed type in the replacement, "factor" did not
+        let target = user_spec("user:scale-v2", DataType::String);
+        registry.register_builtin(target.clone());
+        let relink = plan_relink(&graph, scale_id, &target).unwrap();
+
+        assert_eq!(relink.node.id, scale_id);
+        assert_eq!(relink.node.component_id, "user:scale-v2");
+        assert_eq!(relink.node.display_name, "My Scale");
+        assert!(!relink.node.missing_component);
+        assert_eq!(relink.node.inputs[1].id, old_factor);
+        assert_eq!(relink.node.inputs[1].current_value, Some(NodeValue::F32(3.0)));
+        assert_eq!(relink.dropped_connections.len(), 1);
+        assert_eq!(relink.unmatched_ports, vec!["value: f32".to_string()]);
+
+        let mut history = CommandHistory::new();
+        history.execute(relink.into_command(), &mut graph).unwrap();
+        assert_eq!(graph.connections.len(), 1);
+        assert_eq!(graph.connections[0].to_port, old_factor);
+        assert!(graph.resolve_components(&registry).is_empty());
+
+        history.undo(&mut graph).unwrap();
+        assert_eq!(graph.nodes[&scale_id].component_id, "user:scale");
+        assert_eq!(graph.connections.len(), 2);
+        assert_eq!(graph.resolve_components(&registry).len(), 1);
+    }
+
+    #[test]
+    fn test_relink_to_another_component_revokes_the_grant() {
+        let (mut graph, _, scale_id) = wired_graph();
+        let read = CapabilitySet::file_read(vec![PathBuf::from("/data")]);
+        graph.grant_capability(CapabilityGrant::new(scale_id, read.clone()));
+
+        // A new build of the same component keeps the grant
+        let rebuilt = plan_relink(&graph, scale_id, &user_spec("user:scale", DataType::F32));
+        assert_eq!(rebuilt.unwrap().revoked_grant, None);
+
+        let other = user_spec("user:other", DataType::F32);
+        let relink = plan_relink(&graph, scale_id, &other).unwrap();
+        assert_eq!(relink.grant_request(&CapabilitySet::None), None);
+        let request = relink.grant_request(&read).unwrap();
+        assert_eq!(request.node_id, scale_id);
+        assert_eq!(request.capability_set, read);
+
+        let mut history = CommandHistory::new();
+        history.execute(relink.into_command(), &mut graph).unwrap();
+        assert_eq!(graph.nodes[&scale_id].component_id, "user:other");
+        assert!(!graph.has_capability_grant(scale_id));
+
+        // Undo restores the old component together with its grant
+        history.undo(&mut graph).unwrap();
+        assert_eq!(graph.nodes[&scale_id].component_id, "user:scale");
+        assert_eq!(graph.get_capability_grant(scale_id).unwrap().capability_set, read);
+    }
+
+    #[test]
+    fn test_refresh_rebuilds_ports_from_updated_spec() {
+        let (mut graph, _, scale_id) = wired_graph();
+        let mut registry = ComponentRegistry::new();
+        registry.register_builtin(constant_spec());
+        let old_factor = graph.nodes[&scale_id].inputs[1].id;
+
+        // Recompiled with "value" retyped and a new "offset" input
+        let updated = user_spec("user:scale", DataType::String).with_input(
+            "offset".to_string(),
+            DataType::F32,
+            "Offset".to_string(),
+        );
+        registry.register_builtin(updated);
+        graph.mark_component_users_for_refresh("user:scale");
+        assert_eq!(graph.nodes_needing_refresh(), vec![scale_id]);
+
+        let refresh = plan_refresh(&graph, scale_id, &registry).unwrap();
+        assert_eq!(refresh.node.inputs.len(), 3);
+        assert_eq!(refresh.node.inputs[1].id, old_factor);
+        assert_eq!(refresh.node.inputs[1].current_value, Some(NodeValue::F32(3.0)));
+        assert_eq!(refresh.unmatched_ports, vec!["value: f32".to_string()]);
+
+        let mut history = CommandHistory::new();
+        history.execute(refresh.into_command(), &mut graph).unwrap();
+        assert!(graph.nodes_needing_refresh().is_empty());
+        assert_eq!(graph.nodes[&scale_id].inputs[2].name, "offset");
+        assert_eq!(graph.connections.len(), 1);
+
+        // Undo brings back the old ports, the dropped wire and the flag
+        history.undo(&mut graph).unwrap();
+        assert_eq!(graph.nodes[&scale_id].inputs.len(), 2);
+        assert_eq!(graph.connections.len(), 2);
+        assert_eq!(graph.nodes_needing_refresh(), vec![scale_id]);
+    }
+}
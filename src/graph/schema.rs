@@ -0,0 +1,361 @@
+//! Record schemas for ports
+//!
+//! Components declare record schemas for ports through the optional WIT
+//! `schema` interface. Fields are declared as flat dotted paths
+//! (e.g. `address.city`) because WIT types cannot be recursive; this module
+//! folds them back into nested `DataType::Record`s.
+//!
+//! Record values cross the WIT boundary as JSON-encoded `string-val`s. The
+//! helpers here encode records for guests, decode guest outputs against the
+//! declared schema, and validate produced values so errors name the exact
+//! field that is missing or mistyped.
+
+use crate::graph::node::{DataType, NodeValue};
+use crate::SchemaError;
+use std::collections::BTreeMap;
+
+/// Build a nested record type from dotted field paths
+///
+/// `[("name", String), ("address.city", String)]` becomes
+/// `record{name: string, address: record{city: string}}`. Field order follows
+/// first appearance so the declared order is preserved for display.
+pub fn record_from_field_paths(fields: &[(String, DataType)]) -> DataType {
+    let mut root: Vec<(String, DataType)> = Vec::new();
+    for (path, data_type) in fields {
+        let segments: Vec<&str> = path.split('.').filter(|s| !s.is_empty()).collect();
+        if !segments.is_empty() {
+            insert_field(&mut root, &segments, data_type.clone());
+        }
+    }
+    DataType::Record(root)
+}
+
+fn insert_field(fields: &mut Vec<(String, DataType)>, segments: &[&str], data_type: DataType) {
+    let name = segments[0];
+
+    if segments.len() == 1 {
+        match fields.iter_mut().find(|(n, _)| n == name) {
+            Some(existing) => existing.1 = data_type,
+            None => fields.push((name.to_string(), data_type)),
+        }
+        return;
+    }
+
+    let index = match fields.iter().position(|(n, _)| n == name) {
+        Some(index) => index,
+        None => {
+            fields.push((name.to_string(), DataType::Record(Vec::new())));
+            fields.len() - 1
+        }
+    };
+
+    // A later nested path wins over an earlier scalar declaration
+    if !matches!(fields[index].1, DataType::Record(_)) {
+        fields[index].1 = DataType::Record(Vec::new());
+    }
+    if let DataType::Record(children) = &mut fields[index].1 {
+        insert_field(children, &segments[1..], data_type);
+    }
+}
+
+/// Validate a value against a declared type
+///
+/// Records use width subtyping: every declared field must be present with a
+/// matching type, extra fields are allowed. `path` names the value being
+/// checked (usually the port name) and prefixes field names in errors.
+pub fn validate_value(value: &NodeValue, expected: &DataType, path: &str) -> Result<(), SchemaError> {
+    match (expected, value) {
+        (DataType::Any, _) => Ok(()),
+        (DataType::U32, NodeValue::U32(_))
+        | (DataType::I32, NodeValue::I32(_))
+        | (DataType::F32, NodeValue::F32(_))
+        | (DataType::String, NodeValue::String(_))
+        | (DataType::Bool, NodeValue::Bool(_))
+        | (DataType::Binary, NodeValue::Binary(_)) => Ok(()),
+        (DataType::List(inner), NodeValue::List(items)) => {
+            for (i, item) in items.iter().enumerate() {
+                validate_value(item, inner, &format!("{}[{}]", path, i))?;
+            }
+            Ok(())
+        }
+        (DataType::Record(fields), NodeValue::Record(values)) => {
+            for (name, field_type) in fields {
+                let field_path = join_path(path, name);
+                match values.get(name) {
+                    Some(field_value) => validate_value(field_value, field_type, &field_path)?,
+                    None => return Err(SchemaError::MissingField { field: field_path }),
+                }
+            }
+            Ok(())
+        }
+        _ => Err(SchemaError::TypeMismatch {
+            field: path.to_string(),
+            expected: expected.name(),
+            found: value.type_name().to_string(),
+        }),
+    }
+}
+
+/// Coerce a produced value to its declared type and validate it
+///
+/// Guests return records as JSON strings; when the declared type is a record
+/// (or a list of records) and the value is a string, it is decoded first.
+/// Every other value is validated as-is.
+pub fn coerce_to_schema(value: NodeValue, expected: &DataType, path: &str) -> Result<NodeValue, SchemaError> {
+    let needs_decoding = matches!(value, NodeValue::String(_)) && contains_record(expected);
+
+    let value = match value {
+        NodeValue::String(json) if needs_decoding => {
+            let parsed: serde_json::Value = serde_json::from_str(&json).map_err(|e| {
+                SchemaError::InvalidEncoding {
+                    field: path.to_string(),
+                    reason: e.to_string(),
+                }
+            })?;
+            json_to_node_value(&parsed, expected, path)?
+        }
+        other => other,
+    };
+
+    validate_value(&value, expected, path)?;
+    Ok(value)
+}
+
+/// Whether a type contains a record anywhere (and thus needs JSON decoding)
+pub fn contains_record(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Record(_) => true,
+        DataType::List(inner) => contains_record(inner),
+        _ => false,
+    }
+}
+
+/// Decode a JSON value into a NodeValue guided by the expected type
+///
+/// Numbers are narrowed to the declared numeric type; fields not declared in
+/// the schema are kept with a best-effort inferred type.
+pub fn json_to_node_value(
+    json: &serde_json::Value,
+    expected: &DataType,
+    path: &str,
+) -> Result<NodeValue, SchemaError> {
+    use serde_json::Value as Json;
+
+    let mismatch = || SchemaError::TypeMismatch {
+        field: path.to_string(),
+        expected: expected.name(),
+        found: json_type_name(json).to_string(),
+    };
+
+    match (expected, json) {
+        (DataType::Any, _) => Ok(infer_node_value(json)),
+        (DataType::U32, Json::Number(n)) => n
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .map(NodeValue::U32)
+            .ok_or_else(mismatch),
+        (DataType::I32, Json::Number(n)) => n
+            .as_i64()
+            .and_then(|v| i32::try_from(v).ok())
+            .map(NodeValue::I32)
+            .ok_or_else(mismatch),
+        (DataType::F32, Json::Number(n)) => n
+            .as_f64()
+            .map(|v| NodeValue::F32(v as f32))
+            .ok_or_else(mismatch),
+        (DataType::String, Json::String(s)) => Ok(NodeValue::String(s.clone())),
+        (DataType::Bool, Json::Bool(b)) => Ok(NodeValue::Bool(*b)),
+        (DataType::Binary, Json::Array(items)) => items
+            .iter()
+            .map(|item| item.as_u64().and_then(|b| u8::try_from(b).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .map(NodeValue::Binary)
+            .ok_or_else(mismatch),
+        (DataType::List(inner), Json::Array(items)) => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| json_to_node_value(item, inner, &format!("{}[{}]", path, i)))
+            .collect::<Result<Vec<_>, _>>()
+            .map(NodeValue::List),
+        (DataType::Record(fields), Json::Object(map)) => {
+            let mut record = BTreeMap::new();
+            for (key, field_json) in map {
+                let field_path = join_path(path, key);
+                let value = match fields.iter().find(|(name, _)| name == key) {
+                    Some((_, field_type)) => json_to_node_value(field_json, field_type, &field_path)?,
+                    None => infer_node_value(field_json),
+                };
+                record.insert(key.clone(), value);
+            }
+            Ok(NodeValue::Record(record))
+        }
+        _ => Err(mismatch()),
+    }
+}
+
+/// Encode a NodeValue as JSON (used to pass records to guests)
+pub fn node_value_to_json(value: &NodeValue) -> serde_json::Value {
+    use serde_json::Value as Json;
+
+    match value {
+        NodeValue::U32(v) => Json::from(*v),
+        NodeValue::I32(v) => Json::from(*v),
+        NodeValue::F32(v) => Json::from(*v as f64),
+        NodeValue::String(s) => Json::String(s.clone()),
+        NodeValue::Bool(b) => Json::Bool(*b),
+        NodeValue::Binary(bytes) => Json::Array(bytes.iter().map(|b| Json::from(*b)).collect()),
+        NodeValue::List(items) => Json::Array(items.iter().map(node_value_to_json).collect()),
+        NodeValue::Record(fields) => Json::Object(
+            fields
+                .iter()
+                .map(|(k, v)| (k.clone(), node_value_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Best-effort conversion for JSON without a declared type
+fn infer_node_value(json: &serde_json::Value) -> NodeValue {
+    use serde_json::Value as Json;
+
+    match json {
+        Json::Null => NodeValue::String(String::new()),
+        Json::Bool(b) => NodeValue::Bool(*b),
+        Json::Number(n) => {
+            if let Some(v) = n.as_u64().and_then(|v| u32::try_from(v).ok()) {
+                NodeValue::U32(v)
+            } else if let Some(v) = n.as_i64().and_then(|v| i32::try_from(v).ok()) {
+                NodeValue::I32(v)
+            } else {
+                NodeValue::F32(n.as_f64().unwrap_or_default() as f32)
+            }
+        }
+        Json::String(s) => NodeValue::String(s.clone()),
+        Json::Array(items) => NodeValue::List(items.iter().map(infer_node_value).collect()),
+        Json::Object(map) => NodeValue::Record(
+            map.iter()
+                .map(|(k, v)| (k.clone(), infer_node_value(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn json_type_name(json: &serde_json::Value) -> &'static str {
+    use serde_json::Value as Json;
+
+    match json {
+        Json::Null => "null",
+        Json::Bool(_) => "bool",
+        Json::Number(_) => "number",
+        Json::String(_) => "string",
+        Json::Array(_) => "list",
+        Json::Object(_) => "record",
+    }
+}
+
+fn join_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person_type() -> DataType {
+        record_from_field_paths(&[
+            ("name".to_string(), DataType::String),
+            ("age".to_string(), DataType::U32),
+            ("address.city".to_string(), DataType::String),
+        ])
+    }
+
+    #[test]
+    fn test_record_from_field_paths_nests_dotted_fields() {
+        assert_eq!(
+            person_type(),
+            DataType::Record(vec![
+                ("name".to_string(), DataType::String),
+                ("age".to_string(), DataType::U32),
+                (
+                    "address".to_string(),
+                    DataType::Record(vec![("city".to_string(), DataType::String)])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_value_allows_extra_fields() {
+        let json = r#"{"name":"Ada","age":36,"address":{"city":"London","zip":"N1"},"extra":true}"#;
+        let value = coerce_to_schema(NodeValue::String(json.to_string()), &person_type(), "person")
+            .unwrap();
+
+        if let NodeValue::Record(fields) = &value {
+            assert_eq!(fields.get("age"), Some(&NodeValue::U32(36)));
+            assert_eq!(fields.get("extra"), Some(&NodeValue::Bool(true)));
+        } else {
+            panic!("expected record, got {:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_validate_value_names_missing_field() {
+        let mut address = BTreeMap::new();
+        address.insert("zip".to_string(), NodeValue::String("N1".to_string()));
+        let mut fields = BTreeMap::new();
+        fields.insert("name".to_string(), NodeValue::String("Ada".to_string()));
+        fields.insert("age".to_string(), NodeValue::U32(36));
+        fields.insert("address".to_string(), NodeValue::Record(address));
+
+        let err = validate_value(&NodeValue::Record(fields), &person_type(), "person").unwrap_err();
+        assert_eq!(
+            err,
+            SchemaError::MissingField {
+                field: "person.address.city".to_string()
+            }
+        );
+        assert_eq!(err.to_string(), "missing field 'person.address.city'");
+    }
+
+    #[test]
+    fn test_coerce_names_mistyped_field() {
+        let json = r#"{"name":"Ada","age":"thirty-six","address":{"city":"London"}}"#;
+        let err = coerce_to_schema(NodeValue::String(json.to_string()), &person_type(), "person")
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            SchemaError::TypeMismatch {
+                field: "person.age".to_string(),
+                expected: "u32".to_string(),
+                found: "string".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_coerce_rejects_invalid_json() {
+        let err = coerce_to_schema(NodeValue::String("not json".to_string()), &person_type(), "person")
+            .unwrap_err();
+        assert!(matches!(err, SchemaError::InvalidEncoding { .. }));
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let mut fields = BTreeMap::new();
+        fields.insert("name".to_string(), NodeValue::String("Ada".to_string()));
+        fields.insert("age".to_string(), NodeValue::U32(36));
+        let mut address = BTreeMap::new();
+        address.insert("city".to_string(), NodeValue::String("London".to_string()));
+        fields.insert("address".to_string(), NodeValue::Record(address));
+        let value = NodeValue::Record(fields);
+
+        let json = node_value_to_json(&value).to_string();
+        let decoded = coerce_to_schema(NodeValue::String(json), &person_type(), "person").unwrap();
+        assert_eq!(decoded, value);
+    }
+}
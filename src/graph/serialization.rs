@@ -1,17 +1,37 @@
 //! Graph serialization/deserialization
 //!
 //! Handles saving and loading NodeGraph structures to/from disk with integrity checking.
+//! Files written by older versions are upgraded by `graph::migration`.
 
 use crate::graph::graph::NodeGraph;
+use crate::graph::migration::{self, ChecksumPolicy, MigrationReport};
+use crate::graph::node::ExecutionState;
+use crate::SerializationError;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Magic bytes for file format identification
-const MAGIC_BYTES: &[u8] = b"WASMFLOW";
+pub(crate) const MAGIC_BYTES: &[u8] = b"WASMFLOW";
 
-/// Current file format version
-const FORMAT_VERSION: u32 = 1;
+/// Current file format version (7: graphs hold canvas annotations)
+pub const FORMAT_VERSION: u32 = 7;
+
+/// CRC64 (ECMA-182) used for save file checksums and content keys
+pub fn crc64(bytes: &[u8]) -> u64 {
+    crc::Crc::<u64>::new(&crc::CRC_64_ECMA_182).checksum(bytes)
+}
+
+/// Write a file through a sibling `.tmp` file and a rename
+///
+/// A crash never leaves a torn or truncated file behind.
+pub fn atomic_write(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)
+}
 
 /// Graph save file format
 #[derive(Serialize, Deserialize)]
@@ -20,6 +40,8 @@ pub struct GraphSaveFormat {
     pub magic: Vec<u8>,
     /// File format version
     pub version: u32,
+    /// WasmFlow version that wrote the file (empty if unknown)
+    pub saved_by: String,
     /// The node graph data
     pub graph: NodeGraph,
     /// CRC64 checksum for integrity verification
@@ -31,11 +53,12 @@ impl GraphSaveFormat {
     pub fn new(graph: NodeGraph) -> Self {
         // Serialize graph to calculate checksum
         let graph_bytes = bincode::serialize(&graph).unwrap_or_default();
-        let checksum = crc::Crc::<u64>::new(&crc::CRC_64_ECMA_182).checksum(&graph_bytes);
+        let checksum = crc64(&graph_bytes);
 
         Self {
             magic: MAGIC_BYTES.to_vec(),
             version: FORMAT_VERSION,
+            saved_by: env!("CARGO_PKG_VERSION").to_string(),
             graph,
             checksum,
         }
@@ -65,14 +88,14 @@ impl GraphSaveFormat {
     pub fn validate_checksum(&self) -> Result<()> {
         let graph_bytes = bincode::serialize(&self.graph)
             .context("Failed to serialize graph for checksum validation")?;
-        let computed_checksum = crc::Crc::<u64>::new(&crc::CRC_64_ECMA_182).checksum(&graph_bytes);
+        let computed_checksum = crc64(&graph_bytes);
 
         if computed_checksum != self.checksum {
-            anyhow::bail!(
-                "Checksum mismatch: file may be corrupted. Expected {}, got {}.",
-                self.checksum,
-                computed_checksum
-            );
+            return Err(SerializationError::ChecksumMismatch {
+                expected: self.checksum,
+                computed: computed_checksum,
+            }
+            .into());
         }
         Ok(())
     }
@@ -108,7 +131,14 @@ impl NodeGraph {
             }
             // T008: Ensure continuous nodes runtime state is reset (already handled by #[serde(skip)])
             // The runtime_state field has #[serde(skip)] so it won't be serialized
+
+            // Files never store in-flight execution
+            if node.execution_state == ExecutionState::Running {
+                node.execution_state = ExecutionState::Idle;
+                node.dirty = true;
+            }
         }
+        graph_to_save.version = FORMAT_VERSION;
 
         let save_format = GraphSaveFormat::new(graph_to_save);
         bincode::serialize(&save_format)
@@ -117,58 +147,38 @@ impl NodeGraph {
 
     /// Deserialize a graph from bytes
     ///
-    /// Validates magic bytes, version compatibility, and checksum integrity
-    /// before returning the deserialized graph.
+    /// Accepts every supported format version; see `from_bytes_with_report`.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        log::info!("Deserializing graph from {} bytes", bytes.len());
+        Self::from_bytes_with_report(bytes).map(|(graph, _)| graph)
+    }
 
-        // Try bincode first
-        let save_format: GraphSaveFormat = match bincode::deserialize(bytes) {
-            Ok(format) => {
-                log::info!("Bincode deserialization successful");
-                format
-            }
-            Err(bincode_err) => {
-                log::warn!("Bincode deserialization failed: {}", bincode_err);
-                log::info!("Attempting JSON fallback for backward compatibility...");
-
-                // Try JSON as fallback for old files
-                match serde_json::from_slice(bytes) {
-                    Ok(format) => {
-                        log::info!("JSON deserialization successful (old format)");
-                        format
-                    }
-                    Err(json_err) => {
-                        log::error!("Both bincode and JSON deserialization failed");
-                        log::error!("Bincode error: {}", bincode_err);
-                        log::error!("JSON error: {}", json_err);
-                        return Err(anyhow::anyhow!(
-                            "Failed to deserialize graph file. File may be corrupted or from an incompatible version.\n\
-                             Bincode error: {}\n\
-                             JSON fallback error: {}",
-                            bincode_err, json_err
-                        ));
-                    }
-                }
-            }
-        };
+    /// Deserialize a graph from bytes, upgrading older formats
+    ///
+    /// Validates magic bytes, version and checksum, migrates the file to the
+    /// current format and validates the graph structure. A checksum mismatch
+    /// fails with `SerializationError::ChecksumMismatch`.
+    pub fn from_bytes_with_report(bytes: &[u8]) -> Result<(Self, MigrationReport)> {
+        Self::decode_bytes(bytes, ChecksumPolicy::Verify)
+    }
 
-        // Validate magic and version
-        save_format.validate_magic()
-            .context("Magic bytes validation failed")?;
-        log::info!("Magic bytes valid");
+    /// Deserialize a graph from bytes even if its checksum does not match
+    ///
+    /// Only for files the user chose to open anyway; the mismatch is listed
+    /// in the report's warnings.
+    pub fn from_bytes_ignoring_checksum(bytes: &[u8]) -> Result<(Self, MigrationReport)> {
+        Self::decode_bytes(bytes, ChecksumPolicy::Ignore)
+    }
 
-        save_format.validate_version()
-            .context("Version validation failed")?;
-        log::info!("Version {} is compatible (current: {})", save_format.version, FORMAT_VERSION);
-
-        // Try checksum validation, but don't fail if it doesn't match
-        // This allows loading older files after code structure changes
-        if let Err(e) = save_format.validate_checksum() {
-            log::warn!("Checksum validation failed (this is OK for older files): {}", e);
-            log::warn!("Loading graph anyway - please re-save to update checksum");
-        } else {
-            log::info!("Checksum valid");
+    fn decode_bytes(bytes: &[u8], checksum: ChecksumPolicy) -> Result<(Self, MigrationReport)> {
+        log::info!("Deserializing graph from {} bytes", bytes.len());
+
+        // Reads the header first, then decodes (and upgrades) the declared version
+        let (save_format, report) = migration::decode(bytes, checksum)?;
+        if report.migrated() {
+            log::info!("Migrated graph file: {}", report.summary());
+        }
+        for warning in &report.warnings {
+            log::warn!("{}", warning);
         }
 
         // Validate graph structure
@@ -190,7 +200,7 @@ impl NodeGraph {
         }
 
         log::info!("Graph structure validation passed");
-        Ok(graph)
+        Ok((graph, report))
     }
 
     /// Save the graph to a file
@@ -202,11 +212,30 @@ impl NodeGraph {
 
     /// Load a graph from a file
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let bytes = std::fs::read(path.as_ref())
-            .with_context(|| format!("Failed to read graph from file: {}", path.as_ref().display()))?;
+        Self::load_from_file_with_report(path).map(|(graph, _)| graph)
+    }
 
-        Self::from_bytes(&bytes)
-            .with_context(|| format!("Failed to load graph from file: {}", path.as_ref().display()))
+    /// Load a graph from a file, reporting any format migration
+    pub fn load_from_file_with_report<P: AsRef<Path>>(path: P) -> Result<(Self, MigrationReport)> {
+        Self::load_file(path.as_ref(), ChecksumPolicy::Verify)
+    }
+
+    /// Load a graph from a file even if its checksum does not match
+    ///
+    /// Only for files the user chose to open anyway after a
+    /// `SerializationError::ChecksumMismatch`.
+    pub fn load_from_file_ignoring_checksum<P: AsRef<Path>>(
+        path: P,
+    ) -> Result<(Self, MigrationReport)> {
+        Self::load_file(path.as_ref(), ChecksumPolicy::Ignore)
+    }
+
+    fn load_file(path: &Path, checksum: ChecksumPolicy) -> Result<(Self, MigrationReport)> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read graph from file: {}", path.display()))?;
+
+        Self::decode_bytes(&bytes, checksum)
+            .with_context(|| format!("Failed to load graph from file: {}", path.display()))
     }
 
     /// Validate the graph structure after deserialization
@@ -311,10 +340,15 @@ mod tests {
         save_format.checksum = 0; // Corrupt checksum
 
         let corrupted_bytes = bincode::serialize(&save_format).unwrap();
-        let result = NodeGraph::from_bytes(&corrupted_bytes);
-
-        // Should succeed with warning (checksum validation is non-fatal for backward compatibility)
-        assert!(result.is_ok(), "Checksum validation should be non-fatal");
+        let err = NodeGraph::from_bytes(&corrupted_bytes).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<SerializationError>(),
+            Some(SerializationError::ChecksumMismatch { expected: 0, .. })
+        ));
+
+        // Loads only when the caller explicitly ignores the mismatch
+        let (_, report) = NodeGraph::from_bytes_ignoring_checksum(&corrupted_bytes).unwrap();
+        assert_eq!(report.warnings.len(), 1);
     }
 
     #[test]
@@ -457,4 +491,16 @@ mod tests {
         // Regular nodes should not have creator data
         assert!(loaded_node.creator_data.is_none());
     }
+
+    #[test]
+    fn test_atomic_write_replaces_file_and_leaves_no_temp() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.wfg");
+        std::fs::write(&path, b"old").unwrap();
+
+        atomic_write(&path, b"new").unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"new");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
 }
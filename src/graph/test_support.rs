@@ -0,0 +1,48 @@
+//! Component specs and graphs shared by the graph module's unit tests
+
+use crate::graph::graph::NodeGraph;
+use crate::graph::node::{ComponentSpec, DataType};
+use uuid::Uuid;
+
+/// F32 constant with a single `value` output
+pub(crate) fn constant_spec() -> ComponentSpec {
+    ComponentSpec::new_builtin(
+        "builtin:constant:f32".to_string(),
+        "F32 Constant".to_string(),
+        "Constant".to_string(),
+        None,
+    )
+    .with_output("value".to_string(), DataType::F32, "Value".to_string())
+}
+
+/// `builtin:math:scale` with `value` and F32 `factor` inputs and an F32 `result` output
+pub(crate) fn scale_spec(value_type: DataType) -> ComponentSpec {
+    ComponentSpec::new_builtin(
+        "builtin:math:scale".to_string(),
+        "Scale".to_string(),
+        "Scale a number".to_string(),
+        None,
+    )
+    .with_input("value".to_string(), value_type, "Value".to_string())
+    .with_input("factor".to_string(), DataType::F32, "Factor".to_string())
+    .with_output("result".to_string(), DataType::F32, "Result".to_string())
+}
+
+/// Two nodes, the first one's first output wired into the second one's first input
+///
+/// The second node is named "Second". Returns (graph, first, second).
+pub(crate) fn linked_graph(
+    first: &ComponentSpec,
+    second: &ComponentSpec,
+) -> (NodeGraph, Uuid, Uuid) {
+    let mut graph = NodeGraph::new("Test".to_string(), "Author".to_string());
+    let first = first.create_node(egui::Pos2::ZERO);
+    let mut second = second.create_node(egui::pos2(200.0, 0.0));
+    second.display_name = "Second".to_string();
+    let (first_id, out) = (first.id, first.outputs[0].id);
+    let (second_id, input) = (second.id, second.inputs[0].id);
+    graph.add_node(first);
+    graph.add_node(second);
+    graph.add_connection(first_id, out, second_id, input).unwrap();
+    (graph, first_id, second_id)
+}
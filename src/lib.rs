@@ -67,6 +67,9 @@ pub enum GraphError {
 
     #[error("Component error: {0}")]
     ComponentError(#[from] ComponentError),
+
+    #[error("Execution cancelled")]
+    Cancelled,
 }
 
 /// Component-specific errors
@@ -89,6 +92,30 @@ pub enum ComponentError {
         node_id: uuid::Uuid,
         capability: String,
     },
+
+    #[error("Output '{port}' does not match its declared schema: {source}")]
+    SchemaViolation {
+        port: String,
+        #[source]
+        source: SchemaError,
+    },
+}
+
+/// Record schema violations (missing or mistyped fields)
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SchemaError {
+    #[error("missing field '{field}'")]
+    MissingField { field: String },
+
+    #[error("field '{field}' expected {expected}, found {found}")]
+    TypeMismatch {
+        field: String,
+        expected: String,
+        found: String,
+    },
+
+    #[error("field '{field}' is not a valid JSON record: {reason}")]
+    InvalidEncoding { field: String, reason: String },
 }
 
 /// Serialization-specific errors
@@ -109,6 +136,53 @@ pub enum SerializationError {
 
     #[error("Bincode error: {0}")]
     Bincode(#[from] bincode::Error),
+
+    #[error("Checksum mismatch: expected {expected:016x}, computed {computed:016x}")]
+    ChecksumMismatch { expected: u64, computed: u64 },
+}
+
+/// Secret vault errors
+#[derive(Debug, thiserror::Error)]
+pub enum SecretError {
+    #[error("Wrong passphrase or damaged vault")]
+    WrongPassphrase,
+
+    #[error("Secret vault is locked")]
+    Locked,
+
+    #[error("Invalid secret name '{0}'")]
+    InvalidName(String),
+
+    #[error("Secret vault {path} is corrupt: {reason}")]
+    Corrupt {
+        path: std::path::PathBuf,
+        reason: String,
+    },
+
+    #[error("Secret vault I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Encryption failed: {0}")]
+    Crypto(String),
+}
+
+/// Graph function library errors
+#[derive(Debug, thiserror::Error)]
+pub enum FunctionError {
+    #[error("Invalid function name '{0}': use letters, digits, '-' and '_'")]
+    InvalidName(String),
+
+    #[error("Function '{0}' would call itself")]
+    Recursive(String),
+
+    #[error("Function file {path} is corrupt: {reason}")]
+    Corrupt {
+        path: std::path::PathBuf,
+        reason: String,
+    },
+
+    #[error("Function library I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 /// Continuous execution errors
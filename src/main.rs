@@ -3,14 +3,31 @@
 //! Main entry point for the desktop application.
 
 use eframe::egui;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use wasmflow::graph::diff;
+use wasmflow::graph::graph::NodeGraph;
+use wasmflow::graph::lint::LintSeverity;
+use wasmflow::graph::node::ComponentRegistry;
 use wasmflow::ui;
 
+/// Git-friendly commands that run without a window
+#[derive(Debug)]
+enum Subcommand {
+    /// Print the semantic differences between two graph files
+    Diff { old: PathBuf, new: PathBuf },
+    /// Three-way merge, written over the "ours" file
+    Merge { base: PathBuf, ours: PathBuf, theirs: PathBuf },
+}
+
 /// T099: Command-line arguments
 #[derive(Debug)]
 struct Args {
+    /// Subcommand to run instead of the application
+    subcommand: Option<Subcommand>,
     /// Graph file to open on startup
     graph_file: Option<PathBuf>,
+    /// Graph file to lint without opening a window
+    check_file: Option<PathBuf>,
     /// Hide the component palette
     no_palette: bool,
     /// Log level (error, warn, info, debug, trace)
@@ -20,7 +37,9 @@ struct Args {
 impl Default for Args {
     fn default() -> Self {
         Self {
+            subcommand: None,
             graph_file: None,
+            check_file: None,
             no_palette: false,
             log_level: "info".to_string(),
         }
@@ -30,7 +49,14 @@ impl Default for Args {
 /// Parse command-line arguments
 fn parse_args() -> Args {
     let mut args = Args::default();
-    let mut iter = std::env::args().skip(1); // Skip program name
+    let mut iter = std::env::args().skip(1).peekable(); // Skip program name
+
+    // Subcommands take file paths as positional arguments
+    let subcommand = match iter.peek().map(String::as_str) {
+        Some("diff") | Some("merge") => iter.next(),
+        _ => None,
+    };
+    let mut paths: Vec<PathBuf> = Vec::new();
 
     while let Some(arg) = iter.next() {
         match arg.as_str() {
@@ -42,6 +68,14 @@ fn parse_args() -> Args {
                     std::process::exit(1);
                 }
             }
+            "--check" => {
+                if let Some(path) = iter.next() {
+                    args.check_file = Some(PathBuf::from(path));
+                } else {
+                    eprintln!("Error: --check requires a file path");
+                    std::process::exit(1);
+                }
+            }
             "--no-palette" => {
                 args.no_palette = true;
             }
@@ -57,6 +91,9 @@ fn parse_args() -> Args {
                 print_help();
                 std::process::exit(0);
             }
+            _ if subcommand.is_some() && !arg.starts_with("--") => {
+                paths.push(PathBuf::from(arg));
+            }
             _ => {
                 eprintln!("Unknown argument: {}", arg);
                 eprintln!("Use --help for usage information");
@@ -65,6 +102,29 @@ fn parse_args() -> Args {
         }
     }
 
+    args.subcommand = match (subcommand.as_deref(), paths.len()) {
+        (None, _) => None,
+        (Some("diff"), 2) => Some(Subcommand::Diff {
+            old: paths[0].clone(),
+            new: paths[1].clone(),
+        }),
+        // As git's diff.<driver>.command: path old-file old-hex old-mode new-file ...
+        (Some("diff"), 7) => Some(Subcommand::Diff {
+            old: paths[1].clone(),
+            new: paths[4].clone(),
+        }),
+        (Some("merge"), 3) => Some(Subcommand::Merge {
+            base: paths[0].clone(),
+            ours: paths[1].clone(),
+            theirs: paths[2].clone(),
+        }),
+        (Some(name), _) => {
+            eprintln!("Error: wrong number of files for '{}'", name);
+            eprintln!("Use --help for usage information");
+            std::process::exit(1);
+        }
+    };
+
     args
 }
 
@@ -74,19 +134,186 @@ fn print_help() {
     println!();
     println!("USAGE:");
     println!("    wasmflow [OPTIONS]");
+    println!("    wasmflow diff <OLD> <NEW>");
+    println!("    wasmflow merge <BASE> <OURS> <THEIRS>");
+    println!();
+    println!("COMMANDS:");
+    println!("    diff                   Print the semantic differences between two graphs");
+    println!("    merge                  Three-way merge into OURS (exit 1 on conflicts)");
     println!();
     println!("OPTIONS:");
     println!("    --graph <FILE>         Open the specified graph file on startup");
+    println!("    --check <FILE>         Lint a graph file and exit (non-zero on warnings)");
     println!("    --no-palette           Hide the component palette panel");
     println!("    --log-level <LEVEL>    Set log level (error, warn, info, debug, trace)");
     println!("    -h, --help             Print this help message");
     println!();
     println!("EXAMPLES:");
     println!("    wasmflow --graph my-graph.wasmflow");
+    println!("    wasmflow --check my-graph.wasmflow --log-level warn");
     println!("    wasmflow --log-level debug");
+    println!("    wasmflow diff old.wasmflow new.wasmflow");
     println!("    RUST_LOG=wasmflow=debug wasmflow");
 }
 
+/// Components available to a headless run: builtins, graph functions and
+/// the WASM components in components/bin
+fn headless_registry() -> ComponentRegistry {
+    let mut registry = ComponentRegistry::new();
+    wasmflow::builtin::register_constant_nodes(&mut registry);
+    wasmflow::builtin::register_wasm_creator_node(&mut registry);
+    wasmflow::builtin::register_continuous_example(&mut registry);
+
+    let function_root = wasmflow::graph::function::GraphFunctionLibrary::default_root();
+    match wasmflow::graph::function::GraphFunctionLibrary::load(&function_root) {
+        Ok(library) => {
+            for spec in library.component_specs() {
+                if let Err(e) = registry.register_component(spec) {
+                    log::warn!("Failed to register graph function: {}", e);
+                }
+            }
+        }
+        Err(e) => log::warn!("Failed to load graph functions: {}", e),
+    }
+
+    let components_dir = Path::new("components/bin");
+    let Ok(entries) = std::fs::read_dir(components_dir) else {
+        return registry;
+    };
+    let mut manager = match wasmflow::runtime::wasm_host::ComponentManager::new() {
+        Ok(manager) => manager,
+        Err(e) => {
+            log::warn!("Failed to create component manager: {}", e);
+            return registry;
+        }
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().and_then(|s| s.to_str()) != Some("wasm") {
+            continue;
+        }
+        match manager.load_component_sync(&path) {
+            Ok(specs) => {
+                for spec in specs {
+                    if let Err(e) = registry.register_component(spec) {
+                        log::warn!("Failed to register component {}: {}", path.display(), e);
+                    }
+                }
+            }
+            Err(e) => log::warn!("Failed to load component {}: {}", path.display(), e),
+        }
+    }
+    registry
+}
+
+/// Lint a graph file and print the findings
+///
+/// Returns the exit code: 0 without warnings, 1 with warnings, 2 if the
+/// file could not be loaded.
+fn run_check(path: &Path) -> i32 {
+    let graph = match NodeGraph::load_from_file(path) {
+        Ok(graph) => graph,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            return 2;
+        }
+    };
+
+    let registry = headless_registry();
+    let report = graph.lint(Some(&registry));
+    for finding in &report.findings {
+        let level = match finding.severity() {
+            LintSeverity::Warning => "warning",
+            LintSeverity::Info => "info",
+        };
+        let node = finding
+            .node_id
+            .map(|id| format!(" [{}]", id))
+            .unwrap_or_default();
+        println!("{}[{}]{}: {}", level, finding.rule.name(), node, finding.message);
+    }
+
+    let warnings = report.warning_count();
+    println!(
+        "{}: {} warning(s), {} info",
+        path.display(),
+        warnings,
+        report.findings.len() - warnings
+    );
+    if warnings > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Load a graph for diffing; git passes /dev/null for added or deleted files
+fn load_for_diff(path: &Path) -> anyhow::Result<Option<NodeGraph>> {
+    if path == Path::new("/dev/null") {
+        return Ok(None);
+    }
+    Ok(Some(NodeGraph::load_from_file(path)?))
+}
+
+/// Print the differences between two graph files
+///
+/// Returns the exit code: 0 on success, 2 if a file could not be loaded.
+fn run_diff(old: &Path, new: &Path) -> i32 {
+    let (old_graph, new_graph) = match (load_for_diff(old), load_for_diff(new)) {
+        (Ok(old_graph), Ok(new_graph)) => (old_graph, new_graph),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error: {:#}", e);
+            return 2;
+        }
+    };
+    // A missing side is an empty graph with the other side's name
+    let name = old_graph
+        .as_ref()
+        .or(new_graph.as_ref())
+        .map(|graph| graph.name.clone())
+        .unwrap_or_default();
+    let empty = || NodeGraph::new(name.clone(), String::new());
+    let diff = old_graph
+        .unwrap_or_else(empty)
+        .diff(&new_graph.unwrap_or_else(empty));
+
+    println!("--- {}", old.display());
+    println!("+++ {}", new.display());
+    print!("{}", diff);
+    if diff.is_empty() {
+        println!("(no changes)");
+    }
+    0
+}
+
+/// Merge `theirs` into `ours` using `base` as the common ancestor
+///
+/// Returns the exit code: 0 for a clean merge, 1 with conflicts (the file is
+/// still written, keeping our side of each conflict), 2 on errors.
+fn run_merge(base: &Path, ours: &Path, theirs: &Path) -> i32 {
+    let load = |path: &Path| NodeGraph::load_from_file(path);
+    let (base_graph, ours_graph, theirs_graph) = match (load(base), load(ours), load(theirs)) {
+        (Ok(b), Ok(o), Ok(t)) => (b, o, t),
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+            eprintln!("Error: {:#}", e);
+            return 2;
+        }
+    };
+
+    let result = diff::merge(&base_graph, &ours_graph, &theirs_graph);
+    if let Err(e) = result.graph.save_to_file(ours) {
+        eprintln!("Error: {:#}", e);
+        return 2;
+    }
+    for conflict in &result.conflicts {
+        eprintln!("CONFLICT: {}", conflict.message);
+    }
+    if result.is_clean() {
+        0
+    } else {
+        1
+    }
+}
+
 fn main() -> Result<(), eframe::Error> {
     // T099: Parse command-line arguments
     let args = parse_args();
@@ -99,6 +326,17 @@ fn main() -> Result<(), eframe::Error> {
     log::info!("Starting WasmFlow v{}", env!("CARGO_PKG_VERSION"));
     log::debug!("Arguments: {:?}", args);
 
+    if let Some(path) = &args.check_file {
+        std::process::exit(run_check(path));
+    }
+    match &args.subcommand {
+        Some(Subcommand::Diff { old, new }) => std::process::exit(run_diff(old, new)),
+        Some(Subcommand::Merge { base, ours, theirs }) => {
+            std::process::exit(run_merge(base, ours, theirs))
+        }
+        None => {}
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1280.0, 720.0])
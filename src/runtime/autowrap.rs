@@ -0,0 +1,360 @@
+//! Auto-wrapping of plain WIT components
+//!
+//! Components that don't implement the `wasmflow:node` contract can still be
+//! loaded if they export plain functions whose parameter and result types map
+//! onto node values. Each such function becomes its own node: parameters
+//! become input ports and results become output ports. The function is
+//! called dynamically through `wasmtime::component::Func`.
+
+use crate::graph::node::{DataType, NodeValue};
+use wasmtime::component::types::{ComponentFunc, ComponentItem, Type};
+use wasmtime::component::{Component as WasmComponent, ComponentExportIndex, Val};
+use wasmtime::Engine;
+
+/// WIT types that can cross a plain function boundary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlainType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    S8,
+    S16,
+    S32,
+    F32,
+    F64,
+    Char,
+    String,
+    /// `list<u8>`, mapped to binary data
+    Bytes,
+    StringList,
+    U32List,
+    F32List,
+}
+
+impl PlainType {
+    /// Map a WIT type, or None if it has no node representation
+    pub fn from_wit(ty: &Type) -> Option<Self> {
+        Some(match ty {
+            Type::Bool => PlainType::Bool,
+            Type::U8 => PlainType::U8,
+            Type::U16 => PlainType::U16,
+            Type::U32 => PlainType::U32,
+            Type::S8 => PlainType::S8,
+            Type::S16 => PlainType::S16,
+            Type::S32 => PlainType::S32,
+            Type::Float32 => PlainType::F32,
+            Type::Float64 => PlainType::F64,
+            Type::Char => PlainType::Char,
+            Type::String => PlainType::String,
+            Type::List(list) => match list.ty() {
+                Type::U8 => PlainType::Bytes,
+                Type::String => PlainType::StringList,
+                Type::U32 => PlainType::U32List,
+                Type::Float32 => PlainType::F32List,
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
+
+    /// Port data type for this WIT type
+    pub fn data_type(&self) -> DataType {
+        match self {
+            PlainType::Bool => DataType::Bool,
+            PlainType::U8 | PlainType::U16 | PlainType::U32 => DataType::U32,
+            PlainType::S8 | PlainType::S16 | PlainType::S32 => DataType::I32,
+            PlainType::F32 | PlainType::F64 => DataType::F32,
+            PlainType::Char | PlainType::String => DataType::String,
+            PlainType::Bytes => DataType::Binary,
+            PlainType::StringList => DataType::List(Box::new(DataType::String)),
+            PlainType::U32List => DataType::List(Box::new(DataType::U32)),
+            PlainType::F32List => DataType::List(Box::new(DataType::F32)),
+        }
+    }
+
+    /// Convert a node value into a WIT value of this type
+    pub fn to_val(&self, value: &NodeValue) -> Result<Val, String> {
+        let val = match (self, value) {
+            (PlainType::Bool, NodeValue::Bool(b)) => Val::Bool(*b),
+            (PlainType::U8, NodeValue::U32(v)) => Val::U8(narrow(*v)?),
+            (PlainType::U16, NodeValue::U32(v)) => Val::U16(narrow(*v)?),
+            (PlainType::U32, NodeValue::U32(v)) => Val::U32(*v),
+            (PlainType::S8, NodeValue::I32(v)) => Val::S8(narrow(*v)?),
+            (PlainType::S16, NodeValue::I32(v)) => Val::S16(narrow(*v)?),
+            (PlainType::S32, NodeValue::I32(v)) => Val::S32(*v),
+            (PlainType::F32, NodeValue::F32(v)) => Val::Float32(*v),
+            (PlainType::F64, NodeValue::F32(v)) => Val::Float64(*v as f64),
+            (PlainType::Char, NodeValue::String(s)) => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Val::Char(c),
+                    _ => return Err(format!("expected a single character, got {:?}", s)),
+                }
+            }
+            (PlainType::String, NodeValue::String(s)) => Val::String(s.clone()),
+            (PlainType::Bytes, NodeValue::Binary(bytes)) => {
+                Val::List(bytes.iter().map(|b| Val::U8(*b)).collect())
+            }
+            (PlainType::StringList, NodeValue::List(items)) => Val::List(
+                items
+                    .iter()
+                    .map(|item| PlainType::String.to_val(item))
+                    .collect::<Result<_, _>>()?,
+            ),
+            (PlainType::U32List, NodeValue::List(items)) => Val::List(
+                items
+                    .iter()
+                    .map(|item| PlainType::U32.to_val(item))
+                    .collect::<Result<_, _>>()?,
+            ),
+            (PlainType::F32List, NodeValue::List(items)) => Val::List(
+                items
+                    .iter()
+                    .map(|item| PlainType::F32.to_val(item))
+                    .collect::<Result<_, _>>()?,
+            ),
+            (expected, found) => {
+                return Err(format!(
+                    "expected {}, got {}",
+                    expected.data_type().name(),
+                    found.type_name()
+                ))
+            }
+        };
+        Ok(val)
+    }
+
+    /// Convert a WIT value of this type into a node value
+    pub fn from_val(&self, val: &Val) -> Result<NodeValue, String> {
+        let value = match (self, val) {
+            (PlainType::Bool, Val::Bool(b)) => NodeValue::Bool(*b),
+            (PlainType::U8, Val::U8(v)) => NodeValue::U32(*v as u32),
+            (PlainType::U16, Val::U16(v)) => NodeValue::U32(*v as u32),
+            (PlainType::U32, Val::U32(v)) => NodeValue::U32(*v),
+            (PlainType::S8, Val::S8(v)) => NodeValue::I32(*v as i32),
+            (PlainType::S16, Val::S16(v)) => NodeValue::I32(*v as i32),
+            (PlainType::S32, Val::S32(v)) => NodeValue::I32(*v),
+            (PlainType::F32, Val::Float32(v)) => NodeValue::F32(*v),
+            (PlainType::F64, Val::Float64(v)) => NodeValue::F32(*v as f32),
+            (PlainType::Char, Val::Char(c)) => NodeValue::String(c.to_string()),
+            (PlainType::String, Val::String(s)) => NodeValue::String(s.clone()),
+            (PlainType::Bytes, Val::List(items)) => NodeValue::Binary(
+                items
+                    .iter()
+                    .map(|item| match item {
+                        Val::U8(b) => Ok(*b),
+                        other => Err(format!("expected u8 list item, got {:?}", other)),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            (PlainType::StringList, Val::List(items)) => NodeValue::List(
+                items
+                    .iter()
+                    .map(|item| PlainType::String.from_val(item))
+                    .collect::<Result<_, _>>()?,
+            ),
+            (PlainType::U32List, Val::List(items)) => NodeValue::List(
+                items
+                    .iter()
+                    .map(|item| PlainType::U32.from_val(item))
+                    .collect::<Result<_, _>>()?,
+            ),
+            (PlainType::F32List, Val::List(items)) => NodeValue::List(
+                items
+                    .iter()
+                    .map(|item| PlainType::F32.from_val(item))
+                    .collect::<Result<_, _>>()?,
+            ),
+            (expected, other) => {
+                return Err(format!(
+                    "expected {} result, got {:?}",
+                    expected.data_type().name(),
+                    other
+                ))
+            }
+        };
+        Ok(value)
+    }
+}
+
+fn narrow<T: TryInto<U> + std::fmt::Display + Copy, U>(value: T) -> Result<U, String> {
+    value
+        .try_into()
+        .map_err(|_| format!("value {} is out of range", value))
+}
+
+/// A plain exported function that can be wrapped as a node
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlainFunction {
+    /// Exporting interface, or None for a function exported at the top level
+    pub interface: Option<String>,
+    /// Function name within the interface
+    pub name: String,
+    /// Parameter names and types, in call order
+    pub params: Vec<(String, PlainType)>,
+    /// Result port names and types, in return order
+    pub results: Vec<(String, PlainType)>,
+}
+
+impl PlainFunction {
+    /// Build from a function type, or explain which type is unsupported
+    fn from_func_type(
+        interface: Option<&str>,
+        name: &str,
+        func: &ComponentFunc,
+    ) -> Result<Self, String> {
+        let params = func
+            .params()
+            .map(|(param, ty)| {
+                PlainType::from_wit(&ty)
+                    .map(|plain| (param.to_string(), plain))
+                    .ok_or_else(|| format!("parameter '{}' has unsupported type {:?}", param, ty))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let result_types: Vec<Type> = func.results().collect();
+        let result_count = result_types.len();
+        let results = result_types
+            .iter()
+            .enumerate()
+            .map(|(idx, ty)| {
+                PlainType::from_wit(ty)
+                    .map(|plain| (result_port_name(idx, result_count), plain))
+                    .ok_or_else(|| format!("result has unsupported type {:?}", ty))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            interface: interface.map(str::to_string),
+            name: name.to_string(),
+            params,
+            results,
+        })
+    }
+
+    /// Fully qualified export name (e.g. `docs:adder/add@0.1.0#add`)
+    pub fn export_name(&self) -> String {
+        match &self.interface {
+            Some(interface) => format!("{}#{}", interface, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Short name used in component IDs (interface name without package or version)
+    pub fn short_name(&self) -> String {
+        match &self.interface {
+            Some(interface) => format!("{}.{}", short_interface_name(interface), self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Look up the function's export index in a compiled component
+    pub fn export_index(&self, component: &WasmComponent) -> Option<ComponentExportIndex> {
+        let parent = match &self.interface {
+            Some(interface) => Some(component.export_index(None, interface)?.1),
+            None => None,
+        };
+        component
+            .export_index(parent.as_ref(), &self.name)
+            .map(|(_, index)| index)
+    }
+}
+
+/// Result ports are named `result`, or `result0`, `result1`, ... for several results
+fn result_port_name(idx: usize, count: usize) -> String {
+    if count == 1 {
+        "result".to_string()
+    } else {
+        format!("result{}", idx)
+    }
+}
+
+/// `docs:adder/add@0.1.0` -> `add`
+fn short_interface_name(interface: &str) -> &str {
+    let without_version = interface.split('@').next().unwrap_or(interface);
+    without_version
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or(without_version)
+}
+
+/// Find every exported function with supported types
+///
+/// Functions exported at the top level and inside exported interfaces are
+/// both considered. Functions with unsupported types are skipped and logged.
+pub fn introspect(engine: &Engine, component: &WasmComponent) -> Vec<PlainFunction> {
+    let mut functions = Vec::new();
+    let mut consider = |interface: Option<&str>, name: &str, func: &ComponentFunc| {
+        match PlainFunction::from_func_type(interface, name, func) {
+            Ok(function) => functions.push(function),
+            Err(reason) => log::debug!(
+                "Skipping export {}{}: {}",
+                interface.map(|i| format!("{}#", i)).unwrap_or_default(),
+                name,
+                reason
+            ),
+        }
+    };
+
+    let component_type = component.component_type();
+    for (export_name, item) in component_type.exports(engine) {
+        match item {
+            ComponentItem::ComponentFunc(func) => consider(None, export_name, &func),
+            ComponentItem::ComponentInstance(instance) => {
+                for (func_name, inner) in instance.exports(engine) {
+                    if let ComponentItem::ComponentFunc(func) = inner {
+                        consider(Some(export_name), func_name, &func);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    functions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_interface_name() {
+        assert_eq!(short_interface_name("docs:adder/add@0.1.0"), "add");
+        assert_eq!(short_interface_name("math"), "math");
+    }
+
+    #[test]
+    fn test_result_port_names() {
+        assert_eq!(result_port_name(0, 1), "result");
+        assert_eq!(result_port_name(1, 2), "result1");
+    }
+
+    #[test]
+    fn test_value_round_trip() {
+        let cases = [
+            (PlainType::U32, NodeValue::U32(7)),
+            (PlainType::S32, NodeValue::I32(-3)),
+            (PlainType::F32, NodeValue::F32(1.5)),
+            (PlainType::Bool, NodeValue::Bool(true)),
+            (PlainType::String, NodeValue::String("hi".to_string())),
+            (PlainType::Bytes, NodeValue::Binary(vec![1, 2, 3])),
+            (
+                PlainType::StringList,
+                NodeValue::List(vec![NodeValue::String("a".to_string())]),
+            ),
+        ];
+        for (ty, value) in cases {
+            let val = ty.to_val(&value).unwrap();
+            assert_eq!(ty.from_val(&val).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_narrowing_is_checked() {
+        assert!(PlainType::U8.to_val(&NodeValue::U32(300)).is_err());
+        assert!(PlainType::U8.to_val(&NodeValue::U32(200)).is_ok());
+        assert!(PlainType::U32.to_val(&NodeValue::String("1".to_string())).is_err());
+    }
+}
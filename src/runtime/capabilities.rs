@@ -26,6 +26,10 @@ pub enum Capability {
     TimeAccess,
     /// Access cryptographic random number generation
     CryptoRandom,
+    /// Use the node's private key-value store
+    KeyValue,
+    /// Read named secrets from the local vault
+    Secrets,
 }
 
 impl Capability {
@@ -40,6 +44,8 @@ impl Capability {
             Capability::EnvAccess => "Read environment variables",
             Capability::TimeAccess => "Access system time and clocks",
             Capability::CryptoRandom => "Generate cryptographic random numbers",
+            Capability::KeyValue => "Store data in the node's private key-value store",
+            Capability::Secrets => "Read named secrets from the local vault",
         }
     }
 
@@ -49,10 +55,13 @@ impl Capability {
             Capability::FileWrite | Capability::ProcessSpawn | Capability::NetworkSocket => {
                 RiskLevel::High
             }
-            Capability::FileRead | Capability::NetworkHttp | Capability::EnvAccess => {
-                RiskLevel::Medium
+            Capability::FileRead
+            | Capability::NetworkHttp
+            | Capability::EnvAccess
+            | Capability::Secrets => RiskLevel::Medium,
+            Capability::TimeAccess | Capability::CryptoRandom | Capability::KeyValue => {
+                RiskLevel::Low
             }
-            Capability::TimeAccess | Capability::CryptoRandom => RiskLevel::Low,
         }
     }
 }
@@ -82,6 +91,14 @@ pub enum CapabilitySet {
     Network { allowed_hosts: Vec<String> },
     /// Unrestricted access (requires explicit warning)
     Full,
+    // Bincode stores the variant index: add new variants below, never above
+    /// Access to the node's private key-value store, on top of another set
+    KeyValue { base: Box<CapabilitySet> },
+    /// Access to the named vault secrets, on top of another set
+    Secrets {
+        names: Vec<String>,
+        base: Box<CapabilitySet>,
+    },
 }
 
 impl CapabilitySet {
@@ -110,6 +127,46 @@ impl CapabilitySet {
         CapabilitySet::Network { allowed_hosts }
     }
 
+    /// Add key-value store access to this capability set
+    pub fn with_key_value(self) -> Self {
+        match self {
+            CapabilitySet::Full | CapabilitySet::KeyValue { .. } => self,
+            base => CapabilitySet::KeyValue {
+                base: Box::new(base),
+            },
+        }
+    }
+
+    /// Add access to the named secrets to this capability set
+    pub fn with_secrets(self, names: Vec<String>) -> Self {
+        let (mut names, base) = match self {
+            CapabilitySet::Full => return self,
+            CapabilitySet::Secrets {
+                names: mut existing,
+                base,
+            } => {
+                existing.extend(names);
+                (existing, base)
+            }
+            base => (names, Box::new(base)),
+        };
+        names.sort();
+        names.dedup();
+        CapabilitySet::Secrets { names, base }
+    }
+
+    /// Check if the named secret may be read
+    pub fn allows_secret(&self, name: &str) -> bool {
+        match self {
+            CapabilitySet::Full => true,
+            CapabilitySet::Secrets { names, base } => {
+                names.iter().any(|granted| granted == name) || base.allows_secret(name)
+            }
+            CapabilitySet::KeyValue { base } => base.allows_secret(name),
+            _ => false,
+        }
+    }
+
     /// Create full capability set (dangerous)
     pub fn full() -> Self {
         CapabilitySet::Full
@@ -123,6 +180,10 @@ impl CapabilitySet {
             (CapabilitySet::FileWrite { .. }, Capability::FileWrite) => true,
             (CapabilitySet::FileReadWrite { .. }, Capability::FileRead | Capability::FileWrite) => true,
             (CapabilitySet::Network { .. }, Capability::NetworkHttp) => true,
+            (CapabilitySet::KeyValue { .. }, Capability::KeyValue) => true,
+            (CapabilitySet::KeyValue { base }, capability) => base.has(capability),
+            (CapabilitySet::Secrets { .. }, Capability::Secrets) => true,
+            (CapabilitySet::Secrets { base, .. }, capability) => base.has(capability),
             (CapabilitySet::Full, _) => true,
             _ => false,
         }
@@ -136,6 +197,10 @@ impl CapabilitySet {
             CapabilitySet::FileWrite { .. } => Some(RiskLevel::High),
             CapabilitySet::FileReadWrite { .. } => Some(RiskLevel::High),
             CapabilitySet::Network { .. } => Some(RiskLevel::Medium),
+            CapabilitySet::KeyValue { base } => base.max_risk_level().max(Some(RiskLevel::Low)),
+            CapabilitySet::Secrets { base, .. } => {
+                base.max_risk_level().max(Some(RiskLevel::Medium))
+            }
             CapabilitySet::Full => Some(RiskLevel::High),
         }
     }
@@ -156,6 +221,14 @@ impl CapabilitySet {
             CapabilitySet::Network { allowed_hosts } => {
                 format!("Network access to: {}", allowed_hosts.join(", "))
             }
+            CapabilitySet::KeyValue { base } => match base.as_ref() {
+                CapabilitySet::None => "Private key-value store".to_string(),
+                base => format!("{}; private key-value store", base.description()),
+            },
+            CapabilitySet::Secrets { names, base } => match base.as_ref() {
+                CapabilitySet::None => format!("Secrets: {}", names.join(", ")),
+                base => format!("{}; secrets: {}", base.description(), names.join(", ")),
+            },
             CapabilitySet::Full => "Full system access (all capabilities)".to_string(),
         }
     }
@@ -200,9 +273,28 @@ impl CapabilityGrant {
 
     /// Check if this grant satisfies the required capabilities
     pub fn satisfies(&self, required: &CapabilitySet) -> bool {
-        match (&self.capability_set, required) {
+        Self::set_satisfies(&self.capability_set, required)
+    }
+
+    fn set_satisfies(granted: &CapabilitySet, required: &CapabilitySet) -> bool {
+        match (granted, required) {
             (_, CapabilitySet::None) => true,
             (CapabilitySet::Full, _) => true,
+            // Key-value access must be granted explicitly, the rest is checked on the base
+            (CapabilitySet::KeyValue { base: granted }, CapabilitySet::KeyValue { base: required }) => {
+                Self::set_satisfies(granted, required)
+            }
+            // Every required secret must be granted by name
+            (
+                CapabilitySet::Secrets { names: granted_names, base: granted },
+                CapabilitySet::Secrets { names: required_names, base: required },
+            ) => {
+                required_names.iter().all(|name| granted_names.contains(name))
+                    && Self::set_satisfies(granted, required)
+            }
+            (_, CapabilitySet::KeyValue { .. } | CapabilitySet::Secrets { .. }) => false,
+            (CapabilitySet::KeyValue { base }, required) => Self::set_satisfies(base, required),
+            (CapabilitySet::Secrets { base, .. }, required) => Self::set_satisfies(base, required),
             (CapabilitySet::None, _) => false,
             (granted, req) => {
                 // Both must be the same variant type
@@ -218,6 +310,13 @@ impl CapabilityGrant {
     pub fn to_wasi_ctx(&self) -> Result<wasmtime_wasi::WasiCtx, anyhow::Error> {
         use wasmtime_wasi::WasiCtxBuilder;
 
+        // Key-value and secret access need no WASI configuration
+        if let CapabilitySet::KeyValue { base } | CapabilitySet::Secrets { base, .. } =
+            &self.capability_set
+        {
+            return CapabilityGrant::new(self.node_id, base.as_ref().clone()).to_wasi_ctx();
+        }
+
         let mut builder = WasiCtxBuilder::new();
 
         match &self.capability_set {
@@ -248,6 +347,9 @@ impl CapabilityGrant {
                 // T070: Network capability enforcement
                 builder.inherit_network();
             }
+            CapabilitySet::KeyValue { .. } | CapabilitySet::Secrets { .. } => {
+                // Handled above
+            }
             CapabilitySet::Full => {
                 // Full access - inherit everything
                 builder
@@ -362,4 +464,64 @@ mod tests {
         let full_grant = CapabilityGrant::new(node_id, CapabilitySet::full());
         assert!(full_grant.satisfies(&CapabilitySet::file_read(vec![PathBuf::from("/any")])));
     }
+
+    #[test]
+    fn test_key_value_composes_with_base() {
+        use uuid::Uuid;
+
+        let network = CapabilitySet::network(vec!["example.com".to_string()]);
+        let kv_network = network.clone().with_key_value();
+        assert!(kv_network.has(Capability::KeyValue));
+        assert!(kv_network.has(Capability::NetworkHttp));
+        assert!(!network.has(Capability::KeyValue));
+        assert_eq!(kv_network.max_risk_level(), Some(RiskLevel::Medium));
+        assert_eq!(CapabilitySet::none().with_key_value().max_risk_level(), Some(RiskLevel::Low));
+        assert_eq!(CapabilitySet::full().with_key_value(), CapabilitySet::Full);
+
+        let grant = CapabilityGrant::new(Uuid::new_v4(), kv_network.clone());
+        assert!(grant.satisfies(&kv_network));
+        assert!(grant.satisfies(&network));
+        assert!(grant.satisfies(&CapabilitySet::none().with_key_value()));
+
+        let plain_grant = CapabilityGrant::new(Uuid::new_v4(), network);
+        assert!(!plain_grant.satisfies(&kv_network));
+    }
+
+    #[test]
+    fn test_variant_encoding_is_stable() {
+        // `Full` as the first release encoded it
+        let full: CapabilitySet = bincode::deserialize(&[5, 0, 0, 0]).unwrap();
+        assert_eq!(full, CapabilitySet::Full);
+
+        let network = CapabilitySet::network(vec!["example.com".to_string()]);
+        assert_eq!(bincode::serialize(&network).unwrap()[..4], [4, 0, 0, 0]);
+        let kv = CapabilitySet::None.with_key_value();
+        assert_eq!(bincode::serialize(&kv).unwrap()[..4], [6, 0, 0, 0]);
+        let secrets = CapabilitySet::None.with_secrets(vec!["token".to_string()]);
+        assert_eq!(bincode::serialize(&secrets).unwrap()[..4], [7, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_secrets_are_granted_by_name() {
+        use uuid::Uuid;
+
+        let network = CapabilitySet::network(vec!["api.example.com".to_string()]);
+        let with_key = network
+            .clone()
+            .with_secrets(vec!["api-key".to_string()]);
+        assert!(with_key.has(Capability::Secrets));
+        assert!(with_key.has(Capability::NetworkHttp));
+        assert!(with_key.allows_secret("api-key"));
+        assert!(!with_key.allows_secret("other"));
+        assert!(!network.allows_secret("api-key"));
+        assert!(CapabilitySet::full().allows_secret("api-key"));
+
+        let both = with_key.clone().with_secrets(vec!["other".to_string()]);
+        assert!(both.allows_secret("api-key") && both.allows_secret("other"));
+
+        let grant = CapabilityGrant::new(Uuid::new_v4(), with_key.clone());
+        assert!(grant.satisfies(&network));
+        assert!(grant.satisfies(&with_key));
+        assert!(!grant.satisfies(&both));
+    }
 }
@@ -0,0 +1,89 @@
+//! Content-addressed copies of component binaries
+//!
+//! Every user component the app loads is copied to
+//! `<root>/<content-hash>/<file-name>`, so a node pinned to a binary can keep
+//! running it after the file in `components/bin` is replaced. Keeping the
+//! original file name means the copy loads under the same component id.
+
+use crate::graph::pin::content_hash;
+use crate::graph::serialization::atomic_write;
+use std::path::{Path, PathBuf};
+
+/// Directory of component binaries keyed by content hash
+#[derive(Debug, Clone)]
+pub struct ComponentStore {
+    root: PathBuf,
+}
+
+impl ComponentStore {
+    /// Create a store rooted at `root` (created on first write)
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Default location in the user's data directory
+    pub fn default_root() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("wasmflow")
+            .join("components")
+    }
+
+    /// Root directory of the store
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Keep a copy of a binary loaded from `source`, returning its hash
+    ///
+    /// Storing a binary that is already present is a no-op.
+    pub fn put(&self, bytes: &[u8], source: &Path) -> std::io::Result<String> {
+        let hash = content_hash(bytes);
+        if self.find(&hash).is_some() {
+            return Ok(hash);
+        }
+
+        let file_name = source
+            .file_name()
+            .ok_or_else(|| std::io::Error::other("Component path has no file name"))?;
+        let dir = self.root.join(&hash);
+        std::fs::create_dir_all(&dir)?;
+        atomic_write(&dir.join(file_name), bytes)?;
+        Ok(hash)
+    }
+
+    /// Stored binary with this hash, if any
+    pub fn find(&self, hash: &str) -> Option<PathBuf> {
+        std::fs::read_dir(self.root.join(hash))
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|path| path.extension().and_then(|s| s.to_str()) == Some("wasm"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_and_find_by_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ComponentStore::new(dir.path());
+        let source = Path::new("components/bin/json_parser.wasm");
+
+        assert_eq!(store.find(&content_hash(b"v1")), None);
+        let hash = store.put(b"v1", source).unwrap();
+        assert_eq!(hash, content_hash(b"v1"));
+        let path = store.find(&hash).unwrap();
+        assert_eq!(path.file_name().unwrap(), "json_parser.wasm");
+        assert_eq!(std::fs::read(&path).unwrap(), b"v1");
+
+        // Same bytes are stored once; new bytes get their own entry
+        assert_eq!(store.put(b"v1", Path::new("elsewhere/renamed.wasm")).unwrap(), hash);
+        assert_eq!(store.find(&hash).unwrap(), path);
+        let other = store.put(b"v2", source).unwrap();
+        assert_ne!(other, hash);
+        assert_eq!(std::fs::read(store.find(&other).unwrap()).unwrap(), b"v2");
+    }
+}
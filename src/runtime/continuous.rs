@@ -3,7 +3,7 @@
 //! Manages lifecycle of continuous nodes that run indefinitely until stopped.
 //! Uses background threads with tokio runtimes for async execution.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
@@ -13,21 +13,76 @@ use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 use crate::graph::{NodeValue, NodeGraph};
-use crate::runtime::wasm_host::ComponentManager;
-use crate::ContinuousNodeError;
+use crate::runtime::capabilities::CapabilitySet;
+use crate::runtime::kv::KvScope;
+use crate::runtime::progress::{ExecutionControl, ExecutionProgress};
+use crate::runtime::wasm_host::{ComponentManager, ContinuousSession};
+use crate::{ComponentError, ContinuousNodeError};
 
 // Helper function to safely read interval from graph
-fn read_interval_from_graph(graph: &Arc<Mutex<NodeGraph>>, node_id: Uuid) -> u64 {
+fn read_interval_from_graph(graph: &Arc<Mutex<NodeGraph>>, node_id: Uuid) -> Option<u64> {
     if let Ok(graph_lock) = graph.lock() {
         if let Some(node) = graph_lock.nodes.get(&node_id) {
             if let Some(interval_port) = node.inputs.iter().find(|p| p.name == "interval") {
                 if let Some(NodeValue::U32(interval)) = &interval_port.current_value {
-                    return *interval as u64;
+                    return Some(*interval as u64);
                 }
             }
         }
     }
-    100 // Default 100ms
+    None
+}
+
+/// Display name of a node, for error reports
+fn read_display_name(graph: &Arc<Mutex<NodeGraph>>, node_id: Uuid) -> String {
+    graph
+        .lock()
+        .ok()
+        .and_then(|graph_lock| graph_lock.nodes.get(&node_id).map(|n| n.display_name.clone()))
+        .unwrap_or_default()
+}
+
+/// Default tick interval when neither an input nor the component sets one
+const DEFAULT_INTERVAL_MS: u64 = 100;
+
+/// How often progress is forwarded while a tick is running
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Collect a node's input values: connected outputs first, then unconnected port values
+fn collect_inputs(graph_lock: &NodeGraph, node_id: Uuid) -> HashMap<String, NodeValue> {
+    let mut inputs = HashMap::new();
+
+    // Find all incoming connections to this node
+    for connection in graph_lock.connections.iter() {
+        if connection.to_node == node_id {
+            // Get the source node's output value
+            if let Some(source_node) = graph_lock.nodes.get(&connection.from_node) {
+                if let Some(source_port) = source_node.outputs.iter().find(|p| p.id == connection.from_port) {
+                    if let Some(value) = &source_port.current_value {
+                        // Map to target input port name
+                        if let Some(target_node) = graph_lock.nodes.get(&node_id) {
+                            if let Some(target_port) = target_node.inputs.iter().find(|p| p.id == connection.to_port) {
+                                inputs.insert(target_port.name.clone(), value.clone());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // For unconnected inputs, use default values from the port
+    if let Some(node) = graph_lock.nodes.get(&node_id) {
+        for port in &node.inputs {
+            if !inputs.contains_key(&port.name) {
+                if let Some(value) = &port.current_value {
+                    inputs.insert(port.name.clone(), value.clone());
+                }
+            }
+        }
+    }
+
+    inputs
 }
 
 /// Commands sent from UI thread to execution manager
@@ -71,6 +126,11 @@ pub enum ExecutionResult {
         iteration: u64,
         duration: Duration,
     },
+    /// The component reported progress
+    Progress {
+        node_id: Uuid,
+        progress: ExecutionProgress,
+    },
 }
 
 /// Information about a running continuous node task
@@ -210,10 +270,12 @@ impl ContinuousExecutionManager {
     fn execution_loop(
         node_id: Uuid,
         graph: Arc<Mutex<NodeGraph>>,
-        _component_manager: Arc<Mutex<ComponentManager>>,
+        component_manager: Arc<Mutex<ComponentManager>>,
         cancellation_token: CancellationToken,
         result_tx: Sender<ExecutionResult>,
     ) {
+        let node_name = read_display_name(&graph, node_id);
+
         // T040: Wrap entire execution in panic catcher
         let execution_result = catch_unwind(AssertUnwindSafe(|| {
             // Create tokio runtime for async operations
@@ -225,7 +287,7 @@ impl ContinuousExecutionManager {
                         node_id,
                         error: ContinuousNodeError::ExecutionFailed {
                             node_id,
-                            node_name: "".to_string(),
+                            node_name: node_name.clone(),
                             message: format!("Failed to create tokio runtime: {}", e),
                             source_location: None,
                             timestamp: chrono::Utc::now(),
@@ -245,16 +307,48 @@ impl ContinuousExecutionManager {
             });
 
             // Track previous interval for change detection
-            let mut previous_interval_ms = 100u64;
+            let mut previous_interval_ms = DEFAULT_INTERVAL_MS;
 
             rt.block_on(async {
+                // Guests see stop requests through is-cancelled and report progress here
+                let control = Arc::new(ExecutionControl::with_token(cancellation_token.clone()));
+                let mut last_progress = None;
+
+                // WASM components run through a session that lives for the whole run
+                let mut wasm_session = None;
+                let mut default_interval_ms = DEFAULT_INTERVAL_MS;
+                match Self::start_wasm_session(node_id, &graph, &component_manager, &control).await {
+                    Ok(Some((session, interval_ms))) => {
+                        wasm_session = Some(session);
+                        if interval_ms > 0 {
+                            default_interval_ms = interval_ms as u64;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        log::error!("Continuous node {} failed to start: {}", node_id, e);
+                        let _ = result_tx.send(ExecutionResult::Error {
+                            node_id,
+                            error: ContinuousNodeError::ExecutionFailed {
+                                node_id,
+                                node_name: node_name.clone(),
+                                message: e.to_string(),
+                                source_location: None,
+                                timestamp: chrono::Utc::now(),
+                            },
+                        });
+                        return;
+                    }
+                }
+
                 while !cancellation_token.is_cancelled() {
                     let iteration_start = Instant::now();
 
                     iterations += 1;
 
                     // T044-T045: Fetch latest interval from graph each iteration (reactive to input changes)
-                    let interval_ms = read_interval_from_graph(&graph, node_id);
+                    let interval_ms =
+                        read_interval_from_graph(&graph, node_id).unwrap_or(default_interval_ms);
 
                     // T051: Log interval changes
                     if interval_ms != previous_interval_ms {
@@ -268,75 +362,75 @@ impl ContinuousExecutionManager {
                     }
 
                     // T040: Wrap iteration in panic catcher
-                    let iteration_result = catch_unwind(AssertUnwindSafe(|| {
-                        // Determine which continuous node type this is and execute accordingly
-                        let component_id = if let Ok(graph_lock) = graph.lock() {
-                            graph_lock.nodes.get(&node_id).map(|n| n.component_id.clone())
-                        } else {
-                            None
+                    let iteration_result = if let Some(session) = wasm_session.as_mut() {
+                        let (inputs, config) = match graph.lock() {
+                            Ok(graph_lock) => (
+                                collect_inputs(&graph_lock, node_id),
+                                graph_lock
+                                    .nodes
+                                    .get(&node_id)
+                                    .map(|node| node.config.clone())
+                                    .unwrap_or_default(),
+                            ),
+                            Err(_) => (HashMap::new(), BTreeMap::new()),
                         };
+                        // Guest traps surface as errors; host panics reach the outer catcher
+                        let tick = Self::run_until_cancelled(
+                            node_id,
+                            session.tick(&inputs, &config, iterations),
+                            &control,
+                            &result_tx,
+                            &mut last_progress,
+                        )
+                        .await;
+                        let Some(result) = tick else {
+                            // Abandoned mid-call, so the instance cannot run on-stop
+                            wasm_session = None;
+                            break;
+                        };
+                        Self::send_progress(node_id, &control, &result_tx, &mut last_progress);
+                        Ok(result.map_err(|e| e.to_string()))
+                    } else {
+                        catch_unwind(AssertUnwindSafe(|| {
+                            // Determine which continuous node type this is and execute accordingly
+                            let component_id = if let Ok(graph_lock) = graph.lock() {
+                                graph_lock.nodes.get(&node_id).map(|n| n.component_id.clone())
+                            } else {
+                                None
+                            };
 
-                        match component_id.as_deref() {
-                            Some("builtin:continuous:timer") => {
-                                // Timer node: generate counter and elapsed time
-                                let elapsed_seconds = started_at.elapsed().as_secs_f32();
-                                let mut outputs = HashMap::new();
-                                outputs.insert("counter".to_string(), NodeValue::U32(iterations as u32));
-                                outputs.insert("elapsed_seconds".to_string(), NodeValue::F32(elapsed_seconds));
-                                Ok(outputs)
-                            }
-                            Some("builtin:continuous:combiner") => {
-                                // T050: Combiner node: fetch inputs from connected nodes and execute
-                                if let Ok(graph_lock) = graph.lock() {
-                                    // Collect input values by resolving connections
-                                    let mut inputs = HashMap::new();
-
-                                    // Find all incoming connections to this node
-                                    for connection in graph_lock.connections.iter() {
-                                        if connection.to_node == node_id {
-                                            // Get the source node's output value
-                                            if let Some(source_node) = graph_lock.nodes.get(&connection.from_node) {
-                                                if let Some(source_port) = source_node.outputs.iter().find(|p| p.id == connection.from_port) {
-                                                    if let Some(value) = &source_port.current_value {
-                                                        // Map to target input port name
-                                                        if let Some(target_node) = graph_lock.nodes.get(&node_id) {
-                                                            if let Some(target_port) = target_node.inputs.iter().find(|p| p.id == connection.to_port) {
-                                                                inputs.insert(target_port.name.clone(), value.clone());
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-
-                                    // For unconnected inputs, use default values from the port
-                                    if let Some(node) = graph_lock.nodes.get(&node_id) {
-                                        for port in &node.inputs {
-                                            if !inputs.contains_key(&port.name) {
-                                                if let Some(value) = &port.current_value {
-                                                    inputs.insert(port.name.clone(), value.clone());
-                                                }
-                                            }
-                                        }
+                            match component_id.as_deref() {
+                                Some("builtin:continuous:timer") => {
+                                    // Timer node: generate counter and elapsed time
+                                    let elapsed_seconds = started_at.elapsed().as_secs_f32();
+                                    let mut outputs = HashMap::new();
+                                    outputs.insert("counter".to_string(), NodeValue::U32(iterations as u32));
+                                    outputs.insert("elapsed_seconds".to_string(), NodeValue::F32(elapsed_seconds));
+                                    Ok(outputs)
+                                }
+                                Some("builtin:continuous:combiner") => {
+                                    // T050: Combiner node: fetch inputs from connected nodes and execute
+                                    if let Ok(graph_lock) = graph.lock() {
+                                        // Collect input values by resolving connections
+                                        let inputs = collect_inputs(&graph_lock, node_id);
+
+                                        // Execute the combiner logic
+                                        use crate::builtin::ContinuousCombinerExecutor;
+                                        use crate::runtime::engine::NodeExecutor;
+
+                                        let executor = ContinuousCombinerExecutor;
+                                        executor.execute(&inputs).map_err(|e| e.to_string())
+                                    } else {
+                                        Err("Failed to lock graph".to_string())
                                     }
-
-                                    // Execute the combiner logic
-                                    use crate::builtin::ContinuousCombinerExecutor;
-                                    use crate::runtime::engine::NodeExecutor;
-
-                                    let executor = ContinuousCombinerExecutor;
-                                    executor.execute(&inputs).map_err(|e| e.to_string())
-                                } else {
-                                    Err("Failed to lock graph".to_string())
+                                }
+                                _ => {
+                                    // Unknown continuous node type
+                                    Err(format!("Unknown continuous node type: {:?}", component_id))
                                 }
                             }
-                            _ => {
-                                // Unknown continuous node type
-                                Err(format!("Unknown continuous node type: {:?}", component_id))
-                            }
-                        }
-                    }));
+                        }))
+                    };
 
                     // T039: Handle iteration errors
                     match iteration_result {
@@ -355,7 +449,7 @@ impl ContinuousExecutionManager {
                                 node_id,
                                 error: ContinuousNodeError::ExecutionFailed {
                                     node_id,
-                                    node_name: "".to_string(),
+                                    node_name: node_name.clone(),
                                     message: exec_error,
                                     source_location: None,
                                     timestamp: chrono::Utc::now(),
@@ -379,7 +473,7 @@ impl ContinuousExecutionManager {
                                 node_id,
                                 error: ContinuousNodeError::ExecutionFailed {
                                     node_id,
-                                    node_name: "".to_string(),
+                                    node_name: node_name.clone(),
                                     message: format!("Component panicked: {}", panic_msg),
                                     source_location: None,
                                     timestamp: chrono::Utc::now(),
@@ -415,6 +509,13 @@ impl ContinuousExecutionManager {
                         );
                     }
                 }
+
+                // Graceful stop: let the component release its resources
+                if let Some(session) = wasm_session.as_mut() {
+                    if let Err(e) = session.on_stop().await {
+                        log::warn!("Continuous node {} on-stop failed: {}", node_id, e);
+                    }
+                }
             });
 
             let total_duration = started_at.elapsed();
@@ -450,7 +551,7 @@ impl ContinuousExecutionManager {
                 node_id,
                 error: ContinuousNodeError::ExecutionFailed {
                     node_id,
-                    node_name: "".to_string(),
+                    node_name: node_name.clone(),
                     message: format!("Execution loop panicked: {}", panic_msg),
                     source_location: None,
                     timestamp: chrono::Utc::now(),
@@ -460,6 +561,98 @@ impl ContinuousExecutionManager {
     }
 }
 
+impl ContinuousExecutionManager {
+    /// Start a session for a user WASM component and call its on-start
+    ///
+    /// Returns None for builtin continuous nodes, otherwise the session and the
+    /// component's preferred tick interval. The node's capability grant applies.
+    async fn start_wasm_session(
+        node_id: Uuid,
+        graph: &Arc<Mutex<NodeGraph>>,
+        component_manager: &Arc<Mutex<ComponentManager>>,
+        control: &Arc<ExecutionControl>,
+    ) -> Result<Option<(ContinuousSession, u32)>, ComponentError> {
+        let (component_id, capabilities, inputs, config, kv_scope) = {
+            let graph_lock = graph
+                .lock()
+                .map_err(|_| ComponentError::ExecutionError("Failed to lock graph".to_string()))?;
+            let Some(node) = graph_lock.nodes.get(&node_id) else {
+                return Ok(None);
+            };
+            if !node.component_id.starts_with("user:") {
+                return Ok(None);
+            }
+            let capabilities = graph_lock
+                .get_capability_grant(node_id)
+                .map(|grant| grant.capability_set.clone())
+                .unwrap_or_else(CapabilitySet::none);
+            (
+                node.component_id.clone(),
+                capabilities,
+                collect_inputs(&graph_lock, node_id),
+                node.config.clone(),
+                KvScope::new(graph_lock.id, node_id),
+            )
+        };
+
+        // The manager lock is released before instantiating, which awaits
+        let setup = component_manager
+            .lock()
+            .map_err(|_| {
+                ComponentError::ExecutionError("Failed to lock component manager".to_string())
+            })?
+            .prepare_continuous_session(
+                &component_id,
+                capabilities,
+                Some(kv_scope),
+                Some(control.clone()),
+            )?;
+        let mut session = setup.start().await?;
+
+        let interval_ms = session.on_start(&inputs, &config).await?;
+        Ok(Some((session, interval_ms)))
+    }
+
+    /// Run a tick, sending progress reported while it runs
+    ///
+    /// Returns None if the node is stopped before the tick finishes; the
+    /// tick is dropped mid-call then.
+    async fn run_until_cancelled<T>(
+        node_id: Uuid,
+        tick: impl std::future::Future<Output = T>,
+        control: &ExecutionControl,
+        result_tx: &Sender<ExecutionResult>,
+        last_progress: &mut Option<ExecutionProgress>,
+    ) -> Option<T> {
+        tokio::pin!(tick);
+        loop {
+            tokio::select! {
+                result = &mut tick => return Some(result),
+                () = control.cancelled() => return None,
+                () = tokio::time::sleep(PROGRESS_POLL_INTERVAL) => {
+                    Self::send_progress(node_id, control, result_tx, last_progress);
+                }
+            }
+        }
+    }
+
+    /// Send the latest progress if it differs from `last_sent`
+    fn send_progress(
+        node_id: Uuid,
+        control: &ExecutionControl,
+        result_tx: &Sender<ExecutionResult>,
+        last_sent: &mut Option<ExecutionProgress>,
+    ) {
+        let Some(progress) = control.progress() else {
+            return;
+        };
+        if last_sent.as_ref() != Some(&progress) {
+            *last_sent = Some(progress.clone());
+            let _ = result_tx.send(ExecutionResult::Progress { node_id, progress });
+        }
+    }
+}
+
 impl Default for ContinuousExecutionManager {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,330 @@
+//! `wasmflow:node` contract versions and adapters
+//!
+//! `wasm_host` binds the current contract (`wit/node.wit`). Older contracts
+//! keep their own bindings here, generated from `wit-legacy/`. At load time
+//! the host inspects which `wasmflow:node` version a component exports. Calls
+//! to older components go through the legacy bindings, and their values are
+//! adapted to the current types. The contract can then evolve without
+//! rebuilding every component at once.
+//!
+//! Host imports need no adapters. The component linker resolves a legacy
+//! `wasmflow:node/host@1.1.0` import against the semver-compatible current
+//! `host` instance.
+
+use crate::runtime::wasm_host::{wasmflow as current, with_ui as current_ui};
+use serde::{Deserialize, Serialize};
+use wasmtime::component::Component as WasmComponent;
+use wasmtime::Engine;
+
+/// Bindings for the legacy `wasmflow:node@1.1.0` contract
+pub mod v1_1 {
+    wasmtime::component::bindgen!({
+        path: "wit-legacy/v1.1.0",
+        world: "component",
+        async: true,
+    });
+
+    /// Legacy world with the optional UI interface
+    pub mod with_ui {
+        wasmtime::component::bindgen!({
+            path: "wit-legacy/v1.1.0",
+            world: "component-with-ui",
+            async: true,
+            with: {
+                "wasmflow:node/types@1.1.0": crate::runtime::contract::v1_1::wasmflow::node::types,
+                "wasmflow:node/host@1.1.0": crate::runtime::contract::v1_1::wasmflow::node::host,
+            },
+        });
+    }
+}
+
+/// Interface used to detect the exported contract version
+const EXECUTION_EXPORT_PREFIX: &str = "wasmflow:node/execution@";
+
+/// Versions of the `wasmflow:node` contract this host can load
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ContractVersion {
+    /// 1.1.0: bool and typed list values
+    V1_1,
+    /// 1.2.0: input default values, config and schema interfaces
+    V1_2,
+}
+
+impl ContractVersion {
+    /// Version bound by `wit/node.wit`
+    pub const CURRENT: ContractVersion = ContractVersion::V1_2;
+
+    /// All versions with bindings, oldest first
+    pub const SUPPORTED: &'static [ContractVersion] = &[ContractVersion::V1_1, ContractVersion::V1_2];
+
+    /// Semantic version string as used in WIT package names
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContractVersion::V1_1 => "1.1.0",
+            ContractVersion::V1_2 => "1.2.0",
+        }
+    }
+
+    /// Parse a WIT package version string
+    pub fn parse(version: &str) -> Option<Self> {
+        Self::SUPPORTED
+            .iter()
+            .copied()
+            .find(|supported| supported.as_str() == version)
+    }
+
+    /// Older versions still load but should be rebuilt against the current contract
+    pub fn is_deprecated(&self) -> bool {
+        *self != Self::CURRENT
+    }
+
+    /// Pick the newest supported version among a component's export names
+    pub fn from_export_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        names
+            .into_iter()
+            .filter_map(|name| name.strip_prefix(EXECUTION_EXPORT_PREFIX))
+            .filter_map(Self::parse)
+            .max()
+    }
+
+    /// Detect the contract version a compiled component exports
+    pub fn detect(engine: &Engine, component: &WasmComponent) -> Option<Self> {
+        let component_type = component.component_type();
+        Self::from_export_names(component_type.exports(engine).map(|(name, _)| name))
+    }
+
+    /// Whether a component exports an optional interface of this contract version
+    pub fn exports_interface(&self, engine: &Engine, component: &WasmComponent, interface: &str) -> bool {
+        let name = format!("wasmflow:node/{}@{}", interface, self.as_str());
+        component
+            .component_type()
+            .exports(engine)
+            .any(|(export, _)| export == name)
+    }
+
+    /// Human-readable list of supported versions (for error messages)
+    pub fn supported_list() -> String {
+        Self::SUPPORTED
+            .iter()
+            .map(|v| v.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl std::fmt::Display for ContractVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "wasmflow:node@{}", self.as_str())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 1.1.0 <-> current adapters
+// ---------------------------------------------------------------------------
+
+use current::node::types as cur;
+use v1_1::wasmflow::node::types as old;
+
+impl From<old::Value> for cur::Value {
+    fn from(value: old::Value) -> Self {
+        match value {
+            old::Value::U32Val(v) => cur::Value::U32Val(v),
+            old::Value::I32Val(v) => cur::Value::I32Val(v),
+            old::Value::F32Val(v) => cur::Value::F32Val(v),
+            old::Value::StringVal(v) => cur::Value::StringVal(v),
+            old::Value::BoolVal(v) => cur::Value::BoolVal(v),
+            old::Value::BinaryVal(v) => cur::Value::BinaryVal(v),
+            old::Value::StringListVal(v) => cur::Value::StringListVal(v),
+            old::Value::U32ListVal(v) => cur::Value::U32ListVal(v),
+            old::Value::F32ListVal(v) => cur::Value::F32ListVal(v),
+        }
+    }
+}
+
+impl From<cur::Value> for old::Value {
+    fn from(value: cur::Value) -> Self {
+        match value {
+            cur::Value::U32Val(v) => old::Value::U32Val(v),
+            cur::Value::I32Val(v) => old::Value::I32Val(v),
+            cur::Value::F32Val(v) => old::Value::F32Val(v),
+            cur::Value::StringVal(v) => old::Value::StringVal(v),
+            cur::Value::BoolVal(v) => old::Value::BoolVal(v),
+            cur::Value::BinaryVal(v) => old::Value::BinaryVal(v),
+            cur::Value::StringListVal(v) => old::Value::StringListVal(v),
+            cur::Value::U32ListVal(v) => old::Value::U32ListVal(v),
+            cur::Value::F32ListVal(v) => old::Value::F32ListVal(v),
+        }
+    }
+}
+
+impl From<old::DataType> for cur::DataType {
+    fn from(data_type: old::DataType) -> Self {
+        match data_type {
+            old::DataType::U32Type => cur::DataType::U32Type,
+            old::DataType::I32Type => cur::DataType::I32Type,
+            old::DataType::F32Type => cur::DataType::F32Type,
+            old::DataType::StringType => cur::DataType::StringType,
+            old::DataType::BoolType => cur::DataType::BoolType,
+            old::DataType::BinaryType => cur::DataType::BinaryType,
+            old::DataType::ListType => cur::DataType::ListType,
+            old::DataType::AnyType => cur::DataType::AnyType,
+        }
+    }
+}
+
+impl From<old::PortSpec> for cur::PortSpec {
+    fn from(spec: old::PortSpec) -> Self {
+        cur::PortSpec {
+            name: spec.name,
+            data_type: spec.data_type.into(),
+            optional: spec.optional,
+            description: spec.description,
+            // 1.1.0 cannot declare defaults
+            default_value: None,
+        }
+    }
+}
+
+impl From<old::ComponentInfo> for cur::ComponentInfo {
+    fn from(info: old::ComponentInfo) -> Self {
+        cur::ComponentInfo {
+            name: info.name,
+            version: info.version,
+            description: info.description,
+            author: info.author,
+            category: info.category,
+        }
+    }
+}
+
+impl From<old::ExecutionError> for cur::ExecutionError {
+    fn from(err: old::ExecutionError) -> Self {
+        cur::ExecutionError {
+            message: err.message,
+            input_name: err.input_name,
+            recovery_hint: err.recovery_hint,
+        }
+    }
+}
+
+use current_ui::exports::wasmflow::node::ui as cur_ui;
+use v1_1::with_ui::exports::wasmflow::node::ui as old_ui;
+
+impl From<old_ui::ColoredText> for cur_ui::ColoredText {
+    fn from(text: old_ui::ColoredText) -> Self {
+        cur_ui::ColoredText {
+            text: text.text,
+            r: text.r,
+            g: text.g,
+            b: text.b,
+        }
+    }
+}
+
+impl From<old_ui::KeyValuePair> for cur_ui::KeyValuePair {
+    fn from(kv: old_ui::KeyValuePair) -> Self {
+        cur_ui::KeyValuePair {
+            key: kv.key,
+            value: kv.value,
+        }
+    }
+}
+
+impl From<old_ui::UiElementItem> for cur_ui::UiElementItem {
+    fn from(item: old_ui::UiElementItem) -> Self {
+        match item {
+            old_ui::UiElementItem::Label(text) => cur_ui::UiElementItem::Label(text),
+            old_ui::UiElementItem::ColoredLabel(text) => {
+                cur_ui::UiElementItem::ColoredLabel(text.into())
+            }
+            old_ui::UiElementItem::KeyValue(kv) => cur_ui::UiElementItem::KeyValue(kv.into()),
+            old_ui::UiElementItem::Separator => cur_ui::UiElementItem::Separator,
+        }
+    }
+}
+
+impl From<old_ui::UiElement> for cur_ui::UiElement {
+    fn from(element: old_ui::UiElement) -> Self {
+        match element {
+            old_ui::UiElement::Label(text) => cur_ui::UiElement::Label(text),
+            old_ui::UiElement::ColoredLabel(text) => cur_ui::UiElement::ColoredLabel(text.into()),
+            old_ui::UiElement::KeyValue(kv) => cur_ui::UiElement::KeyValue(kv.into()),
+            old_ui::UiElement::Horizontal(layout) => {
+                cur_ui::UiElement::Horizontal(cur_ui::HorizontalLayout {
+                    elements: layout.elements.into_iter().map(Into::into).collect(),
+                })
+            }
+            old_ui::UiElement::Vertical(layout) => {
+                cur_ui::UiElement::Vertical(cur_ui::VerticalLayout {
+                    elements: layout.elements.into_iter().map(Into::into).collect(),
+                })
+            }
+            old_ui::UiElement::Separator => cur_ui::UiElement::Separator,
+        }
+    }
+}
+
+impl From<old_ui::FooterView> for cur_ui::FooterView {
+    fn from(view: old_ui::FooterView) -> Self {
+        cur_ui::FooterView {
+            elements: view.elements.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_version_from_export_names() {
+        let legacy = [
+            "wasmflow:node/metadata@1.1.0",
+            "wasmflow:node/execution@1.1.0",
+        ];
+        assert_eq!(
+            ContractVersion::from_export_names(legacy),
+            Some(ContractVersion::V1_1)
+        );
+
+        let current = [
+            "wasmflow:node/metadata@1.2.0",
+            "wasmflow:node/execution@1.2.0",
+            "wasmflow:node/config@1.2.0",
+        ];
+        assert_eq!(
+            ContractVersion::from_export_names(current),
+            Some(ContractVersion::CURRENT)
+        );
+    }
+
+    #[test]
+    fn test_unsupported_versions_are_not_detected() {
+        let ancient = ["wasmflow:node/execution@1.0.0"];
+        assert_eq!(ContractVersion::from_export_names(ancient), None);
+
+        let unrelated = ["wasi:cli/run@0.2.0"];
+        assert_eq!(ContractVersion::from_export_names(unrelated), None);
+    }
+
+    #[test]
+    fn test_deprecation() {
+        assert!(ContractVersion::V1_1.is_deprecated());
+        assert!(!ContractVersion::CURRENT.is_deprecated());
+        assert_eq!(ContractVersion::V1_1.to_string(), "wasmflow:node@1.1.0");
+    }
+
+    #[test]
+    fn test_legacy_port_spec_adapts_without_default() {
+        let legacy = old::PortSpec {
+            name: "text".to_string(),
+            data_type: old::DataType::StringType,
+            optional: true,
+            description: "Input".to_string(),
+        };
+        let adapted: cur::PortSpec = legacy.into();
+        assert!(adapted.optional);
+        assert!(adapted.default_value.is_none());
+        assert!(matches!(adapted.data_type, cur::DataType::StringType));
+    }
+}
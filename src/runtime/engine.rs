@@ -1,12 +1,16 @@
 //! Execution engine for running graphs
 
+use crate::graph::function::{self, GraphFunctionLibrary, SharedFunctionLibrary};
 use crate::graph::graph::NodeGraph;
-use crate::graph::node::{ExecutionState, NodeValue};
+use crate::graph::node::{ExecutionState, GraphNode, NodeValue};
+use crate::graph::schema;
 use crate::runtime::capabilities::CapabilitySet;
+use crate::runtime::kv::KvScope;
+use crate::runtime::progress::ExecutionControl;
 use crate::runtime::wasm_host::ComponentManager;
 use crate::{ComponentError, GraphError};
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 use uuid::Uuid;
 
@@ -21,6 +25,10 @@ pub struct ExecutionEngine {
     component_manager: Arc<Mutex<ComponentManager>>,
     /// Execution timeout for components
     execution_timeout: Duration,
+    /// Progress and cancellation shared with running components
+    execution_control: Option<Arc<ExecutionControl>>,
+    /// Graph functions referenced by `function:` nodes
+    function_library: SharedFunctionLibrary,
 }
 
 /// Trait for executing a node
@@ -41,6 +49,10 @@ impl ExecutionEngine {
             executors: HashMap::new(),
             component_manager: Arc::new(Mutex::new(component_manager)),
             execution_timeout: DEFAULT_EXECUTION_TIMEOUT,
+            execution_control: None,
+            function_library: Arc::new(RwLock::new(GraphFunctionLibrary::new(
+                GraphFunctionLibrary::default_root(),
+            ))),
         }
     }
 
@@ -64,6 +76,25 @@ impl ExecutionEngine {
         self.component_manager = component_manager;
     }
 
+    /// Get the graph function library
+    pub fn function_library(&self) -> SharedFunctionLibrary {
+        Arc::clone(&self.function_library)
+    }
+
+    /// Set the graph function library (for sharing across threads)
+    pub fn set_function_library(&mut self, function_library: SharedFunctionLibrary) {
+        self.function_library = function_library;
+    }
+
+    /// Share progress reports and cancellation with the caller
+    ///
+    /// WASM components report progress through `control`, and see its
+    /// cancellation through `is-cancelled`. `execute_graph` also stops before
+    /// the next node once it is cancelled.
+    pub fn set_execution_control(&mut self, control: Option<Arc<ExecutionControl>>) {
+        self.execution_control = control;
+    }
+
     /// Execute the entire graph
     pub fn execute_graph(&mut self, graph: &mut NodeGraph) -> Result<ExecutionReport, GraphError> {
         let mut report = ExecutionReport::default();
@@ -71,6 +102,8 @@ impl ExecutionEngine {
         // Get execution order (topological sort)
         let execution_order = graph.execution_order()?;
 
+        self.prune_stateful_instances(graph);
+
         // Reset all nodes to idle state
         for node in graph.nodes.values_mut() {
             node.execution_state = ExecutionState::Idle;
@@ -78,6 +111,14 @@ impl ExecutionEngine {
 
         // Execute nodes in dependency order
         for node_id in &execution_order {
+            if self
+                .execution_control
+                .as_ref()
+                .is_some_and(|control| control.is_cancelled())
+            {
+                return Err(GraphError::Cancelled);
+            }
+
             // Update input port values from connections (for UI display)
             Self::update_input_values_from_connections(graph, *node_id);
 
@@ -115,6 +156,9 @@ impl ExecutionEngine {
                     // Update footer view for WASM components with custom UI
                     self.update_footer_view(graph, *node_id);
 
+                    // Snapshot guest state of stateful components into the graph
+                    self.update_instance_state(graph, *node_id);
+
                     // Mark as completed
                     if let Some(node) = graph.nodes.get_mut(node_id) {
                         node.execution_state = ExecutionState::Completed;
@@ -153,6 +197,8 @@ impl ExecutionEngine {
         // Get execution order for dirty nodes only
         let execution_order = get_dirty_execution_order(graph)?;
 
+        self.prune_stateful_instances(graph);
+
         if execution_order.is_empty() {
             log::debug!("No dirty nodes to execute");
             return Ok(report);
@@ -204,6 +250,9 @@ impl ExecutionEngine {
                     // Update footer view for WASM components with custom UI
                     self.update_footer_view(graph, *node_id);
 
+                    // Snapshot guest state of stateful components into the graph
+                    self.update_instance_state(graph, *node_id);
+
                     // Mark as completed and clean
                     if let Some(node) = graph.nodes.get_mut(node_id) {
                         node.execution_state = ExecutionState::Completed;
@@ -252,6 +301,15 @@ impl ExecutionEngine {
             ComponentError::ExecutionError(format!("Node {} not found", node_id))
         })?;
 
+        // Placeholders keep their wiring but have nothing to run
+        if node.missing_component {
+            return Err(ComponentError::ExecutionError(format!(
+                "Component '{}' used by '{}' is not loaded; relink the node to an \
+                 available component (File > Missing Components...)",
+                node.component_id, node.display_name
+            )));
+        }
+
         // Gather input values from connected output ports
         let mut inputs = HashMap::new();
 
@@ -313,6 +371,11 @@ impl ExecutionEngine {
             return self.execute_composite_node(node_id, &node.component_id, &inputs, &capability_set, composition_data);
         }
 
+        // Graph functions run the library's current revision
+        if let Some(name) = function::function_name(&node.component_id) {
+            return self.execute_graph_function(node_id, name, &inputs);
+        }
+
         // Check if this is a user-defined component
         if node.component_id.starts_with("user:") {
             // T075: Get capability grant from graph for permission enforcement
@@ -326,7 +389,14 @@ impl ExecutionEngine {
                 });
 
             // Execute WASM component with timeout and error handling
-            return self.execute_wasm_component(node_id, &node.component_id, &inputs, &capability_set);
+            let outputs = self.execute_wasm_component(
+                node,
+                &inputs,
+                &capability_set,
+                Self::input_wiring(graph, node_id),
+                KvScope::new(graph.id, node_id),
+            )?;
+            return Self::validate_output_schemas(node, outputs);
         }
 
         // Get the builtin executor
@@ -338,17 +408,69 @@ impl ExecutionEngine {
         })?;
 
         // Execute builtin component and return outputs
-        executor.execute(&inputs)
+        let outputs = executor.execute(&inputs)?;
+        Self::validate_output_schemas(node, outputs)
+    }
+
+    /// Validate produced values against the record schemas declared on output ports
+    ///
+    /// Guests hand records over as JSON strings; they are decoded here so
+    /// downstream nodes always receive structured `NodeValue::Record`s.
+    fn validate_output_schemas(
+        node: &GraphNode,
+        mut outputs: HashMap<String, NodeValue>,
+    ) -> Result<HashMap<String, NodeValue>, ComponentError> {
+        for port in &node.outputs {
+            if !schema::contains_record(&port.data_type) {
+                continue;
+            }
+
+            if let Some(value) = outputs.remove(&port.name) {
+                let value = schema::coerce_to_schema(value, &port.data_type, &port.name)
+                    .map_err(|source| ComponentError::SchemaViolation {
+                        port: port.name.clone(),
+                        source,
+                    })?;
+                outputs.insert(port.name.clone(), value);
+            }
+        }
+
+        Ok(outputs)
+    }
+
+    /// Fingerprint of a node's incoming connections
+    ///
+    /// Stateful components are reset when this changes.
+    fn input_wiring(graph: &NodeGraph, node_id: Uuid) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let mut wires: Vec<(Uuid, Uuid, Uuid)> = graph
+            .incoming_connections(node_id)
+            .into_iter()
+            .map(|c| (c.from_node, c.from_port, c.to_port))
+            .collect();
+        wires.sort();
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        wires.hash(&mut hasher);
+        hasher.finish()
     }
 
     /// Execute a WASM component with timeout and enhanced error handling
+    ///
+    /// Stateful components run on the node's persistent instance. `kv_scope`
+    /// is the node's namespace in the key-value store.
     fn execute_wasm_component(
         &self,
-        node_id: Uuid,
-        component_id: &str,
+        node: &GraphNode,
         inputs: &HashMap<String, NodeValue>,
         capabilities: &CapabilitySet,
+        wiring: u64,
+        kv_scope: KvScope,
     ) -> Result<HashMap<String, NodeValue>, ComponentError> {
+        let node_id = node.id;
+        let component_id = node.component_id.as_str();
+
         // Use synchronous execution with tokio runtime
         // This creates a new runtime per execution, which is not ideal for performance
         // but works for now until we have a proper async integration
@@ -360,7 +482,10 @@ impl ExecutionEngine {
         let timeout = self.execution_timeout;
         let capabilities = capabilities.clone();
         let inputs = inputs.clone();
+        let config = node.config.clone();
         let component_id_str = component_id.to_string();
+        let stateful = component_manager.is_stateful(component_id);
+        let control = self.execution_control.clone();
 
         // Create a new tokio runtime for this execution
         let runtime = tokio::runtime::Runtime::new().map_err(|e| {
@@ -369,10 +494,34 @@ impl ExecutionEngine {
 
         // Run async execution synchronously using block_on
         let result = runtime.block_on(async {
-            tokio::time::timeout(
-                timeout,
-                component_manager.execute_component(&component_id_str, &inputs, capabilities)
-            ).await
+            if stateful {
+                tokio::time::timeout(
+                    timeout,
+                    component_manager.execute_stateful_component(
+                        node_id,
+                        &component_id_str,
+                        &inputs,
+                        &config,
+                        capabilities,
+                        wiring,
+                        node.instance_state.as_deref(),
+                        Some(kv_scope),
+                        control,
+                    )
+                ).await
+            } else {
+                tokio::time::timeout(
+                    timeout,
+                    component_manager.execute_component(
+                        &component_id_str,
+                        &inputs,
+                        &config,
+                        capabilities,
+                        Some(kv_scope),
+                        control,
+                    )
+                ).await
+            }
         });
 
         match result {
@@ -431,9 +580,64 @@ impl ExecutionEngine {
             );
         }
 
+        self.run_subgraph(
+            internal_graph,
+            &composition_data.exposed_inputs,
+            &composition_data.exposed_outputs,
+            external_inputs,
+            "Composite node",
+            false,
+        )
+    }
+
+    /// Execute a graph function node by running an instance of its subgraph
+    fn execute_graph_function(
+        &self,
+        node_id: Uuid,
+        name: &str,
+        external_inputs: &HashMap<String, NodeValue>,
+    ) -> Result<HashMap<String, NodeValue>, ComponentError> {
+        let instance = {
+            let library = self.function_library.read().unwrap();
+            let graph_function = library.get(name).ok_or_else(|| {
+                ComponentError::ExecutionError(format!(
+                    "Graph function '{}' is not in the function library",
+                    name
+                ))
+            })?;
+            log::debug!(
+                "Executing graph function '{}' revision {} with {} internal nodes",
+                name, graph_function.revision, graph_function.nodes.len());
+            graph_function.instantiate(node_id)
+        };
+
+        self.run_subgraph(
+            instance.graph,
+            &instance.inputs,
+            &instance.outputs,
+            external_inputs,
+            &format!("Graph function '{}'", name),
+            true,
+        )
+    }
+
+    /// Run an internal graph, feeding and collecting values through its exposed ports
+    ///
+    /// Shared by composite nodes and graph functions; `label` prefixes errors.
+    /// With `keep_constants`, constants that hold a value are not re-executed
+    /// (graph functions store the values their constants were saved with).
+    fn run_subgraph(
+        &self,
+        mut internal_graph: NodeGraph,
+        exposed_inputs: &BTreeMap<String, crate::graph::node::PortMapping>,
+        exposed_outputs: &BTreeMap<String, crate::graph::node::PortMapping>,
+        external_inputs: &HashMap<String, NodeValue>,
+        label: &str,
+        keep_constants: bool,
+    ) -> Result<HashMap<String, NodeValue>, ComponentError> {
         // Map external inputs to internal node inputs
         for (external_name, value) in external_inputs {
-            if let Some(mapping) = composition_data.exposed_inputs.get(external_name) {
+            if let Some(mapping) = exposed_inputs.get(external_name) {
                 log::debug!("Mapping external input '{}' to internal node '{}'",
                     external_name, mapping.internal_node_id);
 
@@ -454,7 +658,7 @@ impl ExecutionEngine {
             Err(e) => {
                 log::error!("Failed to get execution order for internal graph: {}", e);
                 return Err(ComponentError::ExecutionError(
-                    format!("Composite node execution order failed: {}", e)
+                    format!("{} execution order failed: {}", label, e)
                 ));
             }
         };
@@ -463,6 +667,16 @@ impl ExecutionEngine {
         for internal_node_id in &execution_order {
             log::debug!("Executing internal node {}", internal_node_id);
 
+            // Graph function constants keep the values they were saved with
+            let is_constant_with_value = keep_constants
+                && internal_graph.nodes.get(internal_node_id).is_some_and(|node| {
+                    node.component_id.starts_with("builtin:constant:")
+                        && node.outputs.iter().all(|p| p.current_value.is_some())
+                });
+            if is_constant_with_value {
+                continue;
+            }
+
             match self.execute_node(&internal_graph, *internal_node_id) {
                 Ok(outputs) => {
                     // Update the internal graph with outputs
@@ -481,7 +695,7 @@ impl ExecutionEngine {
                         node.execution_state = crate::graph::node::ExecutionState::Failed;
                     }
                     return Err(ComponentError::ExecutionError(
-                        format!("Composite node internal execution failed at node {}: {}", internal_node_id, e)
+                        format!("{} internal execution failed at node {}: {}", label, internal_node_id, e)
                     ));
                 }
             }
@@ -491,7 +705,7 @@ impl ExecutionEngine {
 
         // Map internal outputs to external outputs
         let mut external_outputs = HashMap::new();
-        for (external_name, mapping) in &composition_data.exposed_outputs {
+        for (external_name, mapping) in exposed_outputs {
             if let Some(internal_node) = internal_graph.nodes.get(&mapping.internal_node_id) {
                 if let Some(output_port) = internal_node.outputs.iter()
                     .find(|p| p.name == mapping.internal_port_name) {
@@ -507,7 +721,7 @@ impl ExecutionEngine {
             }
         }
 
-        log::debug!("Composite node execution complete with {} outputs", external_outputs.len());
+        log::debug!("{} execution complete with {} outputs", label, external_outputs.len());
         Ok(external_outputs)
     }
 
@@ -591,6 +805,60 @@ impl ExecutionEngine {
         Ok(())
     }
 
+    /// Drop stateful instances of nodes that were removed from the graph
+    ///
+    /// Nodes inside composites and graph function instances own instances
+    /// too, so they are kept while the node that contains them exists.
+    fn prune_stateful_instances(&self, graph: &NodeGraph) {
+        let mut live = HashSet::new();
+        {
+            let library = self.function_library.read().unwrap();
+            for (node_id, node) in &graph.nodes {
+                collect_live_node_ids(*node_id, node, &library, &mut live);
+            }
+        }
+        let mut component_manager = self.component_manager.lock().unwrap();
+        component_manager.retain_stateful_instances(|node_id| live.contains(node_id));
+    }
+
+    /// Store the guest state snapshot of a stateful component on its node
+    ///
+    /// The snapshot is saved with the graph so state survives app restarts.
+    fn update_instance_state(&self, graph: &mut NodeGraph, node_id: Uuid) {
+        let mut component_manager = self.component_manager.lock().unwrap();
+
+        let is_stateful = graph
+            .nodes
+            .get(&node_id)
+            .is_some_and(|node| component_manager.is_stateful(&node.component_id));
+        if !is_stateful {
+            return;
+        }
+
+        let runtime = match tokio::runtime::Runtime::new() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                log::warn!("Failed to create runtime for state snapshot: {}", e);
+                return;
+            }
+        };
+
+        match runtime.block_on(component_manager.save_instance_state(node_id)) {
+            Ok(Some(state)) => {
+                if let Some(node) = graph.nodes.get_mut(&node_id) {
+                    node.instance_state = Some(state);
+                }
+            }
+            Ok(None) => {
+                // No live instance (execution failed before one was created)
+            }
+            Err(e) => {
+                // Snapshot failure must not fail execution
+                log::warn!("Failed to snapshot state of node {}: {}", node_id, e);
+            }
+        }
+    }
+
     /// Update footer view for a node after execution
     ///
     /// For WASM components with custom UI, this calls get-footer-view() with
@@ -606,11 +874,17 @@ impl ExecutionEngine {
             return;
         }
 
+        // The footer instance runs under the same grant as the node
+        let capabilities = graph
+            .get_capability_grant(node_id)
+            .map(|grant| grant.capability_set.clone())
+            .unwrap_or_else(CapabilitySet::none);
+
         // Get the component manager
-        let component_manager = self.component_manager.lock().unwrap();
+        let mut component_manager = self.component_manager.lock().unwrap();
 
         // Try to get footer view from component with current outputs
-        match component_manager.get_footer_view_for_node(node) {
+        match component_manager.refresh_footer_view(node, &capabilities) {
             Ok(Some(footer_view)) => {
                 // Store the footer view on the node
                 if let Some(node) = graph.nodes.get_mut(&node_id) {
@@ -636,6 +910,32 @@ impl Default for ExecutionEngine {
     }
 }
 
+/// Add `node_id` and the ids its composite or graph function runs internal nodes under
+fn collect_live_node_ids(
+    node_id: Uuid,
+    node: &GraphNode,
+    library: &GraphFunctionLibrary,
+    live: &mut HashSet<Uuid>,
+) {
+    // Also stops a function that (indirectly) calls itself
+    if !live.insert(node_id) {
+        return;
+    }
+    if let Some(composition_data) = &node.composition_data {
+        for (internal_id, internal) in &composition_data.internal_nodes {
+            collect_live_node_ids(*internal_id, internal, library, live);
+        }
+    }
+    let graph_function =
+        function::function_name(&node.component_id).and_then(|name| library.get(name));
+    if let Some(graph_function) = graph_function {
+        for (internal_id, internal) in &graph_function.nodes {
+            let instance_id = function::instance_node_id(node_id, *internal_id);
+            collect_live_node_ids(instance_id, internal, library, live);
+        }
+    }
+}
+
 /// Report of graph execution
 #[derive(Debug, Default)]
 pub struct ExecutionReport {
@@ -766,4 +1066,158 @@ mod tests {
         let add_node = graph.nodes.get(&add_id).unwrap();
         assert_eq!(add_node.outputs[0].current_value, Some(NodeValue::F32(8.0)));
     }
+
+    #[test]
+    fn test_input_wiring_changes_on_rewire() {
+        let mut graph = NodeGraph::new("Test".to_string(), "Test".to_string());
+
+        let const_spec = ComponentSpec::new_builtin(
+            "builtin:constant:f32".to_string(),
+            "Constant".to_string(),
+            "Constant".to_string(),
+            None,
+        )
+        .with_output("value".to_string(), DataType::F32, "Value".to_string());
+        let sink_spec = ComponentSpec::new_builtin(
+            "builtin:math:add".to_string(),
+            "Add".to_string(),
+            "Add".to_string(),
+            None,
+        )
+        .with_input("a".to_string(), DataType::F32, "First".to_string())
+        .with_input("b".to_string(), DataType::F32, "Second".to_string());
+
+        let source = const_spec.create_node(egui::Pos2::ZERO);
+        let sink = sink_spec.create_node(egui::Pos2::ZERO);
+        let (source_id, source_out) = (source.id, source.outputs[0].id);
+        let (sink_id, sink_a, sink_b) = (sink.id, sink.inputs[0].id, sink.inputs[1].id);
+        graph.add_node(source);
+        graph.add_node(sink);
+
+        let unwired = ExecutionEngine::input_wiring(&graph, sink_id);
+        graph.add_connection(source_id, source_out, sink_id, sink_a).unwrap();
+        let wired_a = ExecutionEngine::input_wiring(&graph, sink_id);
+        assert_ne!(unwired, wired_a);
+        assert_eq!(wired_a, ExecutionEngine::input_wiring(&graph, sink_id));
+
+        graph.connections.clear();
+        graph.add_connection(source_id, source_out, sink_id, sink_b).unwrap();
+        assert_ne!(wired_a, ExecutionEngine::input_wiring(&graph, sink_id));
+    }
+
+    #[test]
+    fn test_live_node_ids_include_function_instances() {
+        use crate::graph::function::{instance_node_id, GraphFunction, GraphFunctionLibrary};
+
+        let counter_spec = ComponentSpec::new_builtin(
+            "user:counter".to_string(),
+            "Counter".to_string(),
+            "Counter".to_string(),
+            None,
+        )
+        .with_output("count".to_string(), DataType::U32, "Count".to_string());
+        let mut body = NodeGraph::new("Body".to_string(), "Test".to_string());
+        let counter = counter_spec.create_node(egui::Pos2::ZERO);
+        let counter_id = counter.id;
+        body.add_node(counter);
+        let function = GraphFunction::from_selection(
+            "count".to_string(),
+            String::new(),
+            &body,
+            &[counter_id],
+            BTreeMap::new(),
+            BTreeMap::new(),
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut library = GraphFunctionLibrary::new(dir.path());
+        let spec = {
+            let saved = library.save(function).unwrap();
+            saved.to_component_spec(library.path_for(&saved.name))
+        };
+        let call = spec.create_node(egui::Pos2::ZERO);
+        let call_id = call.id;
+
+        let mut live = HashSet::new();
+        collect_live_node_ids(call_id, &call, &library, &mut live);
+        assert_eq!(live, HashSet::from([call_id, instance_node_id(call_id, counter_id)]));
+    }
+
+    #[test]
+    fn test_graph_function_runs_library_revision() {
+        use crate::graph::function::{GraphFunction, GraphFunctionLibrary};
+        use crate::graph::node::PortMapping;
+        use std::collections::BTreeMap;
+
+        let const_spec = ComponentSpec::new_builtin(
+            "builtin:constant:f32".to_string(),
+            "Constant".to_string(),
+            "Constant".to_string(),
+            None,
+        )
+        .with_output("value".to_string(), DataType::F32, "Value".to_string());
+        let add_spec = ComponentSpec::new_builtin(
+            "builtin:math:add".to_string(),
+            "Add".to_string(),
+            "Add".to_string(),
+            None,
+        )
+        .with_input("a".to_string(), DataType::F32, "First".to_string())
+        .with_input("b".to_string(), DataType::F32, "Second".to_string())
+        .with_output("sum".to_string(), DataType::F32, "Sum".to_string());
+
+        // Function body: a + 3, exposing `a` and `sum`
+        let mut body = NodeGraph::new("Body".to_string(), "Test".to_string());
+        let mut three = const_spec.create_node(egui::Pos2::ZERO);
+        three.outputs[0].current_value = Some(NodeValue::F32(3.0));
+        let add = add_spec.create_node(egui::Pos2::ZERO);
+        let (three_id, three_out) = (three.id, three.outputs[0].id);
+        let (add_id, add_b) = (add.id, add.inputs[1].id);
+        body.add_node(three);
+        body.add_node(add);
+        body.add_connection(three_id, three_out, add_id, add_b).unwrap();
+
+        let mapping = |name: &str, port: &str| PortMapping {
+            external_name: name.to_string(),
+            internal_node_id: add_id,
+            internal_port_name: port.to_string(),
+            port_type: DataType::F32,
+        };
+        let inputs = BTreeMap::from([("Add.a".to_string(), mapping("Add.a", "a"))]);
+        let outputs = BTreeMap::from([("Add.sum".to_string(), mapping("Add.sum", "sum"))]);
+        let function = GraphFunction::from_selection(
+            "add-three".to_string(),
+            String::new(),
+            &body,
+            &[three_id, add_id],
+            inputs,
+            outputs,
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut library = GraphFunctionLibrary::new(dir.path());
+        let spec = {
+            let saved = library.save(function).unwrap();
+            saved.to_component_spec(library.path_for(&saved.name))
+        };
+
+        let mut engine = ExecutionEngine::new();
+        register_builtin_executors(&mut engine);
+        engine.set_function_library(Arc::new(RwLock::new(library)));
+
+        // Outer graph: Constant(5) -> add-three
+        let mut graph = NodeGraph::new("Test".to_string(), "Test".to_string());
+        let mut five = const_spec.create_node(egui::Pos2::ZERO);
+        five.outputs[0].current_value = Some(NodeValue::F32(5.0));
+        let call = spec.create_node(egui::Pos2::ZERO);
+        let (five_id, five_out) = (five.id, five.outputs[0].id);
+        let (call_id, call_in) = (call.id, call.inputs[0].id);
+        graph.add_node(five);
+        graph.add_node(call);
+        graph.add_connection(five_id, five_out, call_id, call_in).unwrap();
+
+        engine.execute_graph(&mut graph).unwrap();
+        let call = graph.nodes.get(&call_id).unwrap();
+        assert_eq!(call.outputs[0].current_value, Some(NodeValue::F32(8.0)));
+    }
 }
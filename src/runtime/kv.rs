@@ -0,0 +1,249 @@
+//! Host-provided key-value store for components
+//!
+//! Backs the `wasmflow:node/kv` interface. Each graph node gets its own
+//! namespace, persisted as one bincode file per node under
+//! `<root>/<graph-id>/<node-id>.kv`. Namespaces are loaded lazily and written
+//! through on every change, so data survives app restarts without components
+//! needing file-system grants.
+
+use crate::graph::serialization::atomic_write;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Maximum key length in bytes
+pub const MAX_KEY_LEN: usize = 256;
+
+/// Maximum value size in bytes (1MB)
+pub const MAX_VALUE_SIZE: usize = 1024 * 1024;
+
+/// Maximum number of keys per node namespace
+pub const MAX_KEYS: usize = 10_000;
+
+/// Namespace of a single graph node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KvScope {
+    pub graph_id: Uuid,
+    pub node_id: Uuid,
+}
+
+impl KvScope {
+    pub fn new(graph_id: Uuid, node_id: Uuid) -> Self {
+        Self { graph_id, node_id }
+    }
+}
+
+/// On-disk key-value store shared by all components
+pub struct KvStore {
+    root: PathBuf,
+    namespaces: Mutex<HashMap<KvScope, BTreeMap<String, Vec<u8>>>>,
+}
+
+impl KvStore {
+    /// Create a store rooted at `root` (created on first write)
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            namespaces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Default location in the user's data directory
+    pub fn default_root() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("wasmflow")
+            .join("kv")
+    }
+
+    /// Root directory of the store
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Get a value
+    pub fn get(&self, scope: KvScope, key: &str) -> Result<Option<Vec<u8>>, String> {
+        self.with_namespace(scope, |entries| Ok(entries.get(key).cloned()))
+    }
+
+    /// Set a value, replacing any previous one
+    pub fn set(&self, scope: KvScope, key: &str, value: Vec<u8>) -> Result<(), String> {
+        validate_key(key)?;
+        if value.len() > MAX_VALUE_SIZE {
+            return Err(format!(
+                "Value too large: {} bytes (max: {} bytes)",
+                value.len(),
+                MAX_VALUE_SIZE
+            ));
+        }
+
+        self.update_namespace(scope, |entries| {
+            if !entries.contains_key(key) && entries.len() >= MAX_KEYS {
+                return Err(format!("Too many keys (max: {})", MAX_KEYS));
+            }
+            entries.insert(key.to_string(), value);
+            Ok(())
+        })
+    }
+
+    /// Delete a key; returns whether it existed
+    pub fn delete(&self, scope: KvScope, key: &str) -> Result<bool, String> {
+        self.update_namespace(scope, |entries| Ok(entries.remove(key).is_some()))
+    }
+
+    /// List keys starting with `prefix`, in sorted order
+    pub fn list_keys(&self, scope: KvScope, prefix: &str) -> Result<Vec<String>, String> {
+        self.with_namespace(scope, |entries| {
+            Ok(entries
+                .range(prefix.to_string()..)
+                .take_while(|(key, _)| key.starts_with(prefix))
+                .map(|(key, _)| key.clone())
+                .collect())
+        })
+    }
+
+    /// Remove a node's namespace entirely
+    pub fn clear(&self, scope: KvScope) -> Result<(), String> {
+        let mut namespaces = self.namespaces.lock().map_err(|_| "Store lock poisoned")?;
+        namespaces.remove(&scope);
+
+        let path = self.namespace_path(scope);
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to remove {}: {}", path.display(), e)),
+        }
+    }
+
+    fn namespace_path(&self, scope: KvScope) -> PathBuf {
+        self.root
+            .join(scope.graph_id.to_string())
+            .join(format!("{}.kv", scope.node_id))
+    }
+
+    fn with_namespace<T>(
+        &self,
+        scope: KvScope,
+        f: impl FnOnce(&BTreeMap<String, Vec<u8>>) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut namespaces = self.namespaces.lock().map_err(|_| "Store lock poisoned")?;
+        let entries = self.load_namespace(&mut namespaces, scope)?;
+        f(entries)
+    }
+
+    fn update_namespace<T>(
+        &self,
+        scope: KvScope,
+        f: impl FnOnce(&mut BTreeMap<String, Vec<u8>>) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut namespaces = self.namespaces.lock().map_err(|_| "Store lock poisoned")?;
+        let entries = self.load_namespace(&mut namespaces, scope)?;
+        let result = f(entries)?;
+        self.persist(scope, entries)?;
+        Ok(result)
+    }
+
+    fn load_namespace<'a>(
+        &self,
+        namespaces: &'a mut HashMap<KvScope, BTreeMap<String, Vec<u8>>>,
+        scope: KvScope,
+    ) -> Result<&'a mut BTreeMap<String, Vec<u8>>, String> {
+        if !namespaces.contains_key(&scope) {
+            let path = self.namespace_path(scope);
+            let entries = match std::fs::read(&path) {
+                Ok(bytes) => bincode::deserialize(&bytes)
+                    .map_err(|e| format!("Corrupt key-value file {}: {}", path.display(), e))?,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+                Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+            };
+            namespaces.insert(scope, entries);
+        }
+
+        Ok(namespaces.get_mut(&scope).expect("namespace was just loaded"))
+    }
+
+    fn persist(&self, scope: KvScope, entries: &BTreeMap<String, Vec<u8>>) -> Result<(), String> {
+        let path = self.namespace_path(scope);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let bytes = bincode::serialize(entries).map_err(|e| e.to_string())?;
+
+        atomic_write(&path, &bytes)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+fn validate_key(key: &str) -> Result<(), String> {
+    if key.is_empty() {
+        return Err("Key must not be empty".to_string());
+    }
+    if key.len() > MAX_KEY_LEN {
+        return Err(format!(
+            "Key too long: {} bytes (max: {} bytes)",
+            key.len(),
+            MAX_KEY_LEN
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope() -> KvScope {
+        KvScope::new(Uuid::new_v4(), Uuid::new_v4())
+    }
+
+    #[test]
+    fn test_set_get_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = KvStore::new(dir.path());
+        let scope = scope();
+
+        assert_eq!(store.get(scope, "seen").unwrap(), None);
+        store.set(scope, "seen", vec![1, 2]).unwrap();
+        assert_eq!(store.get(scope, "seen").unwrap(), Some(vec![1, 2]));
+        assert!(store.delete(scope, "seen").unwrap());
+        assert!(!store.delete(scope, "seen").unwrap());
+    }
+
+    #[test]
+    fn test_namespaces_are_isolated_and_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b) = (scope(), scope());
+
+        {
+            let store = KvStore::new(dir.path());
+            store.set(a, "url:1", vec![1]).unwrap();
+            store.set(a, "url:2", vec![2]).unwrap();
+            store.set(a, "other", vec![3]).unwrap();
+            assert_eq!(store.get(b, "url:1").unwrap(), None);
+        }
+
+        // A fresh store reads the namespace back from disk
+        let store = KvStore::new(dir.path());
+        assert_eq!(
+            store.list_keys(a, "url:").unwrap(),
+            vec!["url:1".to_string(), "url:2".to_string()]
+        );
+
+        store.clear(a).unwrap();
+        assert!(store.list_keys(a, "").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = KvStore::new(dir.path());
+        let scope = scope();
+
+        assert!(store.set(scope, "", vec![]).is_err());
+        assert!(store.set(scope, &"k".repeat(MAX_KEY_LEN + 1), vec![]).is_err());
+        assert!(store.set(scope, "big", vec![0; MAX_VALUE_SIZE + 1]).is_err());
+    }
+}
@@ -2,16 +2,24 @@
 //!
 //! This module handles graph execution, component loading, and capability enforcement.
 
+pub mod autowrap; // Plain WIT function exports wrapped as nodes
 pub mod capabilities;
 pub mod compiler;
+pub mod component_store; // Content-addressed copies of component binaries
 pub mod continuous;
+pub mod contract; // wasmflow:node contract versions and legacy adapters
 pub mod engine;
 pub mod instance_pool;
+pub mod kv; // Per-node key-value store behind the kv host interface
+pub mod node_log; // Per-node capture of component log messages
+pub mod progress; // Progress reporting and cooperative cancellation
+pub mod secrets; // Encrypted local secret vault behind the secrets host interface
 pub mod template_generator;
 pub mod wac_integration; // T004: WAC composition integration
 pub mod wasm_host;
 
 pub use capabilities::CapabilitySet;
 pub use compiler::{CompilationConfig, CompilationResult, ComponentCompiler};
+pub use contract::ContractVersion;
 pub use continuous::{ControlMessage, ContinuousExecutionManager, ExecutionResult};
 pub use template_generator::{ComponentMetadata, PortSpec, TemplateGenerator, TemplateType};
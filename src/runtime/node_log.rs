@@ -0,0 +1,194 @@
+//! Per-node log capture
+//!
+//! Messages components send through `host::log` are recorded against the graph
+//! node that produced them, so several instances of the same component can be
+//! told apart. Each node keeps a bounded ring buffer; the oldest entries are
+//! dropped once it is full.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Entries kept per node before the oldest are dropped
+pub const DEFAULT_LOG_CAPACITY: usize = 1000;
+
+/// Severity of a log entry, most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    /// All levels, most severe first
+    pub const ALL: [LogLevel; 4] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+    ];
+
+    /// Parse the level string passed to `host::log`; unknown levels are Info
+    pub fn parse(level: &str) -> Self {
+        match level.to_ascii_lowercase().as_str() {
+            "error" => LogLevel::Error,
+            "warn" | "warning" => LogLevel::Warn,
+            "debug" | "trace" => LogLevel::Debug,
+            _ => LogLevel::Info,
+        }
+    }
+
+    /// Display label
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+        }
+    }
+}
+
+/// A message logged by a component
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// Insertion order across all nodes
+    pub sequence: u64,
+    pub timestamp: chrono::DateTime<chrono::Local>,
+    pub node_id: Uuid,
+    pub component_id: String,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+/// Bounded log buffers for every node
+#[derive(Debug)]
+pub struct NodeLogs {
+    capacity: usize,
+    next_sequence: AtomicU64,
+    buffers: Mutex<HashMap<Uuid, VecDeque<LogEntry>>>,
+}
+
+impl NodeLogs {
+    /// Create buffers holding at most `capacity` entries per node
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            next_sequence: AtomicU64::new(0),
+            buffers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a message from a node
+    pub fn push(&self, node_id: Uuid, component_id: &str, level: LogLevel, message: &str) {
+        let entry = LogEntry {
+            sequence: self.next_sequence.fetch_add(1, Ordering::Relaxed),
+            timestamp: chrono::Local::now(),
+            node_id,
+            component_id: component_id.to_string(),
+            level,
+            message: message.to_string(),
+        };
+
+        let Ok(mut buffers) = self.buffers.lock() else {
+            return;
+        };
+        let buffer = buffers.entry(node_id).or_default();
+        if buffer.len() >= self.capacity {
+            buffer.pop_front();
+        }
+        buffer.push_back(entry);
+    }
+
+    /// Entries at `max_level` or more severe, oldest first
+    ///
+    /// With `node_id` set only that node's entries are returned.
+    pub fn entries(&self, node_id: Option<Uuid>, max_level: LogLevel) -> Vec<LogEntry> {
+        let Ok(buffers) = self.buffers.lock() else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<LogEntry> = buffers
+            .iter()
+            .filter(|(id, _)| node_id.is_none_or(|node_id| **id == node_id))
+            .flat_map(|(_, buffer)| buffer.iter())
+            .filter(|entry| entry.level <= max_level)
+            .cloned()
+            .collect();
+        entries.sort_by_key(|entry| entry.sequence);
+        entries
+    }
+
+    /// Nodes that have logged anything
+    pub fn nodes(&self) -> Vec<Uuid> {
+        self.buffers
+            .lock()
+            .map(|buffers| buffers.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Drop one node's entries, or everything with `None`
+    pub fn clear(&self, node_id: Option<Uuid>) {
+        if let Ok(mut buffers) = self.buffers.lock() {
+            match node_id {
+                Some(node_id) => {
+                    buffers.remove(&node_id);
+                }
+                None => buffers.clear(),
+            }
+        }
+    }
+}
+
+impl Default for NodeLogs {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_is_bounded_per_node() {
+        let logs = NodeLogs::new(2);
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+
+        logs.push(a, "user:echo", LogLevel::Info, "one");
+        logs.push(a, "user:echo", LogLevel::Info, "two");
+        logs.push(a, "user:echo", LogLevel::Info, "three");
+        logs.push(b, "user:echo", LogLevel::Info, "other");
+
+        let messages: Vec<String> = logs
+            .entries(Some(a), LogLevel::Debug)
+            .into_iter()
+            .map(|entry| entry.message)
+            .collect();
+        assert_eq!(messages, vec!["two", "three"]);
+        assert_eq!(logs.entries(None, LogLevel::Debug).len(), 3);
+    }
+
+    #[test]
+    fn test_level_filter() {
+        let logs = NodeLogs::default();
+        let node = Uuid::new_v4();
+
+        logs.push(node, "user:fetch", LogLevel::parse("debug"), "details");
+        logs.push(node, "user:fetch", LogLevel::parse("warning"), "slow");
+        logs.push(node, "user:fetch", LogLevel::parse("error"), "failed");
+
+        let levels: Vec<LogLevel> = logs
+            .entries(None, LogLevel::Warn)
+            .into_iter()
+            .map(|entry| entry.level)
+            .collect();
+        assert_eq!(levels, vec![LogLevel::Warn, LogLevel::Error]);
+
+        logs.clear(Some(node));
+        assert!(logs.entries(None, LogLevel::Debug).is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+//! Progress reporting and cooperative cancellation
+//!
+//! An [`ExecutionControl`] is shared between a running component (through the
+//! `report-progress` and `is-cancelled` host functions) and whoever observes
+//! it: the UI polls the latest progress and requests cancellation, and the
+//! guest checks for it and exits cleanly.
+
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// Latest progress reported by a running component
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionProgress {
+    /// Completed fraction, clamped to 0.0..=1.0
+    pub fraction: f32,
+    /// Short description of the current step
+    pub message: String,
+}
+
+/// Progress and cancellation state of one execution
+#[derive(Debug, Default)]
+pub struct ExecutionControl {
+    progress: Mutex<Option<ExecutionProgress>>,
+    cancellation: CancellationToken,
+}
+
+impl ExecutionControl {
+    /// Create a control with no progress and no cancellation requested
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a control cancelled together with `token`
+    pub fn with_token(token: CancellationToken) -> Self {
+        Self {
+            progress: Mutex::new(None),
+            cancellation: token,
+        }
+    }
+
+    /// Record progress from the component
+    pub fn report_progress(&self, fraction: f32, message: String) {
+        let fraction = if fraction.is_nan() {
+            0.0
+        } else {
+            fraction.clamp(0.0, 1.0)
+        };
+        if let Ok(mut progress) = self.progress.lock() {
+            *progress = Some(ExecutionProgress { fraction, message });
+        }
+    }
+
+    /// Latest reported progress, if any
+    pub fn progress(&self) -> Option<ExecutionProgress> {
+        self.progress.lock().ok().and_then(|progress| progress.clone())
+    }
+
+    /// Ask the component to stop
+    pub fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    /// Whether cancellation was requested
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Wait until cancellation is requested
+    pub async fn cancelled(&self) {
+        self.cancellation.cancelled().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_is_clamped() {
+        let control = ExecutionControl::new();
+        assert_eq!(control.progress(), None);
+
+        control.report_progress(1.5, "done".to_string());
+        assert_eq!(control.progress().unwrap().fraction, 1.0);
+
+        control.report_progress(f32::NAN, "unknown".to_string());
+        assert_eq!(control.progress().unwrap().fraction, 0.0);
+    }
+
+    #[test]
+    fn test_cancel_follows_token() {
+        let token = CancellationToken::new();
+        let control = ExecutionControl::with_token(token.clone());
+        assert!(!control.is_cancelled());
+
+        token.cancel();
+        assert!(control.is_cancelled());
+    }
+}
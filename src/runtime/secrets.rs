@@ -0,0 +1,243 @@
+//! Encrypted local secret vault
+//!
+//! Backs the `wasmflow:node/secrets` interface. Secrets are stored in a single
+//! file encrypted with ChaCha20-Poly1305 under a key derived from the user's
+//! passphrase (Argon2id). Graphs only ever reference secrets by name through
+//! capability grants; values stay in the vault.
+
+use crate::graph::serialization::atomic_write;
+use crate::SecretError;
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Current vault file format
+const VAULT_VERSION: u32 = 1;
+
+/// Salt length for key derivation
+const SALT_LEN: usize = 16;
+
+/// Vault shared between the app (which unlocks it) and running components
+pub type SharedVault = Arc<RwLock<Option<SecretVault>>>;
+
+/// On-disk layout; everything but the salt and nonce is encrypted
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    salt: Vec<u8>,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+/// An unlocked secret vault
+pub struct SecretVault {
+    path: PathBuf,
+    salt: Vec<u8>,
+    key: Key,
+    secrets: BTreeMap<String, String>,
+}
+
+impl SecretVault {
+    /// Default vault location in the user's data directory
+    pub fn default_path() -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("wasmflow")
+            .join("secrets.vault")
+    }
+
+    /// Whether a vault file exists at `path`
+    pub fn exists(path: &Path) -> bool {
+        path.is_file()
+    }
+
+    /// Create an empty vault protected by `passphrase`, replacing any file at `path`
+    pub fn create(path: impl Into<PathBuf>, passphrase: &str) -> Result<Self, SecretError> {
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        let vault = Self {
+            path: path.into(),
+            key: derive_key(passphrase, &salt)?,
+            salt,
+            secrets: BTreeMap::new(),
+        };
+        vault.save()?;
+        Ok(vault)
+    }
+
+    /// Decrypt the vault at `path`
+    pub fn unlock(path: impl Into<PathBuf>, passphrase: &str) -> Result<Self, SecretError> {
+        let path = path.into();
+        let bytes = std::fs::read(&path)?;
+        let file: VaultFile = bincode::deserialize(&bytes).map_err(|e| SecretError::Corrupt {
+            path: path.clone(),
+            reason: e.to_string(),
+        })?;
+        if file.version != VAULT_VERSION {
+            return Err(SecretError::Corrupt {
+                path,
+                reason: format!("unsupported vault version {}", file.version),
+            });
+        }
+        if file.nonce.len() != 12 {
+            return Err(SecretError::Corrupt {
+                path,
+                reason: "bad nonce".to_string(),
+            });
+        }
+
+        let key = derive_key(passphrase, &file.salt)?;
+        let plaintext = ChaCha20Poly1305::new(&key)
+            .decrypt(Nonce::from_slice(&file.nonce), file.ciphertext.as_slice())
+            .map_err(|_| SecretError::WrongPassphrase)?;
+        let secrets = bincode::deserialize(&plaintext).map_err(|e| SecretError::Corrupt {
+            path: path.clone(),
+            reason: e.to_string(),
+        })?;
+
+        Ok(Self {
+            path,
+            salt: file.salt,
+            key,
+            secrets,
+        })
+    }
+
+    /// Path of the vault file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Names of stored secrets, sorted
+    pub fn names(&self) -> Vec<String> {
+        self.secrets.keys().cloned().collect()
+    }
+
+    /// Look up a secret value
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.secrets.get(name).map(String::as_str)
+    }
+
+    /// Store a secret and write the vault
+    pub fn set(&mut self, name: &str, value: String) -> Result<(), SecretError> {
+        validate_name(name)?;
+        self.secrets.insert(name.to_string(), value);
+        self.save()
+    }
+
+    /// Remove a secret and write the vault; returns whether it existed
+    pub fn remove(&mut self, name: &str) -> Result<bool, SecretError> {
+        let existed = self.secrets.remove(name).is_some();
+        if existed {
+            self.save()?;
+        }
+        Ok(existed)
+    }
+
+    fn save(&self) -> Result<(), SecretError> {
+        let plaintext = bincode::serialize(&self.secrets)
+            .map_err(|e| SecretError::Crypto(e.to_string()))?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = ChaCha20Poly1305::new(&self.key)
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|e| SecretError::Crypto(e.to_string()))?;
+
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            salt: self.salt.clone(),
+            nonce: nonce.to_vec(),
+            ciphertext,
+        };
+        let bytes = bincode::serialize(&file).map_err(|e| SecretError::Crypto(e.to_string()))?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        atomic_write(&self.path, &bytes)?;
+        Ok(())
+    }
+}
+
+// Never print secret values
+impl std::fmt::Debug for SecretVault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretVault")
+            .field("path", &self.path)
+            .field("names", &self.secrets.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Secret names are used in capability strings (`secret:<name>`)
+fn validate_name(name: &str) -> Result<(), SecretError> {
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(SecretError::InvalidName(name.to_string()))
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key, SecretError> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| SecretError::Crypto(e.to_string()))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.vault");
+
+        let mut vault = SecretVault::create(&path, "correct horse").unwrap();
+        vault.set("openai-key", "sk-123".to_string()).unwrap();
+        assert!(SecretVault::exists(&path));
+
+        let vault = SecretVault::unlock(&path, "correct horse").unwrap();
+        assert_eq!(vault.get("openai-key"), Some("sk-123"));
+        assert_eq!(vault.names(), vec!["openai-key".to_string()]);
+
+        // The value is not stored in clear text
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(!bytes.windows(6).any(|w| w == b"sk-123"));
+    }
+
+    #[test]
+    fn test_wrong_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.vault");
+        SecretVault::create(&path, "correct horse").unwrap();
+
+        assert!(matches!(
+            SecretVault::unlock(&path, "wrong"),
+            Err(SecretError::WrongPassphrase)
+        ));
+    }
+
+    #[test]
+    fn test_names_are_validated_and_debug_hides_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = SecretVault::create(dir.path().join("v"), "pass").unwrap();
+
+        assert!(vault.set("bad name", "x".to_string()).is_err());
+        vault.set("token", "hunter2".to_string()).unwrap();
+        assert!(!format!("{:?}", vault).contains("hunter2"));
+        assert!(vault.remove("token").unwrap());
+    }
+}
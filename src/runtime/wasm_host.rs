@@ -3,22 +3,35 @@
 //! This module implements the WebAssembly component runtime using wasmtime.
 //! It provides:
 //! - Component loading and validation
-//! - Host function implementations (logging, temp directory access)
+//! - Host function implementations (logging, temp directory access, key-value store,
+//!   progress reporting and cancellation, secrets)
 //! - WASI context configuration based on capability grants
 //! - Component metadata extraction
 
-use crate::graph::node::{ComponentSpec, NodeValue};
+use crate::graph::node::{ComponentSpec, DataType, NodeValue, ParameterKind, ParameterSpec};
+use crate::graph::pin::{content_hash, pinned_id};
+use crate::graph::schema;
 use crate::runtime::capabilities::{Capability, CapabilitySet};
+use crate::runtime::component_store::ComponentStore;
+use crate::runtime::autowrap::{self, PlainFunction};
+use crate::runtime::contract::{self, ContractVersion};
+use crate::runtime::kv::{KvScope, KvStore};
+use crate::runtime::node_log::{LogLevel, NodeLogs};
+use crate::runtime::progress::ExecutionControl;
+use crate::runtime::secrets::SharedVault;
 use crate::ui::wit_ui_renderer::{
-    ColoredText, FooterView as WitFooterView, HorizontalLayout, KeyValuePair, UiElement,
-    UiElementItem, VerticalLayout, WitFooterViewAdapter,
+    ButtonSpec, ChartKind, ChartSpec, CheckboxSpec, ColoredText, DropdownSpec,
+    FooterView as WitFooterView, HorizontalLayout, ImageData, ImageSpec, KeyValuePair,
+    LayoutContainer, LayoutContent, LayoutNode, LayoutTree,
+    ProgressBarSpec, SliderSpec, TableSpec, TextInputSpec, UiElement, UiElementItem, UiEvent,
+    UiEventResponse, UiEventValue, VerticalLayout, WitFooterViewAdapter,
 };
 use crate::ComponentError;
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
-use wasmtime::component::{Component as WasmComponent, Linker, ResourceTable};
+use wasmtime::component::{Component as WasmComponent, Linker, ResourceTable, Val};
 use wasmtime::{Config, Engine, Store};
 use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiView};
 use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
@@ -30,12 +43,79 @@ wasmtime::component::bindgen!({
     async: true,
 });
 
-// Generate bindings for component-with-ui world in a separate module
+// Generate bindings for component-with-ui world in a separate module, sharing the base
+// types and host modules so values need no per-world conversion
 pub mod with_ui {
     wasmtime::component::bindgen!({
         path: "wit",
         world: "component-with-ui",
         async: true,
+        with: {
+            "wasmflow:node/types@1.2.0": crate::runtime::wasm_host::wasmflow::node::types,
+            "wasmflow:node/host@1.2.0": crate::runtime::wasm_host::wasmflow::node::host,
+            "wasmflow:node/kv@1.2.0": crate::runtime::wasm_host::wasmflow::node::kv,
+            "wasmflow:node/secrets@1.2.0": crate::runtime::wasm_host::wasmflow::node::secrets,
+        },
+    });
+}
+
+// Generate bindings for component-with-schema world, sharing the base types and host modules
+pub mod with_schema {
+    wasmtime::component::bindgen!({
+        path: "wit",
+        world: "component-with-schema",
+        async: true,
+        with: {
+            "wasmflow:node/types@1.2.0": crate::runtime::wasm_host::wasmflow::node::types,
+            "wasmflow:node/host@1.2.0": crate::runtime::wasm_host::wasmflow::node::host,
+            "wasmflow:node/kv@1.2.0": crate::runtime::wasm_host::wasmflow::node::kv,
+            "wasmflow:node/secrets@1.2.0": crate::runtime::wasm_host::wasmflow::node::secrets,
+        },
+    });
+}
+
+// Generate bindings for component-with-config world, sharing the base types and host modules
+pub mod with_config {
+    wasmtime::component::bindgen!({
+        path: "wit",
+        world: "component-with-config",
+        async: true,
+        with: {
+            "wasmflow:node/types@1.2.0": crate::runtime::wasm_host::wasmflow::node::types,
+            "wasmflow:node/host@1.2.0": crate::runtime::wasm_host::wasmflow::node::host,
+            "wasmflow:node/kv@1.2.0": crate::runtime::wasm_host::wasmflow::node::kv,
+            "wasmflow:node/secrets@1.2.0": crate::runtime::wasm_host::wasmflow::node::secrets,
+        },
+    });
+}
+
+// Generate bindings for the component-continuous world
+pub mod with_continuous {
+    wasmtime::component::bindgen!({
+        path: "wit",
+        world: "component-continuous",
+        async: true,
+        with: {
+            "wasmflow:node/types@1.2.0": crate::runtime::wasm_host::wasmflow::node::types,
+            "wasmflow:node/host@1.2.0": crate::runtime::wasm_host::wasmflow::node::host,
+            "wasmflow:node/kv@1.2.0": crate::runtime::wasm_host::wasmflow::node::kv,
+            "wasmflow:node/secrets@1.2.0": crate::runtime::wasm_host::wasmflow::node::secrets,
+        },
+    });
+}
+
+// Generate bindings for the component-stateful world
+pub mod with_stateful {
+    wasmtime::component::bindgen!({
+        path: "wit",
+        world: "component-stateful",
+        async: true,
+        with: {
+            "wasmflow:node/types@1.2.0": crate::runtime::wasm_host::wasmflow::node::types,
+            "wasmflow:node/host@1.2.0": crate::runtime::wasm_host::wasmflow::node::host,
+            "wasmflow:node/kv@1.2.0": crate::runtime::wasm_host::wasmflow::node::kv,
+            "wasmflow:node/secrets@1.2.0": crate::runtime::wasm_host::wasmflow::node::secrets,
+        },
     });
 }
 
@@ -58,6 +138,17 @@ pub struct HostState {
     pub component_id: String,
     /// Granted capabilities
     pub capabilities: CapabilitySet,
+    /// Key-value store and the node namespace it is scoped to
+    pub kv: Option<(Arc<KvStore>, KvScope)>,
+    /// Progress and cancellation of the current execution
+    pub control: Option<Arc<ExecutionControl>>,
+    /// Per-node log buffers and the node messages are recorded against
+    pub logs: Option<(Arc<NodeLogs>, uuid::Uuid)>,
+    /// Secret vault (unlocked by the user in the app)
+    pub vault: Option<SharedVault>,
+    /// Secret values handed to the component, redacted from its logs, errors
+    /// and outputs
+    revealed_secrets: Vec<String>,
 }
 
 impl HostState {
@@ -78,9 +169,38 @@ impl HostState {
             table,
             component_id,
             capabilities,
+            kv: None,
+            control: None,
+            logs: None,
+            vault: None,
+            revealed_secrets: Vec::new(),
         })
     }
 
+    /// Give the component access to a node's key-value namespace
+    pub fn with_kv(mut self, store: Arc<KvStore>, scope: Option<KvScope>) -> Self {
+        self.kv = scope.map(|scope| (store, scope));
+        self
+    }
+
+    /// Record log messages against `node_id` in addition to the global log
+    pub fn with_logs(mut self, logs: Arc<NodeLogs>, node_id: Option<uuid::Uuid>) -> Self {
+        self.logs = node_id.map(|node_id| (logs, node_id));
+        self
+    }
+
+    /// Give the component access to the secret vault (subject to its grants)
+    pub fn with_vault(mut self, vault: SharedVault) -> Self {
+        self.vault = Some(vault);
+        self
+    }
+
+    /// Route progress reports and cancellation checks through `control`
+    pub fn with_control(mut self, control: Option<Arc<ExecutionControl>>) -> Self {
+        self.control = control;
+        self
+    }
+
     /// Configure WASI context based on capability set
     /// T067-T070: Implementation of WASI context configuration
     fn configure_wasi(builder: &mut WasiCtxBuilder, capabilities: &CapabilitySet) -> Result<()> {
@@ -123,6 +243,10 @@ impl HostState {
                 // TODO: Implement custom outgoing handler to validate allowed_hosts
                 builder.inherit_network();
             }
+            CapabilitySet::KeyValue { base } | CapabilitySet::Secrets { base, .. } => {
+                // Key-value and secret access are enforced by their host interfaces
+                Self::configure_wasi(builder, base)?;
+            }
             CapabilitySet::Full => {
                 // Full access - inherit everything
                 builder.inherit_stdio().inherit_env().inherit_network();
@@ -134,6 +258,7 @@ impl HostState {
 
     /// Log a message from the component
     pub fn log(&mut self, level: &str, message: &str) {
+        let message = &self.redact(message);
         let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
         match level {
             "error" => log::error!("[{}] [{}] {}", timestamp, self.component_id, message),
@@ -142,6 +267,10 @@ impl HostState {
             "debug" => log::debug!("[{}] [{}] {}", timestamp, self.component_id, message),
             _ => log::info!("[{}] [{}] {}", timestamp, self.component_id, message),
         }
+
+        if let Some((logs, node_id)) = &self.logs {
+            logs.push(*node_id, &self.component_id, LogLevel::parse(level), message);
+        }
     }
 
     /// Get temporary directory path
@@ -157,6 +286,106 @@ impl HostState {
             .map(|s| s.to_string())
             .ok_or_else(|| "Failed to get temp directory".to_string())
     }
+
+    /// Record progress reported by the component
+    pub fn report_progress(&self, fraction: f32, message: String) {
+        match &self.control {
+            Some(control) => control.report_progress(fraction, message),
+            None => log::debug!(
+                "[{}] progress {:.0}%: {}",
+                self.component_id,
+                fraction * 100.0,
+                message
+            ),
+        }
+    }
+
+    /// Whether cancellation of the current execution was requested
+    pub fn is_cancelled(&self) -> bool {
+        self.control
+            .as_ref()
+            .is_some_and(|control| control.is_cancelled())
+    }
+
+    /// Read a secret the node was granted by name
+    pub fn get_secret(&mut self, name: &str) -> Result<String, String> {
+        if !self.capabilities.allows_secret(name) {
+            return Err(format!("Secret '{}' was not granted to this node", name));
+        }
+
+        let vault = self
+            .vault
+            .as_ref()
+            .ok_or_else(|| "Secrets are not available outside a graph node".to_string())?;
+        let value = {
+            let guard = vault
+                .read()
+                .map_err(|_| "Secret vault lock poisoned".to_string())?;
+            let vault = guard
+                .as_ref()
+                .ok_or_else(|| crate::SecretError::Locked.to_string())?;
+            vault
+                .get(name)
+                .ok_or_else(|| format!("Secret '{}' not found in the vault", name))?
+                .to_string()
+        };
+
+        if !value.is_empty() && !self.revealed_secrets.contains(&value) {
+            self.revealed_secrets.push(value.clone());
+        }
+        Ok(value)
+    }
+
+    /// Mask secret values handed to this component
+    pub fn redact(&self, text: &str) -> String {
+        self.revealed_secrets
+            .iter()
+            .fold(text.to_string(), |text, secret| text.replace(secret.as_str(), "••••••"))
+    }
+
+    /// Mask secret values in the text of a component's outputs
+    ///
+    /// Outputs are shown on the canvas and saved with the graph, so a
+    /// component that echoes a secret must not leak it there either.
+    pub fn redact_outputs(
+        &self,
+        outputs: HashMap<String, NodeValue>,
+    ) -> HashMap<String, NodeValue> {
+        if self.revealed_secrets.is_empty() {
+            return outputs;
+        }
+        outputs
+            .into_iter()
+            .map(|(name, value)| (name, self.redact_value(value)))
+            .collect()
+    }
+
+    fn redact_value(&self, value: NodeValue) -> NodeValue {
+        match value {
+            NodeValue::String(text) => NodeValue::String(self.redact(&text)),
+            NodeValue::List(items) => {
+                NodeValue::List(items.into_iter().map(|item| self.redact_value(item)).collect())
+            }
+            NodeValue::Record(fields) => NodeValue::Record(
+                fields
+                    .into_iter()
+                    .map(|(key, field)| (key, self.redact_value(field)))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    /// Key-value namespace of the node, if the component may use it
+    fn kv_namespace(&self) -> Result<(&KvStore, KvScope), String> {
+        if !self.capabilities.has(Capability::KeyValue) {
+            return Err("Component does not have permission for the key-value store".to_string());
+        }
+        self.kv
+            .as_ref()
+            .map(|(store, scope)| (store.as_ref(), *scope))
+            .ok_or_else(|| "Key-value store is not available outside a graph node".to_string())
+    }
 }
 
 impl WasiView for HostState {
@@ -212,13 +441,10 @@ impl wasmflow::node::host::Host for HostState {
     {
         Box::pin(async move { HostState::get_temp_dir(self) })
     }
-}
 
-// Also implement for component-with-ui bindings
-impl with_ui::wasmflow::node::host::Host for HostState {
-    fn log<'life0, 'async_trait>(
+    fn report_progress<'life0, 'async_trait>(
         &'life0 mut self,
-        level: String,
+        fraction: f32,
         message: String,
     ) -> ::core::pin::Pin<
         Box<dyn ::core::future::Future<Output = ()> + ::core::marker::Send + 'async_trait>,
@@ -228,12 +454,28 @@ impl with_ui::wasmflow::node::host::Host for HostState {
         Self: 'async_trait,
     {
         Box::pin(async move {
-            HostState::log(self, &level, &message);
+            HostState::report_progress(self, fraction, message);
         })
     }
 
-    fn get_temp_dir<'life0, 'async_trait>(
+    fn is_cancelled<'life0, 'async_trait>(
+        &'life0 mut self,
+    ) -> ::core::pin::Pin<
+        Box<dyn ::core::future::Future<Output = bool> + ::core::marker::Send + 'async_trait>,
+    >
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move { HostState::is_cancelled(self) })
+    }
+}
+
+// Secrets from the shared vault, limited to the node's granted names
+impl wasmflow::node::secrets::Host for HostState {
+    fn get<'life0, 'async_trait>(
         &'life0 mut self,
+        name: String,
     ) -> ::core::pin::Pin<
         Box<
             dyn ::core::future::Future<Output = Result<String, String>>
@@ -245,7 +487,91 @@ impl with_ui::wasmflow::node::host::Host for HostState {
         'life0: 'async_trait,
         Self: 'async_trait,
     {
-        Box::pin(async move { HostState::get_temp_dir(self) })
+        Box::pin(async move { HostState::get_secret(self, &name) })
+    }
+}
+
+// Key-value store backed by the manager's KvStore, scoped to the executing node
+impl wasmflow::node::kv::Host for HostState {
+    fn get<'life0, 'async_trait>(
+        &'life0 mut self,
+        key: String,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = Result<Option<Vec<u8>>, String>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let (store, scope) = self.kv_namespace()?;
+            store.get(scope, &key)
+        })
+    }
+
+    fn set<'life0, 'async_trait>(
+        &'life0 mut self,
+        key: String,
+        value: Vec<u8>,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = Result<(), String>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let (store, scope) = self.kv_namespace()?;
+            store.set(scope, &key, value)
+        })
+    }
+
+    fn delete<'life0, 'async_trait>(
+        &'life0 mut self,
+        key: String,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = Result<bool, String>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let (store, scope) = self.kv_namespace()?;
+            store.delete(scope, &key)
+        })
+    }
+
+    fn list_keys<'life0, 'async_trait>(
+        &'life0 mut self,
+        prefix: String,
+    ) -> ::core::pin::Pin<
+        Box<
+            dyn ::core::future::Future<Output = Result<Vec<String>, String>>
+                + ::core::marker::Send
+                + 'async_trait,
+        >,
+    >
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let (store, scope) = self.kv_namespace()?;
+            store.list_keys(scope, &prefix)
+        })
     }
 }
 
@@ -258,6 +584,52 @@ struct ComponentData {
     bytecode: Arc<Vec<u8>>,
     /// Cached compiled component (lazily compiled)
     compiled: Option<Arc<WasmComponent>>,
+    /// Component exports the config interface (execute via execute-with-config)
+    supports_config: bool,
+    /// `wasmflow:node` contract version the component exports
+    version: ContractVersion,
+    /// Exported function called directly (auto-wrapped plain WIT components)
+    plain_function: Option<PlainFunction>,
+    /// Component exports the stateful interface (one live instance per node)
+    stateful: bool,
+}
+
+/// What a contract component reports about itself when it is loaded
+struct ComponentProbe {
+    info: wasmflow::node::types::ComponentInfo,
+    inputs: Vec<wasmflow::node::types::PortSpec>,
+    outputs: Vec<wasmflow::node::types::PortSpec>,
+    capabilities: Option<Vec<String>>,
+    /// Configuration parameters (config interface)
+    parameters: Vec<ParameterSpec>,
+    /// Record schemas keyed by port name (schema interface)
+    input_schemas: Vec<(String, DataType)>,
+    output_schemas: Vec<(String, DataType)>,
+    /// Footer view rendered for zeroed outputs (ui interface)
+    footer_view: Option<WitFooterView>,
+}
+
+/// Live instance of a stateful component, owned by one graph node
+struct StatefulInstance {
+    component_id: String,
+    capabilities: CapabilitySet,
+    /// Fingerprint of the node's incoming connections when the state was built
+    wiring: u64,
+    store: Store<HostState>,
+    instance: with_stateful::ComponentStateful,
+    /// Config interface of the same instance, for components with parameters
+    config: Option<with_config::ComponentWithConfig>,
+    /// UI interface of the same instance, so footer input reaches its state
+    ui: Option<with_ui::ComponentWithUi>,
+}
+
+/// Live instance that renders a node's footer view, kept so frequent
+/// refreshes (e.g. while a continuous node drives the graph) skip instantiation
+struct FooterInstance {
+    component_id: String,
+    capabilities: CapabilitySet,
+    store: Store<HostState>,
+    instance: with_ui::ComponentWithUi,
 }
 
 /// Component manager for loading and executing WASM components
@@ -270,6 +642,18 @@ pub struct ComponentManager {
     components: HashMap<String, ComponentData>,
     /// T083: LRU cache for tracking compilation order
     lru_order: Vec<String>,
+    /// Per-node instances of stateful components
+    stateful_instances: HashMap<uuid::Uuid, StatefulInstance>,
+    /// Per-node instances used to refresh custom footer views
+    footer_instances: HashMap<uuid::Uuid, FooterInstance>,
+    /// Backing store of the kv host interface
+    kv_store: Arc<KvStore>,
+    /// Messages logged by components, per graph node
+    node_logs: Arc<NodeLogs>,
+    /// Secret vault behind the secrets host interface (None while locked)
+    secret_vault: SharedVault,
+    /// Keeps a copy of every loaded binary for pinned nodes (None: no copies)
+    component_store: Option<ComponentStore>,
 }
 
 impl ComponentManager {
@@ -301,22 +685,59 @@ impl ComponentManager {
             linker: Arc::new(Mutex::new(linker)),
             components: HashMap::new(),
             lru_order: Vec::new(),
+            stateful_instances: HashMap::new(),
+            footer_instances: HashMap::new(),
+            kv_store: Arc::new(KvStore::new(KvStore::default_root())),
+            node_logs: Arc::new(NodeLogs::default()),
+            secret_vault: SharedVault::default(),
+            component_store: None,
         })
     }
 
+    /// Vault the app unlocks for components to read granted secrets from
+    pub fn secret_vault(&self) -> SharedVault {
+        Arc::clone(&self.secret_vault)
+    }
+
+    /// Log buffers filled by components' `host::log` calls
+    pub fn node_logs(&self) -> Arc<NodeLogs> {
+        Arc::clone(&self.node_logs)
+    }
+
+    /// Use a key-value store rooted elsewhere (e.g. a temp dir in tests)
+    pub fn with_kv_store(mut self, store: KvStore) -> Self {
+        self.kv_store = Arc::new(store);
+        self
+    }
+
+    /// Copy every binary loaded from now on into `store`
+    pub fn set_component_store(&mut self, store: ComponentStore) {
+        self.component_store = Some(store);
+    }
+
+    /// Store that loaded binaries are copied into, if any
+    pub fn component_store(&self) -> Option<&ComponentStore> {
+        self.component_store.as_ref()
+    }
+
     /// Add custom host functions to the linker
     fn add_host_functions(linker: &mut Linker<HostState>) -> Result<()> {
         // Add WasmFlow host functions using generated bindings
         // Both component and component-with-ui worlds import the same host interface,
         // so we only need to add it once
         wasmflow::node::host::add_to_linker(linker, |state| state)?;
+        wasmflow::node::kv::add_to_linker(linker, |state| state)?;
+        wasmflow::node::secrets::add_to_linker(linker, |state| state)?;
 
         Ok(())
     }
 
     /// T083: Load a component from a file (lazy compilation)
     /// Stores bytecode, defers compilation until first execution
-    pub async fn load_component(&mut self, path: &Path) -> Result<ComponentSpec, ComponentError> {
+    pub async fn load_component(
+        &mut self,
+        path: &Path,
+    ) -> Result<Vec<ComponentSpec>, ComponentError> {
         // Validate file size
         let metadata = std::fs::metadata(path).map_err(|e| ComponentError::LoadFailed {
             path: path.to_path_buf(),
@@ -337,25 +758,23 @@ impl ComponentManager {
             reason: format!("Failed to read component file: {}", e),
         })?;
 
-        // Create component spec from basic metadata
-        let component_spec = self.create_basic_spec_from_bytecode(&bytecode, path)?;
+        // Create component specs from basic metadata
+        let loaded = self.create_specs_from_bytecode(&bytecode, path)?;
+        self.archive(&bytecode, path);
 
         // T083: Store bytecode only, defer compilation
-        self.components.insert(
-            component_spec.id.clone(),
-            ComponentData {
-                bytecode: Arc::new(bytecode),
-                compiled: None,
-            },
-        );
+        let specs = self.store_loaded_specs(Arc::new(bytecode), loaded);
 
-        log::debug!("Loaded component bytecode (lazy): {}", component_spec.id);
-        Ok(component_spec)
+        log::debug!("Loaded component bytecode (lazy): {}", path.display());
+        Ok(specs)
     }
 
     /// T083: Load a component synchronously (for UI integration)
     /// Stores bytecode, defers compilation until first execution
-    pub fn load_component_sync(&mut self, path: &Path) -> Result<ComponentSpec, ComponentError> {
+    pub fn load_component_sync(
+        &mut self,
+        path: &Path,
+    ) -> Result<Vec<ComponentSpec>, ComponentError> {
         // Validate file size
         let metadata = std::fs::metadata(path).map_err(|e| ComponentError::LoadFailed {
             path: path.to_path_buf(),
@@ -376,31 +795,109 @@ impl ComponentManager {
             reason: format!("Failed to read component file: {}", e),
         })?;
 
-        // Create component spec from basic metadata
-        let component_spec = self.create_basic_spec_from_bytecode(&bytecode, path)?;
+        // Create component specs from basic metadata
+        let loaded = self.create_specs_from_bytecode(&bytecode, path)?;
+        self.archive(&bytecode, path);
 
         // T083: Store bytecode only, defer compilation
-        self.components.insert(
-            component_spec.id.clone(),
-            ComponentData {
-                bytecode: Arc::new(bytecode),
-                compiled: None,
-            },
-        );
+        let specs = self.store_loaded_specs(Arc::new(bytecode), loaded);
 
-        log::debug!(
-            "Loaded component bytecode (lazy, sync): {}",
-            component_spec.id
-        );
-        Ok(component_spec)
+        log::debug!("Loaded component bytecode (lazy, sync): {}", path.display());
+        Ok(specs)
+    }
+
+    /// Load a binary from the component store under pinned ids
+    ///
+    /// The specs get ids of the form `<component-id>@<hash>`, so the copy
+    /// runs alongside whatever is loaded under the plain id.
+    pub fn load_pinned_component_sync(
+        &mut self,
+        hash: &str,
+    ) -> Result<Vec<ComponentSpec>, ComponentError> {
+        let path = self
+            .component_store
+            .as_ref()
+            .and_then(|store| store.find(hash))
+            .ok_or_else(|| {
+                ComponentError::ValidationFailed(format!(
+                    "No stored copy of component binary {}",
+                    hash
+                ))
+            })?;
+        let bytecode = std::fs::read(&path).map_err(|e| ComponentError::LoadFailed {
+            path: path.clone(),
+            reason: format!("Failed to read component file: {}", e),
+        })?;
+        if content_hash(&bytecode) != hash {
+            return Err(ComponentError::LoadFailed {
+                path,
+                reason: format!("Stored copy does not match hash {}", hash),
+            });
+        }
+
+        let loaded = self
+            .create_specs_from_bytecode(&bytecode, &path)?
+            .into_iter()
+            .map(|(mut spec, plain_function)| {
+                spec.id = pinned_id(&spec.id, hash);
+                spec.name = format!("{} (pinned {})", spec.name, spec.version);
+                (spec, plain_function)
+            })
+            .collect();
+        Ok(self.store_loaded_specs(Arc::new(bytecode), loaded))
+    }
+
+    /// Copy a loaded binary into the component store, if one is set
+    fn archive(&self, bytecode: &[u8], path: &Path) {
+        if let Some(store) = &self.component_store {
+            if let Err(e) = store.put(bytecode, path) {
+                log::warn!("Failed to keep a copy of {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Record loaded specs against their shared bytecode
+    fn store_loaded_specs(
+        &mut self,
+        bytecode: Arc<Vec<u8>>,
+        loaded: Vec<(ComponentSpec, Option<PlainFunction>)>,
+    ) -> Vec<ComponentSpec> {
+        let hash = content_hash(&bytecode);
+        loaded
+            .into_iter()
+            .map(|(mut spec, plain_function)| {
+                spec.content_hash = Some(hash.clone());
+                // Instances built from a replaced component are stale
+                self.stateful_instances
+                    .retain(|_, instance| instance.component_id != spec.id);
+                self.footer_instances
+                    .retain(|_, instance| instance.component_id != spec.id);
+                self.components.insert(
+                    spec.id.clone(),
+                    ComponentData {
+                        bytecode: bytecode.clone(),
+                        compiled: None,
+                        supports_config: !spec.parameters.is_empty(),
+                        version: spec.contract_version.unwrap_or(ContractVersion::CURRENT),
+                        plain_function,
+                        stateful: spec.stateful,
+                    },
+                );
+                spec
+            })
+            .collect()
     }
 
-    /// T083: Create a component spec by extracting metadata from the component
-    fn create_basic_spec_from_bytecode(
+    /// T083: Create component specs by extracting metadata from the component
+    ///
+    /// Components implementing the `wasmflow:node` contract give a single spec.
+    /// Other components are auto-wrapped: each exported function with supported
+    /// types gives its own spec.
+    fn create_specs_from_bytecode(
         &self,
         bytecode: &[u8],
         path: &Path,
-    ) -> Result<ComponentSpec, ComponentError> {
+    ) -> Result<Vec<(ComponentSpec, Option<PlainFunction>)>, ComponentError> {
         // Compile component to extract metadata
         let component = WasmComponent::from_binary(&self.engine, bytecode).map_err(|e| {
             ComponentError::LoadFailed {
@@ -409,63 +906,82 @@ impl ComponentManager {
             }
         })?;
 
-        // Create a minimal host state for metadata extraction (no capabilities needed)
-        let host_state = HostState::new("metadata-extraction".to_string(), CapabilitySet::none())
-            .map_err(|e| ComponentError::LoadFailed {
-            path: path.to_path_buf(),
-            reason: format!("Failed to create host state for metadata: {}", e),
-        })?;
-
-        let mut store = Store::new(&self.engine, host_state);
+        // Detect which wasmflow:node contract version the component exports
+        if let Some(version) = ContractVersion::detect(&self.engine, &component) {
+            let spec = self.create_contract_spec(&component, version, path)?;
+            return Ok(vec![(spec, None)]);
+        }
 
-        // Instantiate component
-        // Clone linker to avoid holding MutexGuard across await
-        let linker = {
-            let guard = self.linker.lock().unwrap();
-            guard.clone()
-        };
-        let instance = tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(async { Component::instantiate_async(&mut store, &component, &linker).await })
-            .map_err(|e| ComponentError::LoadFailed {
+        let functions = autowrap::introspect(&self.engine, &component);
+        if functions.is_empty() {
+            return Err(ComponentError::LoadFailed {
                 path: path.to_path_buf(),
-                reason: format!("Failed to instantiate component for metadata: {}", e),
-            })?;
+                reason: format!(
+                    "Component exports neither a supported wasmflow:node contract (supported: {}) \
+                     nor plain functions with supported types",
+                    ContractVersion::supported_list()
+                ),
+            });
+        }
 
-        // Extract metadata using WIT bindings
-        let metadata_interface = instance.wasmflow_node_metadata();
+        log::info!(
+            "Auto-wrapping {} exported function(s) of {}",
+            functions.len(),
+            path.display()
+        );
+        Ok(functions
+            .into_iter()
+            .map(|function| (Self::create_plain_function_spec(&function, path), Some(function)))
+            .collect())
+    }
 
-        let component_info = tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(async { metadata_interface.call_get_info(&mut store).await })
-            .map_err(|e| ComponentError::LoadFailed {
-                path: path.to_path_buf(),
-                reason: format!("Failed to call get-info: {}", e),
-            })?;
+    /// Create a spec for one auto-wrapped exported function
+    fn create_plain_function_spec(function: &PlainFunction, path: &Path) -> ComponentSpec {
+        let file_stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown");
 
-        let inputs = tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(async { metadata_interface.call_get_inputs(&mut store).await })
-            .map_err(|e| ComponentError::LoadFailed {
-                path: path.to_path_buf(),
-                reason: format!("Failed to call get-inputs: {}", e),
-            })?;
+        let mut spec = ComponentSpec::new_user_defined(
+            format!("user:{}:{}", file_stem, function.short_name()),
+            function.name.clone(),
+            format!("Exported function {} of {}", function.export_name(), file_stem),
+            Some(file_stem.to_string()),
+            path.to_path_buf(),
+        );
 
-        let outputs = tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(async { metadata_interface.call_get_outputs(&mut store).await })
-            .map_err(|e| ComponentError::LoadFailed {
-                path: path.to_path_buf(),
-                reason: format!("Failed to call get-outputs: {}", e),
-            })?;
+        for (name, ty) in &function.params {
+            spec = spec.with_input(name.clone(), ty.data_type(), format!("Parameter {}", name));
+        }
+        for (name, ty) in &function.results {
+            spec = spec.with_output(name.clone(), ty.data_type(), "Function result".to_string());
+        }
 
-        let capabilities = tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(async { metadata_interface.call_get_capabilities(&mut store).await })
+        spec
+    }
+
+    /// Create the spec for a component implementing the `wasmflow:node` contract
+    fn create_contract_spec(
+        &self,
+        component: &WasmComponent,
+        version: ContractVersion,
+        path: &Path,
+    ) -> Result<ComponentSpec, ComponentError> {
+        if version.is_deprecated() {
+            log::warn!(
+                "Component {} uses deprecated contract {}; rebuild it against {}",
+                path.display(),
+                version,
+                ContractVersion::CURRENT
+            );
+        }
+
+        let probe = Self::probe_component(&self.engine, &self.linker, component, version)
             .map_err(|e| ComponentError::LoadFailed {
                 path: path.to_path_buf(),
-                reason: format!("Failed to call get-capabilities: {}", e),
+                reason: format!("Failed to read component metadata: {}", e),
             })?;
+        let component_info = probe.info;
 
         // Create component ID
         let component_id = format!(
@@ -484,17 +1000,23 @@ impl ComponentManager {
             path.to_path_buf(),
         );
 
-        // Add input ports
-        for input in inputs {
-            spec = spec.with_input(
-                input.name,
-                Self::wit_data_type_to_node_data_type(&input.data_type),
-                input.description,
-            );
+        // Add input ports (optional inputs may declare a default value)
+        for input in probe.inputs {
+            let data_type = Self::wit_data_type_to_node_data_type(&input.data_type);
+            spec = if input.optional {
+                spec.with_optional_input(
+                    input.name,
+                    data_type,
+                    input.description,
+                    input.default_value.as_ref().map(wit_to_node_value),
+                )
+            } else {
+                spec.with_input(input.name, data_type, input.description)
+            };
         }
 
         // Add output ports
-        for output in &outputs {
+        for output in &probe.outputs {
             spec = spec.with_output(
                 output.name.clone(),
                 Self::wit_data_type_to_node_data_type(&output.data_type),
@@ -503,20 +1025,35 @@ impl ComponentManager {
         }
 
         // Store capabilities in spec
-        if let Some(caps) = capabilities {
+        if let Some(caps) = probe.capabilities {
             spec.required_capabilities = caps;
         }
+        spec.contract_version = Some(version);
 
-        // Try to extract custom footer view from component (if it exports UI interface)
-        // Clone outputs so we don't move the value
-        let outputs_for_ui = outputs.clone();
-        if let Ok(footer_view) = Self::try_extract_footer_view_with_ui(
-            &self.engine,
-            &self.linker,
-            &component,
-            &outputs_for_ui,
-            path,
-        ) {
+        // Stateful components keep one instance per node across executions
+        spec.stateful = version.exports_interface(&self.engine, component, "stateful");
+
+        // Continuous components are driven through on-start/tick/on-stop
+        spec.supports_continuous =
+            version.exports_interface(&self.engine, component, "continuous");
+
+        // Configuration parameters (config interface)
+        spec.parameters = probe.parameters;
+
+        // Record schemas replace the flat port types (schema interface)
+        for (port_name, data_type) in probe.input_schemas {
+            if let Some(port) = spec.input_spec.iter_mut().find(|p| p.name == port_name) {
+                port.data_type = data_type;
+            }
+        }
+        for (port_name, data_type) in probe.output_schemas {
+            if let Some(port) = spec.output_spec.iter_mut().find(|p| p.name == port_name) {
+                port.data_type = data_type;
+            }
+        }
+
+        // Custom footer view (ui interface)
+        if let Some(footer_view) = probe.footer_view {
             log::debug!("Component has custom UI footer view");
             let adapter = WitFooterViewAdapter::new(footer_view);
             spec = spec.with_footer_view(Arc::new(adapter));
@@ -525,78 +1062,268 @@ impl ComponentManager {
         Ok(spec)
     }
 
-    /// Try to extract footer view from a component with UI interface
-    /// Attempts to instantiate with component-with-ui bindings and extract footer view
-    fn try_extract_footer_view_with_ui(
+    /// Read a contract component's metadata and optional interfaces
+    ///
+    /// The optional interfaces are detected from the component's exports and
+    /// bound to the same instance as the metadata interface, so the component
+    /// is instantiated once. A failing optional interface is logged and left
+    /// out of the probe.
+    fn probe_component(
         engine: &Engine,
         linker: &Arc<Mutex<Linker<HostState>>>,
         component: &WasmComponent,
-        outputs: &[wasmflow::node::types::PortSpec],
-        _path: &Path,
-    ) -> Result<WitFooterView, Box<dyn std::error::Error>> {
-        // Create host state for UI extraction
-        let host_state = HostState::new("ui-extraction".to_string(), CapabilitySet::none())?;
+        version: ContractVersion,
+    ) -> anyhow::Result<ComponentProbe> {
+        let exports = |interface: &str| version.exports_interface(engine, component, interface);
+        let has_config = exports("config");
+        let has_schema = exports("schema");
+        let has_ui = exports("ui");
 
+        // Create a minimal host state for metadata extraction (no capabilities needed)
+        let host_state = HostState::new("metadata-extraction".to_string(), CapabilitySet::none())?;
         let mut store = Store::new(engine, host_state);
 
-        // Try to instantiate with component-with-ui bindings
-        let linker_clone = {
+        // Clone linker to avoid holding MutexGuard across await
+        let linker = {
             let guard = linker.lock().unwrap();
             guard.clone()
         };
 
-        // Instantiate using component-with-ui bindings
-        let instance = tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(async {
-                with_ui::ComponentWithUi::instantiate_async(&mut store, component, &linker_clone)
-                    .await
-            })
-            .map_err(|e| format!("Failed to instantiate with UI bindings: {}", e))?;
+        tokio::runtime::Runtime::new()?.block_on(async {
+            let instance = linker.instantiate_async(&mut store, component).await?;
+            let mut probe = Self::read_metadata(&mut store, &instance, version).await?;
 
-        // Create sample outputs to pass to get-footer-view
-        let sample_outputs: Vec<(String, with_ui::wasmflow::node::types::Value)> = outputs
+            if has_config {
+                match Self::read_parameters(&mut store, &instance).await {
+                    Ok(parameters) => probe.parameters = parameters,
+                    Err(e) => log::warn!("Failed to read configuration parameters: {}", e),
+                }
+            }
+            if has_schema {
+                match Self::read_port_schemas(&mut store, &instance).await {
+                    Ok((inputs, outputs)) => {
+                        probe.input_schemas = inputs;
+                        probe.output_schemas = outputs;
+                    }
+                    Err(e) => log::warn!("Failed to read record schemas: {}", e),
+                }
+            }
+            if has_ui {
+                let outputs = Self::sample_outputs(&probe.outputs);
+                match Self::read_footer_view(&mut store, &instance, version, &outputs).await {
+                    Ok(footer_view) => probe.footer_view = footer_view,
+                    Err(e) => log::warn!("Failed to read footer view: {}", e),
+                }
+            }
+            Ok(probe)
+        })
+    }
+
+    /// Read metadata through the bindings for the component's contract version
+    /// Legacy results are adapted to the current WIT types
+    async fn read_metadata(
+        store: &mut Store<HostState>,
+        instance: &wasmtime::component::Instance,
+        version: ContractVersion,
+    ) -> anyhow::Result<ComponentProbe> {
+        let (info, inputs, outputs, capabilities) = match version {
+            ContractVersion::V1_2 => {
+                let bindings = Component::new(&mut *store, instance)?;
+                let metadata = bindings.wasmflow_node_metadata();
+                (
+                    metadata.call_get_info(&mut *store).await?,
+                    metadata.call_get_inputs(&mut *store).await?,
+                    metadata.call_get_outputs(&mut *store).await?,
+                    metadata.call_get_capabilities(&mut *store).await?,
+                )
+            }
+            ContractVersion::V1_1 => {
+                let bindings = contract::v1_1::Component::new(&mut *store, instance)?;
+                let metadata = bindings.wasmflow_node_metadata();
+                (
+                    metadata.call_get_info(&mut *store).await?.into(),
+                    metadata
+                        .call_get_inputs(&mut *store)
+                        .await?
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                    metadata
+                        .call_get_outputs(&mut *store)
+                        .await?
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                    metadata.call_get_capabilities(&mut *store).await?,
+                )
+            }
+        };
+
+        Ok(ComponentProbe {
+            info,
+            inputs,
+            outputs,
+            capabilities,
+            parameters: Vec::new(),
+            input_schemas: Vec::new(),
+            output_schemas: Vec::new(),
+            footer_view: None,
+        })
+    }
+
+    /// Call get-footer-view on a fresh instance of the component
+    fn call_footer_view(
+        engine: &Engine,
+        linker: &Arc<Mutex<Linker<HostState>>>,
+        component: &WasmComponent,
+        version: ContractVersion,
+        host_label: String,
+        outputs: &[(String, wasmflow::node::types::Value)],
+    ) -> Result<Option<WitFooterView>, Box<dyn std::error::Error>> {
+        let host_state = HostState::new(host_label, CapabilitySet::none())?;
+        let mut store = Store::new(engine, host_state);
+
+        let linker_clone = {
+            let guard = linker.lock().unwrap();
+            guard.clone()
+        };
+
+        let view = tokio::runtime::Runtime::new()?
+            .block_on(async {
+                let instance = linker_clone.instantiate_async(&mut store, component).await?;
+                Self::read_footer_view(&mut store, &instance, version, outputs).await
+            })
+            .map_err(|e| format!("Failed to call get-footer-view: {}", e))?;
+
+        Ok(view)
+    }
+
+    /// Call get-footer-view through the bindings for the component's contract version
+    async fn read_footer_view(
+        store: &mut Store<HostState>,
+        instance: &wasmtime::component::Instance,
+        version: ContractVersion,
+        outputs: &[(String, wasmflow::node::types::Value)],
+    ) -> anyhow::Result<Option<WitFooterView>> {
+        let wit_view = match version {
+            ContractVersion::V1_2 => {
+                let bindings = with_ui::ComponentWithUi::new(&mut *store, instance)?;
+                bindings
+                    .wasmflow_node_ui()
+                    .call_get_footer_view(&mut *store, outputs)
+                    .await?
+            }
+            ContractVersion::V1_1 => {
+                let bindings =
+                    contract::v1_1::with_ui::ComponentWithUi::new(&mut *store, instance)?;
+                let legacy_outputs: Vec<(String, contract::v1_1::wasmflow::node::types::Value)> =
+                    outputs
+                        .iter()
+                        .map(|(name, value)| (name.clone(), value.clone().into()))
+                        .collect();
+                bindings
+                    .wasmflow_node_ui()
+                    .call_get_footer_view(&mut *store, &legacy_outputs)
+                    .await?
+                    .map(Into::into)
+            }
+        };
+
+        Ok(wit_view.map(Self::convert_wit_footer_view))
+    }
+
+    /// Zeroed sample outputs used to render a footer view at load time
+    fn sample_outputs(
+        outputs: &[wasmflow::node::types::PortSpec],
+    ) -> Vec<(String, wasmflow::node::types::Value)> {
+        use self::wasmflow::node::types::{DataType as WitDataType, Value};
+
+        outputs
             .iter()
             .map(|output| {
                 let value = match output.data_type {
-                    wasmflow::node::types::DataType::U32Type => {
-                        with_ui::wasmflow::node::types::Value::U32Val(0)
-                    }
-                    wasmflow::node::types::DataType::I32Type => {
-                        with_ui::wasmflow::node::types::Value::I32Val(0)
-                    }
-                    wasmflow::node::types::DataType::F32Type => {
-                        with_ui::wasmflow::node::types::Value::F32Val(0.0)
-                    }
-                    wasmflow::node::types::DataType::StringType => {
-                        with_ui::wasmflow::node::types::Value::StringVal(String::new())
-                    }
-                    wasmflow::node::types::DataType::BinaryType => {
-                        with_ui::wasmflow::node::types::Value::BinaryVal(vec![])
-                    }
-                    _ => with_ui::wasmflow::node::types::Value::StringVal(String::new()),
+                    WitDataType::U32Type => Value::U32Val(0),
+                    WitDataType::I32Type => Value::I32Val(0),
+                    WitDataType::F32Type => Value::F32Val(0.0),
+                    WitDataType::StringType => Value::StringVal(String::new()),
+                    WitDataType::BinaryType => Value::BinaryVal(vec![]),
+                    _ => Value::StringVal(String::new()),
                 };
                 (output.name.clone(), value)
             })
-            .collect();
+            .collect()
+    }
 
-        // Get UI interface and call get-footer-view
-        let ui_interface = instance.wasmflow_node_ui();
-        let wit_footer_view_opt = tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(async {
-                ui_interface
-                    .call_get_footer_view(&mut store, &sample_outputs)
-                    .await
-            })
-            .map_err(|e| format!("Failed to call get-footer-view: {}", e))?;
+    /// Read record port schemas through the schema interface
+    /// Returns (input schemas, output schemas) keyed by port name
+    #[allow(clippy::type_complexity)]
+    async fn read_port_schemas(
+        store: &mut Store<HostState>,
+        instance: &wasmtime::component::Instance,
+    ) -> anyhow::Result<(Vec<(String, DataType)>, Vec<(String, DataType)>)> {
+        let bindings = with_schema::ComponentWithSchema::new(&mut *store, instance)?;
+        let schema_interface = bindings.wasmflow_node_schema();
+        let inputs = schema_interface.call_get_input_schemas(&mut *store).await?;
+        let outputs = schema_interface.call_get_output_schemas(&mut *store).await?;
+
+        let convert = |schemas: Vec<with_schema::exports::wasmflow::node::schema::PortSchema>| {
+            schemas
+                .into_iter()
+                .map(|port_schema| {
+                    let fields: Vec<(String, DataType)> = port_schema
+                        .fields
+                        .iter()
+                        .map(|field| {
+                            (
+                                field.path.clone(),
+                                Self::wit_data_type_to_node_data_type(&field.data_type),
+                            )
+                        })
+                        .collect();
+                    (port_schema.port_name, schema::record_from_field_paths(&fields))
+                })
+                .collect::<Vec<_>>()
+        };
 
-        // Convert Option<WIT FooterView> to our FooterView
-        if let Some(wit_view) = wit_footer_view_opt {
-            Ok(Self::convert_wit_footer_view(wit_view))
-        } else {
-            Err("Component returned None for footer view".into())
-        }
+        Ok((convert(inputs), convert(outputs)))
+    }
+
+    /// Read configuration parameters through the config interface
+    async fn read_parameters(
+        store: &mut Store<HostState>,
+        instance: &wasmtime::component::Instance,
+    ) -> anyhow::Result<Vec<ParameterSpec>> {
+        use with_config::exports::wasmflow::node::config::ParamKind;
+
+        let bindings = with_config::ComponentWithConfig::new(&mut *store, instance)?;
+        let wit_parameters = bindings
+            .wasmflow_node_config()
+            .call_get_parameters(&mut *store)
+            .await?;
+
+        Ok(wit_parameters
+            .into_iter()
+            .map(|param| ParameterSpec {
+                name: param.name,
+                label: param.label,
+                description: param.description,
+                kind: match param.kind {
+                    ParamKind::Text => ParameterKind::Text,
+                    ParamKind::Choice(choices) => ParameterKind::Choice(choices),
+                    ParamKind::IntRange(range) => ParameterKind::IntRange {
+                        min: range.min,
+                        max: range.max,
+                    },
+                    ParamKind::FloatRange(range) => ParameterKind::FloatRange {
+                        min: range.min,
+                        max: range.max,
+                        step: range.step,
+                    },
+                    ParamKind::Toggle => ParameterKind::Toggle,
+                },
+                default_value: wit_to_node_value(&param.default_value),
+            })
+            .collect())
     }
 
     /// Convert WIT FooterView to our internal representation
@@ -652,9 +1379,39 @@ impl ComponentManager {
                         .collect(),
                 }))
             }
+            with_ui::exports::wasmflow::node::ui::UiElement::Layout(tree) => {
+                Some(UiElement::Layout(tree.into()))
+            }
             with_ui::exports::wasmflow::node::ui::UiElement::Separator => {
                 Some(UiElement::Separator)
             }
+            with_ui::exports::wasmflow::node::ui::UiElement::Button(spec) => {
+                Some(UiElement::Button(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElement::Checkbox(spec) => {
+                Some(UiElement::Checkbox(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElement::Slider(spec) => {
+                Some(UiElement::Slider(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElement::TextInput(spec) => {
+                Some(UiElement::TextInput(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElement::Dropdown(spec) => {
+                Some(UiElement::Dropdown(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElement::Image(spec) => {
+                Some(UiElement::Image(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElement::Table(spec) => {
+                Some(UiElement::Table(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElement::Chart(spec) => {
+                Some(UiElement::Chart(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElement::ProgressBar(spec) => {
+                Some(UiElement::ProgressBar(spec.into()))
+            }
         }
     }
 
@@ -683,6 +1440,33 @@ impl ComponentManager {
             with_ui::exports::wasmflow::node::ui::UiElementItem::Separator => {
                 Some(UiElementItem::Separator)
             }
+            with_ui::exports::wasmflow::node::ui::UiElementItem::Button(spec) => {
+                Some(UiElementItem::Button(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElementItem::Checkbox(spec) => {
+                Some(UiElementItem::Checkbox(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElementItem::Slider(spec) => {
+                Some(UiElementItem::Slider(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElementItem::TextInput(spec) => {
+                Some(UiElementItem::TextInput(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElementItem::Dropdown(spec) => {
+                Some(UiElementItem::Dropdown(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElementItem::Image(spec) => {
+                Some(UiElementItem::Image(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElementItem::Table(spec) => {
+                Some(UiElementItem::Table(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElementItem::Chart(spec) => {
+                Some(UiElementItem::Chart(spec.into()))
+            }
+            with_ui::exports::wasmflow::node::ui::UiElementItem::ProgressBar(spec) => {
+                Some(UiElementItem::ProgressBar(spec.into()))
+            }
         }
     }
 
@@ -710,12 +1494,12 @@ impl ComponentManager {
     #[allow(dead_code)]
     async fn extract_metadata(
         &self,
-        _component: &WasmComponent,
+        component: &WasmComponent,
         path: &Path,
     ) -> Result<ComponentSpec, ComponentError> {
-        // T083: With lazy compilation, we don't need to instantiate for metadata
-        // Just create a basic spec from the path
-        self.create_basic_spec_from_bytecode(&[], path)
+        let version =
+            ContractVersion::detect(&self.engine, component).unwrap_or(ContractVersion::CURRENT);
+        self.create_contract_spec(component, version, path)
     }
 
     /// Validate a component meets requirements
@@ -840,20 +1624,37 @@ impl ComponentManager {
     /// Execute a component with given inputs
     /// T075: Permission enforcement - capabilities are enforced via WASI context
     /// T083: Now uses lazy compilation - compiles on first execution
+    ///
+    /// `config` holds the node's configuration parameter values; it is only
+    /// passed to components that export the config interface. `kv_scope` is the
+    /// node's key-value namespace, if any; `control` receives progress reports
+    /// and carries cancellation requests.
     pub async fn execute_component(
         &mut self,
         component_id: &str,
         inputs: &HashMap<String, NodeValue>,
+        config: &BTreeMap<String, NodeValue>,
         capabilities: CapabilitySet,
+        kv_scope: Option<KvScope>,
+        control: Option<Arc<ExecutionControl>>,
     ) -> Result<HashMap<String, NodeValue>, ComponentError> {
         // T083: Get component (lazy compilation happens here)
         let component = self.get_component(component_id)?;
+        let (supports_config, version, plain_function) = self
+            .components
+            .get(component_id)
+            .map(|data| (data.supports_config, data.version, data.plain_function.clone()))
+            .unwrap_or((false, ContractVersion::CURRENT, None));
 
         // T075: Create host state with granted capabilities (WASI context configured here)
-        let host_state =
-            HostState::new(component_id.to_string(), capabilities.clone()).map_err(|e| {
+        let host_state = HostState::new(component_id.to_string(), capabilities.clone())
+            .map_err(|e| {
                 ComponentError::ExecutionError(format!("Failed to create host state: {}", e))
-            })?;
+            })?
+            .with_kv(self.kv_store.clone(), kv_scope)
+            .with_logs(self.node_logs.clone(), kv_scope.map(|scope| scope.node_id))
+            .with_vault(self.secret_vault.clone())
+            .with_control(control);
 
         let mut store = Store::new(&self.engine, host_state);
 
@@ -863,12 +1664,21 @@ impl ComponentManager {
             let guard = self.linker.lock().unwrap();
             guard.clone()
         };
-        let instance = Component::instantiate_async(&mut store, &component, &linker)
-            .await
-            .map_err(|e| {
-                // T076: Check for permission-related errors during instantiation
-                Self::map_wasi_error_to_permission_denied(e, component_id, &capabilities)
-            })?;
+
+        // Auto-wrapped plain functions are called directly with dynamic values
+        if let Some(function) = plain_function {
+            let outputs = Self::call_plain_function(
+                &mut store,
+                &linker,
+                &component,
+                &function,
+                inputs,
+                component_id,
+                &capabilities,
+            )
+            .await?;
+            return Ok(store.data().redact_outputs(outputs));
+        }
 
         // Convert inputs from NodeValue to WIT Value format
         let wit_inputs: Vec<(String, wasmflow::node::types::Value)> = inputs
@@ -876,15 +1686,66 @@ impl ComponentManager {
             .map(|(name, value)| (name.clone(), node_value_to_wit(value)))
             .collect();
 
-        // Call the component's execute() function via WIT bindings
-        let result = instance
-            .wasmflow_node_execution()
-            .call_execute(&mut store, &wit_inputs)
-            .await
-            .map_err(|e| {
-                // T076: Check for permission-related errors during execution
-                Self::map_wasi_error_to_permission_denied(e, component_id, &capabilities)
-            })?;
+        let result = if version == ContractVersion::V1_1 {
+            let instance =
+                contract::v1_1::Component::instantiate_async(&mut store, &component, &linker)
+                    .await
+                    .map_err(|e| {
+                        Self::map_wasi_error_to_permission_denied(e, component_id, &capabilities)
+                    })?;
+
+            // Legacy components receive and return values through the 1.1.0 types
+            let legacy_inputs: Vec<(String, contract::v1_1::wasmflow::node::types::Value)> =
+                wit_inputs
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone().into()))
+                    .collect();
+
+            instance
+                .wasmflow_node_execution()
+                .call_execute(&mut store, &legacy_inputs)
+                .await
+                .map(|result| {
+                    result
+                        .map(|outputs| {
+                            outputs
+                                .into_iter()
+                                .map(|(name, value)| (name, value.into()))
+                                .collect()
+                        })
+                        .map_err(Into::into)
+                })
+        } else if supports_config {
+            let instance =
+                with_config::ComponentWithConfig::instantiate_async(&mut store, &component, &linker)
+                    .await
+                    .map_err(|e| {
+                        Self::map_wasi_error_to_permission_denied(e, component_id, &capabilities)
+                    })?;
+
+            // Configuration goes to execute-with-config, separate from data inputs
+            instance
+                .wasmflow_node_config()
+                .call_execute_with_config(&mut store, &wit_inputs, &config_to_wit(config))
+                .await
+        } else {
+            let instance = Component::instantiate_async(&mut store, &component, &linker)
+                .await
+                .map_err(|e| {
+                    // T076: Check for permission-related errors during instantiation
+                    Self::map_wasi_error_to_permission_denied(e, component_id, &capabilities)
+                })?;
+
+            // Call the component's execute() function via WIT bindings
+            instance
+                .wasmflow_node_execution()
+                .call_execute(&mut store, &wit_inputs)
+                .await
+        }
+        .map_err(|e| {
+            // T076: Check for permission-related errors during execution
+            Self::map_wasi_error_to_permission_denied(e, component_id, &capabilities)
+        })?;
 
         // Handle execution result
         match result {
@@ -900,15 +1761,337 @@ impl ComponentManager {
                     component_id,
                     outputs.len()
                 );
-                Ok(outputs)
+                Ok(store.data().redact_outputs(outputs))
             }
             Err(err) => Err(ComponentError::ExecutionError(format!(
                 "Component execution error: {}",
-                err.message
+                store.data().redact(&err.message)
             ))),
         }
     }
 
+    /// Whether a loaded component keeps per-node instance state
+    pub fn is_stateful(&self, component_id: &str) -> bool {
+        self.components
+            .get(component_id)
+            .map(|data| data.stateful)
+            .unwrap_or(false)
+    }
+
+    /// Whether a loaded component takes configuration through execute-with-config
+    fn supports_config(&self, component_id: &str) -> bool {
+        self.components
+            .get(component_id)
+            .map(|data| data.supports_config)
+            .unwrap_or(false)
+    }
+
+    /// Execute a stateful component on the node's persistent instance
+    ///
+    /// The instance is created on first use, restored from `saved_state` if
+    /// given. `wiring` fingerprints the node's incoming connections; when it
+    /// changes the component's `reset` is called before executing. An instance
+    /// that traps is discarded. Components with parameters receive `config`
+    /// through execute-with-config.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute_stateful_component(
+        &mut self,
+        node_id: uuid::Uuid,
+        component_id: &str,
+        inputs: &HashMap<String, NodeValue>,
+        config: &BTreeMap<String, NodeValue>,
+        capabilities: CapabilitySet,
+        wiring: u64,
+        saved_state: Option<&[u8]>,
+        kv_scope: Option<KvScope>,
+        control: Option<Arc<ExecutionControl>>,
+    ) -> Result<HashMap<String, NodeValue>, ComponentError> {
+        // Rebuild instances made for another component or capability grant
+        if self.stateful_instances.get(&node_id).is_some_and(|instance| {
+            instance.component_id != component_id || instance.capabilities != capabilities
+        }) {
+            self.stateful_instances.remove(&node_id);
+        }
+
+        if !self.stateful_instances.contains_key(&node_id) {
+            let instance = self
+                .create_stateful_instance(
+                    component_id,
+                    capabilities.clone(),
+                    wiring,
+                    saved_state,
+                    kv_scope,
+                )
+                .await?;
+            self.stateful_instances.insert(node_id, instance);
+        }
+
+        let entry = self
+            .stateful_instances
+            .get_mut(&node_id)
+            .expect("stateful instance was just created");
+        // The instance outlives executions; report to this execution's control
+        entry.store.data_mut().control = control;
+
+        let wit_inputs: Vec<(String, wasmflow::node::types::Value)> = inputs
+            .iter()
+            .map(|(name, value)| (name.clone(), node_value_to_wit(value)))
+            .collect();
+        let wit_config = config_to_wit(config);
+
+        let result = async {
+            if entry.wiring != wiring {
+                log::debug!("Inputs of node {} were rewired, resetting component state", node_id);
+                entry
+                    .instance
+                    .wasmflow_node_stateful()
+                    .call_reset(&mut entry.store)
+                    .await?;
+                entry.wiring = wiring;
+            }
+            match &entry.config {
+                Some(with_config) => {
+                    with_config
+                        .wasmflow_node_config()
+                        .call_execute_with_config(&mut entry.store, &wit_inputs, &wit_config)
+                        .await
+                }
+                None => {
+                    entry
+                        .instance
+                        .wasmflow_node_execution()
+                        .call_execute(&mut entry.store, &wit_inputs)
+                        .await
+                }
+            }
+        }
+        .await;
+
+        match result {
+            Ok(Ok(wit_outputs)) => Ok(entry.store.data().redact_outputs(
+                wit_outputs
+                    .iter()
+                    .map(|(name, value)| (name.clone(), wit_to_node_value(value)))
+                    .collect(),
+            )),
+            Ok(Err(err)) => Err(ComponentError::ExecutionError(format!(
+                "Component execution error: {}",
+                entry.store.data().redact(&err.message)
+            ))),
+            Err(e) => {
+                // A trapped instance cannot be reused
+                self.stateful_instances.remove(&node_id);
+                Err(Self::map_wasi_error_to_permission_denied(
+                    e,
+                    component_id,
+                    &capabilities,
+                ))
+            }
+        }
+    }
+
+    async fn create_stateful_instance(
+        &mut self,
+        component_id: &str,
+        capabilities: CapabilitySet,
+        wiring: u64,
+        saved_state: Option<&[u8]>,
+        kv_scope: Option<KvScope>,
+    ) -> Result<StatefulInstance, ComponentError> {
+        let component = self.get_component(component_id)?;
+        let supports_config = self.supports_config(component_id);
+        let supports_ui = ContractVersion::V1_2.exports_interface(&self.engine, &component, "ui");
+
+        let host_state = HostState::new(component_id.to_string(), capabilities.clone())
+            .map_err(|e| {
+                ComponentError::ExecutionError(format!("Failed to create host state: {}", e))
+            })?
+            .with_kv(self.kv_store.clone(), kv_scope)
+            .with_logs(self.node_logs.clone(), kv_scope.map(|scope| scope.node_id))
+            .with_vault(self.secret_vault.clone());
+        let mut store = Store::new(&self.engine, host_state);
+
+        let linker = {
+            let guard = self.linker.lock().unwrap();
+            guard.clone()
+        };
+
+        let (instance, config, ui) = async {
+            let raw = linker.instantiate_async(&mut store, &component).await?;
+            let instance = with_stateful::ComponentStateful::new(&mut store, &raw)?;
+            let config = supports_config
+                .then(|| with_config::ComponentWithConfig::new(&mut store, &raw))
+                .transpose()?;
+            let ui = supports_ui
+                .then(|| with_ui::ComponentWithUi::new(&mut store, &raw))
+                .transpose()?;
+            anyhow::Ok((instance, config, ui))
+        }
+        .await
+        .map_err(|e| Self::map_wasi_error_to_permission_denied(e, component_id, &capabilities))?;
+
+        if let Some(state) = saved_state {
+            let restored = instance
+                .wasmflow_node_stateful()
+                .call_load_state(&mut store, state)
+                .await
+                .map_err(|e| {
+                    Self::map_wasi_error_to_permission_denied(e, component_id, &capabilities)
+                })?;
+            // A snapshot from an older build may not load; start fresh instead
+            if let Err(reason) = restored {
+                log::warn!(
+                    "Component {} rejected saved state, starting fresh: {}",
+                    component_id,
+                    reason
+                );
+            }
+        }
+
+        Ok(StatefulInstance {
+            component_id: component_id.to_string(),
+            capabilities,
+            wiring,
+            store,
+            instance,
+            config,
+            ui,
+        })
+    }
+
+    /// Snapshot a node's stateful instance through save-state
+    ///
+    /// Returns None if the node has no live instance.
+    pub async fn save_instance_state(
+        &mut self,
+        node_id: uuid::Uuid,
+    ) -> Result<Option<Vec<u8>>, ComponentError> {
+        let Some(entry) = self.stateful_instances.get_mut(&node_id) else {
+            return Ok(None);
+        };
+
+        let state = entry
+            .instance
+            .wasmflow_node_stateful()
+            .call_save_state(&mut entry.store)
+            .await
+            .map_err(|e| {
+                ComponentError::ExecutionError(format!("Failed to save component state: {}", e))
+            })?;
+        Ok(Some(state))
+    }
+
+    /// Drop stateful (and footer) instances of nodes that no longer exist
+    pub fn retain_stateful_instances(&mut self, keep: impl Fn(&uuid::Uuid) -> bool) {
+        self.stateful_instances.retain(|node_id, _| keep(node_id));
+        self.footer_instances.retain(|node_id, _| keep(node_id));
+    }
+
+    /// Gather what a continuous session needs, without instantiating it
+    ///
+    /// Instantiation is async; doing it through the returned setup lets the
+    /// caller release its lock on the manager first. `control` stays attached
+    /// for the whole run.
+    pub fn prepare_continuous_session(
+        &mut self,
+        component_id: &str,
+        capabilities: CapabilitySet,
+        kv_scope: Option<KvScope>,
+        control: Option<Arc<ExecutionControl>>,
+    ) -> Result<ContinuousSessionSetup, ComponentError> {
+        let component = self.get_component(component_id)?;
+
+        let host_state = HostState::new(component_id.to_string(), capabilities.clone())
+            .map_err(|e| {
+                ComponentError::ExecutionError(format!("Failed to create host state: {}", e))
+            })?
+            .with_kv(self.kv_store.clone(), kv_scope)
+            .with_logs(self.node_logs.clone(), kv_scope.map(|scope| scope.node_id))
+            .with_vault(self.secret_vault.clone())
+            .with_control(control);
+        let store = Store::new(&self.engine, host_state);
+
+        let linker = {
+            let guard = self.linker.lock().unwrap();
+            guard.clone()
+        };
+
+        Ok(ContinuousSessionSetup {
+            component_id: component_id.to_string(),
+            capabilities,
+            component,
+            linker,
+            store,
+        })
+    }
+
+    /// Call an auto-wrapped exported function
+    ///
+    /// Inputs are passed positionally in parameter order; every parameter is required.
+    async fn call_plain_function(
+        store: &mut Store<HostState>,
+        linker: &Linker<HostState>,
+        component: &WasmComponent,
+        function: &PlainFunction,
+        inputs: &HashMap<String, NodeValue>,
+        component_id: &str,
+        capabilities: &CapabilitySet,
+    ) -> Result<HashMap<String, NodeValue>, ComponentError> {
+        let params = function
+            .params
+            .iter()
+            .map(|(name, ty)| {
+                let value = inputs.get(name).ok_or_else(|| {
+                    ComponentError::ExecutionError(format!("Missing input '{}'", name))
+                })?;
+                ty.to_val(value).map_err(|e| {
+                    ComponentError::ExecutionError(format!("Input '{}': {}", name, e))
+                })
+            })
+            .collect::<Result<Vec<Val>, _>>()?;
+
+        let export_index = function.export_index(component).ok_or_else(|| {
+            ComponentError::ExecutionError(format!(
+                "Component does not export {}",
+                function.export_name()
+            ))
+        })?;
+
+        let instance = linker
+            .instantiate_async(&mut *store, component)
+            .await
+            .map_err(|e| Self::map_wasi_error_to_permission_denied(e, component_id, capabilities))?;
+        let func = instance
+            .get_func(&mut *store, export_index)
+            .ok_or_else(|| {
+                ComponentError::ExecutionError(format!(
+                    "Export {} is not a function",
+                    function.export_name()
+                ))
+            })?;
+
+        let mut results = vec![Val::Bool(false); function.results.len()];
+        func.call_async(&mut *store, &params, &mut results)
+            .await
+            .map_err(|e| Self::map_wasi_error_to_permission_denied(e, component_id, capabilities))?;
+        func.post_return_async(&mut *store)
+            .await
+            .map_err(|e| Self::map_wasi_error_to_permission_denied(e, component_id, capabilities))?;
+
+        function
+            .results
+            .iter()
+            .zip(results.iter())
+            .map(|((name, ty), val)| {
+                ty.from_val(val)
+                    .map(|value| (name.clone(), value))
+                    .map_err(|e| {
+                        ComponentError::ExecutionError(format!("Result '{}': {}", name, e))
+                    })
+            })
+            .collect()
+    }
+
     /// Get footer view for a node with current output values
     ///
     /// For WASM components with custom UI, this calls get-footer-view() with
@@ -917,6 +2100,212 @@ impl ComponentManager {
         &self,
         node: &crate::graph::node::GraphNode,
     ) -> Result<Option<WitFooterView>, Box<dyn std::error::Error>> {
+        let Some((compiled, version)) = self.ui_component_for_node(node)? else {
+            return Ok(None);
+        };
+
+        Self::call_footer_view(
+            &self.engine,
+            &self.linker,
+            &compiled,
+            version,
+            format!("{}-ui", node.component_id),
+            &Self::wit_outputs_for_node(node),
+        )
+    }
+
+    /// Get footer view for a node, reusing a live UI instance across calls
+    ///
+    /// Cheap enough to call every frame while outputs change. Components
+    /// without the UI interface return `None`; 1.1.0 components fall back to
+    /// a fresh instance per call.
+    pub fn refresh_footer_view(
+        &mut self,
+        node: &crate::graph::node::GraphNode,
+        capabilities: &CapabilitySet,
+    ) -> Result<Option<WitFooterView>, Box<dyn std::error::Error>> {
+        let Some((compiled, version)) = self.ui_component_for_node(node)? else {
+            return Ok(None);
+        };
+        if !version.exports_interface(&self.engine, &compiled, "ui") {
+            return Ok(None);
+        }
+        if version != ContractVersion::V1_2 {
+            return self.get_footer_view_for_node(node);
+        }
+
+        // A current-thread runtime is cheap to build, unlike the default one
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        self.prepare_ui_instance(node, &compiled, capabilities, &runtime)?;
+
+        let outputs = Self::wit_outputs_for_node(node);
+        let (store, instance) = self.live_ui_instance(node.id);
+        let result = runtime.block_on(
+            instance
+                .wasmflow_node_ui()
+                .call_get_footer_view(store, &outputs),
+        );
+
+        match result {
+            Ok(view) => Ok(view.map(Self::convert_wit_footer_view)),
+            Err(e) => {
+                // A trapped instance cannot be reused; the next refresh recreates it
+                self.discard_ui_instance(node.id);
+                Err(format!("Failed to call get-footer-view: {}", e).into())
+            }
+        }
+    }
+
+    /// Deliver input on an interactive footer element through on-ui-event()
+    ///
+    /// The event reaches the node's live instance under its capability grant.
+    /// Returns `None` for components that cannot receive UI events (built-ins,
+    /// plain functions and 1.1.0 components).
+    pub fn dispatch_ui_event(
+        &mut self,
+        node: &crate::graph::node::GraphNode,
+        capabilities: &CapabilitySet,
+        event: &UiEvent,
+    ) -> Result<Option<UiEventResponse>, Box<dyn std::error::Error>> {
+        let Some((compiled, version)) = self.ui_component_for_node(node)? else {
+            return Ok(None);
+        };
+        // Interactive elements were added in 1.2.0
+        if version != ContractVersion::V1_2
+            || !version.exports_interface(&self.engine, &compiled, "ui")
+        {
+            return Ok(None);
+        }
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        self.prepare_ui_instance(node, &compiled, capabilities, &runtime)?;
+
+        let wit_event: with_ui::exports::wasmflow::node::ui::UiEvent = event.into();
+        let outputs = Self::wit_outputs_for_node(node);
+        let (store, instance) = self.live_ui_instance(node.id);
+        let result = runtime.block_on(
+            instance
+                .wasmflow_node_ui()
+                .call_on_ui_event(store, &wit_event, &outputs),
+        );
+
+        let response = match result {
+            Ok(response) => response,
+            Err(e) => {
+                self.discard_ui_instance(node.id);
+                return Err(format!("Failed to call on-ui-event: {}", e).into());
+            }
+        };
+
+        Ok(Some(UiEventResponse {
+            parameters: response
+                .parameters
+                .iter()
+                .map(|(name, value)| (name.clone(), wit_to_node_value(value)))
+                .collect(),
+            execute: response.execute,
+        }))
+    }
+
+    /// Make sure a node has a live instance to take UI calls
+    ///
+    /// A stateful instance with the UI interface serves them itself; otherwise
+    /// a footer instance is kept, rebuilt when the node's component or grant
+    /// changes.
+    fn prepare_ui_instance(
+        &mut self,
+        node: &crate::graph::node::GraphNode,
+        compiled: &WasmComponent,
+        capabilities: &CapabilitySet,
+        runtime: &tokio::runtime::Runtime,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Instances made for another component or grant are stale
+        if self.stateful_instances.get(&node.id).is_some_and(|entry| {
+            entry.component_id != node.component_id || entry.capabilities != *capabilities
+        }) {
+            self.stateful_instances.remove(&node.id);
+        }
+        if self
+            .stateful_instances
+            .get(&node.id)
+            .is_some_and(|entry| entry.ui.is_some())
+        {
+            return Ok(());
+        }
+
+        if self.footer_instances.get(&node.id).is_some_and(|entry| {
+            entry.component_id != node.component_id || entry.capabilities != *capabilities
+        }) {
+            self.footer_instances.remove(&node.id);
+        }
+        if self.footer_instances.contains_key(&node.id) {
+            return Ok(());
+        }
+
+        let host_state = HostState::new(format!("{}-ui", node.component_id), capabilities.clone())?
+            .with_vault(self.secret_vault.clone());
+        let mut store = Store::new(&self.engine, host_state);
+        let linker = {
+            let guard = self.linker.lock().unwrap();
+            guard.clone()
+        };
+        let instance = runtime.block_on(with_ui::ComponentWithUi::instantiate_async(
+            &mut store, compiled, &linker,
+        ))?;
+        self.footer_instances.insert(
+            node.id,
+            FooterInstance {
+                component_id: node.component_id.clone(),
+                capabilities: capabilities.clone(),
+                store,
+                instance,
+            },
+        );
+        Ok(())
+    }
+
+    /// Store and UI binding of the instance set up by `prepare_ui_instance`
+    fn live_ui_instance(
+        &mut self,
+        node_id: uuid::Uuid,
+    ) -> (&mut Store<HostState>, &with_ui::ComponentWithUi) {
+        if let Some(StatefulInstance {
+            store,
+            ui: Some(ui),
+            ..
+        }) = self.stateful_instances.get_mut(&node_id)
+        {
+            return (store, ui);
+        }
+        let entry = self
+            .footer_instances
+            .get_mut(&node_id)
+            .expect("UI instance was just prepared");
+        (&mut entry.store, &entry.instance)
+    }
+
+    /// Drop the instance behind a trapped UI call
+    fn discard_ui_instance(&mut self, node_id: uuid::Uuid) {
+        if self
+            .stateful_instances
+            .get(&node_id)
+            .is_some_and(|entry| entry.ui.is_some())
+        {
+            self.stateful_instances.remove(&node_id);
+        } else {
+            self.footer_instances.remove(&node_id);
+        }
+    }
+
+    /// Compiled component and contract version for a node that may export the UI interface
+    fn ui_component_for_node(
+        &self,
+        node: &crate::graph::node::GraphNode,
+    ) -> Result<Option<(Arc<WasmComponent>, ContractVersion)>, Box<dyn std::error::Error>> {
         // Only handle user-defined components
         if !node.component_id.starts_with("user:") {
             return Ok(None);
@@ -928,6 +2317,11 @@ impl ComponentManager {
             .get(&node.component_id)
             .ok_or("Component not found")?;
 
+        // Auto-wrapped plain functions have no UI interface
+        if component_data.plain_function.is_some() {
+            return Ok(None);
+        }
+
         // Get or compile the component
         let compiled = if let Some(compiled) = &component_data.compiled {
             compiled.clone()
@@ -939,119 +2333,21 @@ impl ComponentManager {
             )?)
         };
 
-        // Create host state for UI extraction
-        let host_state =
-            HostState::new(format!("{}-ui", node.component_id), CapabilitySet::none())?;
-
-        let mut store = Store::new(&self.engine, host_state);
-
-        // Try to instantiate with component-with-ui bindings
-        let linker_clone = {
-            let guard = self.linker.lock().unwrap();
-            guard.clone()
-        };
-
-        // Instantiate using component-with-ui bindings
-        let instance = tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(async {
-                with_ui::ComponentWithUi::instantiate_async(&mut store, &compiled, &linker_clone)
-                    .await
-            })
-            .map_err(|e| format!("Failed to instantiate with UI bindings: {}", e))?;
+        Ok(Some((compiled, component_data.version)))
+    }
 
-        // Convert node outputs to WIT format
-        let wit_outputs: Vec<(String, with_ui::wasmflow::node::types::Value)> = node
-            .outputs
+    /// Node outputs that have a value, in WIT format
+    fn wit_outputs_for_node(
+        node: &crate::graph::node::GraphNode,
+    ) -> Vec<(String, wasmflow::node::types::Value)> {
+        node.outputs
             .iter()
             .filter_map(|port| {
-                port.current_value.as_ref().map(|value| {
-                    let wit_value = Self::node_value_to_wit_ui_value(value);
-                    (port.name.clone(), wit_value)
-                })
+                port.current_value
+                    .as_ref()
+                    .map(|value| (port.name.clone(), node_value_to_wit(value)))
             })
-            .collect();
-
-        // Get UI interface and call get-footer-view
-        let ui_interface = instance.wasmflow_node_ui();
-        let wit_footer_view_opt = tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(async {
-                ui_interface
-                    .call_get_footer_view(&mut store, &wit_outputs)
-                    .await
-            })
-            .map_err(|e| format!("Failed to call get-footer-view: {}", e))?;
-
-        // Convert Option<WIT FooterView> to our FooterView
-        if let Some(wit_view) = wit_footer_view_opt {
-            Ok(Some(Self::convert_wit_footer_view(wit_view)))
-        } else {
-            Ok(None)
-        }
-    }
-
-    /// Convert NodeValue to WIT UI Value (for component-with-ui bindings)
-    fn node_value_to_wit_ui_value(value: &NodeValue) -> with_ui::wasmflow::node::types::Value {
-        use with_ui::wasmflow::node::types::Value;
-        match value {
-            NodeValue::U32(v) => Value::U32Val(*v),
-            NodeValue::I32(v) => Value::I32Val(*v),
-            NodeValue::F32(v) => Value::F32Val(*v),
-            NodeValue::String(s) => Value::StringVal(s.clone()),
-            NodeValue::Bool(b) => Value::BoolVal(*b),
-            NodeValue::Binary(b) => Value::BinaryVal(b.clone()),
-            NodeValue::List(items) => {
-                // Try to detect homogeneous list type and convert to typed list
-                if items.is_empty() {
-                    Value::StringListVal(vec![])
-                } else {
-                    match &items[0] {
-                        NodeValue::String(_) => {
-                            let strings: Vec<String> = items
-                                .iter()
-                                .filter_map(|v| {
-                                    if let NodeValue::String(s) = v {
-                                        Some(s.clone())
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect();
-                            Value::StringListVal(strings)
-                        }
-                        NodeValue::U32(_) => {
-                            let nums: Vec<u32> = items
-                                .iter()
-                                .filter_map(|v| {
-                                    if let NodeValue::U32(n) = v {
-                                        Some(*n)
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect();
-                            Value::U32ListVal(nums)
-                        }
-                        NodeValue::F32(_) => {
-                            let nums: Vec<f32> = items
-                                .iter()
-                                .filter_map(|v| {
-                                    if let NodeValue::F32(n) = v {
-                                        Some(*n)
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect();
-                            Value::F32ListVal(nums)
-                        }
-                        _ => Value::StringVal("<mixed-list>".to_string()),
-                    }
-                }
-            }
-            NodeValue::Record(_) => Value::StringVal("<record>".to_string()),
-        }
+            .collect()
     }
 
     /// T076: Map WASI errors to permission denied errors
@@ -1085,12 +2381,151 @@ impl ComponentManager {
     }
 }
 
+/// A continuous component ready to be instantiated
+///
+/// Created by `ComponentManager::prepare_continuous_session`; holds no lock
+/// on the manager.
+pub struct ContinuousSessionSetup {
+    component_id: String,
+    capabilities: CapabilitySet,
+    component: Arc<WasmComponent>,
+    linker: Linker<HostState>,
+    store: Store<HostState>,
+}
+
+impl ContinuousSessionSetup {
+    /// Instantiate the component for a run of on-start/tick/on-stop calls
+    ///
+    /// The instance lives in the returned session, so guest state persists
+    /// between ticks.
+    pub async fn start(mut self) -> Result<ContinuousSession, ComponentError> {
+        let instance = with_continuous::ComponentContinuous::instantiate_async(
+            &mut self.store,
+            &self.component,
+            &self.linker,
+        )
+        .await
+        .map_err(|e| {
+            ComponentManager::map_wasi_error_to_permission_denied(
+                e,
+                &self.component_id,
+                &self.capabilities,
+            )
+        })?;
+
+        Ok(ContinuousSession {
+            component_id: self.component_id,
+            capabilities: self.capabilities,
+            store: self.store,
+            instance,
+        })
+    }
+}
+
+/// A live instance of a continuous component
+///
+/// Created by `ContinuousSessionSetup::start` and driven by the continuous
+/// execution manager.
+pub struct ContinuousSession {
+    component_id: String,
+    capabilities: CapabilitySet,
+    store: Store<HostState>,
+    instance: with_continuous::ComponentContinuous,
+}
+
+impl ContinuousSession {
+    /// Call on-start; returns the component's preferred tick interval in milliseconds
+    pub async fn on_start(
+        &mut self,
+        inputs: &HashMap<String, NodeValue>,
+        config: &BTreeMap<String, NodeValue>,
+    ) -> Result<u32, ComponentError> {
+        let wit_inputs = Self::to_wit(inputs);
+        let result = self
+            .instance
+            .wasmflow_node_continuous()
+            .call_on_start(&mut self.store, &wit_inputs, &config_to_wit(config))
+            .await
+            .map_err(|e| self.map_error(e))?;
+
+        result.map_err(|err| {
+            ComponentError::ExecutionError(format!(
+                "Component on-start error: {}",
+                self.store.data().redact(&err.message)
+            ))
+        })
+    }
+
+    /// Call tick with the current inputs and configuration
+    pub async fn tick(
+        &mut self,
+        inputs: &HashMap<String, NodeValue>,
+        config: &BTreeMap<String, NodeValue>,
+        iteration: u64,
+    ) -> Result<HashMap<String, NodeValue>, ComponentError> {
+        let wit_inputs = Self::to_wit(inputs);
+        let result = self
+            .instance
+            .wasmflow_node_continuous()
+            .call_tick(&mut self.store, &wit_inputs, &config_to_wit(config), iteration)
+            .await
+            .map_err(|e| self.map_error(e))?;
+
+        match result {
+            Ok(wit_outputs) => Ok(self.store.data().redact_outputs(
+                wit_outputs
+                    .iter()
+                    .map(|(name, value)| (name.clone(), wit_to_node_value(value)))
+                    .collect(),
+            )),
+            Err(err) => Err(ComponentError::ExecutionError(format!(
+                "Component tick error: {}",
+                self.store.data().redact(&err.message)
+            ))),
+        }
+    }
+
+    /// Call on-stop
+    pub async fn on_stop(&mut self) -> Result<(), ComponentError> {
+        self.instance
+            .wasmflow_node_continuous()
+            .call_on_stop(&mut self.store)
+            .await
+            .map_err(|e| self.map_error(e))
+    }
+
+    fn to_wit(inputs: &HashMap<String, NodeValue>) -> Vec<(String, wasmflow::node::types::Value)> {
+        inputs
+            .iter()
+            .map(|(name, value)| (name.clone(), node_value_to_wit(value)))
+            .collect()
+    }
+
+    fn map_error(&self, error: anyhow::Error) -> ComponentError {
+        ComponentManager::map_wasi_error_to_permission_denied(
+            error,
+            &self.component_id,
+            &self.capabilities,
+        )
+    }
+}
+
 impl Default for ComponentManager {
     fn default() -> Self {
         Self::new().expect("Failed to create component manager")
     }
 }
 
+/// Convert a node's configuration values for execute-with-config
+fn config_to_wit(
+    config: &BTreeMap<String, NodeValue>,
+) -> Vec<(String, self::wasmflow::node::types::Value)> {
+    config
+        .iter()
+        .map(|(name, value)| (name.clone(), node_value_to_wit(value)))
+        .collect()
+}
+
 /// Convert NodeValue to WIT Value
 fn node_value_to_wit(value: &NodeValue) -> self::wasmflow::node::types::Value {
     use self::wasmflow::node::types::Value;
@@ -1159,9 +2594,8 @@ fn node_value_to_wit(value: &NodeValue) -> self::wasmflow::node::types::Value {
             }
         }
         NodeValue::Record(_) => {
-            // Records need special handling - not implemented yet
-            // For now, convert to a placeholder string
-            Value::StringVal("<record>".to_string())
+            // Records cross the WIT boundary as JSON strings (see the schema interface)
+            Value::StringVal(schema::node_value_to_json(value).to_string())
         }
     }
 }
@@ -1188,6 +2622,166 @@ fn wit_to_node_value(value: &self::wasmflow::node::types::Value) -> NodeValue {
     }
 }
 
+impl From<&with_ui::exports::wasmflow::node::ui::ButtonSpec> for ButtonSpec {
+    fn from(spec: &with_ui::exports::wasmflow::node::ui::ButtonSpec) -> Self {
+        Self {
+            id: spec.id.clone(),
+            label: spec.label.clone(),
+        }
+    }
+}
+
+impl From<&with_ui::exports::wasmflow::node::ui::CheckboxSpec> for CheckboxSpec {
+    fn from(spec: &with_ui::exports::wasmflow::node::ui::CheckboxSpec) -> Self {
+        Self {
+            id: spec.id.clone(),
+            label: spec.label.clone(),
+            checked: spec.checked,
+        }
+    }
+}
+
+impl From<&with_ui::exports::wasmflow::node::ui::SliderSpec> for SliderSpec {
+    fn from(spec: &with_ui::exports::wasmflow::node::ui::SliderSpec) -> Self {
+        Self {
+            id: spec.id.clone(),
+            label: spec.label.clone(),
+            value: spec.value,
+            min: spec.min,
+            max: spec.max,
+            step: spec.step,
+        }
+    }
+}
+
+impl From<&with_ui::exports::wasmflow::node::ui::TextInputSpec> for TextInputSpec {
+    fn from(spec: &with_ui::exports::wasmflow::node::ui::TextInputSpec) -> Self {
+        Self {
+            id: spec.id.clone(),
+            label: spec.label.clone(),
+            value: spec.value.clone(),
+            hint: spec.hint.clone(),
+        }
+    }
+}
+
+impl From<&with_ui::exports::wasmflow::node::ui::DropdownSpec> for DropdownSpec {
+    fn from(spec: &with_ui::exports::wasmflow::node::ui::DropdownSpec) -> Self {
+        Self {
+            id: spec.id.clone(),
+            label: spec.label.clone(),
+            options: spec.options.clone(),
+            selected: spec.selected.clone(),
+        }
+    }
+}
+
+impl From<&with_ui::exports::wasmflow::node::ui::ImageSpec> for ImageSpec {
+    fn from(spec: &with_ui::exports::wasmflow::node::ui::ImageSpec) -> Self {
+        use with_ui::exports::wasmflow::node::ui::ImageData as WitImageData;
+        let data = match &spec.data {
+            WitImageData::Png(bytes) => ImageData::Png(bytes.clone()),
+            WitImageData::Rgba(image) => ImageData::Rgba {
+                width: image.width,
+                height: image.height,
+                pixels: image.pixels.clone(),
+            },
+        };
+        ImageSpec::new(data, spec.max_width)
+    }
+}
+
+impl From<&with_ui::exports::wasmflow::node::ui::TableSpec> for TableSpec {
+    fn from(spec: &with_ui::exports::wasmflow::node::ui::TableSpec) -> Self {
+        Self {
+            headers: spec.headers.clone(),
+            rows: spec.rows.clone(),
+            max_height: spec.max_height,
+        }
+    }
+}
+
+impl From<&with_ui::exports::wasmflow::node::ui::ChartSpec> for ChartSpec {
+    fn from(spec: &with_ui::exports::wasmflow::node::ui::ChartSpec) -> Self {
+        use with_ui::exports::wasmflow::node::ui::ChartKind as WitChartKind;
+        Self {
+            kind: match spec.kind {
+                WitChartKind::Line => ChartKind::Line,
+                WitChartKind::Bar => ChartKind::Bar,
+            },
+            values: spec.values.clone(),
+            label: spec.label.clone(),
+            height: spec.height,
+        }
+    }
+}
+
+impl From<&with_ui::exports::wasmflow::node::ui::ProgressBarSpec> for ProgressBarSpec {
+    fn from(spec: &with_ui::exports::wasmflow::node::ui::ProgressBarSpec) -> Self {
+        Self {
+            fraction: spec.fraction,
+            label: spec.label.clone(),
+        }
+    }
+}
+
+impl From<&with_ui::exports::wasmflow::node::ui::LayoutTree> for LayoutTree {
+    fn from(tree: &with_ui::exports::wasmflow::node::ui::LayoutTree) -> Self {
+        use with_ui::exports::wasmflow::node::ui::{
+            LayoutContainer as WitLayoutContainer, LayoutContent as WitLayoutContent,
+        };
+        let nodes = tree
+            .nodes
+            .iter()
+            .map(|node| {
+                let content = match &node.content {
+                    WitLayoutContent::Container(container) => {
+                        LayoutContent::Container(match container {
+                            WitLayoutContainer::Horizontal => LayoutContainer::Horizontal,
+                            WitLayoutContainer::Vertical => LayoutContainer::Vertical,
+                            WitLayoutContainer::Grid(grid) => LayoutContainer::Grid {
+                                columns: grid.columns,
+                                striped: grid.striped,
+                            },
+                            WitLayoutContainer::Collapsible(section) => {
+                                LayoutContainer::Collapsible {
+                                    title: section.title.clone(),
+                                    default_open: section.default_open,
+                                }
+                            }
+                        })
+                    }
+                    // Every item converts; the fallback only keeps parent indices aligned
+                    WitLayoutContent::Element(item) => LayoutContent::Element(
+                        ComponentManager::convert_wit_ui_element_item(item)
+                            .unwrap_or(UiElementItem::Separator),
+                    ),
+                };
+                LayoutNode {
+                    parent: node.parent,
+                    content,
+                }
+            })
+            .collect();
+        LayoutTree { nodes }
+    }
+}
+
+impl From<&UiEvent> for with_ui::exports::wasmflow::node::ui::UiEvent {
+    fn from(event: &UiEvent) -> Self {
+        use with_ui::exports::wasmflow::node::ui::UiEventValue as WitUiEventValue;
+        Self {
+            element_id: event.element_id.clone(),
+            value: match &event.value {
+                UiEventValue::Clicked => WitUiEventValue::Clicked,
+                UiEventValue::Toggled(checked) => WitUiEventValue::Toggled(*checked),
+                UiEventValue::Number(value) => WitUiEventValue::Number(*value),
+                UiEventValue::Text(text) => WitUiEventValue::Text(text.clone()),
+            },
+        }
+    }
+}
+
 /// Parse capability string format (e.g., "file-read:/path", "network:example.com")
 pub fn parse_capability_string(cap_str: &str) -> Result<(Capability, Option<String>), String> {
     let parts: Vec<&str> = cap_str.splitn(2, ':').collect();
@@ -1203,6 +2797,8 @@ pub fn parse_capability_string(cap_str: &str) -> Result<(Capability, Option<Stri
         ["env"] => Ok((Capability::EnvAccess, None)),
         ["time"] => Ok((Capability::TimeAccess, None)),
         ["crypto"] => Ok((Capability::CryptoRandom, None)),
+        ["kv"] => Ok((Capability::KeyValue, None)),
+        ["secret", name] if !name.is_empty() => Ok((Capability::Secrets, Some(name.to_string()))),
         _ => Err(format!("Unknown capability format: {}", cap_str)),
     }
 }
@@ -1220,6 +2816,8 @@ pub fn parse_capability_requests(capability_strings: &[String]) -> Result<Capabi
     let mut file_write_paths = Vec::new();
     let mut network_hosts = Vec::new();
     let mut has_full = false;
+    let mut has_key_value = false;
+    let mut secret_names = Vec::new();
 
     for cap_str in capability_strings {
         if cap_str == "full" {
@@ -1239,6 +2837,12 @@ pub fn parse_capability_requests(capability_strings: &[String]) -> Result<Capabi
             (Capability::NetworkHttp, Some(host)) => {
                 network_hosts.push(host);
             }
+            (Capability::KeyValue, _) => {
+                has_key_value = true;
+            }
+            (Capability::Secrets, Some(name)) => {
+                secret_names.push(name);
+            }
             _ => {
                 // Other capabilities not yet mapped to CapabilitySet variants
                 // For now, we ignore them or could return an error
@@ -1251,7 +2855,7 @@ pub fn parse_capability_requests(capability_strings: &[String]) -> Result<Capabi
     }
 
     // Prioritize the most specific capability set
-    if !file_write_paths.is_empty() {
+    let base = if !file_write_paths.is_empty() {
         // If both read and write paths, use FileReadWrite
         if !file_read_paths.is_empty() {
             // Combine both sets of paths
@@ -1259,16 +2863,28 @@ pub fn parse_capability_requests(capability_strings: &[String]) -> Result<Capabi
             all_paths.extend(file_read_paths);
             all_paths.sort();
             all_paths.dedup();
-            Ok(CapabilitySet::file_read_write(all_paths))
+            CapabilitySet::file_read_write(all_paths)
         } else {
-            Ok(CapabilitySet::file_write(file_write_paths))
+            CapabilitySet::file_write(file_write_paths)
         }
     } else if !file_read_paths.is_empty() {
-        Ok(CapabilitySet::file_read(file_read_paths))
+        CapabilitySet::file_read(file_read_paths)
     } else if !network_hosts.is_empty() {
-        Ok(CapabilitySet::network(network_hosts))
+        CapabilitySet::network(network_hosts)
     } else {
-        Ok(CapabilitySet::none())
+        CapabilitySet::none()
+    };
+
+    // Secret and key-value access combine with any of the above
+    let base = if secret_names.is_empty() {
+        base
+    } else {
+        base.with_secrets(secret_names)
+    };
+    if has_key_value {
+        Ok(base.with_key_value())
+    } else {
+        Ok(base)
     }
 }
 
@@ -1289,6 +2905,10 @@ mod tests {
         let (cap, _) = parse_capability_string("time").unwrap();
         assert_eq!(cap, Capability::TimeAccess);
 
+        let (cap, name) = parse_capability_string("secret:openai-key").unwrap();
+        assert_eq!(cap, Capability::Secrets);
+        assert_eq!(name, Some("openai-key".to_string()));
+
         assert!(parse_capability_string("invalid:format:too:many:colons").is_err());
     }
 
@@ -1302,6 +2922,22 @@ mod tests {
         state.log("error", "Test error");
     }
 
+    #[test]
+    fn test_host_state_logs_per_node() {
+        let logs = Arc::new(NodeLogs::default());
+        let node_id = uuid::Uuid::new_v4();
+        let mut state = HostState::new("user:echo".to_string(), CapabilitySet::none())
+            .unwrap()
+            .with_logs(logs.clone(), Some(node_id));
+
+        state.log("warn", "slow response");
+
+        let entries = logs.entries(Some(node_id), LogLevel::Debug);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].level, LogLevel::Warn);
+        assert_eq!(entries[0].component_id, "user:echo");
+    }
+
     #[test]
     fn test_host_state_temp_dir_no_permission() {
         let state = HostState::new("test-component".to_string(), CapabilitySet::none()).unwrap();
@@ -1323,6 +2959,115 @@ mod tests {
         assert!(state.get_temp_dir().is_ok());
     }
 
+    #[test]
+    fn test_host_state_kv_requires_capability() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(KvStore::new(dir.path()));
+        let scope = KvScope::new(uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+
+        let denied = HostState::new("test-component".to_string(), CapabilitySet::none())
+            .unwrap()
+            .with_kv(store.clone(), Some(scope));
+        assert!(denied.kv_namespace().is_err());
+
+        let granted = HostState::new(
+            "test-component".to_string(),
+            CapabilitySet::none().with_key_value(),
+        )
+        .unwrap()
+        .with_kv(store, Some(scope));
+        assert!(granted.kv_namespace().is_ok());
+    }
+
+    #[test]
+    fn test_host_state_secrets_by_grant() {
+        use crate::runtime::secrets::SecretVault;
+        use std::sync::RwLock;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = SecretVault::create(dir.path().join("secrets.vault"), "pass").unwrap();
+        vault.set("api-key", "sk-secret".to_string()).unwrap();
+        vault.set("other", "hidden".to_string()).unwrap();
+        let shared: SharedVault = Arc::new(RwLock::new(Some(vault)));
+
+        let mut state = HostState::new(
+            "test-component".to_string(),
+            CapabilitySet::none().with_secrets(vec!["api-key".to_string()]),
+        )
+        .unwrap()
+        .with_vault(shared.clone());
+
+        assert_eq!(state.get_secret("api-key").unwrap(), "sk-secret");
+        assert!(state.get_secret("other").is_err());
+        assert_eq!(state.redact("auth: sk-secret"), "auth: ••••••");
+
+        // Locking the vault cuts off access
+        *shared.write().unwrap() = None;
+        assert!(state.get_secret("api-key").is_err());
+    }
+
+    #[test]
+    fn test_host_state_redacts_echoed_secrets_from_outputs() {
+        use crate::runtime::secrets::SecretVault;
+        use std::sync::RwLock;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = SecretVault::create(dir.path().join("secrets.vault"), "pass").unwrap();
+        vault.set("api-key", "sk-secret".to_string()).unwrap();
+        let mut state = HostState::new(
+            "user:echo".to_string(),
+            CapabilitySet::none().with_secrets(vec!["api-key".to_string()]),
+        )
+        .unwrap()
+        .with_vault(Arc::new(RwLock::new(Some(vault))));
+
+        // Nothing to mask until the component has read a secret
+        let echoed = HashMap::from([(
+            "output".to_string(),
+            NodeValue::String("sk-secret".to_string()),
+        )]);
+        assert_eq!(state.redact_outputs(echoed.clone()), echoed);
+
+        // The component echoes the secret it read, directly and nested
+        let secret = state.get_secret("api-key").unwrap();
+        let outputs = HashMap::from([
+            ("output".to_string(), NodeValue::String(secret.clone())),
+            (
+                "headers".to_string(),
+                NodeValue::List(vec![NodeValue::Record(BTreeMap::from([(
+                    "authorization".to_string(),
+                    NodeValue::String(format!("Bearer {}", secret)),
+                )]))]),
+            ),
+            ("length".to_string(), NodeValue::U32(secret.len() as u32)),
+        ]);
+        let redacted = state.redact_outputs(outputs);
+        assert_eq!(redacted["output"], NodeValue::String("••••••".to_string()));
+        assert_eq!(
+            redacted["headers"],
+            NodeValue::List(vec![NodeValue::Record(BTreeMap::from([(
+                "authorization".to_string(),
+                NodeValue::String("Bearer ••••••".to_string()),
+            )]))])
+        );
+        assert_eq!(redacted["length"], NodeValue::U32(9));
+    }
+
+    #[test]
+    fn test_host_state_progress_and_cancellation() {
+        let control = Arc::new(ExecutionControl::new());
+        let state = HostState::new("test-component".to_string(), CapabilitySet::none())
+            .unwrap()
+            .with_control(Some(control.clone()));
+
+        state.report_progress(0.25, "fetching".to_string());
+        assert_eq!(control.progress().unwrap().message, "fetching");
+
+        assert!(!state.is_cancelled());
+        control.cancel();
+        assert!(state.is_cancelled());
+    }
+
     #[test]
     fn test_parse_capability_requests() {
         use std::path::PathBuf;
@@ -1,6 +1,7 @@
 //! Main application state and UI logic
 
 // Sub-modules for organized application logic
+mod clipboard;
 mod components;
 mod composition;
 mod execution;
@@ -9,9 +10,14 @@ mod state;
 
 use super::canvas::NodeCanvas;
 use super::dialogs::{
-    AboutDialog, GraphMetadataDialog, PermissionDialog, PermissionsViewDialog,
-    UnsavedChangesAction, UnsavedChangesDialog,
+    AboutDialog, AnnotationDialog, ChecksumMismatchDialog, ComponentChangesDialog,
+    GraphMetadataDialog, MigrationReportDialog, MissingComponentsDialog, PermissionDialog,
+    PermissionsViewDialog, SaveFunctionDialog, SecretsDialog, UnsavedChangesAction,
+    UnsavedChangesDialog,
 };
+use super::diff_view::DiffView;
+use super::lint_panel::LintPanel;
+use super::log_console::LogConsole;
 use super::palette::{Palette, PaletteAction};
 use super::spotlight::{SpotlightAction, SpotlightSearch};
 use super::theme::Theme;
@@ -19,9 +25,10 @@ use crate::builtin::{
     register_constant_nodes, register_continuous_example,
     register_wasm_creator_node,
 };
-use crate::graph::command::CommandHistory;
+use crate::graph::command::{CommandHistory, GraphDetails, NodeEdit};
 use crate::graph::graph::NodeGraph;
 use crate::graph::node::ComponentRegistry;
+use crate::runtime::component_store::ComponentStore;
 use crate::runtime::capabilities::{CapabilityGrant, CapabilitySet};
 use crate::runtime::continuous::{ContinuousExecutionManager, ExecutionResult};
 use crate::runtime::engine::{register_builtin_executors, ExecutionEngine};
@@ -68,6 +75,8 @@ pub struct WasmFlowApp {
     palette: Palette,
     /// T092: Graph metadata editor dialog
     metadata_dialog: GraphMetadataDialog,
+    /// Editor for frames, groups and notes
+    annotation_dialog: AnnotationDialog,
     /// Spotlight search for quick node creation
     spotlight: SpotlightSearch,
     /// Last space key press time for double-space detection
@@ -86,12 +95,52 @@ pub struct WasmFlowApp {
     downstream_result_rx: Receiver<(Uuid, NodeExecutionResult)>,
     /// Channel for sending downstream node execution results
     downstream_result_tx: std::sync::mpsc::Sender<(Uuid, NodeExecutionResult)>,
+    /// Channel for handing footer input to the UI event worker
+    ui_event_tx: std::sync::mpsc::Sender<execution::UiEventJob>,
+    /// Channel for receiving component responses to footer input
+    ui_event_result_rx: Receiver<(Uuid, UiEventResult)>,
+    /// Footer events sent to the worker whose response has not arrived
+    ui_events_in_flight: usize,
     /// T028: Component composer for WAC composition
     composer: crate::runtime::wac_integration::ComponentComposer,
     /// T032: Error dialog for composition failures
     composition_error: Option<String>,
     /// T037: View stack for drill-down navigation
     view_stack: crate::graph::drill_down::ViewStack,
+    /// Log console panel
+    log_console: LogConsole,
+    /// Lint findings panel
+    lint_panel: LintPanel,
+    /// Side-by-side comparison with another version of the graph
+    diff_view: DiffView,
+    /// Component log messages per node (shared with the component manager)
+    node_logs: std::sync::Arc<crate::runtime::node_log::NodeLogs>,
+    /// Secret vault management dialog
+    secrets_dialog: SecretsDialog,
+    /// Secret vault (shared with the component manager)
+    secret_vault: crate::runtime::secrets::SharedVault,
+    /// Nodes whose custom footer needs re-rendering with new outputs
+    pending_footer_refresh: std::collections::HashSet<Uuid>,
+    /// Dialog for saving the selection as a graph function
+    save_function_dialog: SaveFunctionDialog,
+    /// Shows what changed when an older graph file was upgraded on load
+    migration_report_dialog: MigrationReportDialog,
+    /// Asks whether to open a file whose checksum does not match
+    checksum_mismatch_dialog: ChecksumMismatchDialog,
+    /// Lists placeholder nodes and relinks them to available components
+    missing_components_dialog: MissingComponentsDialog,
+    /// Offers to upgrade or pin nodes whose component binary was replaced
+    component_changes_dialog: ComponentChangesDialog,
+    /// Last fragment copied in this window (for Edit → Paste)
+    clipboard_text: Option<String>,
+    /// Number of pastes since the last copy, used to offset each paste
+    paste_count: u32,
+    /// Grants of pasted or relinked nodes waiting for the user's re-approval
+    pending_grant_approvals: std::collections::VecDeque<CapabilityGrant>,
+    /// Editable node state at the last history revision, used to record widget edits
+    edit_baseline: Option<(u64, std::collections::BTreeMap<Uuid, NodeEdit>)>,
+    /// Whether a drag or text edit was under way last frame
+    editing: bool,
 }
 
 /// State for incremental execution on the main thread
@@ -104,12 +153,17 @@ struct IncrementalExecutionState {
     execution_receiver: Option<
         Receiver<Result<std::collections::HashMap<String, crate::graph::node::NodeValue>, String>>,
     >,
+    /// Progress and cancellation of the node currently executing
+    control: Option<std::sync::Arc<crate::runtime::progress::ExecutionControl>>,
 }
 
 /// Result from background node execution
 type NodeExecutionResult =
     Result<std::collections::HashMap<String, crate::graph::node::NodeValue>, String>;
 
+/// Component response to one footer event
+type UiEventResult = Result<Option<crate::ui::wit_ui_renderer::UiEventResponse>, String>;
+
 /// T073: Component awaiting permission approval
 #[derive(Debug, Clone)]
 struct PendingPermissionRequest {
@@ -143,6 +197,19 @@ impl WasmFlowApp {
         // The engine creates its own ComponentManager internally
         let mut engine = ExecutionEngine::new();
         register_builtin_executors(&mut engine);
+        let (node_logs, secret_vault) = {
+            let manager = engine.component_manager().lock().unwrap();
+            (manager.node_logs(), manager.secret_vault())
+        };
+
+        // Load saved graph functions; they are registered with the palette below
+        let function_root = crate::graph::function::GraphFunctionLibrary::default_root();
+        let function_library = crate::graph::function::GraphFunctionLibrary::load(&function_root)
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to load graph functions from {}: {}", function_root.display(), e);
+                crate::graph::function::GraphFunctionLibrary::new(function_root.clone())
+            });
+        engine.set_function_library(std::sync::Arc::new(std::sync::RwLock::new(function_library)));
 
         // Create initial graph
         let graph = NodeGraph::new("Untitled Graph".to_string(), "User".to_string());
@@ -155,6 +222,11 @@ impl WasmFlowApp {
         // Create channel for downstream execution results
         let (downstream_result_tx, downstream_result_rx) = channel();
 
+        // Footer input is delivered to components off the UI thread
+        let (ui_event_result_tx, ui_event_result_rx) = channel();
+        let ui_event_tx =
+            execution::spawn_ui_event_worker(engine.component_manager(), ui_event_result_tx);
+
         let mut app = Self {
             graph,
             registry,
@@ -174,6 +246,7 @@ impl WasmFlowApp {
             about_dialog: AboutDialog::new(),
             palette: Palette::new(),
             metadata_dialog: GraphMetadataDialog::new(),
+            annotation_dialog: AnnotationDialog::new(),
             spotlight: SpotlightSearch::new(),
             last_space_time: None,
             theme: Theme::dark(),
@@ -183,13 +256,41 @@ impl WasmFlowApp {
             continuous_result_tx,
             downstream_result_rx,
             downstream_result_tx,
+            ui_event_tx,
+            ui_event_result_rx,
+            ui_events_in_flight: 0,
             composer: crate::runtime::wac_integration::ComponentComposer::new(), // T028
             composition_error: None,                                             // T032
             view_stack: crate::graph::drill_down::ViewStack::new(),              // T037
+            log_console: LogConsole::new(),
+            lint_panel: LintPanel::new(),
+            diff_view: DiffView::new(),
+            node_logs,
+            secrets_dialog: SecretsDialog::new(),
+            secret_vault,
+            pending_footer_refresh: std::collections::HashSet::new(),
+            save_function_dialog: SaveFunctionDialog::new(),
+            migration_report_dialog: MigrationReportDialog::new(),
+            checksum_mismatch_dialog: ChecksumMismatchDialog::new(),
+            missing_components_dialog: MissingComponentsDialog::new(),
+            component_changes_dialog: ComponentChangesDialog::new(),
+            clipboard_text: None,
+            paste_count: 0,
+            pending_grant_approvals: std::collections::VecDeque::new(),
+            edit_baseline: None,
+            editing: false,
         };
 
+        // Keep copies of loaded binaries for nodes pinned to them
+        app.engine
+            .component_manager()
+            .lock()
+            .unwrap()
+            .set_component_store(ComponentStore::new(ComponentStore::default_root()));
+
         // Auto-load components from components/ directory on startup
         app.reload_components();
+        app.register_graph_functions();
 
         app
     }
@@ -282,21 +383,46 @@ impl WasmFlowApp {
         if self.metadata_dialog.saved() {
             let (name, author, description) = self.metadata_dialog.get_metadata();
 
-            // Update graph metadata
-            self.graph.name = name;
-            self.graph.metadata.author = author;
-            self.graph.metadata.description = description;
-            self.graph.metadata.touch();
-
-            // Mark as dirty
-            self.dirty = true;
-            self.status_message = "Graph metadata updated".to_string();
+            // Update graph metadata through command history
+            let cmd = crate::graph::command::Command::EditGraphDetails {
+                before: GraphDetails::capture(&self.graph),
+                after: GraphDetails {
+                    name,
+                    author,
+                    description,
+                },
+            };
+            if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+                self.error_message = Some(format!("Failed to update metadata: {}", e));
+            } else {
+                // Mark as dirty
+                self.dirty = true;
+                self.status_message = "Graph metadata updated".to_string();
+            }
 
             // Reset dialog
             self.metadata_dialog.reset();
         }
     }
 
+    /// Apply a saved edit of a frame, group or note
+    fn handle_annotation_dialog(&mut self, ctx: &egui::Context) {
+        let Some((old, new)) = self.annotation_dialog.show(ctx) else {
+            return;
+        };
+        if old == new || !self.graph.annotations.contains_key(&old.id) {
+            return;
+        }
+        let cmd = crate::graph::command::Command::UpdateAnnotation { old, new };
+        let label = cmd.label();
+        if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+            self.error_message = Some(format!("{} failed: {}", label, e));
+        } else {
+            self.dirty = true;
+            self.status_message = label;
+        }
+    }
+
     /// Render the top menu bar
     fn render_menu_bar(&mut self, ctx: &egui::Context) {
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
@@ -319,6 +445,14 @@ impl WasmFlowApp {
                         self.save_graph_as();
                         ui.close();
                     }
+                    if ui
+                        .button("Compare With...")
+                        .on_hover_text("Show the changes from another version of this graph")
+                        .clicked()
+                    {
+                        self.compare_with_file();
+                        ui.close();
+                    }
                     ui.separator();
 
                     // Recent Files submenu
@@ -357,6 +491,22 @@ impl WasmFlowApp {
                         self.reload_components();
                         ui.close();
                     }
+                    if ui.button("Missing Components...").clicked() {
+                        let missing = self.graph.missing_components();
+                        if missing.is_empty() {
+                            self.status_message = "All components are loaded".to_string();
+                        }
+                        self.missing_components_dialog.open(&self.graph, missing);
+                        ui.close();
+                    }
+                    // Rebuild ports of nodes whose component was recompiled
+                    let outdated = self.graph.nodes_needing_refresh();
+                    ui.add_enabled_ui(!outdated.is_empty(), |ui| {
+                        if ui.button("Refresh Updated Nodes").clicked() {
+                            self.refresh_nodes(&outdated);
+                            ui.close();
+                        }
+                    });
 
                     ui.separator();
                     if ui.button("Quit").clicked() {
@@ -381,11 +531,60 @@ impl WasmFlowApp {
 
                     ui.separator();
 
+                    let has_selection = self.view_stack.is_main_canvas()
+                        && self.graph.nodes.values().any(|n| n.selected);
+                    ui.add_enabled_ui(has_selection, |ui| {
+                        if ui.button("Cut (Ctrl+X)").clicked() {
+                            self.cut_selection(ctx);
+                            ui.close();
+                        }
+                        if ui.button("Copy (Ctrl+C)").clicked() {
+                            self.copy_selection(ctx);
+                            ui.close();
+                        }
+                    });
+                    ui.add_enabled_ui(self.can_paste() && self.view_stack.is_main_canvas(), |ui| {
+                        if ui.button("Paste (Ctrl+V)").clicked() {
+                            self.paste_from_app_clipboard();
+                            ui.close();
+                        }
+                    });
+                    ui.add_enabled_ui(has_selection, |ui| {
+                        if ui.button("Duplicate (Ctrl+D)").clicked() {
+                            self.duplicate_selection();
+                            ui.close();
+                        }
+                        if ui.button("Delete (Del)").clicked() {
+                            self.delete_selection();
+                            ui.close();
+                        }
+                    });
+
+                    ui.separator();
+
                     // T092: Graph metadata editor
                     if ui.button("Graph Properties...").clicked() {
                         self.open_metadata_dialog();
                         ui.close();
                     }
+
+                    if ui.button("Secrets...").clicked() {
+                        self.secrets_dialog.open();
+                        ui.close();
+                    }
+                });
+
+                ui.menu_button("View", |ui| {
+                    let mut show_logs = self.log_console.is_open();
+                    if ui.checkbox(&mut show_logs, "Log Console").clicked() {
+                        self.log_console.toggle();
+                        ui.close();
+                    }
+                    let mut show_lint = self.lint_panel.is_open();
+                    if ui.checkbox(&mut show_lint, "Lint Warnings").clicked() {
+                        self.lint_panel.toggle();
+                        ui.close();
+                    }
                 });
 
                 // T100: Help menu with About dialog
@@ -406,7 +605,11 @@ impl WasmFlowApp {
                     ui.separator();
                 }
 
-                if ui.button("▶ Execute").clicked() {
+                if self.execution_state.is_some() {
+                    if ui.button("⏹ Cancel").clicked() {
+                        self.cancel_execution();
+                    }
+                } else if ui.button("▶ Execute").clicked() {
                     self.execute_graph();
                 }
 
@@ -430,6 +633,20 @@ impl WasmFlowApp {
                     }
                 });
 
+                // Save any selection, builtins included, as a reusable graph function
+                let can_save_function = selected_count >= 1 && self.view_stack.is_main_canvas();
+                ui.add_enabled_ui(can_save_function, |ui| {
+                    let save_button = ui.button("📚 Save as Function");
+                    if save_button.clicked() {
+                        self.save_function_dialog.open(selected_count);
+                    }
+                    save_button.on_hover_text(if can_save_function {
+                        "Save the selected nodes to the function library"
+                    } else {
+                        "Select nodes on the main canvas to save them as a function"
+                    });
+                });
+
                 ui.separator();
 
                 // Show dirty indicator
@@ -547,11 +764,9 @@ impl WasmFlowApp {
                                 granted_at: chrono::Utc::now().to_rfc3339(),
                                 scope: grant.scope.clone(),
                             };
-                            self.graph.grant_capability(new_grant);
 
-                            // Add the node
-                            let cmd = crate::graph::command::Command::AddNode { node };
-                            if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+                            // Add the node and its grant as one step
+                            if let Err(e) = self.add_node_with_grant(node, new_grant) {
                                 self.error_message = Some(format!("Failed to add node: {}", e));
                             } else {
                                 self.status_message = format!(
@@ -580,6 +795,23 @@ impl WasmFlowApp {
         }
     }
 
+    /// Show a node of the main graph by making it the only selected node
+    fn jump_to_node(&mut self, node_id: Uuid) {
+        if !self.graph.nodes.contains_key(&node_id) {
+            return;
+        }
+        if !self.view_stack.is_main_canvas() {
+            self.view_stack.reset_to_main();
+            self.canvas.mark_dirty();
+        }
+
+        for node in self.graph.nodes.values_mut() {
+            node.selected = node.id == node_id;
+        }
+        self.canvas.selection.clear_selection();
+        self.status_message = format!("Selected {}", self.graph.nodes[&node_id].display_name);
+    }
+
     /// Render the main canvas area
     fn render_canvas(&mut self, ctx: &egui::Context) {
         egui::CentralPanel::default().show(ctx, |ui| {
@@ -613,31 +845,64 @@ impl WasmFlowApp {
 
         // T043: Only process modifications when viewing main canvas (read-only drill-down)
         if self.view_stack.is_main_canvas() {
+            // Run canvas wiring and move edits through command history
+            for cmd in std::mem::take(&mut self.canvas.pending_commands) {
+                let label = cmd.label();
+                if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+                    // Drop the rejected wire from the canvas
+                    self.canvas.mark_dirty();
+                    self.error_message = Some(format!("{} failed: {}", label, e));
+                } else {
+                    self.error_message = None;
+                    self.dirty = true;
+                }
+            }
+
             // Process pending node deletions through command history
             if !self.canvas.pending_deletions.is_empty() {
-                for node_id in self.canvas.pending_deletions.drain(..) {
-                    // Create RemoveNode command
-                    let cmd = crate::graph::command::Command::RemoveNode {
-                        node_id,
+                let mut node_ids: Vec<Uuid> = self.canvas.pending_deletions.drain(..).collect();
+                node_ids.sort();
+                node_ids.dedup();
+                let count = node_ids.len();
+
+                // Deleting several nodes at once is a single undo step
+                let cmd = if count == 1 {
+                    crate::graph::command::Command::RemoveNode {
+                        node_id: node_ids[0],
                         node: crate::graph::node::GraphNode::new(
                             String::new(),
                             String::new(),
                             egui::Pos2::ZERO,
                         ), // Placeholder, will be filled by execute()
                         connections: Vec::new(), // Placeholder, will be filled by execute()
-                    };
+                    }
+                } else {
+                    crate::graph::command::Command::RemoveNodes {
+                        node_ids,
+                        nodes: Vec::new(),       // Filled by execute()
+                        connections: Vec::new(), // Filled by execute()
+                    }
+                };
 
-                    if let Err(e) = self.history.execute(cmd, &mut self.graph) {
-                        self.error_message = Some(format!("Failed to delete node: {}", e));
+                if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+                    self.error_message = Some(format!("Failed to delete node: {}", e));
+                } else {
+                    self.status_message = if count == 1 {
+                        "Node deleted".to_string()
                     } else {
-                        self.status_message = "Node deleted".to_string();
-                        self.error_message = None;
-                        self.dirty = true;
-                    }
+                        format!("{} nodes deleted", count)
+                    };
+                    self.error_message = None;
+                    self.dirty = true;
                 }
             }
         } else {
             // In drill-down mode - discard any modification attempts
+            if !self.canvas.pending_commands.is_empty() {
+                self.canvas.pending_commands.clear();
+                // Put wires and positions back the way the composite stores them
+                self.canvas.mark_dirty();
+            }
             if !self.canvas.pending_deletions.is_empty() {
                 self.canvas.pending_deletions.clear();
                 self.status_message =
@@ -655,6 +920,26 @@ impl WasmFlowApp {
             }
         }
 
+        if let Some(node_id) = self.canvas.pending_log_view.take() {
+            self.log_console.show_node(node_id);
+        }
+
+        if let Some(node_id) = self.canvas.pending_relink.take() {
+            if let Some(node) = self.graph.nodes.get(&node_id) {
+                self.missing_components_dialog.open_for_node(node);
+            }
+        }
+
+        if let Some(node_id) = self.canvas.pending_refresh.take() {
+            self.refresh_nodes(&[node_id]);
+        }
+
+        if let Some(annotation_id) = self.canvas.pending_annotation_edit.take() {
+            if let Some(annotation) = self.graph.annotations.get(&annotation_id) {
+                self.annotation_dialog.open(annotation.clone());
+            }
+        }
+
         // T040: Process pending drill-down request
         if let Some(composite_node_id) = self.canvas.pending_drill_down.take() {
             self.handle_drill_down(composite_node_id);
@@ -747,6 +1032,18 @@ impl eframe::App for WasmFlowApp {
         // Poll downstream execution results (triggered by continuous nodes)
         self.poll_downstream_results();
 
+        // Apply component responses to footer input
+        self.poll_ui_event_results();
+        if self.ui_events_in_flight > 0 {
+            ctx.request_repaint();
+        }
+
+        // Re-render custom footers with the outputs that arrived this frame
+        self.refresh_live_footer_views();
+        if !self.pending_footer_refresh.is_empty() {
+            ctx.request_repaint();
+        }
+
         // Request continuous repaints when there are running nodes (for spinner/elapsed time)
         let has_running_nodes = self.graph.nodes.values().any(|n| {
             matches!(
@@ -795,10 +1092,37 @@ impl eframe::App for WasmFlowApp {
 
         // T092: Handle graph metadata dialog
         self.handle_metadata_dialog(ctx);
+        self.handle_annotation_dialog(ctx);
 
         // T100: Show about dialog
         self.about_dialog.show(ctx);
 
+        // Secret vault dialog
+        self.secrets_dialog.show(ctx, &self.secret_vault);
+
+        // Save selection as graph function
+        if let Some((name, description)) = self.save_function_dialog.show(ctx) {
+            self.handle_save_function_action(name, description);
+        }
+
+        // Format migration report for the last opened file
+        self.migration_report_dialog.show(ctx);
+
+        // Files that failed checksum validation only load after confirmation
+        if let Some(path) = self.checksum_mismatch_dialog.show(ctx) {
+            self.load_graph_ignoring_checksum(path);
+        }
+
+        // Placeholder nodes waiting to be relinked
+        if let Some(request) = self.missing_components_dialog.show(ctx, &self.registry) {
+            self.relink_nodes(request);
+        }
+
+        // Nodes whose component binary was replaced
+        if let Some(request) = self.component_changes_dialog.show(ctx) {
+            self.relink_nodes(request);
+        }
+
         // T032: Show composition error dialog
         self.show_composition_error_dialog(ctx);
 
@@ -827,6 +1151,9 @@ impl eframe::App for WasmFlowApp {
             self.load_graph();
         }
 
+        // Ctrl+C / Ctrl+X / Ctrl+V / Ctrl+D on node selections
+        self.handle_clipboard_shortcuts(ctx);
+
         // Handle double-space for spotlight search (only when no dialog is open)
         if !self.spotlight.is_visible()
             && !self.unsaved_changes_dialog.is_open()
@@ -834,6 +1161,12 @@ impl eframe::App for WasmFlowApp {
             && !self.permissions_view_dialog.is_open()
             && !self.about_dialog.is_open()
             && !self.metadata_dialog.is_open()
+            && !self.secrets_dialog.is_open()
+            && !self.save_function_dialog.is_open()
+            && !self.migration_report_dialog.is_open()
+            && !self.checksum_mismatch_dialog.is_open()
+            && !self.missing_components_dialog.is_open()
+            && !self.component_changes_dialog.is_open()
         {
             if ctx.input(|i| i.key_pressed(egui::Key::Space)) {
                 let now = std::time::Instant::now();
@@ -858,8 +1191,23 @@ impl eframe::App for WasmFlowApp {
         self.render_menu_bar(ctx);
         self.render_status_bar(ctx);
         self.render_palette(ctx);
+        self.log_console.show(ctx, &self.node_logs, &self.graph);
+        if let Some(node_id) =
+            self.lint_panel.show(ctx, &self.graph, &self.registry, self.history.revision())
+        {
+            self.jump_to_node(node_id);
+        }
+        if let Some(node_id) = self.diff_view.show(ctx, &self.graph, self.history.revision()) {
+            self.jump_to_node(node_id);
+        }
         self.render_canvas(ctx);
 
+        // Widget edits made this frame become undo steps once finished
+        self.record_node_edits(ctx);
+
+        // Deliver input on interactive footer elements to components
+        self.process_ui_events();
+
         // Render spotlight search (must be after canvas to overlay on top)
         let mouse_pos = ctx.input(|i| i.pointer.hover_pos());
         if let Some(action) = self.spotlight.show(ctx, &self.registry, mouse_pos) {
@@ -0,0 +1,309 @@
+//! Copy, cut, paste and duplicate of node selections
+//!
+//! Selections travel through the system clipboard as `GraphFragment` text.
+//! Pasting is a single undoable command; capability grants carried by the
+//! fragment are re-approved unless the graph already grants the same
+//! capabilities to that component.
+
+use super::WasmFlowApp;
+use crate::graph::clipboard::{GraphFragment, PASTE_OFFSET};
+use crate::graph::command::Command;
+use crate::runtime::capabilities::{CapabilityGrant, CapabilitySet};
+use crate::ui::dialogs::PermissionAction;
+use uuid::Uuid;
+
+impl WasmFlowApp {
+    /// Selected nodes on the main canvas
+    fn selected_node_ids(&self) -> Vec<Uuid> {
+        self.graph
+            .nodes
+            .values()
+            .filter(|node| node.selected)
+            .map(|node| node.id)
+            .collect()
+    }
+
+    /// Handle Ctrl+C / Ctrl+X / Ctrl+V / Ctrl+D / Delete on the canvas
+    ///
+    /// Ignored while a text field has focus so ordinary text editing keeps working.
+    pub(super) fn handle_clipboard_shortcuts(&mut self, ctx: &egui::Context) {
+        if !self.view_stack.is_main_canvas() || ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let events = ctx.input(|i| i.events.clone());
+        for event in events {
+            match event {
+                egui::Event::Copy => self.copy_selection(ctx),
+                egui::Event::Cut => self.cut_selection(ctx),
+                egui::Event::Paste(text) => self.paste_text(&text),
+                _ => {}
+            }
+        }
+
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::D)) {
+            self.duplicate_selection();
+        }
+        if ctx.input(|i| i.key_pressed(egui::Key::Delete)) {
+            self.delete_selection();
+        }
+    }
+
+    /// Copy the selection to the clipboard
+    pub(super) fn copy_selection(&mut self, ctx: &egui::Context) {
+        if self.store_selection(ctx) {
+            // The first paste lands next to the originals
+            self.paste_count = 1;
+            self.status_message = "Copied selection".to_string();
+        }
+    }
+
+    /// Copy the selection to the clipboard and remove it in one undoable step
+    pub(super) fn cut_selection(&mut self, ctx: &egui::Context) {
+        if !self.store_selection(ctx) {
+            return;
+        }
+        // The first paste puts the nodes back where they were
+        self.paste_count = 0;
+        self.remove_selection("Cut");
+    }
+
+    /// Delete the selection in one undoable step
+    pub(super) fn delete_selection(&mut self) {
+        self.remove_selection("Deleted");
+    }
+
+    /// Remove every selected node as a single command
+    fn remove_selection(&mut self, verb: &str) {
+        let node_ids = self.selected_node_ids();
+        if node_ids.is_empty() {
+            return;
+        }
+        let count = node_ids.len();
+        let cmd = Command::RemoveNodes {
+            node_ids,
+            nodes: Vec::new(),       // Filled by execute()
+            connections: Vec::new(), // Filled by execute()
+        };
+        if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+            self.error_message = Some(format!("Failed to remove nodes: {}", e));
+            return;
+        }
+
+        self.canvas.selection.clear_selection();
+        self.canvas.mark_dirty();
+        self.status_message = format!("{} {} node(s)", verb, count);
+        self.error_message = None;
+        self.dirty = true;
+    }
+
+    /// Paste the last fragment copied in this window (Edit menu)
+    pub(super) fn paste_from_app_clipboard(&mut self) {
+        if let Some(text) = self.clipboard_text.clone() {
+            self.paste_text(&text);
+        }
+    }
+
+    /// Paste clipboard text if it holds a fragment
+    pub(super) fn paste_text(&mut self, text: &str) {
+        let Some(fragment) = GraphFragment::from_text(text) else {
+            return;
+        };
+        let offset = PASTE_OFFSET * self.paste_count as f32;
+        self.paste_count += 1;
+        self.paste_fragment(&fragment, offset, "Pasted");
+    }
+
+    /// Duplicate the selection without touching the clipboard
+    pub(super) fn duplicate_selection(&mut self) {
+        let selected = self.selected_node_ids();
+        if let Some(fragment) = GraphFragment::from_selection(&self.graph, &selected) {
+            self.paste_fragment(&fragment, PASTE_OFFSET, "Duplicated");
+        }
+    }
+
+    /// Whether there is anything to paste from the Edit menu
+    pub(super) fn can_paste(&self) -> bool {
+        self.clipboard_text.is_some()
+    }
+
+    /// Serialize the selection into the system and app clipboards
+    fn store_selection(&mut self, ctx: &egui::Context) -> bool {
+        let selected = self.selected_node_ids();
+        let Some(fragment) = GraphFragment::from_selection(&self.graph, &selected) else {
+            return false;
+        };
+
+        match fragment.to_text() {
+            Ok(text) => {
+                ctx.copy_text(text.clone());
+                self.clipboard_text = Some(text);
+                true
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to copy selection: {}", e));
+                false
+            }
+        }
+    }
+
+    /// Insert a fragment as one undoable step and select the new nodes
+    fn paste_fragment(&mut self, fragment: &GraphFragment, offset: egui::Vec2, verb: &str) {
+        let pasted = fragment.instantiate(offset);
+        let count = pasted.nodes.len();
+
+        for node in self.graph.nodes.values_mut() {
+            node.selected = false;
+        }
+
+        // Remember which component each pasted node runs before the nodes move into the command
+        let component_ids: std::collections::HashMap<Uuid, String> = pasted
+            .nodes
+            .iter()
+            .map(|node| (node.id, node.component_id.clone()))
+            .collect();
+
+        // Grants only carry over silently when this graph already trusts the
+        // component with exactly the same capabilities
+        let mut reused = Vec::new();
+        for grant in pasted.grants {
+            let component_id = component_ids.get(&grant.node_id);
+            let already_granted = self.graph.capability_grants.values().any(|existing| {
+                existing.capability_set == grant.capability_set
+                    && self
+                        .graph
+                        .nodes
+                        .get(&existing.node_id)
+                        .map(|node| Some(&node.component_id) == component_id)
+                        .unwrap_or(false)
+            });
+
+            if already_granted {
+                reused.push(Command::SetCapabilityGrant {
+                    node_id: grant.node_id,
+                    old: None,
+                    new: Some(CapabilityGrant {
+                        granted_at: chrono::Utc::now().to_rfc3339(),
+                        ..grant
+                    }),
+                });
+            } else {
+                self.pending_grant_approvals.push_back(grant);
+            }
+        }
+
+        let paste = Command::PasteNodes {
+            nodes: pasted.nodes,
+            connections: pasted.connections,
+        };
+        let reused_count = reused.len();
+        let cmd = if reused.is_empty() {
+            paste
+        } else {
+            // Nodes and their carried-over grants undo together
+            let mut commands = vec![paste];
+            commands.extend(reused);
+            Command::Batch {
+                label: format!("{} {} node(s)", verb, count),
+                commands,
+            }
+        };
+        if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+            self.error_message = Some(format!("Failed to paste nodes: {}", e));
+            return;
+        }
+
+        self.canvas.selection.clear_selection();
+        self.canvas.mark_dirty();
+        self.status_message = if self.pending_grant_approvals.is_empty() {
+            format!("{} {} node(s)", verb, count)
+        } else {
+            format!(
+                "{} {} node(s); {} need permission approval",
+                verb,
+                count,
+                self.pending_grant_approvals.len()
+            )
+        };
+        if reused_count > 0 {
+            log::debug!("Reused {} existing capability grants for pasted nodes", reused_count);
+        }
+        self.error_message = None;
+        self.dirty = true;
+    }
+
+    /// Ask for approval of the next pasted or relinked grant, if the permission dialog is free
+    pub(super) fn open_next_grant_approval(&mut self) {
+        if self.permission_dialog.is_open() || self.pending_permission_request.is_some() {
+            return;
+        }
+
+        while let Some(grant) = self.pending_grant_approvals.front() {
+            // Skip nodes that were removed again (e.g. the paste was undone)
+            let Some(node) = self.graph.nodes.get(&grant.node_id) else {
+                self.pending_grant_approvals.pop_front();
+                continue;
+            };
+            self.permission_dialog.open(
+                node.display_name.clone(),
+                "Pasted or relinked node needs its permissions approved again".to_string(),
+                grant.capability_set.clone(),
+            );
+            return;
+        }
+    }
+
+    /// Apply the user's answer for the pasted grant at the front of the queue
+    ///
+    /// Returns false when no pasted grant was waiting for an answer.
+    pub(super) fn resolve_pasted_grant(&mut self, action: PermissionAction) -> bool {
+        if self.pending_permission_request.is_some() {
+            return false;
+        }
+        let Some(grant) = self.pending_grant_approvals.pop_front() else {
+            return false;
+        };
+        let name = self
+            .graph
+            .nodes
+            .get(&grant.node_id)
+            .map(|node| node.display_name.clone())
+            .unwrap_or_default();
+
+        let node_id = grant.node_id;
+        match action {
+            PermissionAction::Approve => {
+                let grant = CapabilityGrant {
+                    granted_at: chrono::Utc::now().to_rfc3339(),
+                    ..grant
+                };
+                if let Err(e) = self.set_capability_grant(node_id, Some(grant)) {
+                    self.error_message = Some(format!("Failed to grant permissions: {}", e));
+                    return true;
+                }
+                self.status_message = format!("Approved permissions for {} node", name);
+            }
+            PermissionAction::ApproveAsFull => {
+                let grant = CapabilityGrant {
+                    capability_set: CapabilitySet::Full,
+                    granted_at: chrono::Utc::now().to_rfc3339(),
+                    ..grant
+                };
+                if let Err(e) = self.set_capability_grant(node_id, Some(grant)) {
+                    self.error_message = Some(format!("Failed to grant permissions: {}", e));
+                    return true;
+                }
+                self.status_message =
+                    format!("{} node granted FULL ACCESS (unrestricted)", name);
+            }
+            PermissionAction::Deny => {
+                self.status_message = format!(
+                    "Permission denied for {} node - it will fail to execute without approval",
+                    name
+                );
+            }
+        }
+        self.dirty = true;
+        true
+    }
+}
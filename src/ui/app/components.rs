@@ -3,6 +3,12 @@
 //! This module handles loading WASM components and managing the component registry.
 
 use super::WasmFlowApp;
+use crate::graph::command::Command;
+use crate::graph::node::ComponentSpec;
+use crate::graph::pin::pinned_id;
+use crate::graph::placeholder::{plan_refresh, plan_relink, Relink};
+use crate::ui::dialogs::{RelinkRequest, RelinkTarget};
+use uuid::Uuid;
 
 impl WasmFlowApp {
     /// Load a custom WASM component
@@ -16,15 +22,22 @@ impl WasmFlowApp {
             let mut cm = component_manager.lock().unwrap();
 
             // Load component and register with registry
+            // (auto-wrapped plain WIT components give one spec per exported function)
             match cm.load_component_sync(&path) {
-                Ok(component_spec) => {
-                    let component_name = component_spec.name.clone();
+                Ok(component_specs) => {
+                    let component_names: Vec<String> =
+                        component_specs.iter().map(|spec| spec.name.clone()).collect();
 
                     // Register with the component registry
-                    match self.registry.register_component(component_spec) {
+                    let registered: Result<(), _> = component_specs
+                        .into_iter()
+                        .try_for_each(|spec| self.registry.register_component(spec));
+                    match registered {
                         Ok(()) => {
-                            self.status_message = format!("Loaded component: {}", component_name);
+                            self.status_message =
+                                format!("Loaded component: {}", component_names.join(", "));
                             self.error_message = None;
+                            self.resolve_components();
                         }
                         Err(e) => {
                             self.error_message =
@@ -62,19 +75,21 @@ impl WasmFlowApp {
                 if path.extension().and_then(|s| s.to_str()) == Some("wasm") {
                     // Load component and register with registry
                     match cm.load_component_sync(&path) {
-                        Ok(component_spec) => {
+                        Ok(component_specs) => {
                             // Register with the component registry
-                            match self.registry.register_component(component_spec) {
-                                Ok(()) => {
-                                    loaded_count += 1;
-                                }
-                                Err(e) => {
-                                    error_count += 1;
-                                    log::warn!(
-                                        "Failed to register component {}: {}",
-                                        path.display(),
-                                        e
-                                    );
+                            for component_spec in component_specs {
+                                match self.registry.register_component(component_spec) {
+                                    Ok(()) => {
+                                        loaded_count += 1;
+                                    }
+                                    Err(e) => {
+                                        error_count += 1;
+                                        log::warn!(
+                                            "Failed to register component {}: {}",
+                                            path.display(),
+                                            e
+                                        );
+                                    }
                                 }
                             }
                         }
@@ -87,6 +102,10 @@ impl WasmFlowApp {
             }
         }
 
+        // Port types and requested capabilities may have changed
+        self.lint_panel.invalidate();
+        self.resolve_components();
+
         if error_count > 0 {
             self.status_message = format!(
                 "Loaded {} components ({} errors)",
@@ -100,4 +119,214 @@ impl WasmFlowApp {
 
         self.error_message = None;
     }
+
+    /// Match the graph's nodes against the loaded components
+    ///
+    /// Loads stored binaries that nodes are pinned to, flags nodes whose
+    /// component is missing, pins nodes from older files, and lists missing
+    /// and changed components for the user to resolve.
+    pub(super) fn resolve_components(&mut self) {
+        for (component_id, hash) in self.graph.pinned_binaries() {
+            if self.registry.get_by_id(&pinned_id(&component_id, &hash)).is_none() {
+                self.load_pinned_component(&hash);
+            }
+        }
+
+        let missing = self.graph.resolve_components(&self.registry);
+        self.graph.pin_unpinned(&self.registry);
+        self.canvas.mark_dirty();
+        if !missing.is_empty() {
+            self.missing_components_dialog.open(&self.graph, missing);
+        } else if !self.missing_components_dialog.is_single_node() {
+            self.missing_components_dialog.close();
+        }
+
+        let changes = self.graph.changed_components(&self.registry);
+        self.component_changes_dialog.open(&self.graph, changes);
+    }
+
+    /// Register the stored copy of a binary under its pinned ids
+    fn load_pinned_component(&mut self, hash: &str) -> Vec<ComponentSpec> {
+        let component_manager = self.engine.component_manager();
+        let loaded = component_manager.lock().unwrap().load_pinned_component_sync(hash);
+        match loaded {
+            Ok(specs) => {
+                for spec in &specs {
+                    if let Err(e) = self.registry.register_component(spec.clone()) {
+                        log::warn!("Failed to register pinned component {}: {}", spec.id, e);
+                    }
+                }
+                specs
+            }
+            Err(e) => {
+                // Nodes pinned to it show up as placeholders
+                log::warn!("Failed to load pinned component {}: {}", hash, e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Relink nodes to another component in one undoable step
+    pub(super) fn relink_nodes(&mut self, request: RelinkRequest) {
+        let spec = match &request.target {
+            RelinkTarget::Component(id) => self.registry.get_by_id(id).cloned(),
+            RelinkTarget::File => self.load_relink_target(&request.component_id),
+            RelinkTarget::Pinned(hash) => {
+                let id = pinned_id(&request.component_id, hash);
+                let spec = match self.registry.get_by_id(&id) {
+                    Some(spec) => Some(spec.clone()),
+                    None => self
+                        .load_pinned_component(hash)
+                        .into_iter()
+                        .find(|spec| spec.id == id),
+                };
+                if spec.is_none() {
+                    self.error_message = Some(format!(
+                        "No stored copy of {} ({}) is available; upgrade the nodes instead",
+                        request.component_id,
+                        hash
+                    ));
+                }
+                spec
+            }
+        };
+        let Some(spec) = spec else {
+            return;
+        };
+
+        let relinks: Vec<_> = request
+            .node_ids
+            .iter()
+            .filter_map(|node_id| plan_relink(&self.graph, *node_id, &spec))
+            .collect();
+        // Grants are revoked along with the old component; the new one asks again
+        let requested = Self::parse_capabilities(&spec.required_capabilities);
+        let grant_requests: Vec<_> = relinks
+            .iter()
+            .filter_map(|relink| relink.grant_request(&requested))
+            .collect();
+        let label = format!("Relink {} node(s) to {}", relinks.len(), spec.name);
+        let summary = format!("Relinked {} node(s) to {}", relinks.len(), spec.id);
+        if !self.apply_relinks(relinks, label, summary) {
+            return;
+        }
+        if !grant_requests.is_empty() {
+            self.status_message.push_str(&format!(
+                "; {} need permission approval",
+                grant_requests.len()
+            ));
+            self.pending_grant_approvals.extend(grant_requests);
+        }
+
+        if self.missing_components_dialog.is_single_node() {
+            self.missing_components_dialog.close();
+        }
+        self.resolve_components();
+    }
+
+    /// Rebuild nodes from the current spec of their own component
+    ///
+    /// Picks up port changes after a component is recompiled. Ports whose
+    /// name and type still match keep their connections and values.
+    pub(super) fn refresh_nodes(&mut self, node_ids: &[Uuid]) {
+        let relinks: Vec<_> = node_ids
+            .iter()
+            .filter_map(|node_id| plan_refresh(&self.graph, *node_id, &self.registry))
+            .collect();
+        if relinks.is_empty() {
+            self.status_message = "No nodes to refresh".to_string();
+            return;
+        }
+        let label = format!("Refresh {} node(s)", relinks.len());
+        let summary = format!("Refreshed {} node(s)", relinks.len());
+        if self.apply_relinks(relinks, label, summary) {
+            self.canvas.mark_dirty();
+        }
+    }
+
+    /// Execute planned relinks as one undoable step and report dropped wires
+    ///
+    /// Returns false if there was nothing to do or the command failed.
+    fn apply_relinks(&mut self, relinks: Vec<Relink>, label: String, summary: String) -> bool {
+        let dropped: usize = relinks.iter().map(|relink| relink.dropped_connections.len()).sum();
+        let mut unmatched: Vec<String> = relinks
+            .iter()
+            .flat_map(|relink| relink.unmatched_ports.iter().cloned())
+            .collect();
+        unmatched.sort();
+        unmatched.dedup();
+
+        let mut commands: Vec<Command> =
+            relinks.into_iter().map(|relink| relink.into_command()).collect();
+        let cmd = match commands.len() {
+            0 => return false,
+            1 => commands.remove(0),
+            _ => Command::Batch { label, commands },
+        };
+        if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+            self.error_message = Some(format!("Failed to update nodes: {}", e));
+            return false;
+        }
+
+        self.dirty = true;
+        self.lint_panel.invalidate();
+        self.status_message = if dropped == 0 {
+            summary
+        } else {
+            format!(
+                "{}; removed {} connection(s) to unmatched ports ({})",
+                summary,
+                dropped,
+                unmatched.join(", ")
+            )
+        };
+        self.error_message = None;
+        true
+    }
+
+    /// Pick and load a `.wasm` file to relink to
+    ///
+    /// Prefers the component with the id the nodes already use, otherwise the
+    /// first component the file provides.
+    fn load_relink_target(&mut self, component_id: &str) -> Option<ComponentSpec> {
+        let path = rfd::FileDialog::new()
+            .add_filter("WebAssembly Component", &["wasm"])
+            .pick_file()?;
+
+        let component_manager = self.engine.component_manager();
+        let loaded = component_manager.lock().unwrap().load_component_sync(&path);
+        let specs = match loaded {
+            Ok(specs) => specs,
+            Err(e) => {
+                self.error_message = Some(format!("Failed to load component: {}", e));
+                return None;
+            }
+        };
+
+        let chosen = specs
+            .iter()
+            .find(|spec| spec.id == component_id)
+            .or_else(|| specs.first())
+            .cloned();
+        for spec in specs {
+            if let Err(e) = self.registry.register_component(spec) {
+                self.error_message = Some(format!("Failed to register component: {}", e));
+                return None;
+            }
+        }
+        if chosen.is_none() {
+            self.error_message = Some(format!("{} provides no components", path.display()));
+        }
+        chosen
+    }
+
+    /// Register every function in the graph function library with the palette
+    pub(super) fn register_graph_functions(&mut self) {
+        let specs = self.engine.function_library().read().unwrap().component_specs();
+        for spec in specs {
+            if let Err(e) = self.registry.register_component(spec) {
+                log::warn!("Failed to register graph function: {}", e);
+            }
+        }
+    }
 }
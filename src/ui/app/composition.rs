@@ -1,9 +1,10 @@
 //! Composition workflow and drill-down navigation
 //!
 //! This module handles WebAssembly component composition, boundary port aggregation,
-//! and drill-down navigation into composite nodes.
+//! saving selections as graph functions, and drill-down navigation into composite nodes.
 
 use super::WasmFlowApp;
+use crate::graph::command::Command;
 use uuid::Uuid;
 
 impl WasmFlowApp {
@@ -181,13 +182,24 @@ impl WasmFlowApp {
         composite_node.outputs = composite_outputs;
         composite_node.composition_data = Some(composition_data);
 
-        // Add the composite node to the graph
+        // Replace the selected nodes with the composite node in one undoable step
         let composite_id = composite_node.id;
-        let _ = self.graph.add_node(composite_node);
-
-        // Remove selected nodes
-        for node_id in &selected_nodes {
-            let _ = self.graph.remove_node(*node_id);
+        let cmd = Command::Batch {
+            label: format!("Compose {} nodes", selected_nodes.len()),
+            commands: vec![
+                Command::AddNode {
+                    node: composite_node,
+                },
+                Command::RemoveNodes {
+                    node_ids: selected_nodes.clone(),
+                    nodes: Vec::new(),       // Filled by execute()
+                    connections: Vec::new(), // Filled by execute()
+                },
+            ],
+        };
+        if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+            self.composition_error = Some(format!("Failed to insert composite node: {}", e));
+            return;
         }
 
         // Clear selection
@@ -208,6 +220,176 @@ impl WasmFlowApp {
         self.canvas.mark_dirty();
     }
 
+    /// Save the selected nodes as a graph function and replace them with an instance
+    ///
+    /// Unlike composition this accepts builtin nodes and a single node; the
+    /// boundary ports become the function's ports, and connections crossing the
+    /// boundary are rewired to the new instance.
+    pub(super) fn handle_save_function_action(&mut self, name: String, description: String) {
+        use crate::graph::function::GraphFunction;
+
+        let selected_nodes: Vec<Uuid> = self
+            .graph
+            .nodes
+            .iter()
+            .filter(|(_, node)| node.selected)
+            .map(|(id, _)| *id)
+            .collect();
+
+        if selected_nodes.is_empty() {
+            self.error_message = Some("Select nodes to save as a function".to_string());
+            return;
+        }
+
+        let (_, _, input_mappings, output_mappings) = self.aggregate_boundary_ports(&selected_nodes);
+        let function = GraphFunction::from_selection(
+            name,
+            description,
+            &self.graph,
+            &selected_nodes,
+            input_mappings,
+            output_mappings,
+        );
+
+        let function_library = self.engine.function_library();
+        let (spec, revision) = {
+            let mut library = function_library.write().unwrap();
+            match library.save(function) {
+                Ok(saved) => {
+                    let path = library.path_for(&saved.name);
+                    (saved.to_component_spec(path), saved.revision)
+                }
+                Err(e) => {
+                    self.error_message = Some(format!("Failed to save graph function: {}", e));
+                    log::error!("Failed to save graph function: {}", e);
+                    return;
+                }
+            }
+        };
+
+        self.registry.unregister_component(&spec.id);
+        if let Err(e) = self.registry.register_component(spec.clone()) {
+            self.error_message = Some(format!("Failed to register graph function: {}", e));
+            return;
+        }
+        if revision > 1 {
+            // Existing instances now run the new revision
+            self.graph.mark_component_users_for_refresh(&spec.id);
+        }
+
+        // Boundary connections, described by the instance port they move to
+        let selected_set: std::collections::HashSet<Uuid> = selected_nodes.iter().copied().collect();
+        let port_label = |node_id: Uuid, port_id: Uuid| {
+            self.graph.nodes.get(&node_id).and_then(|node| {
+                node.inputs
+                    .iter()
+                    .chain(node.outputs.iter())
+                    .find(|p| p.id == port_id)
+                    .map(|p| format!("{}.{}", node.display_name, p.name))
+            })
+        };
+        let incoming: Vec<(Uuid, Uuid, String)> = self
+            .graph
+            .connections
+            .iter()
+            .filter(|c| selected_set.contains(&c.to_node) && !selected_set.contains(&c.from_node))
+            .filter_map(|c| Some((c.from_node, c.from_port, port_label(c.to_node, c.to_port)?)))
+            .collect();
+        let outgoing: Vec<(String, Uuid, Uuid)> = self
+            .graph
+            .connections
+            .iter()
+            .filter(|c| selected_set.contains(&c.from_node) && !selected_set.contains(&c.to_node))
+            .filter_map(|c| Some((port_label(c.from_node, c.from_port)?, c.to_node, c.to_port)))
+            .collect();
+
+        let count = selected_nodes.len() as f32;
+        let center_pos = egui::pos2(
+            selected_nodes
+                .iter()
+                .filter_map(|id| self.graph.nodes.get(id))
+                .map(|n| n.position.x)
+                .sum::<f32>()
+                / count,
+            selected_nodes
+                .iter()
+                .filter_map(|id| self.graph.nodes.get(id))
+                .map(|n| n.position.y)
+                .sum::<f32>()
+                / count,
+        );
+
+        let instance = spec.create_node(center_pos);
+        let instance_id = instance.id;
+        let input_ports: std::collections::HashMap<String, Uuid> =
+            instance.inputs.iter().map(|p| (p.name.clone(), p.id)).collect();
+        let output_ports: std::collections::HashMap<String, Uuid> =
+            instance.outputs.iter().map(|p| (p.name.clone(), p.id)).collect();
+
+        // Swap the selection for the instance as one undoable step; the saved
+        // library file itself stays
+        let mut commands = vec![
+            Command::AddNode { node: instance },
+            Command::RemoveNodes {
+                node_ids: selected_nodes.clone(),
+                nodes: Vec::new(),       // Filled by execute()
+                connections: Vec::new(), // Filled by execute()
+            },
+        ];
+        commands.extend(selected_nodes.iter().filter_map(|node_id| {
+            Some(Command::SetCapabilityGrant {
+                node_id: *node_id,
+                old: Some(self.graph.get_capability_grant(*node_id)?.clone()),
+                new: None,
+            })
+        }));
+        for (from_node, from_port, port_name) in incoming {
+            if let Some(to_port) = input_ports.get(&port_name) {
+                commands.push(Command::AddConnection {
+                    from_node,
+                    from_port,
+                    to_node: instance_id,
+                    to_port: *to_port,
+                    connection_id: None,
+                });
+            }
+        }
+        for (port_name, to_node, to_port) in outgoing {
+            if let Some(from_port) = output_ports.get(&port_name) {
+                commands.push(Command::AddConnection {
+                    from_node: instance_id,
+                    from_port: *from_port,
+                    to_node,
+                    to_port,
+                    connection_id: None,
+                });
+            }
+        }
+        let cmd = Command::Batch {
+            label: format!("Replace {} nodes with {}", selected_nodes.len(), spec.name),
+            commands,
+        };
+        if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+            self.error_message = Some(format!(
+                "Saved graph function '{}' but failed to insert it: {}",
+                spec.name, e
+            ));
+            return;
+        }
+
+        self.canvas.selection.clear_selection();
+        self.dirty = true;
+        self.status_message = format!(
+            "Saved {} nodes as graph function '{}' (revision {})",
+            selected_nodes.len(),
+            spec.name,
+            revision
+        );
+        log::info!("Saved graph function '{}' revision {}", spec.name, revision);
+
+        self.canvas.mark_dirty();
+    }
+
     /// Aggregate boundary ports from selected nodes for composite node
     ///
     /// Returns: (input_ports, output_ports, input_mappings, output_mappings)
@@ -374,7 +556,7 @@ impl WasmFlowApp {
     ///
     /// For user-defined components, returns the path to the .wasm file.
     /// For composite components, returns the socket component path.
-    /// For builtin components, returns None.
+    /// For builtin components and graph functions, returns None.
     fn get_component_path(
         &self,
         node: &crate::graph::node::GraphNode,
@@ -383,7 +565,8 @@ impl WasmFlowApp {
         let spec = self.registry.get_by_id(&node.component_id)?;
 
         match &spec.component_type {
-            crate::graph::node::ComponentType::Builtin => None,
+            crate::graph::node::ComponentType::Builtin
+            | crate::graph::node::ComponentType::GraphFunction(_) => None,
             crate::graph::node::ComponentType::UserDefined(path) => Some(path.clone()),
             crate::graph::node::ComponentType::Composed { socket_path, .. } => {
                 Some(socket_path.clone())
@@ -3,13 +3,52 @@
 //! This module handles synchronous graph execution, continuous node lifecycle,
 //! and reactive dataflow propagation.
 
-use super::{IncrementalExecutionState, NodeExecutionResult, WasmFlowApp};
+use super::{IncrementalExecutionState, NodeExecutionResult, UiEventResult, WasmFlowApp};
+use crate::graph::node::GraphNode;
+use crate::runtime::capabilities::CapabilitySet;
 use crate::runtime::continuous::ExecutionResult;
 use crate::runtime::engine::{register_builtin_executors, ExecutionEngine};
-use std::sync::mpsc::channel;
+use crate::runtime::progress::ExecutionControl;
+use crate::runtime::wasm_host::ComponentManager;
+use crate::ui::wit_ui_renderer::{UiEvent, UiEventResponse};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex, TryLockError};
 use std::thread;
 use uuid::Uuid;
 
+/// Footer input for one node, handed to the UI event worker
+pub(super) struct UiEventJob {
+    node: GraphNode,
+    capabilities: CapabilitySet,
+    events: Vec<UiEvent>,
+}
+
+/// Start the thread that delivers footer input to components
+///
+/// A single worker keeps each node's events in order, and component calls
+/// never block the UI thread. Every event gets one result on `result_tx`.
+pub(super) fn spawn_ui_event_worker(
+    component_manager: Arc<Mutex<ComponentManager>>,
+    result_tx: Sender<(Uuid, UiEventResult)>,
+) -> Sender<UiEventJob> {
+    let (job_tx, job_rx) = channel::<UiEventJob>();
+    thread::spawn(move || {
+        for job in job_rx {
+            for event in &job.events {
+                let result = component_manager
+                    .lock()
+                    .unwrap()
+                    .dispatch_ui_event(&job.node, &job.capabilities, event)
+                    .map_err(|e| e.to_string());
+                if result_tx.send((job.node.id, result)).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    job_tx
+}
+
 impl WasmFlowApp {
     /// Execute the graph in incremental mode
     pub(super) fn execute_graph(&mut self) {
@@ -45,6 +84,7 @@ impl WasmFlowApp {
             execution_order,
             current_index: 0,
             execution_receiver: None,
+            control: None,
         });
 
         // Auto-start all continuous nodes that are enabled
@@ -74,6 +114,25 @@ impl WasmFlowApp {
         self.status_message = "Starting execution...".to_string();
     }
 
+    /// Ask the running component to stop; remaining nodes are skipped
+    pub(super) fn cancel_execution(&mut self) {
+        let Some(exec_state) = &self.execution_state else {
+            return;
+        };
+
+        match &exec_state.control {
+            // The component sees this through is-cancelled and returns early
+            Some(control) => {
+                control.cancel();
+                self.status_message = "Cancelling execution...".to_string();
+            }
+            None => {
+                self.execution_state = None;
+                self.status_message = "Execution cancelled".to_string();
+            }
+        }
+    }
+
     /// Process one step of incremental execution on the main thread
     pub(super) fn process_execution_step(&mut self) {
         let mut exec_state = match self.execution_state.take() {
@@ -122,6 +181,11 @@ impl WasmFlowApp {
 
             // Share the component manager with the background thread
             let component_manager = self.engine.component_manager();
+            let function_library = self.engine.function_library();
+
+            // Progress reports and cancellation requests for this node
+            let control = Arc::new(ExecutionControl::new());
+            let thread_control = control.clone();
 
             thread::spawn(move || {
                 // Create execution engine in background thread
@@ -131,6 +195,8 @@ impl WasmFlowApp {
                 // Replace the engine's component manager with the shared one
                 // This gives access to all loaded WASM components
                 engine.set_component_manager(component_manager);
+                engine.set_function_library(function_library);
+                engine.set_execution_control(Some(thread_control));
 
                 // Execute the node
                 let result = engine.execute_node_with_outputs(&graph_clone, node_id);
@@ -141,6 +207,7 @@ impl WasmFlowApp {
 
             // Store receiver and wait for result
             exec_state.execution_receiver = Some(rx);
+            exec_state.control = Some(control);
             self.execution_state = Some(exec_state);
         } else {
             // We have a receiver - check if result is ready
@@ -148,11 +215,39 @@ impl WasmFlowApp {
 
             match receiver.try_recv() {
                 Ok(result) => {
+                    if let Some(node) = self.graph.nodes.get_mut(&node_id) {
+                        node.execution_progress = None;
+                    }
+
+                    let cancelled = exec_state
+                        .control
+                        .as_ref()
+                        .is_some_and(|control| control.is_cancelled());
+                    if cancelled {
+                        // Discard whatever the component returned after the cancel request
+                        if let Some(node) = self.graph.nodes.get_mut(&node_id) {
+                            node.execution_state = crate::graph::node::ExecutionState::Idle;
+                            node.execution_started_at = None;
+                        }
+                        self.status_message = "Execution cancelled".to_string();
+                        self.canvas.mark_dirty();
+                        return;
+                    }
+
                     // Result is ready! Apply it
                     self.apply_execution_result(node_id, result, exec_state);
                 }
                 Err(std::sync::mpsc::TryRecvError::Empty) => {
-                    // Still executing - put state back and wait
+                    // Still executing - show the latest reported progress
+                    let progress = exec_state.control.as_ref().and_then(|c| c.progress());
+                    if let Some(node) = self.graph.nodes.get_mut(&node_id) {
+                        if node.execution_progress != progress {
+                            node.execution_progress = progress;
+                            self.canvas.mark_dirty();
+                        }
+                    }
+
+                    // Put state back and wait
                     self.execution_state = Some(exec_state);
                 }
                 Err(std::sync::mpsc::TryRecvError::Disconnected) => {
@@ -160,6 +255,7 @@ impl WasmFlowApp {
                     if let Some(node) = self.graph.nodes.get_mut(&node_id) {
                         node.execution_state = crate::graph::node::ExecutionState::Failed;
                         node.execution_started_at = None;
+                        node.execution_progress = None;
                     }
                     self.error_message =
                         Some("Background execution thread disconnected".to_string());
@@ -206,6 +302,7 @@ impl WasmFlowApp {
                                     config.runtime_state.is_running = false;
                                     config.runtime_state.started_at = None;
                                 }
+                                node.execution_progress = None;
                             }
                             self.status_message = format!(
                                 "Continuous node stopped after {} iterations in {:?}",
@@ -226,6 +323,7 @@ impl WasmFlowApp {
                             // Propagate values to connected downstream nodes and trigger their execution
                             self.propagate_continuous_outputs(node_id);
 
+                            self.pending_footer_refresh.insert(node_id);
                             self.canvas.mark_dirty();
                         }
                         ExecutionResult::Error { node_id, error } => {
@@ -237,6 +335,7 @@ impl WasmFlowApp {
                                     config.runtime_state.is_running = false;
                                     config.runtime_state.last_error = Some(error.to_string());
                                 }
+                                node.execution_progress = None;
                             }
                             self.error_message = Some(format!("Continuous node error: {}", error));
                             self.canvas.mark_dirty();
@@ -254,6 +353,12 @@ impl WasmFlowApp {
                             }
                             // Don't mark dirty for iteration updates to avoid excessive repaints
                         }
+                        ExecutionResult::Progress { node_id, progress } => {
+                            if let Some(node) = self.graph.nodes.get_mut(&node_id) {
+                                node.execution_progress = Some(progress);
+                            }
+                            self.canvas.mark_dirty();
+                        }
                     }
                 }
                 Err(TryRecvError::Empty) => break, // No more results
@@ -273,6 +378,7 @@ impl WasmFlowApp {
                 execution_order: exec_state.execution_order,
                 current_index: next_index,
                 execution_receiver: None,
+                control: None,
             });
         } else {
             // Execution complete
@@ -417,6 +523,7 @@ impl WasmFlowApp {
             // Execute the downstream node in a background thread
             let graph_clone = self.graph.clone();
             let component_manager = self.engine.component_manager();
+            let function_library = self.engine.function_library();
             let node_id_for_thread = downstream_node_id;
             let tx = result_tx.clone();
 
@@ -425,6 +532,7 @@ impl WasmFlowApp {
                 let mut engine = ExecutionEngine::new();
                 register_builtin_executors(&mut engine);
                 engine.set_component_manager(component_manager);
+                engine.set_function_library(function_library);
 
                 // Execute the node
                 let result = engine.execute_node_with_outputs(&graph_clone, node_id_for_thread);
@@ -457,6 +565,7 @@ impl WasmFlowApp {
                             // This ensures the entire dataflow graph updates reactively
                             self.propagate_continuous_outputs(node_id);
 
+                            self.pending_footer_refresh.insert(node_id);
                             self.canvas.mark_dirty();
                         }
                         Err(e) => {
@@ -473,6 +582,113 @@ impl WasmFlowApp {
         }
     }
 
+    /// Hand queued footer input to the UI event worker
+    pub(super) fn process_ui_events(&mut self) {
+        let pending: Vec<(Uuid, Vec<UiEvent>)> = self
+            .graph
+            .nodes
+            .values_mut()
+            .filter(|node| !node.pending_ui_events.is_empty())
+            .map(|node| (node.id, std::mem::take(&mut node.pending_ui_events)))
+            .collect();
+
+        for (node_id, events) in pending {
+            let Some(node) = self.graph.nodes.get(&node_id) else {
+                continue;
+            };
+            // Components handle UI events under the node's grant
+            let capabilities = self
+                .graph
+                .get_capability_grant(node_id)
+                .map(|grant| grant.capability_set.clone())
+                .unwrap_or_else(CapabilitySet::none);
+            let count = events.len();
+            let job = UiEventJob {
+                node: node.clone(),
+                capabilities,
+                events,
+            };
+            if self.ui_event_tx.send(job).is_err() {
+                log::error!("UI event worker stopped");
+                return;
+            }
+            self.ui_events_in_flight += count;
+        }
+    }
+
+    /// Apply component responses to footer input
+    pub(super) fn poll_ui_event_results(&mut self) {
+        let mut execute = false;
+        while let Ok((node_id, result)) = self.ui_event_result_rx.try_recv() {
+            self.ui_events_in_flight = self.ui_events_in_flight.saturating_sub(1);
+            match result {
+                Ok(Some(response)) => {
+                    execute |= response.execute;
+                    self.apply_ui_event_response(node_id, response);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::warn!("Failed to deliver UI event to node {}: {}", node_id, e);
+                    self.error_message = Some(format!("UI event failed: {}", e));
+                }
+            }
+        }
+
+        if execute {
+            self.execute_graph();
+        }
+    }
+
+    /// Apply parameter updates requested by on-ui-event
+    fn apply_ui_event_response(&mut self, node_id: Uuid, response: UiEventResponse) {
+        let Some(node) = self.graph.nodes.get_mut(&node_id) else {
+            return;
+        };
+        let parameters = self
+            .registry
+            .get_by_id(&node.component_id)
+            .map(|spec| spec.parameters.as_slice())
+            .unwrap_or_default();
+
+        for (name, value) in response.parameters {
+            match parameters.iter().find(|parameter| parameter.name == name) {
+                Some(parameter) => {
+                    node.config.insert(name, parameter.normalize(&value));
+                    node.dirty = true;
+                    self.dirty = true;
+                }
+                None => log::warn!(
+                    "Component '{}' set unknown parameter '{}' from a UI event",
+                    node.component_id,
+                    name
+                ),
+            }
+        }
+
+        if response.execute {
+            node.dirty = true;
+        }
+    }
+
+    /// Refresh custom footers of nodes whose outputs changed since the last frame
+    ///
+    /// Continuous nodes can tick faster than the UI redraws; collecting nodes
+    /// per frame calls get-footer-view at most once per node per frame.
+    pub(super) fn refresh_live_footer_views(&mut self) {
+        let pending: Vec<Uuid> = self.pending_footer_refresh.drain().collect();
+        for node_id in pending {
+            let has_custom_footer = self
+                .graph
+                .nodes
+                .get(&node_id)
+                .and_then(|node| self.registry.get_by_id(&node.component_id))
+                .is_some_and(|spec| spec.has_footer_view());
+            if has_custom_footer {
+                self.update_footer_view(node_id);
+            }
+        }
+    }
+
     /// Update footer view for a node after execution
     fn update_footer_view(&mut self, node_id: Uuid) {
         let node = match self.graph.nodes.get(&node_id) {
@@ -485,12 +701,26 @@ impl WasmFlowApp {
             return;
         }
 
-        // Get the component manager
+        let capabilities = self
+            .graph
+            .get_capability_grant(node_id)
+            .map(|grant| grant.capability_set.clone())
+            .unwrap_or_else(CapabilitySet::none);
+
+        // Executions hold the manager for a whole component call; rather than
+        // stall the frame, try again on the next one
         let component_manager = self.engine.component_manager();
-        let component_manager = component_manager.lock().unwrap();
+        let mut component_manager = match component_manager.try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::WouldBlock) => {
+                self.pending_footer_refresh.insert(node_id);
+                return;
+            }
+            Err(TryLockError::Poisoned(e)) => panic!("{}", e),
+        };
 
         // Try to get footer view from component with current outputs
-        match component_manager.get_footer_view_for_node(node) {
+        match component_manager.refresh_footer_view(node, &capabilities) {
             Ok(Some(footer_view)) => {
                 if let Some(node) = self.graph.nodes.get_mut(&node_id) {
                     node.cached_footer_view = Some(footer_view);
@@ -3,10 +3,46 @@
 //! This module handles permission dialogs and capability parsing for user-defined components.
 
 use super::{PendingPermissionRequest, WasmFlowApp};
+use crate::graph::command::Command;
+use crate::graph::node::GraphNode;
 use crate::runtime::capabilities::{CapabilityGrant, CapabilitySet};
 use std::path::PathBuf;
 
 impl WasmFlowApp {
+    /// Add a node together with its capability grant as one undoable step
+    pub(super) fn add_node_with_grant(
+        &mut self,
+        node: GraphNode,
+        grant: CapabilityGrant,
+    ) -> Result<(), String> {
+        let cmd = Command::Batch {
+            label: format!("Add {}", node.display_name),
+            commands: vec![
+                Command::SetCapabilityGrant {
+                    node_id: node.id,
+                    old: None,
+                    new: Some(grant),
+                },
+                Command::AddNode { node },
+            ],
+        };
+        self.history.execute(cmd, &mut self.graph)
+    }
+
+    /// Replace a node's capability grant, or revoke it with `None`, as an undoable step
+    pub(super) fn set_capability_grant(
+        &mut self,
+        node_id: uuid::Uuid,
+        grant: Option<CapabilityGrant>,
+    ) -> Result<(), String> {
+        let cmd = Command::SetCapabilityGrant {
+            node_id,
+            old: self.graph.get_capability_grant(node_id).cloned(),
+            new: grant,
+        };
+        self.history.execute(cmd, &mut self.graph)
+    }
+
     /// T078: Handle permission view dialog
     pub(super) fn handle_permissions_view_dialog(&mut self, ctx: &egui::Context) {
         // Show the dialog
@@ -18,11 +54,18 @@ impl WasmFlowApp {
                 match action {
                     crate::ui::dialogs::PermissionViewAction::Revoke => {
                         // Revoke the capability grant
-                        self.graph.revoke_capability(node_id);
-                        self.status_message =
-                            "Permissions revoked - node will fail to execute without re-approval"
-                                .to_string();
-                        self.dirty = true;
+                        match self.set_capability_grant(node_id, None) {
+                            Ok(()) => {
+                                self.status_message =
+                                    "Permissions revoked - node will fail to execute without re-approval"
+                                        .to_string();
+                                self.dirty = true;
+                            }
+                            Err(e) => {
+                                self.error_message =
+                                    Some(format!("Failed to revoke permissions: {}", e));
+                            }
+                        }
                     }
                     crate::ui::dialogs::PermissionViewAction::UpgradeToFull => {
                         // Upgrade to Full access
@@ -32,9 +75,17 @@ impl WasmFlowApp {
                             granted_at: chrono::Utc::now().to_rfc3339(),
                             scope: "graph".to_string(),
                         };
-                        self.graph.grant_capability(grant);
-                        self.status_message = "Upgraded to FULL ACCESS (unrestricted)".to_string();
-                        self.dirty = true;
+                        match self.set_capability_grant(node_id, Some(grant)) {
+                            Ok(()) => {
+                                self.status_message =
+                                    "Upgraded to FULL ACCESS (unrestricted)".to_string();
+                                self.dirty = true;
+                            }
+                            Err(e) => {
+                                self.error_message =
+                                    Some(format!("Failed to upgrade permissions: {}", e));
+                            }
+                        }
                     }
                 }
             }
@@ -44,7 +95,13 @@ impl WasmFlowApp {
 
     /// T073: Handle permission dialog response
     pub(super) fn handle_permission_dialog(&mut self, ctx: &egui::Context) {
+        // Pasted nodes queue their grants for re-approval
+        self.open_next_grant_approval();
+
         if let Some(action) = self.permission_dialog.show(ctx) {
+            if self.resolve_pasted_grant(action) {
+                return;
+            }
             match action {
                 crate::ui::dialogs::PermissionAction::Approve => {
                     // User approved the permissions - create the node with grant
@@ -58,12 +115,8 @@ impl WasmFlowApp {
                             scope: "graph".to_string(),
                         };
 
-                        // Add the grant to the graph
-                        self.graph.grant_capability(grant);
-
-                        // Add the node to the graph through command history
-                        let cmd = crate::graph::command::Command::AddNode { node };
-                        if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+                        // Add the node and its grant through command history
+                        if let Err(e) = self.add_node_with_grant(node, grant) {
                             self.error_message = Some(format!("Failed to add node: {}", e));
                         } else {
                             self.status_message = format!(
@@ -86,12 +139,8 @@ impl WasmFlowApp {
                             scope: "graph".to_string(),
                         };
 
-                        // Add the grant to the graph
-                        self.graph.grant_capability(grant);
-
-                        // Add the node to the graph through command history
-                        let cmd = crate::graph::command::Command::AddNode { node };
-                        if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+                        // Add the node and its grant through command history
+                        if let Err(e) = self.add_node_with_grant(node, grant) {
                             self.error_message = Some(format!("Failed to add node: {}", e));
                         } else {
                             self.status_message = format!(
@@ -121,6 +170,8 @@ impl WasmFlowApp {
     /// - "file-read:/path"  -> FileRead { paths }
     /// - "file-write:/path" -> FileWrite { paths }
     /// - "network:host.com" -> Network { allowed_hosts }
+    /// - "kv"               -> KeyValue { base } around any of the above
+    /// - "secret:name"      -> Secrets { names, base } around any of the above
     /// - Empty vec          -> None
     pub(super) fn parse_capabilities(capabilities: &[String]) -> CapabilitySet {
         if capabilities.is_empty() {
@@ -130,9 +181,15 @@ impl WasmFlowApp {
         let mut file_read_paths = Vec::new();
         let mut file_write_paths = Vec::new();
         let mut network_hosts = Vec::new();
+        let mut key_value = false;
+        let mut secret_names = Vec::new();
 
         for cap in capabilities {
-            if let Some(path) = cap.strip_prefix("file-read:") {
+            if cap == "kv" {
+                key_value = true;
+            } else if let Some(name) = cap.strip_prefix("secret:").filter(|n| !n.is_empty()) {
+                secret_names.push(name.to_string());
+            } else if let Some(path) = cap.strip_prefix("file-read:") {
                 file_read_paths.push(PathBuf::from(path));
             } else if let Some(path) = cap.strip_prefix("file-write:") {
                 file_write_paths.push(PathBuf::from(path));
@@ -142,7 +199,7 @@ impl WasmFlowApp {
         }
 
         // Determine the most appropriate capability set
-        if !file_read_paths.is_empty() && !file_write_paths.is_empty() {
+        let base = if !file_read_paths.is_empty() && !file_write_paths.is_empty() {
             // Combine read and write paths
             let mut all_paths = file_read_paths;
             all_paths.extend(file_write_paths);
@@ -162,6 +219,18 @@ impl WasmFlowApp {
         } else {
             // Unknown capability format, default to None for safety
             CapabilitySet::None
+        };
+
+        // Secret and key-value access combine with any of the above
+        let base = if secret_names.is_empty() {
+            base
+        } else {
+            base.with_secrets(secret_names)
+        };
+        if key_value {
+            base.with_key_value()
+        } else {
+            base
         }
     }
 
@@ -220,11 +289,9 @@ impl WasmFlowApp {
                     granted_at: chrono::Utc::now().to_rfc3339(),
                     scope: grant.scope.clone(),
                 };
-                self.graph.grant_capability(new_grant);
 
-                // Add the node
-                let cmd = crate::graph::command::Command::AddNode { node };
-                if let Err(e) = self.history.execute(cmd, &mut self.graph) {
+                // Add the node and its grant as one step
+                if let Err(e) = self.add_node_with_grant(node, new_grant) {
                     self.error_message = Some(format!("Failed to add node: {}", e));
                 } else {
                     self.status_message = format!(
@@ -238,7 +305,7 @@ impl WasmFlowApp {
         } else {
             // Builtin component - add directly without permission dialog
             let node = spec.create_node(position);
-            let cmd = crate::graph::command::Command::AddNode { node };
+            let cmd = Command::AddNode { node };
 
             if let Err(e) = self.history.execute(cmd, &mut self.graph) {
                 self.error_message = Some(format!("Failed to add node: {}", e));
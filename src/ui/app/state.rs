@@ -3,17 +3,28 @@
 //! This module handles file operations, undo/redo, and graph lifecycle management.
 
 use super::WasmFlowApp;
+use crate::graph::command::{Command, CommandHistory, NodeEdit};
 use crate::graph::graph::NodeGraph;
-use std::path::PathBuf;
+use crate::graph::migration::MigrationReport;
+use crate::SerializationError;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
 
 impl WasmFlowApp {
     /// Undo the last command
     pub(super) fn undo(&mut self) {
+        // An edit still being made becomes its own step first
+        self.flush_node_edits();
+        let label = self.history.undo_label().unwrap_or_default();
         match self.history.undo(&mut self.graph) {
             Ok(()) => {
-                self.status_message = "Undone".to_string();
+                self.status_message = format!("Undone: {}", label);
                 self.error_message = None;
                 self.dirty = true;
+                // Placeholder flags are not kept in a persisted history
+                self.graph.resolve_components(&self.registry);
+                self.canvas.mark_dirty();
             }
             Err(e) => {
                 self.error_message = Some(format!("Cannot undo: {}", e));
@@ -23,11 +34,15 @@ impl WasmFlowApp {
 
     /// Redo the last undone command
     pub(super) fn redo(&mut self) {
+        self.flush_node_edits();
+        let label = self.history.redo_label().unwrap_or_default();
         match self.history.redo(&mut self.graph) {
             Ok(()) => {
-                self.status_message = "Redone".to_string();
+                self.status_message = format!("Redone: {}", label);
                 self.error_message = None;
                 self.dirty = true;
+                self.graph.resolve_components(&self.registry);
+                self.canvas.mark_dirty();
             }
             Err(e) => {
                 self.error_message = Some(format!("Cannot redo: {}", e));
@@ -35,16 +50,103 @@ impl WasmFlowApp {
         }
     }
 
+    /// Record widget edits of node names, values, configuration and code as undo steps
+    ///
+    /// Widgets edit nodes in place, so edits are found by comparing against the
+    /// state after the last history change. The comparison only runs when a drag
+    /// or text edit ends, not on every frame with input.
+    pub(super) fn record_node_edits(&mut self, ctx: &egui::Context) {
+        if !self.view_stack.is_main_canvas() {
+            return;
+        }
+        let busy = ctx.input(|i| i.pointer.any_down()) || ctx.wants_keyboard_input();
+        let finished = std::mem::replace(&mut self.editing, busy) && !busy;
+        let stale = !matches!(
+            &self.edit_baseline,
+            Some((revision, _)) if *revision == self.history.revision()
+        );
+        if finished || (stale && !busy) {
+            self.flush_node_edits();
+        }
+    }
+
+    /// Turn differences from the edit baseline into one history entry
+    pub(super) fn flush_node_edits(&mut self) {
+        let baseline = match self.edit_baseline.take() {
+            Some((revision, baseline)) if revision == self.history.revision() => baseline,
+            // The graph changed through history (or was replaced); start over
+            _ => {
+                self.edit_baseline = Some(self.capture_edit_baseline());
+                return;
+            }
+        };
+
+        let mut edits: Vec<Command> = baseline
+            .into_iter()
+            .filter_map(|(node_id, before)| {
+                let after = NodeEdit::capture(&self.graph, node_id)?;
+                before.differs_from(&after).then_some(Command::EditNode {
+                    node_id,
+                    before,
+                    after,
+                })
+            })
+            .collect();
+        match edits.len() {
+            0 => {}
+            1 => self.history.record(edits.remove(0)),
+            count => self.history.record(Command::Batch {
+                label: format!("Edit {} nodes", count),
+                commands: edits,
+            }),
+        }
+
+        self.edit_baseline = Some(self.capture_edit_baseline());
+    }
+
+    /// Snapshot the editable state of every node
+    fn capture_edit_baseline(&self) -> (u64, BTreeMap<Uuid, NodeEdit>) {
+        let nodes = self
+            .graph
+            .nodes
+            .keys()
+            .filter_map(|id| Some((*id, NodeEdit::capture(&self.graph, *id)?)))
+            .collect();
+        (self.history.revision(), nodes)
+    }
+
+    /// Reset history for a newly loaded graph, restoring it if it was saved with the file
+    fn restore_history(&mut self, path: Option<&Path>) {
+        self.history = path
+            .and_then(|path| CommandHistory::load_for(path, self.graph.id))
+            .unwrap_or_else(CommandHistory::new);
+        self.edit_baseline = None;
+        // A fresh history restarts revisions, so cached lint results are stale
+        self.lint_panel.invalidate();
+        if self.history.can_undo() {
+            log::info!("Restored undo history for {}", self.graph.name);
+        }
+    }
+
+    /// Keep the undo history of a just-saved file for the next time it is opened
+    fn persist_history(&mut self, path: &Path) {
+        if let Err(e) = self.history.save_for(path, self.graph.id) {
+            log::warn!("Failed to save undo history for {}: {:#}", path.display(), e);
+        }
+    }
+
     /// Save the current graph to a file
     pub(super) fn save_graph(&mut self) {
-        if let Some(path) = &self.current_file {
+        self.flush_node_edits();
+        if let Some(path) = self.current_file.clone() {
             // Save to existing file
-            match self.graph.save_to_file(path) {
+            match self.graph.save_to_file(&path) {
                 Ok(()) => {
                     self.status_message = format!("Saved to {}", path.display());
                     self.error_message = None;
                     self.dirty = false;
-                    self.add_recent_file(path.clone());
+                    self.persist_history(&path);
+                    self.add_recent_file(path);
                 }
                 Err(e) => {
                     self.error_message = Some(format!("Failed to save: {}", e));
@@ -63,12 +165,14 @@ impl WasmFlowApp {
             .set_file_name("graph.wasmflow")
             .save_file()
         {
+            self.flush_node_edits();
             match self.graph.save_to_file(&path) {
                 Ok(()) => {
                     self.status_message = format!("Saved to {}", path.display());
                     self.error_message = None;
                     self.current_file = Some(path.clone());
                     self.dirty = false;
+                    self.persist_history(&path);
                     self.add_recent_file(path);
                 }
                 Err(e) => {
@@ -84,28 +188,17 @@ impl WasmFlowApp {
             .add_filter("WasmFlow Graph", &["wasmflow"])
             .pick_file()
         {
-            match NodeGraph::load_from_file(&path) {
-                Ok(graph) => {
+            match NodeGraph::load_from_file_with_report(&path) {
+                Ok((graph, report)) => {
                     log::info!("Successfully deserialized graph from {}", path.display());
                     log::info!(
                         "Graph has {} nodes and {} connections",
                         graph.nodes.len(),
                         graph.connections.len()
                     );
-
-                    self.graph = graph;
-                    self.current_file = Some(path.clone());
-                    self.dirty = false;
-                    self.history = crate::graph::command::CommandHistory::new();
-                    self.status_message = format!("Loaded {}", path.display());
-                    self.error_message = None;
-                    self.add_recent_file(path.clone());
-
-                    // Mark canvas dirty to force re-sync with loaded graph
-                    self.canvas.mark_dirty();
-
-                    log::info!("Graph loaded successfully from {}", path.display());
+                    self.finish_loading(path, graph, report);
                 }
+                Err(e) if self.confirm_checksum_mismatch(&path, &e) => {}
                 Err(e) => {
                     // Log full error chain for debugging
                     log::error!("Failed to load graph from {}", path.display());
@@ -116,8 +209,6 @@ impl WasmFlowApp {
                         "Invalid file format: This is not a WasmFlow graph file.".to_string()
                     } else if e.to_string().contains("version") {
                         format!("Incompatible file version: {}. Please upgrade WasmFlow.", e)
-                    } else if e.to_string().contains("Checksum mismatch") {
-                        "File appears to be corrupted. Please try another file.".to_string()
                     } else {
                         format!("Failed to load graph: {:#}", e)
                     };
@@ -127,6 +218,94 @@ impl WasmFlowApp {
         }
     }
 
+    /// Make a freshly loaded graph the current one
+    fn finish_loading(&mut self, path: PathBuf, graph: NodeGraph, report: MigrationReport) {
+        self.graph = graph;
+        self.current_file = Some(path.clone());
+        self.dirty = false;
+        self.restore_history(Some(&path));
+        self.status_message = format!("Loaded {}", path.display());
+        self.error_message = None;
+        self.add_recent_file(path.clone());
+
+        // Mark canvas dirty to force re-sync with loaded graph
+        self.canvas.mark_dirty();
+        self.show_migration_report(&path, report);
+        self.resolve_components();
+
+        log::info!("Graph loaded successfully from {}", path.display());
+    }
+
+    /// Ask the user before opening a file that failed checksum validation
+    ///
+    /// Returns false, leaving the error to the caller, for any other error.
+    fn confirm_checksum_mismatch(&mut self, path: &Path, error: &anyhow::Error) -> bool {
+        let mismatch = error.chain().any(|cause| {
+            matches!(
+                cause.downcast_ref::<SerializationError>(),
+                Some(SerializationError::ChecksumMismatch { .. })
+            )
+        });
+        if mismatch {
+            log::warn!("Checksum mismatch in {}: {:#}", path.display(), error);
+            self.checksum_mismatch_dialog.open(path.to_path_buf());
+        }
+        mismatch
+    }
+
+    /// Load a graph the user chose to open despite a checksum mismatch
+    pub(super) fn load_graph_ignoring_checksum(&mut self, path: PathBuf) {
+        match NodeGraph::load_from_file_ignoring_checksum(&path) {
+            Ok((graph, report)) => self.finish_loading(path, graph, report),
+            Err(e) => {
+                self.error_message = Some(format!("Failed to load graph: {:#}", e));
+                log::error!("Failed to load graph from {}: {:#}", path.display(), e);
+            }
+        }
+    }
+
+    /// Compare the current graph with a graph file (e.g. an older version)
+    pub(super) fn compare_with_file(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("WasmFlow Graph", &["wasmflow"])
+            .pick_file()
+        else {
+            return;
+        };
+        match NodeGraph::load_from_file(&path) {
+            Ok(other) => {
+                let file_name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string());
+                self.diff_view.open(file_name, other);
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to load {}: {:#}", path.display(), e));
+            }
+        }
+    }
+
+    /// Tell the user when a file was upgraded or loaded with warnings
+    fn show_migration_report(&mut self, path: &Path, report: MigrationReport) {
+        if report.is_empty() {
+            return;
+        }
+        if report.migrated() {
+            self.status_message = format!(
+                "Loaded {} (upgraded from format v{})",
+                path.display(),
+                report.source_version
+            );
+        }
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        self.migration_report_dialog.open(file_name, report);
+    }
+
     /// Create a new graph (with confirmation if current graph has unsaved changes)
     pub(super) fn new_graph(&mut self) {
         if self.dirty {
@@ -145,7 +324,7 @@ impl WasmFlowApp {
         self.graph = NodeGraph::new("Untitled Graph".to_string(), "User".to_string());
         self.current_file = None;
         self.dirty = false;
-        self.history = crate::graph::command::CommandHistory::new();
+        self.restore_history(None);
         self.status_message = "New graph created".to_string();
         self.error_message = None;
     }
@@ -212,29 +391,15 @@ impl WasmFlowApp {
             return;
         }
 
-        match NodeGraph::load_from_file(&path) {
-            Ok(graph) => {
-                self.graph = graph;
-                self.current_file = Some(path.clone());
-                self.dirty = false;
-                self.history = crate::graph::command::CommandHistory::new();
-                self.status_message = format!("Loaded {}", path.display());
-                self.error_message = None;
-                self.add_recent_file(path.clone());
-
-                // Mark canvas dirty to force re-sync with loaded graph
-                self.canvas.mark_dirty();
-
-                log::info!("Graph loaded successfully from {}", path.display());
-            }
+        match NodeGraph::load_from_file_with_report(&path) {
+            Ok((graph, report)) => self.finish_loading(path, graph, report),
+            Err(e) if self.confirm_checksum_mismatch(&path, &e) => {}
             Err(e) => {
                 // Provide user-friendly error messages for common issues
                 let error_msg = if e.to_string().contains("magic bytes") {
                     "Invalid file format: This is not a WasmFlow graph file.".to_string()
                 } else if e.to_string().contains("version") {
                     format!("Incompatible file version: {}. Please upgrade WasmFlow.", e)
-                } else if e.to_string().contains("Checksum mismatch") {
-                    "File appears to be corrupted. Please try another file.".to_string()
                 } else {
                     format!("Failed to load graph: {}", e)
                 };
@@ -245,21 +410,9 @@ impl WasmFlowApp {
 
     /// T099: Load a graph from a specific path (for CLI support)
     pub fn load_graph_from_path(&mut self, path: PathBuf) {
-        match NodeGraph::load_from_file(&path) {
-            Ok(graph) => {
-                self.graph = graph;
-                self.current_file = Some(path.clone());
-                self.dirty = false;
-                self.history = crate::graph::command::CommandHistory::new();
-                self.status_message = format!("Loaded {}", path.display());
-                self.error_message = None;
-                self.add_recent_file(path.clone());
-
-                // Mark canvas dirty to force re-sync with loaded graph
-                self.canvas.mark_dirty();
-
-                log::info!("Graph loaded successfully from {}", path.display());
-            }
+        match NodeGraph::load_from_file_with_report(&path) {
+            Ok((graph, report)) => self.finish_loading(path, graph, report),
+            Err(e) if self.confirm_checksum_mismatch(&path, &e) => {}
             Err(e) => {
                 let error_msg = format!("Failed to load graph: {:#}", e);
                 self.error_message = Some(error_msg.clone());
@@ -3,20 +3,25 @@
 //! This module implements the visual node editor using egui-snarl for
 //! node rendering, connection management, and user interactions.
 
+mod annotations; // Painting and dragging of frames, groups and notes
 mod node_data;
 mod footer;
+mod parameters;
 mod selection;
 mod viewer;
 
+use crate::graph::command::Command;
 use crate::graph::graph::NodeGraph;
 use crate::graph::node::{ComponentRegistry, GraphNode};
 use crate::ui::selection::{CanvasMode, SelectionState}; // T008: Rectangle selection state
 use egui::Color32;
 use egui_snarl::ui::{NodeLayout, PinPlacement, SnarlStyle};
 use egui_snarl::{InPinId, NodeId, OutPinId, Snarl};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+use annotations::AnnotationDrag;
+pub use annotations::show_markdown;
 pub use node_data::{SnarlNodeData, SnarlPort};
 use selection::SelectionHelper;
 use viewer::CanvasViewer;
@@ -31,14 +36,32 @@ pub struct NodeCanvas {
     uuid_to_snarl: HashMap<Uuid, NodeId>,
     /// Nodes pending deletion (to be handled by app with undo/redo)
     pub pending_deletions: Vec<Uuid>,
+    /// Connection and move edits made on the canvas (to be run by app through history)
+    pub pending_commands: Vec<Command>,
+    /// Positions of nodes being dragged, as they were when the drag started
+    drag_origins: HashMap<Uuid, egui::Pos2>,
     /// T078: Node pending permission view (to be handled by app)
     pub pending_permission_view: Option<Uuid>,
+    /// Node whose logs should be shown in the log console (to be handled by app)
+    pub pending_log_view: Option<Uuid>,
+    /// Node to relink to a different component (to be handled by app)
+    pub pending_relink: Option<Uuid>,
+    /// Node to rebuild from its updated component (to be handled by app)
+    pub pending_refresh: Option<Uuid>,
     /// Continuous nodes pending start
     pub pending_continuous_start: Vec<Uuid>,
     /// Continuous nodes pending stop
     pub pending_continuous_stop: Vec<Uuid>,
     /// T038: Composite node pending drill-down (to be handled by app)
     pub pending_drill_down: Option<Uuid>,
+    /// Annotation to open in the editor (to be handled by app)
+    pub pending_annotation_edit: Option<Uuid>,
+    /// Frame, group or note being moved or resized
+    annotation_drag: Option<AnnotationDrag>,
+    /// Nodes left out of the snarl because their group is collapsed
+    hidden_nodes: HashSet<Uuid>,
+    /// Visible area in graph coordinates, as last drawn by the viewer
+    viewport: Option<egui::Rect>,
     /// T085: Dirty flag to track if graph needs re-sync
     needs_sync: bool,
     /// T085: Cached graph node count for detecting changes
@@ -63,10 +86,19 @@ impl NodeCanvas {
             snarl_to_uuid: HashMap::new(),
             uuid_to_snarl: HashMap::new(),
             pending_deletions: Vec::new(),
+            pending_commands: Vec::new(),
+            drag_origins: HashMap::new(),
             pending_permission_view: None,
+            pending_log_view: None,
+            pending_relink: None,
+            pending_refresh: None,
             pending_continuous_start: Vec::new(),
             pending_continuous_stop: Vec::new(),
             pending_drill_down: None, // T038: No pending drill-down initially
+            pending_annotation_edit: None,
+            annotation_drag: None,
+            hidden_nodes: HashSet::new(),
+            viewport: None,
             needs_sync: true, // T085: Initially dirty
             cached_node_count: 0,
             cached_connection_count: 0,
@@ -87,8 +119,10 @@ impl NodeCanvas {
         // T085: Only sync if needed or if structural changes detected
         let node_count_changed = graph.nodes.len() != self.cached_node_count;
         let connection_count_changed = graph.connections.len() != self.cached_connection_count;
+        let hidden_nodes = graph.hidden_nodes();
+        let hidden_changed = hidden_nodes != self.hidden_nodes;
 
-        if !self.needs_sync && !node_count_changed && !connection_count_changed {
+        if !self.needs_sync && !node_count_changed && !connection_count_changed && !hidden_changed {
             // No changes detected - skip expensive sync
             return;
         }
@@ -105,8 +139,12 @@ impl NodeCanvas {
         self.snarl_to_uuid.clear();
         self.uuid_to_snarl.clear();
 
-        // Add all nodes
+        // Add all nodes, except those inside collapsed groups
+        self.hidden_nodes = hidden_nodes;
         for (uuid, node) in &graph.nodes {
+            if self.hidden_nodes.contains(uuid) {
+                continue;
+            }
             let snarl_node = Self::create_snarl_node(node);
             let snarl_id = self.snarl.insert_node(node.position, snarl_node);
 
@@ -210,6 +248,7 @@ impl NodeCanvas {
             outputs,
             execution_state: node.execution_state,
             needs_component_refresh: node.needs_component_refresh, // T059
+            missing_component: node.missing_component,
             // WASM Creator nodes start with a default width of 650px for comfortable code editing
             custom_width: if node.component_id == "builtin:development:wasm-creator" {
                 Some(650.0)
@@ -217,6 +256,7 @@ impl NodeCanvas {
                 None
             },
             is_composite: node.composition_data.is_some(), // T040: Flag composite nodes
+            has_config: !node.config.is_empty(),
             input_mappings,
             output_mappings,
         }
@@ -324,11 +364,18 @@ impl NodeCanvas {
             }
 
             // Create viewer
+            let canvas_rect = ui.available_rect_before_wrap();
             let mut viewer = CanvasViewer {
                 graph,
                 registry,
                 pending_deletions: &mut self.pending_deletions,
+                pending_commands: &mut self.pending_commands,
+                pending_annotation_edit: &mut self.pending_annotation_edit,
+                viewport: &mut self.viewport,
                 pending_permission_view: &mut self.pending_permission_view,
+                pending_log_view: &mut self.pending_log_view,
+                pending_relink: &mut self.pending_relink,
+                pending_refresh: &mut self.pending_refresh,
                 pending_continuous_start: &mut self.pending_continuous_start,
                 pending_continuous_stop: &mut self.pending_continuous_stop,
                 pending_drill_down: &mut self.pending_drill_down, // T040
@@ -354,6 +401,25 @@ impl NodeCanvas {
                 self.snarl.show(&mut viewer, &style, "canvas", ui);
             });
 
+            // Keep the graph-to-screen transform the viewer drew with
+            if let Some(viewport) = self.viewport.filter(|v| v.width() > 0.0) {
+                self.viewport_scale = canvas_rect.width() / viewport.width();
+                self.viewport_offset =
+                    canvas_rect.min.to_vec2() - viewport.min.to_vec2() * self.viewport_scale;
+            }
+            if is_interactive {
+                let edit = annotations::show_handles(
+                    ui,
+                    graph,
+                    self.viewport_scale,
+                    self.viewport_offset,
+                    &mut self.annotation_drag,
+                );
+                if edit.is_some() {
+                    self.pending_annotation_edit = edit;
+                }
+            }
+
             //T016-T017: Selection highlighting
             // Note: We skip preview highlighting during drag to avoid coordinate transform complexity
             // Only show final selection highlighting after drag completes
@@ -432,20 +498,62 @@ impl NodeCanvas {
         }
 
         // Sync changes back to graph (positions, connections)
-        self.sync_to_graph(graph);
+        let pointer_down = ui.input(|i| i.pointer.any_down());
+        self.sync_to_graph(graph, pointer_down);
+
+        // Annotations move their nodes after the snarl positions were read back,
+        // and the snarl picks the new positions up on the next sync
+        if pointer_down {
+            if let Some(drag) = &self.annotation_drag {
+                drag.preview(graph);
+                self.needs_sync = true;
+            }
+        } else if let Some(drag) = self.annotation_drag.take() {
+            self.pending_commands.extend(drag.finish(graph));
+            self.mark_dirty();
+        }
     }
 
     /// Sync changes from snarl back to graph (positions, connections)
-    fn sync_to_graph(&mut self, graph: &mut NodeGraph) {
-        // Update node positions
+    ///
+    /// Positions follow the snarl live while dragging; the finished drag and
+    /// any wiring changes are queued in `pending_commands` for the app.
+    fn sync_to_graph(&mut self, graph: &mut NodeGraph, pointer_down: bool) {
+        // Update node positions, remembering where each drag started
         for (snarl_id, uuid) in &self.snarl_to_uuid {
             if let Some(node_info) = self.snarl.get_node_info(*snarl_id) {
                 if let Some(graph_node) = graph.nodes.get_mut(uuid) {
-                    graph_node.position = node_info.pos;
+                    if graph_node.position != node_info.pos {
+                        self.drag_origins.entry(*uuid).or_insert(graph_node.position);
+                        graph_node.position = node_info.pos;
+                    }
                 }
             }
         }
 
+        if !pointer_down && !self.drag_origins.is_empty() {
+            let moves: Vec<Command> = self
+                .drag_origins
+                .drain()
+                .filter_map(|(node_id, old_position)| {
+                    let new_position = graph.nodes.get(&node_id)?.position;
+                    (new_position != old_position).then_some(Command::MoveNode {
+                        node_id,
+                        old_position,
+                        new_position,
+                    })
+                })
+                .collect();
+            match moves.len() {
+                0 => {}
+                1 => self.pending_commands.extend(moves),
+                count => self.pending_commands.push(Command::Batch {
+                    label: format!("Move {} nodes", count),
+                    commands: moves,
+                }),
+            }
+        }
+
         // Sync connections from snarl to graph
         // Collect all connections that should exist according to snarl
         let mut snarl_connections = std::collections::HashSet::new();
@@ -476,31 +584,47 @@ impl NodeCanvas {
             graph_connections.insert((conn.from_node, conn.from_port, conn.to_node, conn.to_port));
         }
 
-        // Add connections that exist in snarl but not in graph
-        for (from_node, from_port, to_node, to_port) in &snarl_connections {
-            if !graph_connections.contains(&(*from_node, *from_port, *to_node, *to_port)) {
-                // Try to add connection to graph
-                let _ = graph.add_connection(*from_node, *from_port, *to_node, *to_port);
-            }
-        }
-
-        // Remove connections that exist in graph but not in snarl
-        let connections_to_remove: Vec<_> = graph
+        // Remove connections that exist in graph but not in snarl, ignoring
+        // those of nodes the snarl does not show
+        let mut wiring: Vec<Command> = graph
             .connections
             .iter()
             .filter(|conn| {
-                !snarl_connections.contains(&(
+                self.uuid_to_snarl.contains_key(&conn.from_node)
+                    && self.uuid_to_snarl.contains_key(&conn.to_node)
+                    && !snarl_connections.contains(&(
                     conn.from_node,
                     conn.from_port,
                     conn.to_node,
                     conn.to_port,
                 ))
             })
-            .map(|conn| conn.id)
+            .map(|conn| Command::RemoveConnection {
+                connection: conn.clone(),
+            })
             .collect();
 
-        for conn_id in connections_to_remove {
-            let _ = graph.remove_connection(conn_id);
+        // Add connections that exist in snarl but not in graph
+        for (from_node, from_port, to_node, to_port) in snarl_connections {
+            if !graph_connections.contains(&(from_node, from_port, to_node, to_port)) {
+                wiring.push(Command::AddConnection {
+                    from_node,
+                    from_port,
+                    to_node,
+                    to_port,
+                    connection_id: None,
+                });
+            }
+        }
+
+        // Replacing a wire on an input removes the old one and adds the new one in one step
+        match wiring.len() {
+            0 => {}
+            1 => self.pending_commands.extend(wiring),
+            _ => self.pending_commands.push(Command::Batch {
+                label: "Rewire".to_string(),
+                commands: wiring,
+            }),
         }
     }
 
@@ -0,0 +1,298 @@
+//! Drawing and dragging of frames, groups and notes
+//!
+//! Annotations are painted by `CanvasViewer::draw_background`, behind the
+//! nodes. Their title bars and resize corners are plain egui widgets laid over
+//! the snarl, placed with the viewport transform seen in the previous frame.
+
+use crate::graph::annotation::{Annotation, AnnotationKind};
+use crate::graph::command::Command;
+use crate::graph::graph::NodeGraph;
+use egui::{Color32, FontId, Pos2, Rect, Stroke, Vec2};
+use uuid::Uuid;
+
+/// Side of the square in the bottom-right corner that resizes an annotation
+const RESIZE_HANDLE: f32 = 14.0;
+
+/// Smallest size an annotation can be resized to
+const MIN_SIZE: Vec2 = egui::vec2(120.0, 60.0);
+
+/// Text colour on notes, which always have a light background
+const NOTE_TEXT: Color32 = Color32::from_gray(30);
+
+fn rgb(color: [u8; 3]) -> Color32 {
+    Color32::from_rgb(color[0], color[1], color[2])
+}
+
+/// One line of note text as it is drawn
+struct NoteLine {
+    text: String,
+    size: f32,
+    strong: bool,
+    monospace: bool,
+}
+
+/// Split note text into lines, applying the markdown the canvas understands
+///
+/// Headings, bullet lists, quotes and fenced code are styled; inline emphasis
+/// markers are dropped.
+fn note_lines(text: &str) -> Vec<NoteLine> {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(NoteLine {
+                text: line.to_string(),
+                size: 12.0,
+                strong: false,
+                monospace: true,
+            });
+            continue;
+        }
+        let heading = line.chars().take_while(|c| *c == '#').count();
+        let (text, size, strong) = match heading {
+            1..=3 if line[heading..].starts_with(' ') => {
+                (line[heading + 1..].to_string(), 20.0 - 2.0 * heading as f32, true)
+            }
+            _ => {
+                let trimmed = line.trim_start();
+                match trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+                    Some(item) => (format!("• {}", item), 13.0, false),
+                    None => (line.strip_prefix("> ").unwrap_or(line).to_string(), 13.0, false),
+                }
+            }
+        };
+        lines.push(NoteLine {
+            text: text.replace("**", "").replace('`', ""),
+            size,
+            strong,
+            monospace: false,
+        });
+    }
+    lines
+}
+
+/// Show note text with the same markdown styling as on the canvas
+pub fn show_markdown(ui: &mut egui::Ui, text: &str) {
+    for line in note_lines(text) {
+        let mut rich = egui::RichText::new(line.text).size(line.size);
+        if line.strong {
+            rich = rich.strong();
+        }
+        if line.monospace {
+            rich = rich.monospace();
+        }
+        ui.label(rich);
+    }
+}
+
+/// Paint an annotation in graph coordinates
+pub(super) fn paint(
+    painter: &egui::Painter,
+    annotation: &Annotation,
+    graph: &NodeGraph,
+    style: &egui::Style,
+) {
+    let color = rgb(annotation.color);
+    let rect = annotation.rect();
+
+    if let AnnotationKind::Note { text } = &annotation.kind {
+        painter.rect_filled(rect, 4.0, color);
+        let painter = painter.with_clip_rect(rect.shrink(4.0).intersect(painter.clip_rect()));
+        let mut pos = rect.min + egui::vec2(8.0, 6.0);
+        for line in note_lines(text) {
+            let font = if line.monospace {
+                FontId::monospace(line.size)
+            } else {
+                FontId::proportional(line.size)
+            };
+            let galley = painter.layout(line.text, font, NOTE_TEXT, rect.width() - 16.0);
+            let height = galley.size().y;
+            painter.galley(pos, galley, NOTE_TEXT);
+            pos.y += height + 2.0;
+        }
+        return;
+    }
+
+    painter.rect_filled(rect, 6.0, color.gamma_multiply(0.15));
+    painter.add(egui::Shape::rect_stroke(
+        rect,
+        6.0,
+        Stroke::new(1.5, color),
+        egui::epaint::StrokeKind::Inside,
+    ));
+    let title_rect = annotation.title_rect();
+    let title_corners = egui::CornerRadius {
+        nw: 6,
+        ne: 6,
+        sw: 0,
+        se: 0,
+    };
+    painter.rect_filled(title_rect, title_corners, color.gamma_multiply(0.7));
+
+    let icon = match annotation.kind {
+        AnnotationKind::Group { collapsed: true } => "▸ ",
+        AnnotationKind::Group { collapsed: false } => "▾ ",
+        _ => "",
+    };
+    let clipped = painter.with_clip_rect(rect.intersect(painter.clip_rect()));
+    clipped.text(
+        title_rect.left_center() + egui::vec2(8.0, 0.0),
+        egui::Align2::LEFT_CENTER,
+        format!("{}{}", icon, annotation.title),
+        FontId::proportional(15.0),
+        Color32::WHITE,
+    );
+    if annotation.is_collapsed() {
+        clipped.text(
+            title_rect.left_bottom() + egui::vec2(8.0, 6.0),
+            egui::Align2::LEFT_TOP,
+            annotation.summary(graph),
+            FontId::proportional(12.0),
+            style.visuals.text_color(),
+        );
+    } else {
+        let corner = Rect::from_min_max(rect.max - Vec2::splat(RESIZE_HANDLE), rect.max);
+        painter.line_segment(
+            [corner.right_top(), corner.left_bottom()],
+            Stroke::new(1.5, color),
+        );
+    }
+}
+
+/// An annotation being moved or resized on the canvas
+pub(super) struct AnnotationDrag {
+    original: Annotation,
+    /// Positions of the member nodes when the drag started
+    node_origins: Vec<(Uuid, Pos2)>,
+    /// Distance dragged so far, in graph coordinates
+    delta: Vec2,
+    resize: bool,
+}
+
+impl AnnotationDrag {
+    fn new(original: &Annotation, graph: &NodeGraph, resize: bool) -> Self {
+        let node_origins = original
+            .node_ids
+            .iter()
+            .filter_map(|id| Some((*id, graph.nodes.get(id)?.position)))
+            .collect();
+        Self {
+            original: original.clone(),
+            node_origins,
+            delta: Vec2::ZERO,
+            resize,
+        }
+    }
+
+    /// The annotation as it looks with the drag applied
+    fn current(&self, graph: &NodeGraph) -> Annotation {
+        let mut annotation = self.original.clone();
+        if !self.resize {
+            annotation.position += self.delta;
+            return annotation;
+        }
+        annotation.width = (annotation.width + self.delta.x).max(MIN_SIZE.x);
+        annotation.height = (annotation.height + self.delta.y).max(MIN_SIZE.y);
+        if !matches!(annotation.kind, AnnotationKind::Note { .. }) {
+            // A resized frame holds whatever it now encloses
+            annotation.node_ids = annotation.nodes_inside(graph);
+        }
+        annotation
+    }
+
+    /// Show the drag in progress, without recording an undo step
+    pub(super) fn preview(&self, graph: &mut NodeGraph) {
+        let annotation = self.current(graph);
+        graph.annotations.insert(annotation.id, annotation);
+        if !self.resize {
+            self.place_nodes(graph, self.delta);
+        }
+    }
+
+    /// Undo the preview and return the command that makes the drag permanent
+    pub(super) fn finish(self, graph: &mut NodeGraph) -> Option<Command> {
+        let annotation = self.current(graph);
+        self.place_nodes(graph, Vec2::ZERO);
+        graph.annotations.insert(self.original.id, self.original.clone());
+        if annotation == self.original {
+            return None;
+        }
+        Some(if self.resize {
+            Command::UpdateAnnotation {
+                old: self.original,
+                new: annotation,
+            }
+        } else {
+            self.original.move_command(graph, self.delta)
+        })
+    }
+
+    fn place_nodes(&self, graph: &mut NodeGraph, delta: Vec2) {
+        for (node_id, origin) in &self.node_origins {
+            if let Some(node) = graph.nodes.get_mut(node_id) {
+                node.position = *origin + delta;
+            }
+        }
+    }
+}
+
+/// Lay the move and resize handles of every annotation over the canvas
+///
+/// `scale` and `offset` map graph to screen coordinates. Returns the
+/// annotation that was double-clicked, to be edited.
+pub(super) fn show_handles(
+    ui: &mut egui::Ui,
+    graph: &NodeGraph,
+    scale: f32,
+    offset: Vec2,
+    drag: &mut Option<AnnotationDrag>,
+) -> Option<Uuid> {
+    let to_screen = |rect: Rect| {
+        Rect::from_min_max(
+            (rect.min.to_vec2() * scale + offset).to_pos2(),
+            (rect.max.to_vec2() * scale + offset).to_pos2(),
+        )
+    };
+    let clip = ui.clip_rect();
+    let mut edit = None;
+
+    // Later widgets win overlapping hits, so register the topmost last
+    for annotation in graph.annotations_back_to_front() {
+        let id = ui.id().with(("annotation", annotation.id));
+        let handle = to_screen(annotation.title_rect());
+        if !handle.intersects(clip) {
+            continue;
+        }
+
+        let response = ui
+            .interact(handle, id, egui::Sense::click_and_drag())
+            .on_hover_cursor(egui::CursorIcon::Grab);
+        if response.drag_started() {
+            *drag = Some(AnnotationDrag::new(annotation, graph, false));
+        }
+        if response.double_clicked() {
+            edit = Some(annotation.id);
+        }
+
+        if annotation.is_collapsed() {
+            continue;
+        }
+        let corner = to_screen(annotation.rect()).max;
+        let resize = Rect::from_min_max(corner - Vec2::splat(RESIZE_HANDLE), corner);
+        let response = ui
+            .interact(resize, id.with("resize"), egui::Sense::drag())
+            .on_hover_cursor(egui::CursorIcon::ResizeNwSe);
+        if response.drag_started() {
+            *drag = Some(AnnotationDrag::new(annotation, graph, true));
+        }
+    }
+
+    if let Some(drag) = drag {
+        drag.delta += ui.input(|i| i.pointer.delta()) / scale;
+    }
+    edit
+}
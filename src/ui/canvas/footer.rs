@@ -4,6 +4,7 @@
 //! input editors for unconnected inputs and output value displays.
 
 use crate::graph::node::{DataType, ExecutionState, GraphNode, NodeValue};
+use crate::runtime::progress::ExecutionProgress;
 use egui_snarl::{NodeId, Snarl};
 use super::node_data::SnarlNodeData;
 
@@ -70,11 +71,29 @@ impl DefaultFooterView {
                     break;
                 }
 
-                // Input label
-                ui.label(
-                    egui::RichText::new(format!("{}:", input_port.name))
-                        .color(egui::Color32::from_rgb(180, 180, 180))
-                );
+                // Input label, with a reset button once a declared default is overridden
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!("{}:", input_port.name))
+                            .color(egui::Color32::from_rgb(180, 180, 180))
+                    );
+
+                    if input_port.is_overridden() {
+                        let default_text = input_port
+                            .default_value
+                            .as_ref()
+                            .map(|v| v.format_display())
+                            .unwrap_or_default();
+                        if ui
+                            .small_button("↺")
+                            .on_hover_text(format!("Reset to default ({})", default_text))
+                            .clicked()
+                        {
+                            input_port.reset_to_default();
+                            node.dirty = true;
+                        }
+                    }
+                });
 
                 // Match on data type to emit appropriate widget
                 match &input_port.data_type {
@@ -168,8 +187,30 @@ impl DefaultFooterView {
         Ok(())
     }
 
+    /// Render progress reported by the component
+    fn render_progress(ui: &mut egui::Ui, progress: &ExecutionProgress) {
+        let bar = egui::ProgressBar::new(progress.fraction).show_percentage();
+        let bar = if progress.message.is_empty() {
+            bar
+        } else {
+            bar.text(format!(
+                "{} ({:.0}%)",
+                progress.message,
+                progress.fraction * 100.0
+            ))
+        };
+        ui.add(bar);
+    }
+
     /// Render FooterBody: Output values and execution status
     fn render_footer_body(ui: &mut egui::Ui, node: &GraphNode) -> Result<(), String> {
+        // Continuous nodes report progress while they run, alongside their outputs
+        if node.continuous_config.is_some() {
+            if let Some(progress) = &node.execution_progress {
+                Self::render_progress(ui, progress);
+            }
+        }
+
         // Handle execution state awareness
         match node.execution_state {
             ExecutionState::Idle => {
@@ -177,6 +218,12 @@ impl DefaultFooterView {
                 return Ok(());
             }
             ExecutionState::Running => {
+                // Components that report progress get a progress bar instead of a spinner
+                if let Some(progress) = &node.execution_progress {
+                    Self::render_progress(ui, progress);
+                    return Ok(());
+                }
+
                 // Show spinner if execution is taking longer than 500ms
                 if let Some(started_at) = node.execution_started_at {
                     let elapsed = started_at.elapsed();
@@ -18,10 +18,14 @@ pub struct SnarlNodeData {
     pub execution_state: ExecutionState,
     /// T059: Flag indicating component was updated
     pub needs_component_refresh: bool,
+    /// Component is not loaded (placeholder node)
+    pub missing_component: bool,
     /// Custom width for resizable nodes (e.g., WASM Creator)
     pub custom_width: Option<f32>,
     /// T040: Flag indicating this is a composite node (has internal structure)
     pub is_composite: bool,
+    /// Node has configuration parameters (rendered in the body)
+    pub has_config: bool,
     /// T048: Port mapping info for composite nodes (external_port_name -> (internal_node_name, internal_port_name))
     pub input_mappings: std::collections::BTreeMap<String, (String, String)>,
     pub output_mappings: std::collections::BTreeMap<String, (String, String)>,
@@ -0,0 +1,92 @@
+//! Configuration parameter widgets for the node body
+//!
+//! Components declare static configuration through `ParameterSpec`s. Values
+//! live in `GraphNode::config` and are edited here, separately from the data
+//! input editors in the footer.
+
+use crate::graph::node::{NodeValue, ParameterKind, ParameterSpec};
+use std::collections::BTreeMap;
+
+/// Render widgets for all declared parameters
+///
+/// Returns `true` if any value changed.
+pub(super) fn render_parameters(
+    ui: &mut egui::Ui,
+    node_id: uuid::Uuid,
+    parameters: &[ParameterSpec],
+    config: &mut BTreeMap<String, NodeValue>,
+) -> bool {
+    let mut changed = false;
+
+    egui::Grid::new(("node_parameters", node_id))
+        .num_columns(2)
+        .spacing([8.0, 4.0])
+        .show(ui, |ui| {
+            for parameter in parameters {
+                ui.label(
+                    egui::RichText::new(&parameter.label)
+                        .color(egui::Color32::from_rgb(180, 180, 180)),
+                )
+                .on_hover_text(&parameter.description);
+
+                let value = config
+                    .entry(parameter.name.clone())
+                    .or_insert_with(|| parameter.default_value.clone());
+                // Repair values saved against an older parameter schema
+                let normalized = parameter.normalize(value);
+                if *value != normalized {
+                    *value = normalized;
+                    changed = true;
+                }
+
+                if render_parameter(ui, node_id, parameter, value) {
+                    changed = true;
+                }
+                ui.end_row();
+            }
+        });
+
+    changed
+}
+
+fn render_parameter(
+    ui: &mut egui::Ui,
+    node_id: uuid::Uuid,
+    parameter: &ParameterSpec,
+    value: &mut NodeValue,
+) -> bool {
+    match (&parameter.kind, value) {
+        (ParameterKind::Text, NodeValue::String(s)) => {
+            ui.add(egui::TextEdit::singleline(s).desired_width(120.0)).changed()
+        }
+        (ParameterKind::Choice(choices), NodeValue::String(selected)) => {
+            let mut changed = false;
+            egui::ComboBox::from_id_salt(("node_parameter", node_id, &parameter.name))
+                .selected_text(selected.as_str())
+                .show_ui(ui, |ui| {
+                    for choice in choices {
+                        if ui
+                            .selectable_label(selected == choice, choice)
+                            .clicked()
+                            && selected != choice
+                        {
+                            *selected = choice.clone();
+                            changed = true;
+                        }
+                    }
+                });
+            changed
+        }
+        (ParameterKind::IntRange { min, max }, NodeValue::I32(v)) => {
+            ui.add(egui::Slider::new(v, *min..=*max)).changed()
+        }
+        (ParameterKind::FloatRange { min, max, step }, NodeValue::F32(v)) => ui
+            .add(egui::Slider::new(v, *min..=*max).step_by(*step as f64))
+            .changed(),
+        (ParameterKind::Toggle, NodeValue::Bool(b)) => ui.checkbox(b, "").changed(),
+        _ => {
+            ui.label("(unsupported value)");
+            false
+        }
+    }
+}
@@ -3,23 +3,34 @@
 //! This module implements the egui-snarl SnarlViewer trait which handles
 //! node rendering, pin rendering, and user interactions with nodes.
 
+use crate::graph::annotation::{Annotation, AnnotationKind};
+use crate::graph::command::Command;
 use crate::graph::graph::NodeGraph;
 use crate::graph::node::{ComponentRegistry, DataType};
 use crate::ui::execution_status;
 use egui::{Color32, Pos2};
-use egui_snarl::ui::{NodeLayout, PinInfo, SnarlViewer};
+use egui_snarl::ui::{BackgroundPattern, NodeLayout, PinInfo, SnarlStyle, SnarlViewer};
 use egui_snarl::{InPin, NodeId, OutPin, Snarl};
 use std::collections::HashMap;
 use uuid::Uuid;
+use super::annotations;
 use super::node_data::SnarlNodeData;
 use super::footer::DefaultFooterView;
+use super::parameters;
 
 /// Viewer implementation for egui-snarl
 pub(super) struct CanvasViewer<'a> {
     pub graph: &'a mut NodeGraph,
     pub registry: &'a ComponentRegistry,
     pub pending_deletions: &'a mut Vec<Uuid>,
+    pub pending_commands: &'a mut Vec<Command>,
+    pub pending_annotation_edit: &'a mut Option<Uuid>,
+    /// Receives the visible area in graph coordinates
+    pub viewport: &'a mut Option<egui::Rect>,
     pub pending_permission_view: &'a mut Option<Uuid>,
+    pub pending_log_view: &'a mut Option<Uuid>,
+    pub pending_relink: &'a mut Option<Uuid>,
+    pub pending_refresh: &'a mut Option<Uuid>,
     pub pending_continuous_start: &'a mut Vec<Uuid>,
     pub pending_continuous_stop: &'a mut Vec<Uuid>,
     pub pending_drill_down: &'a mut Option<Uuid>, // T040: Drill-down requests
@@ -157,7 +168,15 @@ impl<'a> SnarlViewer<SnarlNodeData> for CanvasViewer<'a> {
                     // T059: Show warning icon if component was updated
                     if node_data.needs_component_refresh {
                         ui.label(egui::RichText::new("⚠").color(egui::Color32::from_rgb(255, 180, 0)))
-                            .on_hover_text("Component updated - node may need refresh");
+                            .on_hover_text("Component updated - right-click to refresh");
+                    }
+
+                    if node_data.missing_component {
+                        ui.label(egui::RichText::new("⛔").color(egui::Color32::from_rgb(255, 80, 80)))
+                            .on_hover_text(format!(
+                                "Component '{}' is not loaded - right-click to relink",
+                                node_data.component_id
+                            ));
                     }
 
                     // Right-aligned buttons
@@ -223,7 +242,15 @@ impl<'a> SnarlViewer<SnarlNodeData> for CanvasViewer<'a> {
                     // T059: Show warning icon if component was updated
                     if node_data.needs_component_refresh {
                         ui.label(egui::RichText::new("⚠").color(egui::Color32::from_rgb(255, 180, 0)))
-                            .on_hover_text("Component updated - node may need refresh");
+                            .on_hover_text("Component updated - right-click to refresh");
+                    }
+
+                    if node_data.missing_component {
+                        ui.label(egui::RichText::new("⛔").color(egui::Color32::from_rgb(255, 80, 80)))
+                            .on_hover_text(format!(
+                                "Component '{}' is not loaded - right-click to relink",
+                                node_data.component_id
+                            ));
                     }
 
                     // Right-aligned buttons
@@ -261,7 +288,15 @@ impl<'a> SnarlViewer<SnarlNodeData> for CanvasViewer<'a> {
                     // T059: Show warning icon if component was updated
                     if node_data.needs_component_refresh {
                         ui.label(egui::RichText::new("⚠").color(egui::Color32::from_rgb(255, 180, 0)))
-                            .on_hover_text("Component updated - node may need refresh");
+                            .on_hover_text("Component updated - right-click to refresh");
+                    }
+
+                    if node_data.missing_component {
+                        ui.label(egui::RichText::new("⛔").color(egui::Color32::from_rgb(255, 80, 80)))
+                            .on_hover_text(format!(
+                                "Component '{}' is not loaded - right-click to relink",
+                                node_data.component_id
+                            ));
                     }
 
                     // Right-aligned buttons
@@ -302,7 +337,15 @@ impl<'a> SnarlViewer<SnarlNodeData> for CanvasViewer<'a> {
                     // T059: Show warning icon if component was updated
                     if node_data.needs_component_refresh {
                         ui.label(egui::RichText::new("⚠").color(egui::Color32::from_rgb(255, 180, 0)))
-                            .on_hover_text("Component updated - node may need refresh");
+                            .on_hover_text("Component updated - right-click to refresh");
+                    }
+
+                    if node_data.missing_component {
+                        ui.label(egui::RichText::new("⛔").color(egui::Color32::from_rgb(255, 80, 80)))
+                            .on_hover_text(format!(
+                                "Component '{}' is not loaded - right-click to relink",
+                                node_data.component_id
+                            ));
                     }
 
                     // Right-aligned buttons
@@ -343,7 +386,8 @@ impl<'a> SnarlViewer<SnarlNodeData> for CanvasViewer<'a> {
 
     fn has_body(&mut self, node: &SnarlNodeData) -> bool {
         // T045: Enable body for composite nodes to show footer with component names
-        node.is_composite
+        // Nodes with configuration parameters render their widgets in the body
+        node.is_composite || node.has_config
     }
 
     fn show_body(
@@ -354,6 +398,35 @@ impl<'a> SnarlViewer<SnarlNodeData> for CanvasViewer<'a> {
         ui: &mut egui::Ui,
         snarl: &mut Snarl<SnarlNodeData>,
     ) {
+        // Configuration parameter widgets
+        if let Some(node_data) = snarl.get_node(node) {
+            if node_data.has_config {
+                let node_uuid = node_data.uuid;
+                if let Some(graph_node) = self.graph.nodes.get_mut(&node_uuid) {
+                    let parameters = self
+                        .registry
+                        .get_by_id(&graph_node.component_id)
+                        .map(|spec| spec.parameters.as_slice())
+                        .unwrap_or_default();
+
+                    if parameters.is_empty() {
+                        ui.label(
+                            egui::RichText::new("(parameters unavailable)")
+                                .small()
+                                .color(egui::Color32::from_rgb(150, 150, 160)),
+                        );
+                    } else if parameters::render_parameters(
+                        ui,
+                        node_uuid,
+                        parameters,
+                        &mut graph_node.config,
+                    ) {
+                        graph_node.dirty = true;
+                    }
+                }
+            }
+        }
+
         // T045: Show footer for composite nodes with component names
         if let Some(node_data) = snarl.get_node(node) {
             if node_data.is_composite {
@@ -464,15 +537,74 @@ impl<'a> SnarlViewer<SnarlNodeData> for CanvasViewer<'a> {
 
     fn show_graph_menu(
         &mut self,
-        _pos: Pos2,
+        pos: Pos2,
         ui: &mut egui::Ui,
         _snarl: &mut Snarl<SnarlNodeData>,
     ) {
+        let selected: Vec<Uuid> = self
+            .graph
+            .nodes
+            .values()
+            .filter(|node| node.selected)
+            .map(|node| node.id)
+            .collect();
+
+        if let Some(annotation) = self.graph.annotation_at(pos).cloned() {
+            self.show_annotation_menu(&annotation, &selected, ui);
+            ui.separator();
+        }
+
+        if ui.button("📝 Add Note").clicked() {
+            let note = Annotation::note("New note".to_string(), pos);
+            *self.pending_annotation_edit = Some(note.id);
+            self.pending_commands.push(Command::AddAnnotation { annotation: note });
+            ui.close();
+        }
+        if !selected.is_empty() {
+            if ui.button("▭ Frame Selected Nodes").clicked() {
+                self.pending_commands.extend(
+                    Annotation::frame("Frame".to_string(), self.graph, &selected)
+                        .map(|annotation| Command::AddAnnotation { annotation }),
+                );
+                ui.close();
+            }
+            if ui.button("▣ Group Selected Nodes").clicked() {
+                self.pending_commands.extend(
+                    Annotation::group("Group".to_string(), self.graph, &selected)
+                        .map(|annotation| Command::AddAnnotation { annotation }),
+                );
+                ui.close();
+            }
+        }
+        ui.separator();
+
         ui.label("Right-click on nodes for options");
         ui.label("Drag from output → input to connect");
         ui.label("Use palette on left to add nodes");
     }
 
+    fn draw_background(
+        &mut self,
+        background: Option<&BackgroundPattern>,
+        viewport: &egui::Rect,
+        snarl_style: &SnarlStyle,
+        style: &egui::Style,
+        painter: &egui::Painter,
+        _snarl: &Snarl<SnarlNodeData>,
+    ) {
+        if let Some(background) = background {
+            background.draw(viewport, snarl_style, style, painter);
+        }
+
+        // Frames, groups and notes sit between the background and the nodes
+        *self.viewport = Some(*viewport);
+        for annotation in self.graph.annotations_back_to_front() {
+            if annotation.rect().intersects(*viewport) {
+                annotations::paint(painter, annotation, self.graph, style);
+            }
+        }
+    }
+
     fn has_node_menu(&mut self, _node: &SnarlNodeData) -> bool {
         true
     }
@@ -511,6 +643,34 @@ impl<'a> SnarlViewer<SnarlNodeData> for CanvasViewer<'a> {
                 ui.close();
             }
 
+            // Messages the component logged through host::log
+            if node_data.component_id.starts_with("user:")
+                && ui.button("📜 Show Logs").clicked()
+            {
+                if let Some(&uuid) = self.snarl_to_uuid.get(&node) {
+                    *self.pending_log_view = Some(uuid);
+                }
+                ui.close();
+            }
+
+            // Rebuild ports from the recompiled component, keeping matching ones
+            if node_data.needs_component_refresh && ui.button("⟳ Refresh Node").clicked() {
+                if let Some(&uuid) = self.snarl_to_uuid.get(&node) {
+                    *self.pending_refresh = Some(uuid);
+                }
+                ui.close();
+            }
+
+            // Point the node at a different component, keeping matching ports
+            if node_data.component_id.starts_with("user:")
+                && ui.button("🔗 Relink Component...").clicked()
+            {
+                if let Some(&uuid) = self.snarl_to_uuid.get(&node) {
+                    *self.pending_relink = Some(uuid);
+                }
+                ui.close();
+            }
+
             if ui.button("Delete Node").clicked() {
                 // Queue for deletion instead of deleting directly
                 // This allows the app to handle deletion through command history
@@ -732,6 +892,61 @@ impl<'a> SnarlViewer<SnarlNodeData> for CanvasViewer<'a> {
 }
 
 impl CanvasViewer<'_> {
+    /// Actions for the frame, group or note under the graph menu
+    fn show_annotation_menu(
+        &mut self,
+        annotation: &Annotation,
+        selected: &[Uuid],
+        ui: &mut egui::Ui,
+    ) {
+        ui.strong(format!("{}: {}", annotation.kind_name(), annotation.label()));
+
+        if ui.button("✏ Edit...").clicked() {
+            *self.pending_annotation_edit = Some(annotation.id);
+            ui.close();
+        }
+
+        let mut updated = None;
+        if let AnnotationKind::Group { collapsed } = annotation.kind {
+            let label = if collapsed { "▾ Expand Group" } else { "▸ Collapse Group" };
+            if ui.button(label).clicked() {
+                let mut new = annotation.clone();
+                new.kind = AnnotationKind::Group {
+                    collapsed: !collapsed,
+                };
+                updated = Some(new);
+            }
+        }
+        if !matches!(annotation.kind, AnnotationKind::Note { .. }) && !selected.is_empty() {
+            if ui.button("➕ Add Selected Nodes").clicked() {
+                let mut new = annotation.clone();
+                new.node_ids.extend(selected.iter().copied());
+                updated = Some(new);
+            }
+            if selected.iter().any(|id| annotation.node_ids.contains(id))
+                && ui.button("➖ Remove Selected Nodes").clicked()
+            {
+                let mut new = annotation.clone();
+                new.node_ids.retain(|id| !selected.contains(id));
+                updated = Some(new);
+            }
+        }
+        if let Some(new) = updated.filter(|new| new != annotation) {
+            self.pending_commands.push(Command::UpdateAnnotation {
+                old: annotation.clone(),
+                new,
+            });
+            ui.close();
+        }
+
+        if ui.button(format!("🗑 Delete {}", annotation.kind_name())).clicked() {
+            self.pending_commands.push(Command::RemoveAnnotation {
+                annotation: annotation.clone(),
+            });
+            ui.close();
+        }
+    }
+
     /// Get color for data type
     fn type_color(data_type: &DataType) -> Color32 {
         match data_type {
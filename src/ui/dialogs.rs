@@ -2,7 +2,9 @@
 //!
 //! This module contains various dialog windows for user interaction.
 
+use crate::graph::annotation::{Annotation, AnnotationKind};
 use crate::runtime::capabilities::{CapabilityGrant, CapabilitySet, RiskLevel};
+use crate::runtime::secrets::{SecretVault, SharedVault};
 use eframe::egui;
 
 /// Result of the unsaved changes dialog
@@ -344,6 +346,21 @@ impl PermissionDialog {
                         ui.label(format!("  🌐 {}", host));
                     }
                 }
+                CapabilitySet::KeyValue { base } => {
+                    ui.label("• Private key-value store for this node");
+                    if **base != CapabilitySet::None {
+                        ui.label(format!("• {}", base.description()));
+                    }
+                }
+                CapabilitySet::Secrets { names, base } => {
+                    ui.label("• Read secrets from the vault:");
+                    for name in names {
+                        ui.label(format!("  🔑 {}", name));
+                    }
+                    if **base != CapabilitySet::None {
+                        ui.label(format!("• {}", base.description()));
+                    }
+                }
                 CapabilitySet::Full => {
                     ui.colored_label(
                         egui::Color32::from_rgb(255, 100, 100),
@@ -631,6 +648,21 @@ impl PermissionsViewDialog {
                         ui.label(format!("  🌐 {}", host));
                     }
                 }
+                CapabilitySet::KeyValue { base } => {
+                    ui.label("• Private key-value store for this node");
+                    if **base != CapabilitySet::None {
+                        ui.label(format!("• {}", base.description()));
+                    }
+                }
+                CapabilitySet::Secrets { names, base } => {
+                    ui.label("• Read secrets from the vault:");
+                    for name in names {
+                        ui.label(format!("  🔑 {}", name));
+                    }
+                    if **base != CapabilitySet::None {
+                        ui.label(format!("• {}", base.description()));
+                    }
+                }
                 CapabilitySet::Full => {
                     ui.colored_label(
                         egui::Color32::from_rgb(255, 100, 100),
@@ -965,3 +997,941 @@ impl Default for GraphMetadataDialog {
         Self::new()
     }
 }
+
+/// Dialog for editing a frame, group or note
+pub struct AnnotationDialog {
+    /// Annotation as it was when the dialog opened
+    original: Option<Annotation>,
+    /// Edited title (frames and groups)
+    title: String,
+    /// Edited markdown text (notes)
+    text: String,
+    /// Edited colour
+    color: [u8; 3],
+}
+
+impl AnnotationDialog {
+    /// Create a closed dialog
+    pub fn new() -> Self {
+        Self {
+            original: None,
+            title: String::new(),
+            text: String::new(),
+            color: [0; 3],
+        }
+    }
+
+    /// Open the dialog for an annotation
+    pub fn open(&mut self, annotation: Annotation) {
+        self.title = annotation.title.clone();
+        self.text = match &annotation.kind {
+            AnnotationKind::Note { text } => text.clone(),
+            _ => String::new(),
+        };
+        self.color = annotation.color;
+        self.original = Some(annotation);
+    }
+
+    /// Show the dialog
+    ///
+    /// Returns the annotation before and after editing when the user saves.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(Annotation, Annotation)> {
+        let original = self.original.as_ref()?;
+        let is_note = matches!(original.kind, AnnotationKind::Note { .. });
+        let mut saved = false;
+        let mut close_dialog = false;
+
+        egui::Window::new(format!("Edit {}", original.kind_name()))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                if is_note {
+                    ui.label("Text (markdown):");
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.text)
+                            .desired_rows(8)
+                            .desired_width(f32::INFINITY),
+                    );
+                    egui::CollapsingHeader::new("Preview")
+                        .default_open(true)
+                        .show(ui, |ui| crate::ui::canvas::show_markdown(ui, &self.text));
+                } else {
+                    ui.label("Title:");
+                    ui.text_edit_singleline(&mut self.title);
+                    ui.label(format!("Contains {} node(s)", original.node_ids.len()));
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Color:");
+                    ui.color_edit_button_srgb(&mut self.color);
+                });
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("💾 Save").clicked() {
+                        saved = true;
+                        close_dialog = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close_dialog = true;
+                    }
+                });
+            });
+
+        if !close_dialog {
+            return None;
+        }
+        let original = self.original.take()?;
+        if !saved {
+            return None;
+        }
+        let mut edited = original.clone();
+        edited.title = std::mem::take(&mut self.title);
+        edited.color = self.color;
+        if let AnnotationKind::Note { text } = &mut edited.kind {
+            *text = std::mem::take(&mut self.text);
+        }
+        Some((original, edited))
+    }
+}
+
+impl Default for AnnotationDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Dialog for managing the local secret vault
+///
+/// Only secret names are ever displayed; values can be added or replaced but
+/// not read back.
+pub struct SecretsDialog {
+    /// Whether the dialog is open
+    is_open: bool,
+    /// Passphrase used to create or unlock the vault
+    passphrase: String,
+    /// Passphrase confirmation when creating the vault
+    confirm_passphrase: String,
+    /// Name of the secret being added
+    new_name: String,
+    /// Value of the secret being added
+    new_value: String,
+    /// Last error message
+    error: Option<String>,
+}
+
+impl SecretsDialog {
+    /// Create a new secrets dialog
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            passphrase: String::new(),
+            confirm_passphrase: String::new(),
+            new_name: String::new(),
+            new_value: String::new(),
+            error: None,
+        }
+    }
+
+    /// Open the dialog
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.error = None;
+    }
+
+    /// Check if the dialog is open
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Show the dialog
+    pub fn show(&mut self, ctx: &egui::Context, vault: &SharedVault) {
+        if !self.is_open {
+            return;
+        }
+
+        let mut close_dialog = false;
+
+        egui::Window::new("🔑 Secrets")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let path = SecretVault::default_path();
+                let unlocked = vault.read().map(|v| v.is_some()).unwrap_or(false);
+
+                if unlocked {
+                    self.show_unlocked(ui, vault);
+                } else if SecretVault::exists(&path) {
+                    self.show_unlock(ui, vault, &path);
+                } else {
+                    self.show_create(ui, vault, &path);
+                }
+
+                if let Some(error) = &self.error {
+                    ui.add_space(5.0);
+                    ui.colored_label(egui::Color32::from_rgb(230, 80, 80), error);
+                }
+
+                ui.add_space(10.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Close").clicked() {
+                        close_dialog = true;
+                    }
+                });
+            });
+
+        if close_dialog {
+            self.is_open = false;
+            self.passphrase.clear();
+            self.confirm_passphrase.clear();
+            self.new_value.clear();
+        }
+    }
+
+    fn show_create(&mut self, ui: &mut egui::Ui, vault: &SharedVault, path: &std::path::Path) {
+        ui.label("No secret vault exists yet. Choose a passphrase to create one.");
+        ui.add_space(5.0);
+        ui.add(
+            egui::TextEdit::singleline(&mut self.passphrase)
+                .password(true)
+                .hint_text("Passphrase"),
+        );
+        ui.add(
+            egui::TextEdit::singleline(&mut self.confirm_passphrase)
+                .password(true)
+                .hint_text("Confirm passphrase"),
+        );
+
+        if ui.button("Create Vault").clicked() {
+            if self.passphrase.is_empty() {
+                self.error = Some("Passphrase must not be empty".to_string());
+            } else if self.passphrase != self.confirm_passphrase {
+                self.error = Some("Passphrases do not match".to_string());
+            } else {
+                match SecretVault::create(path, &self.passphrase) {
+                    Ok(created) => {
+                        if let Ok(mut guard) = vault.write() {
+                            *guard = Some(created);
+                        }
+                        self.passphrase.clear();
+                        self.confirm_passphrase.clear();
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(e.to_string()),
+                }
+            }
+        }
+    }
+
+    fn show_unlock(&mut self, ui: &mut egui::Ui, vault: &SharedVault, path: &std::path::Path) {
+        ui.label("The secret vault is locked.");
+        ui.add_space(5.0);
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut self.passphrase)
+                .password(true)
+                .hint_text("Passphrase"),
+        );
+        let submitted =
+            response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+        if ui.button("🔓 Unlock").clicked() || submitted {
+            match SecretVault::unlock(path, &self.passphrase) {
+                Ok(unlocked) => {
+                    if let Ok(mut guard) = vault.write() {
+                        *guard = Some(unlocked);
+                    }
+                    self.passphrase.clear();
+                    self.error = None;
+                }
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+    }
+
+    fn show_unlocked(&mut self, ui: &mut egui::Ui, vault: &SharedVault) {
+        let Ok(mut guard) = vault.write() else {
+            return;
+        };
+        let Some(unlocked) = guard.as_mut() else {
+            return;
+        };
+
+        let names = unlocked.names();
+        if names.is_empty() {
+            ui.label("(no secrets stored)");
+        } else {
+            let mut to_remove = None;
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for name in &names {
+                        ui.horizontal(|ui| {
+                            ui.monospace(name);
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                                        to_remove = Some(name.clone());
+                                    }
+                                },
+                            );
+                        });
+                    }
+                });
+            if let Some(name) = to_remove {
+                if let Err(e) = unlocked.remove(&name) {
+                    self.error = Some(e.to_string());
+                }
+            }
+        }
+
+        ui.add_space(10.0);
+        ui.label("Add or replace a secret:");
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_name)
+                    .hint_text("name")
+                    .desired_width(140.0),
+            );
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_value)
+                    .password(true)
+                    .hint_text("value")
+                    .desired_width(180.0),
+            );
+            if ui.button("Save").clicked() {
+                match unlocked.set(self.new_name.trim(), self.new_value.clone()) {
+                    Ok(()) => {
+                        self.new_name.clear();
+                        self.new_value.clear();
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(e.to_string()),
+                }
+            }
+        });
+        ui.label(
+            egui::RichText::new("Components request a secret with the capability secret:<name>")
+                .small()
+                .weak(),
+        );
+
+        ui.add_space(5.0);
+        if ui.button("🔒 Lock").clicked() {
+            *guard = None;
+        }
+    }
+}
+
+impl Default for SecretsDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Dialog for saving the selection as a reusable graph function
+pub struct SaveFunctionDialog {
+    /// Whether the dialog is open
+    is_open: bool,
+    /// Function name
+    name: String,
+    /// Function description
+    description: String,
+    /// Number of selected nodes being saved
+    node_count: usize,
+}
+
+impl SaveFunctionDialog {
+    /// Create a new save function dialog
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            name: String::new(),
+            description: String::new(),
+            node_count: 0,
+        }
+    }
+
+    /// Open the dialog for a selection of `node_count` nodes
+    pub fn open(&mut self, node_count: usize) {
+        self.is_open = true;
+        self.node_count = node_count;
+    }
+
+    /// Check if the dialog is open
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Show the dialog, returning (name, description) when the user saves
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(String, String)> {
+        if !self.is_open {
+            return None;
+        }
+
+        let mut result = None;
+        let mut close_dialog = false;
+
+        egui::Window::new("📚 Save as Function")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Save {} selected node(s) to the function library. Saving over an existing name publishes a new revision to every graph that uses it.",
+                    self.node_count
+                ));
+                ui.add_space(10.0);
+
+                ui.label("Name:");
+                ui.text_edit_singleline(&mut self.name);
+                let name = self.name.trim().to_string();
+                let name_error = crate::graph::function::validate_name(&name).err();
+                if let (Some(error), false) = (&name_error, name.is_empty()) {
+                    ui.colored_label(egui::Color32::from_rgb(220, 80, 80), error.to_string());
+                }
+                ui.add_space(10.0);
+
+                ui.label("Description:");
+                ui.text_edit_multiline(&mut self.description);
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(name_error.is_none(), egui::Button::new("💾 Save"))
+                        .clicked()
+                    {
+                        result = Some((name, self.description.trim().to_string()));
+                        close_dialog = true;
+                    }
+
+                    if ui.button("Cancel").clicked() {
+                        close_dialog = true;
+                    }
+                });
+            });
+
+        if close_dialog {
+            self.is_open = false;
+            self.name.clear();
+            self.description.clear();
+        }
+
+        result
+    }
+}
+
+impl Default for SaveFunctionDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Dialog listing what was migrated when an older graph file was opened
+pub struct MigrationReportDialog {
+    /// Whether the dialog is open
+    is_open: bool,
+    /// File the report belongs to
+    file_name: String,
+    /// Report to show
+    report: crate::graph::migration::MigrationReport,
+}
+
+impl MigrationReportDialog {
+    /// Create a new migration report dialog
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            file_name: String::new(),
+            report: Default::default(),
+        }
+    }
+
+    /// Open the dialog for a loaded file
+    pub fn open(&mut self, file_name: String, report: crate::graph::migration::MigrationReport) {
+        self.is_open = true;
+        self.file_name = file_name;
+        self.report = report;
+    }
+
+    /// Check if the dialog is open
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Show the dialog
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if !self.is_open {
+            return;
+        }
+
+        let mut close_dialog = false;
+
+        egui::Window::new("🔄 Graph File Upgraded")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(450.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(&self.file_name).strong());
+                ui.add_space(10.0);
+
+                for line in self.report.summary().lines() {
+                    if line.starts_with('⚠') {
+                        ui.colored_label(egui::Color32::from_rgb(230, 180, 60), line);
+                    } else {
+                        ui.label(line);
+                    }
+                }
+                ui.add_space(10.0);
+
+                if self.report.migrated() {
+                    ui.label(
+                        egui::RichText::new(
+                            "The file on disk is unchanged until you save. Saving writes the \
+                             current format, which older versions of WasmFlow cannot open.",
+                        )
+                        .weak(),
+                    );
+                    ui.add_space(10.0);
+                }
+
+                if ui.button("OK").clicked() {
+                    close_dialog = true;
+                }
+            });
+
+        if close_dialog {
+            self.is_open = false;
+        }
+    }
+}
+
+impl Default for MigrationReportDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Dialog asking whether to open a graph file whose checksum does not match
+pub struct ChecksumMismatchDialog {
+    /// File waiting for the user's decision
+    path: Option<std::path::PathBuf>,
+}
+
+impl ChecksumMismatchDialog {
+    /// Create a new checksum mismatch dialog
+    pub fn new() -> Self {
+        Self { path: None }
+    }
+
+    /// Open the dialog for a file that failed checksum validation
+    pub fn open(&mut self, path: std::path::PathBuf) {
+        self.path = Some(path);
+    }
+
+    /// Check if the dialog is open
+    pub fn is_open(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Show the dialog; returns the file to load if the user chose to open it anyway
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<std::path::PathBuf> {
+        let path = self.path.as_ref()?;
+
+        let mut open_anyway = false;
+        let mut close_dialog = false;
+
+        egui::Window::new("⚠ File May Be Corrupted")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(450.0)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(path.display().to_string()).strong());
+                ui.add_space(10.0);
+                ui.label(
+                    "The file's checksum does not match its contents. It may have been \
+                     damaged or edited outside WasmFlow, and some values could be wrong.",
+                );
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Open Anyway").clicked() {
+                        open_anyway = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close_dialog = true;
+                    }
+                });
+            });
+
+        if open_anyway {
+            return self.path.take();
+        }
+        if close_dialog {
+            self.path = None;
+        }
+        None
+    }
+}
+
+impl Default for ChecksumMismatchDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where the user wants placeholder nodes relinked to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelinkTarget {
+    /// Load a `.wasm` file and use the component it provides
+    File,
+    /// Use a component that is already loaded
+    Component(String),
+    /// Keep running the stored copy of the binary with this hash
+    Pinned(String),
+}
+
+/// Request from the missing components dialog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelinkRequest {
+    /// Component the nodes currently reference
+    pub component_id: String,
+    pub node_ids: Vec<uuid::Uuid>,
+    pub target: RelinkTarget,
+}
+
+/// Nodes that are relinked together
+struct RelinkEntry {
+    component_id: String,
+    node_ids: Vec<uuid::Uuid>,
+    node_names: Vec<String>,
+    /// Loaded component picked in the combo box
+    target_id: Option<String>,
+}
+
+/// Dialog listing components a graph uses that are not loaded, with relinking
+pub struct MissingComponentsDialog {
+    /// Whether the dialog is open
+    is_open: bool,
+    /// Relinking a single node from its context menu
+    single_node: bool,
+    entries: Vec<RelinkEntry>,
+}
+
+impl MissingComponentsDialog {
+    /// Create a new missing components dialog
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            single_node: false,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Open the dialog for the graph's missing components (closes it if none)
+    pub fn open(
+        &mut self,
+        graph: &crate::graph::NodeGraph,
+        missing: Vec<crate::graph::placeholder::MissingComponent>,
+    ) {
+        self.is_open = !missing.is_empty();
+        self.single_node = false;
+        self.entries = missing
+            .into_iter()
+            .map(|missing| RelinkEntry {
+                node_names: missing
+                    .node_ids
+                    .iter()
+                    .filter_map(|id| graph.nodes.get(id))
+                    .map(|node| node.display_name.clone())
+                    .collect(),
+                component_id: missing.component_id,
+                node_ids: missing.node_ids,
+                target_id: None,
+            })
+            .collect();
+    }
+
+    /// Open the dialog to relink one node, missing or not
+    pub fn open_for_node(&mut self, node: &crate::graph::GraphNode) {
+        self.is_open = true;
+        self.single_node = true;
+        self.entries = vec![RelinkEntry {
+            component_id: node.component_id.clone(),
+            node_ids: vec![node.id],
+            node_names: vec![node.display_name.clone()],
+            target_id: None,
+        }];
+    }
+
+    /// Check if the dialog is open
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Whether the dialog was opened for a single node
+    pub fn is_single_node(&self) -> bool {
+        self.single_node
+    }
+
+    /// Close the dialog
+    pub fn close(&mut self) {
+        self.is_open = false;
+        self.entries.clear();
+    }
+
+    /// Show the dialog, returning a relink request when the user makes one
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        registry: &crate::graph::node::ComponentRegistry,
+    ) -> Option<RelinkRequest> {
+        if !self.is_open {
+            return None;
+        }
+
+        let mut available: Vec<&str> =
+            registry.list_all().into_iter().map(|spec| spec.id.as_str()).collect();
+        available.sort_unstable();
+
+        let mut result = None;
+        let mut close_dialog = false;
+        let title = if self.single_node {
+            "🔗 Relink Component"
+        } else {
+            "⛔ Missing Components"
+        };
+
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(true)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                if !self.single_node {
+                    ui.label(
+                        "These components are not in components/bin. Their nodes are kept as \
+                         placeholders with their connections, but cannot run until relinked.",
+                    );
+                }
+                ui.label(
+                    egui::RichText::new(
+                        "Ports are matched by name and type; connections to ports without a \
+                         match are removed.",
+                    )
+                    .weak(),
+                );
+                ui.add_space(10.0);
+
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for (index, entry) in self.entries.iter_mut().enumerate() {
+                        ui.group(|ui| {
+                            ui.label(egui::RichText::new(&entry.component_id).monospace().strong());
+                            ui.label(format!("Used by: {}", entry.node_names.join(", ")));
+
+                            ui.horizontal(|ui| {
+                                if ui.button("📂 Relink to .wasm...").clicked() {
+                                    result = Some(RelinkRequest {
+                                        component_id: entry.component_id.clone(),
+                                        node_ids: entry.node_ids.clone(),
+                                        target: RelinkTarget::File,
+                                    });
+                                }
+                            });
+
+                            ui.horizontal(|ui| {
+                                let selected =
+                                    entry.target_id.as_deref().unwrap_or("Loaded component...");
+                                egui::ComboBox::from_id_salt(("relink_target", index))
+                                    .selected_text(selected)
+                                    .width(260.0)
+                                    .show_ui(ui, |ui| {
+                                        for id in &available {
+                                            ui.selectable_value(
+                                                &mut entry.target_id,
+                                                Some(id.to_string()),
+                                                *id,
+                                            );
+                                        }
+                                    });
+                                let relink = egui::Button::new("🔗 Relink");
+                                if ui.add_enabled(entry.target_id.is_some(), relink).clicked() {
+                                    if let Some(target_id) = entry.target_id.clone() {
+                                        result = Some(RelinkRequest {
+                                            component_id: entry.component_id.clone(),
+                                            node_ids: entry.node_ids.clone(),
+                                            target: RelinkTarget::Component(target_id),
+                                        });
+                                    }
+                                }
+                            });
+                        });
+                        ui.add_space(4.0);
+                    }
+                });
+                ui.add_space(10.0);
+
+                if ui.button("Close").clicked() {
+                    close_dialog = true;
+                }
+            });
+
+        if close_dialog {
+            self.close();
+        }
+
+        result
+    }
+}
+
+impl Default for MissingComponentsDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Nodes of one component whose binary changed since they were created
+struct ChangeEntry {
+    change: crate::graph::pin::ComponentChange,
+    node_names: Vec<String>,
+}
+
+/// Dialog listing nodes whose component binary was replaced
+pub struct ComponentChangesDialog {
+    /// Whether the dialog is open
+    is_open: bool,
+    entries: Vec<ChangeEntry>,
+}
+
+impl ComponentChangesDialog {
+    /// Create a new component changes dialog
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Open the dialog for the graph's changed components (closes it if none)
+    pub fn open(
+        &mut self,
+        graph: &crate::graph::NodeGraph,
+        changes: Vec<crate::graph::pin::ComponentChange>,
+    ) {
+        self.is_open = !changes.is_empty();
+        self.entries = changes
+            .into_iter()
+            .map(|change| ChangeEntry {
+                node_names: change
+                    .node_ids
+                    .iter()
+                    .filter_map(|id| graph.nodes.get(id))
+                    .map(|node| node.display_name.clone())
+                    .collect(),
+                change,
+            })
+            .collect();
+    }
+
+    /// Check if the dialog is open
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Show the dialog, returning a request to upgrade or pin nodes
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<RelinkRequest> {
+        if !self.is_open {
+            return None;
+        }
+
+        let mut result = None;
+        let mut close_dialog = false;
+        let short = |hash: &str| hash.chars().take(8).collect::<String>();
+
+        egui::Window::new("📌 Components Changed")
+            .collapsible(false)
+            .resizable(true)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    "These components were replaced since their nodes were created. Upgrade \
+                     the nodes to the loaded binary, or keep running the copy they were \
+                     built with.",
+                );
+                ui.add_space(10.0);
+
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for entry in &self.entries {
+                        let change = &entry.change;
+                        ui.group(|ui| {
+                            let id = egui::RichText::new(&change.component_id).monospace();
+                            ui.label(id.strong());
+                            ui.label(format!("Used by: {}", entry.node_names.join(", ")));
+                            ui.label(format!(
+                                "Created with v{} ({}), loaded now: v{} ({})",
+                                change.pinned.version,
+                                short(&change.pinned.content_hash),
+                                change.current.version,
+                                short(&change.current.content_hash)
+                            ));
+
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .button("⬆ Upgrade")
+                                    .on_hover_text("Rebuild the nodes from the loaded binary")
+                                    .clicked()
+                                {
+                                    result = Some(RelinkRequest {
+                                        component_id: change.component_id.clone(),
+                                        node_ids: change.node_ids.clone(),
+                                        target: RelinkTarget::Component(
+                                            change.component_id.clone(),
+                                        ),
+                                    });
+                                }
+                                if ui
+                                    .button("📌 Keep pinned")
+                                    .on_hover_text("Run the stored copy of the original binary")
+                                    .clicked()
+                                {
+                                    result = Some(RelinkRequest {
+                                        component_id: change.component_id.clone(),
+                                        node_ids: change.node_ids.clone(),
+                                        target: RelinkTarget::Pinned(
+                                            change.pinned.content_hash.clone(),
+                                        ),
+                                    });
+                                }
+                            });
+                        });
+                        ui.add_space(4.0);
+                    }
+                });
+                ui.add_space(10.0);
+
+                if ui.button("Decide later").clicked() {
+                    close_dialog = true;
+                }
+            });
+
+        if close_dialog {
+            self.is_open = false;
+        }
+
+        result
+    }
+}
+
+impl Default for ComponentChangesDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,274 @@
+//! Side-by-side graph comparison
+//!
+//! Shows a graph loaded from another file next to the current graph, with
+//! nodes and connections coloured by how they changed, followed by the list
+//! of changes from `NodeGraph::diff`. The diff is recomputed whenever the
+//! current graph's undo history moves.
+
+use crate::graph::connection::Connection;
+use crate::graph::diff::GraphDiff;
+use crate::graph::node::GraphNode;
+use crate::graph::NodeGraph;
+use eframe::egui;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Approximate size of a node on the canvas, in graph coordinates
+const NODE_SIZE: egui::Vec2 = egui::vec2(140.0, 60.0);
+
+/// Height of each graph preview
+const PREVIEW_HEIGHT: f32 = 300.0;
+
+/// How a node or connection differs between the two graphs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Same,
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Status {
+    fn color(self) -> egui::Color32 {
+        match self {
+            Status::Same => egui::Color32::from_gray(120),
+            Status::Added => egui::Color32::from_rgb(80, 200, 120),
+            Status::Removed => egui::Color32::from_rgb(230, 90, 90),
+            Status::Changed => egui::Color32::from_rgb(230, 180, 60),
+        }
+    }
+}
+
+/// Window comparing the current graph with another version of it
+pub struct DiffView {
+    /// File name and graph the current graph is compared against
+    other: Option<(String, NodeGraph)>,
+    /// Differences from the other graph to the current one, with the
+    /// history revision they were computed at
+    cached: Option<(u64, GraphDiff)>,
+}
+
+impl DiffView {
+    /// Create a closed view
+    pub fn new() -> Self {
+        Self {
+            other: None,
+            cached: None,
+        }
+    }
+
+    /// Compare the current graph against `other`
+    pub fn open(&mut self, file_name: String, other: NodeGraph) {
+        self.other = Some((file_name, other));
+        self.cached = None;
+    }
+
+    /// Whether the view is shown
+    pub fn is_open(&self) -> bool {
+        self.other.is_some()
+    }
+
+    /// Render the view
+    ///
+    /// Returns the node to jump to when the user clicks one in the current
+    /// graph or in the change list.
+    pub fn show(&mut self, ctx: &egui::Context, graph: &NodeGraph, revision: u64) -> Option<Uuid> {
+        let (file_name, other) = self.other.as_ref()?;
+        if self.cached.as_ref().map(|(rev, _)| *rev) != Some(revision) {
+            self.cached = Some((revision, other.diff(graph)));
+        }
+        let (_, diff) = self.cached.as_ref()?;
+
+        let removed_nodes: HashSet<Uuid> = diff.removed_nodes.iter().map(|n| n.node_id).collect();
+        let added_nodes: HashSet<Uuid> = diff.added_nodes.iter().map(|n| n.node_id).collect();
+        let changed_nodes: HashSet<Uuid> = diff.changed_nodes.iter().map(|n| n.node_id).collect();
+        let removed_conns: Vec<&Connection> =
+            diff.removed_connections.iter().map(|c| &c.connection).collect();
+        let added_conns: Vec<&Connection> =
+            diff.added_connections.iter().map(|c| &c.connection).collect();
+        let node_status = |node_id: Uuid| {
+            if removed_nodes.contains(&node_id) {
+                Status::Removed
+            } else if added_nodes.contains(&node_id) {
+                Status::Added
+            } else if changed_nodes.contains(&node_id) {
+                Status::Changed
+            } else {
+                Status::Same
+            }
+        };
+
+        let mut open = true;
+        let mut jump_to = None;
+        egui::Window::new("🔀 Compare Graphs")
+            .open(&mut open)
+            .default_size([900.0, 620.0])
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} change(s) from {} (left) to the current graph (right)",
+                    diff.change_count(),
+                    file_name
+                ));
+                ui.horizontal(|ui| {
+                    for (status, label) in [
+                        (Status::Added, "added"),
+                        (Status::Removed, "removed"),
+                        (Status::Changed, "changed"),
+                    ] {
+                        ui.colored_label(status.color(), format!("■ {}", label));
+                    }
+                });
+                ui.separator();
+
+                ui.columns(2, |columns| {
+                    columns[0].strong(file_name);
+                    paint_graph(&mut columns[0], other, &node_status, |conn| {
+                        if removed_conns.iter().any(|c| same_endpoints(c, conn)) {
+                            Status::Removed
+                        } else {
+                            Status::Same
+                        }
+                    });
+                    columns[1].strong("Current graph");
+                    let clicked = paint_graph(&mut columns[1], graph, &node_status, |conn| {
+                        if added_conns.iter().any(|c| same_endpoints(c, conn)) {
+                            Status::Added
+                        } else {
+                            Status::Same
+                        }
+                    });
+                    if clicked.is_some() {
+                        jump_to = clicked;
+                    }
+                });
+                ui.separator();
+
+                if diff.is_empty() {
+                    ui.label("✅ The graphs are equivalent");
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        let text = diff.to_string();
+                        for line in text.lines() {
+                            let status = match line.chars().next() {
+                                Some('+') => Status::Added,
+                                Some('-') => Status::Removed,
+                                _ => Status::Changed,
+                            };
+                            ui.colored_label(status.color(), line);
+                        }
+                        ui.add_space(4.0);
+                        for node in &diff.changed_nodes {
+                            if ui
+                                .small_button(format!("Jump to \"{}\"", node.display_name))
+                                .clicked()
+                            {
+                                jump_to = Some(node.node_id);
+                            }
+                        }
+                    });
+            });
+
+        if !open {
+            self.other = None;
+            self.cached = None;
+        }
+        jump_to.filter(|node_id| graph.nodes.contains_key(node_id))
+    }
+}
+
+impl Default for DiffView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn same_endpoints(a: &Connection, b: &Connection) -> bool {
+    a.from_node == b.from_node
+        && a.from_port == b.from_port
+        && a.to_node == b.to_node
+        && a.to_port == b.to_port
+}
+
+/// Draw a scaled-down, read-only picture of a graph
+///
+/// Returns the node that was clicked, if any.
+fn paint_graph(
+    ui: &mut egui::Ui,
+    graph: &NodeGraph,
+    node_status: &dyn Fn(Uuid) -> Status,
+    conn_status: impl Fn(&Connection) -> Status,
+) -> Option<Uuid> {
+    let size = egui::vec2(ui.available_width(), PREVIEW_HEIGHT);
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::click());
+    let rect = response.rect;
+    painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+    if graph.nodes.is_empty() {
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            "Empty graph",
+            egui::FontId::proportional(13.0),
+            ui.visuals().weak_text_color(),
+        );
+        return None;
+    }
+
+    // Fit every node into the preview, never enlarging
+    let bounds = graph
+        .nodes
+        .values()
+        .map(|node| egui::Rect::from_min_size(node.position, NODE_SIZE))
+        .fold(egui::Rect::NOTHING, |bounds, node_rect| bounds.union(node_rect));
+    let inner = rect.shrink(12.0);
+    let scale = (inner.width() / bounds.width())
+        .min(inner.height() / bounds.height())
+        .min(1.0);
+    let node_rect = |node: &GraphNode| {
+        let min = inner.center() + (node.position - bounds.center()) * scale;
+        egui::Rect::from_min_size(min, NODE_SIZE * scale)
+    };
+
+    for conn in &graph.connections {
+        let (Some(from), Some(to)) =
+            (graph.nodes.get(&conn.from_node), graph.nodes.get(&conn.to_node))
+        else {
+            continue;
+        };
+        let status = conn_status(conn);
+        let width = if status == Status::Same { 1.0 } else { 2.5 };
+        painter.line_segment(
+            [node_rect(from).right_center(), node_rect(to).left_center()],
+            egui::Stroke::new(width, status.color()),
+        );
+    }
+
+    let clicked_at = response.interact_pointer_pos().filter(|_| response.clicked());
+    let mut clicked = None;
+    for node in graph.nodes.values() {
+        let node_rect = node_rect(node);
+        let color = node_status(node.id).color();
+        painter.rect_filled(node_rect, 3.0, color.gamma_multiply(0.3));
+        painter.add(egui::Shape::rect_stroke(
+            node_rect,
+            3.0,
+            egui::Stroke::new(1.5, color),
+            egui::epaint::StrokeKind::Middle,
+        ));
+        painter.with_clip_rect(node_rect.intersect(rect)).text(
+            node_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            &node.display_name,
+            egui::FontId::proportional((12.0 * scale).clamp(8.0, 13.0)),
+            ui.visuals().text_color(),
+        );
+        if clicked_at.is_some_and(|pos| node_rect.contains(pos)) {
+            clicked = Some(node.id);
+        }
+    }
+    response.on_hover_text("Click a node to select it on the canvas");
+    clicked
+}
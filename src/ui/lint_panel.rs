@@ -0,0 +1,135 @@
+//! Lint panel
+//!
+//! Lists `NodeGraph::lint` findings for the main graph. The report is cached
+//! per undo-history revision, so it refreshes after every recorded edit.
+
+use crate::graph::lint::{LintReport, LintSeverity};
+use crate::graph::node::ComponentRegistry;
+use crate::graph::NodeGraph;
+use eframe::egui;
+use uuid::Uuid;
+
+/// Right-hand panel listing lint findings
+pub struct LintPanel {
+    /// Whether the panel is shown
+    is_open: bool,
+    /// Findings and the history revision they were computed at
+    cached: Option<(u64, LintReport)>,
+}
+
+impl LintPanel {
+    /// Create a closed panel
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            cached: None,
+        }
+    }
+
+    /// Whether the panel is shown
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Show or hide the panel
+    pub fn toggle(&mut self) {
+        self.is_open = !self.is_open;
+        self.cached = None;
+    }
+
+    /// Re-lint on the next frame (e.g. after components were reloaded)
+    pub fn invalidate(&mut self) {
+        self.cached = None;
+    }
+
+    /// Render the panel (call before the central panel)
+    ///
+    /// Returns the node to jump to when the user asks for one.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        graph: &NodeGraph,
+        registry: &ComponentRegistry,
+        revision: u64,
+    ) -> Option<Uuid> {
+        if !self.is_open {
+            return None;
+        }
+        if self.cached.as_ref().map(|(rev, _)| *rev) != Some(revision) {
+            self.cached = Some((revision, graph.lint(Some(registry))));
+        }
+
+        let Some((_, report)) = &self.cached else {
+            return None;
+        };
+        let warnings = report.warning_count();
+
+        let mut jump_to = None;
+        let mut close = false;
+        let mut relint = false;
+        egui::SidePanel::right("lint_panel")
+            .resizable(true)
+            .default_width(340.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong("🔎 Lint");
+                    ui.separator();
+                    ui.label(format!(
+                        "{} warning(s), {} info",
+                        warnings,
+                        report.findings.len() - warnings
+                    ));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        close = ui.button("✖").on_hover_text("Close").clicked();
+                        relint = ui.button("⟳").on_hover_text("Lint again").clicked();
+                    });
+                });
+                ui.separator();
+
+                if report.is_clean() {
+                    ui.label("✅ No problems found");
+                    return;
+                }
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for finding in &report.findings {
+                            let (icon, color) = match finding.severity() {
+                                LintSeverity::Warning => ("⚠", egui::Color32::from_rgb(230, 180, 60)),
+                                LintSeverity::Info => ("ℹ", egui::Color32::from_rgb(100, 200, 255)),
+                            };
+                            ui.horizontal_wrapped(|ui| {
+                                ui.colored_label(color, icon)
+                                    .on_hover_text(finding.rule.name());
+                                ui.label(&finding.message);
+                                if let Some(node_id) = finding.node_id {
+                                    if ui
+                                        .small_button("Jump to node")
+                                        .on_hover_text("Select this node on the canvas")
+                                        .clicked()
+                                    {
+                                        jump_to = Some(node_id);
+                                    }
+                                }
+                            });
+                            ui.add_space(4.0);
+                        }
+                    });
+            });
+
+        if close {
+            self.is_open = false;
+        }
+        if relint {
+            self.cached = None;
+        }
+        jump_to
+    }
+}
+
+impl Default for LintPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,205 @@
+//! Log console panel
+//!
+//! Shows messages components logged through `host::log`, filtered by node and
+//! level. The panel docks at the bottom or on the right of the window.
+
+use crate::graph::NodeGraph;
+use crate::runtime::node_log::{LogLevel, NodeLogs};
+use eframe::egui;
+use uuid::Uuid;
+
+/// Where the console panel is docked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogDock {
+    Bottom,
+    Right,
+}
+
+/// Dockable log console
+pub struct LogConsole {
+    /// Whether the panel is shown
+    is_open: bool,
+    /// Panel placement
+    dock: LogDock,
+    /// Only show this node's entries
+    node_filter: Option<Uuid>,
+    /// Least severe level shown
+    max_level: LogLevel,
+    /// Case-insensitive message filter
+    search: String,
+}
+
+impl LogConsole {
+    /// Create a closed console showing info and above
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            dock: LogDock::Bottom,
+            node_filter: None,
+            max_level: LogLevel::Info,
+            search: String::new(),
+        }
+    }
+
+    /// Whether the panel is shown
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Show or hide the panel
+    pub fn toggle(&mut self) {
+        self.is_open = !self.is_open;
+    }
+
+    /// Open the panel filtered to one node
+    pub fn show_node(&mut self, node_id: Uuid) {
+        self.is_open = true;
+        self.node_filter = Some(node_id);
+    }
+
+    /// Render the panel (call before the central panel)
+    pub fn show(&mut self, ctx: &egui::Context, logs: &NodeLogs, graph: &NodeGraph) {
+        if !self.is_open {
+            return;
+        }
+
+        match self.dock {
+            LogDock::Bottom => {
+                egui::TopBottomPanel::bottom("log_console")
+                    .resizable(true)
+                    .default_height(200.0)
+                    .show(ctx, |ui| self.contents(ui, logs, graph));
+            }
+            LogDock::Right => {
+                egui::SidePanel::right("log_console")
+                    .resizable(true)
+                    .default_width(380.0)
+                    .show(ctx, |ui| self.contents(ui, logs, graph));
+            }
+        }
+    }
+
+    fn contents(&mut self, ui: &mut egui::Ui, logs: &NodeLogs, graph: &NodeGraph) {
+        ui.horizontal_wrapped(|ui| {
+            ui.strong("📜 Logs");
+            ui.separator();
+
+            // Nodes in the graph plus any that logged before being deleted
+            let mut nodes: Vec<(Uuid, String)> = graph
+                .nodes
+                .values()
+                .map(|node| (node.id, node.display_name.clone()))
+                .collect();
+            for node_id in logs.nodes() {
+                if !graph.nodes.contains_key(&node_id) {
+                    nodes.push((node_id, Self::node_label(graph, node_id)));
+                }
+            }
+            nodes.sort_by(|a, b| a.1.cmp(&b.1));
+
+            let selected = match self.node_filter {
+                Some(node_id) => Self::node_label(graph, node_id),
+                None => "All nodes".to_string(),
+            };
+            egui::ComboBox::from_id_salt("log_console_node")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.node_filter, None, "All nodes");
+                    for (node_id, name) in nodes {
+                        ui.selectable_value(&mut self.node_filter, Some(node_id), name);
+                    }
+                });
+
+            egui::ComboBox::from_id_salt("log_console_level")
+                .selected_text(self.max_level.label())
+                .show_ui(ui, |ui| {
+                    for level in LogLevel::ALL {
+                        ui.selectable_value(&mut self.max_level, level, level.label());
+                    }
+                });
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.search)
+                    .hint_text("Filter messages")
+                    .desired_width(140.0),
+            );
+
+            if ui.button("Clear").clicked() {
+                logs.clear(self.node_filter);
+            }
+
+            let (dock_icon, next_dock) = match self.dock {
+                LogDock::Bottom => ("➡", LogDock::Right),
+                LogDock::Right => ("⬇", LogDock::Bottom),
+            };
+            if ui
+                .button(dock_icon)
+                .on_hover_text("Move the console")
+                .clicked()
+            {
+                self.dock = next_dock;
+            }
+
+            if ui.button("✖").on_hover_text("Close").clicked() {
+                self.is_open = false;
+            }
+        });
+        ui.separator();
+
+        let search = self.search.to_lowercase();
+        let entries: Vec<_> = logs
+            .entries(self.node_filter, self.max_level)
+            .into_iter()
+            .filter(|entry| search.is_empty() || entry.message.to_lowercase().contains(&search))
+            .collect();
+
+        if entries.is_empty() {
+            ui.label("(no log messages)");
+            return;
+        }
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, entries.len(), |ui, rows| {
+                for entry in &entries[rows] {
+                    ui.horizontal(|ui| {
+                        ui.monospace(entry.timestamp.format("%H:%M:%S%.3f").to_string());
+                        ui.colored_label(
+                            Self::level_color(entry.level),
+                            egui::RichText::new(format!("{:<5}", entry.level.label())).monospace(),
+                        );
+                        ui.label(
+                            egui::RichText::new(Self::node_label(graph, entry.node_id))
+                                .color(egui::Color32::from_rgb(180, 180, 180)),
+                        )
+                        .on_hover_text(&entry.component_id);
+                        ui.monospace(&entry.message);
+                    });
+                }
+            });
+    }
+
+    fn node_label(graph: &NodeGraph, node_id: Uuid) -> String {
+        match graph.nodes.get(&node_id) {
+            Some(node) => node.display_name.clone(),
+            None => format!("(removed {})", &node_id.to_string()[..8]),
+        }
+    }
+
+    fn level_color(level: LogLevel) -> egui::Color32 {
+        match level {
+            LogLevel::Error => egui::Color32::from_rgb(230, 80, 80),
+            LogLevel::Warn => egui::Color32::from_rgb(230, 180, 60),
+            LogLevel::Info => egui::Color32::from_rgb(100, 200, 255),
+            LogLevel::Debug => egui::Color32::from_rgb(140, 140, 140),
+        }
+    }
+}
+
+impl Default for LogConsole {
+    fn default() -> Self {
+        Self::new()
+    }
+}
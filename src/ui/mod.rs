@@ -7,7 +7,10 @@ pub(crate) mod canvas;
 pub mod code_editor; // T029: Rust code editor for WASM Creator Node
 pub mod component_view;
 mod dialogs;
+mod diff_view; // Side-by-side comparison of two versions of a graph
 pub(crate) mod execution_status; // T031: Visual indicators for continuous execution states
+mod lint_panel; // Lint findings with jump to node
+mod log_console; // Per-node component log panel
 mod palette;
 pub mod selection; // T002: Rectangle selection state management
 mod spotlight; // Spotlight search for quick node creation
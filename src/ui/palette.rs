@@ -110,6 +110,7 @@ impl Palette {
         let mut builtin_categories: std::collections::HashMap<String, Vec<&ComponentSpec>> =
             std::collections::HashMap::new();
         let mut user_defined_components: Vec<&ComponentSpec> = Vec::new();
+        let mut function_components: Vec<&ComponentSpec> = Vec::new();
 
         for spec in registry.list_all() {
             match &spec.component_type {
@@ -124,6 +125,9 @@ impl Palette {
                     // Composite nodes appear in user-defined section
                     user_defined_components.push(spec);
                 }
+                crate::graph::node::ComponentType::GraphFunction(_) => {
+                    function_components.push(spec);
+                }
             }
         }
 
@@ -138,6 +142,18 @@ impl Palette {
             });
         }
 
+        // Saved graph functions, sorted by name
+        function_components.sort_by(|a, b| a.name.cmp(&b.name));
+        if !function_components.is_empty() {
+            ui.collapsing(crate::graph::function::FUNCTION_CATEGORY, |ui| {
+                for spec in function_components {
+                    if let Some(new_action) = self.render_component_button(ui, spec, theme) {
+                        action = Some(new_action);
+                    }
+                }
+            });
+        }
+
         // Show builtin categories sorted alphabetically
         let mut sorted_categories: Vec<_> = builtin_categories.iter().collect();
         sorted_categories.sort_by(|a, b| a.0.cmp(b.0));
@@ -177,18 +193,20 @@ impl Palette {
             // Choose color based on component type
             let bg_color = match &spec.component_type {
                 crate::graph::node::ComponentType::Builtin => theme.palette_colors.builtin_component,
-                crate::graph::node::ComponentType::UserDefined(_) | crate::graph::node::ComponentType::Composed { .. } => theme.palette_colors.user_defined_component,
+                crate::graph::node::ComponentType::UserDefined(_)
+            | crate::graph::node::ComponentType::Composed { .. }
+            | crate::graph::node::ComponentType::GraphFunction(_) => theme.palette_colors.user_defined_component,
             };
 
             // Highlight selected item
             let button = if is_selected {
                 ui.add(
-                    egui::Button::new(egui::RichText::new(&spec.name).strong())
+                    egui::Button::new(egui::RichText::new(Self::button_label(spec)).strong())
                         .fill(bg_color)
                 )
             } else {
                 ui.add(
-                    egui::Button::new(&spec.name)
+                    egui::Button::new(Self::button_label(spec))
                         .fill(bg_color)
                 )
             };
@@ -210,7 +228,7 @@ impl Palette {
 
             // Show category and description in tooltip
             let category = spec.category.clone().unwrap_or_else(|| "Other".to_string());
-            let tooltip_text = format!(
+            let mut tooltip_text = format!(
                 "{}\n\nCategory: {}\nAuthor: {}\nVersion: {}\nType: {}",
                 spec.description,
                 category,
@@ -220,8 +238,12 @@ impl Palette {
                     crate::graph::node::ComponentType::Builtin => "Built-in",
                     crate::graph::node::ComponentType::UserDefined(_) => "User-defined",
                     crate::graph::node::ComponentType::Composed { .. } => "Composite",
+                    crate::graph::node::ComponentType::GraphFunction(_) => "Graph function",
                 }
             );
+            if let Some(warning) = Self::contract_warning(spec) {
+                tooltip_text.push_str(&format!("\n\n{}", warning));
+            }
             button.on_hover_text(tooltip_text);
         }
 
@@ -233,11 +255,13 @@ impl Palette {
         // Choose color based on component type
         let bg_color = match &spec.component_type {
             crate::graph::node::ComponentType::Builtin => theme.palette_colors.builtin_component,
-            crate::graph::node::ComponentType::UserDefined(_) | crate::graph::node::ComponentType::Composed { .. } => theme.palette_colors.user_defined_component,
+            crate::graph::node::ComponentType::UserDefined(_)
+            | crate::graph::node::ComponentType::Composed { .. }
+            | crate::graph::node::ComponentType::GraphFunction(_) => theme.palette_colors.user_defined_component,
         };
 
         let button = ui.add(
-            egui::Button::new(&spec.name)
+            egui::Button::new(Self::button_label(spec))
                 .fill(bg_color)
         );
 
@@ -249,7 +273,7 @@ impl Palette {
         }
 
         // Show detailed tooltip with component info
-        let tooltip_text = format!(
+        let mut tooltip_text = format!(
             "{}\n\nAuthor: {}\nVersion: {}\nType: {}",
             spec.description,
             spec.author,
@@ -258,13 +282,36 @@ impl Palette {
                 crate::graph::node::ComponentType::Builtin => "Built-in",
                 crate::graph::node::ComponentType::UserDefined(_) => "User-defined",
                 crate::graph::node::ComponentType::Composed { .. } => "Composite",
+                crate::graph::node::ComponentType::GraphFunction(_) => "Graph function",
             }
         );
+        if let Some(warning) = Self::contract_warning(spec) {
+            tooltip_text.push_str(&format!("\n\n{}", warning));
+        }
         button.on_hover_text(tooltip_text);
 
         None
     }
 
+    /// Button label, flagged when the component uses a deprecated contract
+    fn button_label(spec: &ComponentSpec) -> String {
+        if spec.uses_deprecated_contract() {
+            format!("⚠ {}", spec.name)
+        } else {
+            spec.name.clone()
+        }
+    }
+
+    /// Tooltip warning for components built against a deprecated contract
+    fn contract_warning(spec: &ComponentSpec) -> Option<String> {
+        let version = spec.contract_version.filter(|v| v.is_deprecated())?;
+        Some(format!(
+            "⚠ Built against {} (deprecated) - rebuild against {}",
+            version,
+            crate::runtime::contract::ContractVersion::CURRENT
+        ))
+    }
+
     /// Get filtered components based on search query
     /// Uses fuzzy matching on name, category, and description
     fn get_filtered_components<'a>(&self, registry: &'a ComponentRegistry) -> Vec<&'a ComponentSpec> {
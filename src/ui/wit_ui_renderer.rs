@@ -5,9 +5,29 @@
 //! knowing about egui implementation details.
 //!
 //! The WASM component exports a `get-footer-view` function that returns a declarative
-//! description of UI elements, which this module renders.
+//! description of UI elements, which this module renders. Interactive elements
+//! produce [`UiEvent`]s that the host delivers back through `on-ui-event`.
+//! Image textures are cached by content so footers re-rendered every frame
+//! decode and upload each image only once.
 
 use egui::Color32;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Textures kept before the least recently used are dropped
+const MAX_CACHED_TEXTURES: usize = 64;
+
+/// Largest accepted image width or height in pixels
+const MAX_IMAGE_DIMENSION: u32 = 4096;
+
+/// Deepest container nesting accepted in a layout tree
+const MAX_LAYOUT_DEPTH: usize = 32;
+
+/// Table height before scrolling when the component gives none
+const DEFAULT_TABLE_HEIGHT: f32 = 150.0;
+
+/// Chart height when the component gives none
+const DEFAULT_CHART_HEIGHT: f32 = 40.0;
 
 /// UI element types matching the WIT interface definition
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -17,7 +37,17 @@ pub enum UiElement {
     KeyValue(KeyValuePair),
     Horizontal(HorizontalLayout),
     Vertical(VerticalLayout),
+    Layout(LayoutTree),
     Separator,
+    Button(ButtonSpec),
+    Checkbox(CheckboxSpec),
+    Slider(SliderSpec),
+    TextInput(TextInputSpec),
+    Dropdown(DropdownSpec),
+    Image(ImageSpec),
+    Table(TableSpec),
+    Chart(ChartSpec),
+    ProgressBar(ProgressBarSpec),
 }
 
 /// Horizontal layout of UI elements
@@ -32,6 +62,78 @@ pub struct VerticalLayout {
     pub elements: Vec<UiElementItem>,
 }
 
+/// Container node in a layout tree
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum LayoutContainer {
+    Horizontal,
+    Vertical,
+    /// Children fill rows of `columns` cells
+    Grid { columns: u32, striped: bool },
+    /// Expandable section
+    Collapsible { title: String, default_open: bool },
+}
+
+/// Content of a layout tree node
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum LayoutContent {
+    Container(LayoutContainer),
+    Element(UiElementItem),
+}
+
+/// Node in a layout tree
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LayoutNode {
+    /// Index of the containing node; `None` at the top level
+    pub parent: Option<u32>,
+    pub content: LayoutContent,
+}
+
+/// Arbitrarily nested layout, flattened into a list with parent indices
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LayoutTree {
+    pub nodes: Vec<LayoutNode>,
+}
+
+impl LayoutTree {
+    /// Top-level node indices and each node's children, in list order
+    ///
+    /// Fails if a node comes before its parent, its parent is not a container,
+    /// or it is nested deeper than `MAX_LAYOUT_DEPTH`.
+    fn children(&self) -> Result<(Vec<usize>, Vec<Vec<usize>>), String> {
+        let mut roots = Vec::new();
+        let mut children = vec![Vec::new(); self.nodes.len()];
+        // Parents come first, so each node's depth is known when its children are reached
+        let mut depths = vec![0; self.nodes.len()];
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let Some(parent) = node.parent else {
+                roots.push(index);
+                continue;
+            };
+            let parent = parent as usize;
+            if parent >= index {
+                return Err(format!(
+                    "Layout node {} must come after its parent {}",
+                    index, parent
+                ));
+            }
+            if !matches!(self.nodes[parent].content, LayoutContent::Container(_)) {
+                return Err(format!("Layout node {} is not a container", parent));
+            }
+            depths[index] = depths[parent] + 1;
+            if depths[index] > MAX_LAYOUT_DEPTH {
+                return Err(format!(
+                    "Layout node {} is nested deeper than {} levels",
+                    index, MAX_LAYOUT_DEPTH
+                ));
+            }
+            children[parent].push(index);
+        }
+
+        Ok((roots, children))
+    }
+}
+
 /// UI element item (non-recursive version for layouts)
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum UiElementItem {
@@ -39,6 +141,15 @@ pub enum UiElementItem {
     ColoredLabel(ColoredText),
     KeyValue(KeyValuePair),
     Separator,
+    Button(ButtonSpec),
+    Checkbox(CheckboxSpec),
+    Slider(SliderSpec),
+    TextInput(TextInputSpec),
+    Dropdown(DropdownSpec),
+    Image(ImageSpec),
+    Table(TableSpec),
+    Chart(ChartSpec),
+    ProgressBar(ProgressBarSpec),
 }
 
 /// Colored text specification
@@ -57,12 +168,174 @@ pub struct KeyValuePair {
     pub value: String,
 }
 
+/// Push button
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ButtonSpec {
+    pub id: String,
+    pub label: String,
+}
+
+/// On/off checkbox
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CheckboxSpec {
+    pub id: String,
+    pub label: String,
+    pub checked: bool,
+}
+
+/// Number slider
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SliderSpec {
+    pub id: String,
+    pub label: String,
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+}
+
+/// Single-line text field
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TextInputSpec {
+    pub id: String,
+    pub label: String,
+    pub value: String,
+    pub hint: String,
+}
+
+/// Choice from a fixed list
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DropdownSpec {
+    pub id: String,
+    pub label: String,
+    pub options: Vec<String>,
+    pub selected: String,
+}
+
+/// Image pixel data
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum ImageData {
+    /// Encoded PNG file
+    Png(Vec<u8>),
+    /// Row-major RGBA8 pixels
+    Rgba {
+        width: u32,
+        height: u32,
+        pixels: Vec<u8>,
+    },
+}
+
+/// Image element
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ImageSpec {
+    pub data: ImageData,
+    /// Largest display width in points (0 fits the available width)
+    pub max_width: f32,
+    /// Content hash keying the texture cache (computed on first render)
+    #[serde(skip)]
+    cache_key: Option<u64>,
+}
+
+impl ImageSpec {
+    /// Create an image element
+    pub fn new(data: ImageData, max_width: f32) -> Self {
+        Self {
+            data,
+            max_width,
+            cache_key: None,
+        }
+    }
+
+    fn cache_key(&mut self) -> u64 {
+        *self.cache_key.get_or_insert_with(|| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            match &self.data {
+                ImageData::Png(bytes) => bytes.hash(&mut hasher),
+                ImageData::Rgba {
+                    width,
+                    height,
+                    pixels,
+                } => (width, height, pixels).hash(&mut hasher),
+            }
+            hasher.finish()
+        })
+    }
+}
+
+/// Scrollable table
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TableSpec {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Height before the table scrolls (0 for the default)
+    pub max_height: f32,
+}
+
+/// Sparkline style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ChartKind {
+    Line,
+    Bar,
+}
+
+/// Sparkline chart
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ChartSpec {
+    pub kind: ChartKind,
+    pub values: Vec<f32>,
+    pub label: String,
+    /// Chart height in points (0 for the default)
+    pub height: f32,
+}
+
+/// Progress bar
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProgressBarSpec {
+    pub fraction: f32,
+    pub label: String,
+}
+
 /// Footer view data structure
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct FooterView {
     pub elements: Vec<UiElement>,
 }
 
+/// New value of an interactive element
+#[derive(Debug, Clone, PartialEq)]
+pub enum UiEventValue {
+    Clicked,
+    Toggled(bool),
+    Number(f32),
+    Text(String),
+}
+
+/// User input on an interactive footer element
+#[derive(Debug, Clone, PartialEq)]
+pub struct UiEvent {
+    /// `id` of the element
+    pub element_id: String,
+    pub value: UiEventValue,
+}
+
+impl UiEvent {
+    fn new(element_id: &str, value: UiEventValue) -> Self {
+        Self {
+            element_id: element_id.to_string(),
+            value,
+        }
+    }
+}
+
+/// Component's answer to a [`UiEvent`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UiEventResponse {
+    /// Configuration parameter values to set
+    pub parameters: Vec<(String, crate::graph::node::NodeValue)>,
+    /// Re-execute the node after applying the update
+    pub execute: bool,
+}
+
 /// Render a footer view into egui UI
 ///
 /// This function takes a declarative UI description from a WASM component
@@ -89,25 +362,36 @@ pub struct FooterView {
 /// };
 ///
 /// // Host renders it:
-/// render_footer_view(ui, &footer);
+/// let events = render_footer_view(ui, &mut footer)?;
 /// ```
-pub fn render_footer_view(ui: &mut egui::Ui, view: &FooterView) -> Result<(), String> {
+///
+/// Interactive elements edit their spec in place so the new state shows until
+/// the view is refreshed; the returned events describe what the user changed.
+pub fn render_footer_view(ui: &mut egui::Ui, view: &mut FooterView) -> Result<Vec<UiEvent>, String> {
+    let mut events = Vec::new();
+
     // Wrap all elements in vertical layout so they stack vertically
     ui.vertical(|ui| {
         // Force the vertical layout to use full available width
         // Without this, the layout can shrink and cause narrow column rendering
         ui.set_min_width(ui.available_width());
 
-        for element in &view.elements {
-            render_element(ui, element)?;
+        for element in &mut view.elements {
+            render_element(ui, element, &mut events)?;
         }
         Ok::<(), String>(())
     })
-    .inner
+    .inner?;
+
+    Ok(events)
 }
 
 /// Render a single UI element
-fn render_element(ui: &mut egui::Ui, element: &UiElement) -> Result<(), String> {
+fn render_element(
+    ui: &mut egui::Ui,
+    element: &mut UiElement,
+    events: &mut Vec<UiEvent>,
+) -> Result<(), String> {
     match element {
         UiElement::Label(text) => {
             ui.add_sized(
@@ -137,8 +421,8 @@ fn render_element(ui: &mut egui::Ui, element: &UiElement) -> Result<(), String>
         }
         UiElement::Horizontal(layout) => {
             ui.horizontal(|ui| {
-                for elem in &layout.elements {
-                    render_element_item(ui, elem)?;
+                for elem in &mut layout.elements {
+                    render_element_item(ui, elem, events)?;
                 }
                 Ok::<(), String>(())
             })
@@ -149,22 +433,36 @@ fn render_element(ui: &mut egui::Ui, element: &UiElement) -> Result<(), String>
                 // Force the vertical layout to use full available width
                 ui.set_min_width(ui.available_width());
 
-                for elem in &layout.elements {
-                    render_element_item(ui, elem)?;
+                for elem in &mut layout.elements {
+                    render_element_item(ui, elem, events)?;
                 }
                 Ok::<(), String>(())
             })
             .inner?;
         }
+        UiElement::Layout(tree) => render_layout_tree(ui, tree, events)?,
         UiElement::Separator => {
             ui.separator();
         }
+        UiElement::Button(spec) => render_button(ui, spec, events),
+        UiElement::Checkbox(spec) => render_checkbox(ui, spec, events),
+        UiElement::Slider(spec) => render_slider(ui, spec, events),
+        UiElement::TextInput(spec) => render_text_input(ui, spec, events),
+        UiElement::Dropdown(spec) => render_dropdown(ui, spec, events),
+        UiElement::Image(spec) => render_image(ui, spec),
+        UiElement::Table(spec) => render_table(ui, spec),
+        UiElement::Chart(spec) => render_chart(ui, spec),
+        UiElement::ProgressBar(spec) => render_progress_bar(ui, spec),
     }
     Ok(())
 }
 
 /// Render a single UI element item (non-recursive)
-fn render_element_item(ui: &mut egui::Ui, element: &UiElementItem) -> Result<(), String> {
+fn render_element_item(
+    ui: &mut egui::Ui,
+    element: &mut UiElementItem,
+    events: &mut Vec<UiEvent>,
+) -> Result<(), String> {
     match element {
         UiElementItem::Label(text) => {
             // Don't use ui.available_width() here - this is used in horizontal layouts
@@ -193,10 +491,405 @@ fn render_element_item(ui: &mut egui::Ui, element: &UiElementItem) -> Result<(),
         UiElementItem::Separator => {
             ui.separator();
         }
+        UiElementItem::Button(spec) => render_button(ui, spec, events),
+        UiElementItem::Checkbox(spec) => render_checkbox(ui, spec, events),
+        UiElementItem::Slider(spec) => render_slider(ui, spec, events),
+        UiElementItem::TextInput(spec) => render_text_input(ui, spec, events),
+        UiElementItem::Dropdown(spec) => render_dropdown(ui, spec, events),
+        UiElementItem::Image(spec) => render_image(ui, spec),
+        UiElementItem::Table(spec) => render_table(ui, spec),
+        UiElementItem::Chart(spec) => render_chart(ui, spec),
+        UiElementItem::ProgressBar(spec) => render_progress_bar(ui, spec),
     }
     Ok(())
 }
 
+/// Render a layout tree
+fn render_layout_tree(
+    ui: &mut egui::Ui,
+    tree: &mut LayoutTree,
+    events: &mut Vec<UiEvent>,
+) -> Result<(), String> {
+    let (roots, children) = tree.children()?;
+
+    // Scope grid and section ids to this tree so several trees in one footer don't clash
+    let id = ui.next_auto_id();
+    ui.push_id(id, |ui| {
+        for index in roots {
+            render_layout_node(ui, &mut tree.nodes, &children, index, events)?;
+        }
+        Ok(())
+    })
+    .inner
+}
+
+/// Render one layout node and, for containers, its children
+///
+/// `LayoutTree::children` bounds the nesting, and with it the recursion depth.
+fn render_layout_node(
+    ui: &mut egui::Ui,
+    nodes: &mut [LayoutNode],
+    children: &[Vec<usize>],
+    index: usize,
+    events: &mut Vec<UiEvent>,
+) -> Result<(), String> {
+    let container = match &mut nodes[index].content {
+        LayoutContent::Element(item) => return render_element_item(ui, item, events),
+        LayoutContent::Container(container) => container.clone(),
+    };
+
+    let render_children = |ui: &mut egui::Ui,
+                           nodes: &mut [LayoutNode],
+                           events: &mut Vec<UiEvent>|
+     -> Result<(), String> {
+        for &child in &children[index] {
+            render_layout_node(ui, nodes, children, child, events)?;
+        }
+        Ok(())
+    };
+
+    match container {
+        LayoutContainer::Horizontal => {
+            ui.horizontal(|ui| render_children(ui, nodes, events)).inner
+        }
+        LayoutContainer::Vertical => ui.vertical(|ui| render_children(ui, nodes, events)).inner,
+        LayoutContainer::Grid { columns, striped } => {
+            let columns = columns.max(1) as usize;
+            egui::Grid::new(("wit_layout_grid", index))
+                .striped(striped)
+                .show(ui, |ui| {
+                    for (position, &child) in children[index].iter().enumerate() {
+                        render_layout_node(ui, nodes, children, child, events)?;
+                        if (position + 1) % columns == 0 {
+                            ui.end_row();
+                        }
+                    }
+                    Ok(())
+                })
+                .inner
+        }
+        LayoutContainer::Collapsible {
+            title,
+            default_open,
+        } => egui::CollapsingHeader::new(title)
+            .id_salt(("wit_layout_collapsible", index))
+            .default_open(default_open)
+            .show(ui, |ui| render_children(ui, nodes, events))
+            .body_returned
+            .unwrap_or(Ok(())),
+    }
+}
+
+fn render_button(ui: &mut egui::Ui, spec: &ButtonSpec, events: &mut Vec<UiEvent>) {
+    if ui.button(&spec.label).clicked() {
+        events.push(UiEvent::new(&spec.id, UiEventValue::Clicked));
+    }
+}
+
+fn render_checkbox(ui: &mut egui::Ui, spec: &mut CheckboxSpec, events: &mut Vec<UiEvent>) {
+    if ui.checkbox(&mut spec.checked, &spec.label).changed() {
+        events.push(UiEvent::new(&spec.id, UiEventValue::Toggled(spec.checked)));
+    }
+}
+
+fn render_slider(ui: &mut egui::Ui, spec: &mut SliderSpec, events: &mut Vec<UiEvent>) {
+    let (min, max) = if spec.min <= spec.max {
+        (spec.min, spec.max)
+    } else {
+        (spec.max, spec.min)
+    };
+    let mut slider = egui::Slider::new(&mut spec.value, min..=max).text(&spec.label);
+    if spec.step > 0.0 {
+        slider = slider.step_by(spec.step as f64);
+    }
+    let response = ui.add(slider);
+
+    // Report once the drag ends rather than on every frame of it
+    if response.drag_stopped() || (response.changed() && !response.dragged()) {
+        events.push(UiEvent::new(&spec.id, UiEventValue::Number(spec.value)));
+    }
+}
+
+fn render_text_input(ui: &mut egui::Ui, spec: &mut TextInputSpec, events: &mut Vec<UiEvent>) {
+    ui.horizontal(|ui| {
+        if !spec.label.is_empty() {
+            ui.label(&spec.label);
+        }
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut spec.value)
+                .hint_text(&spec.hint)
+                .desired_width(140.0),
+        );
+        if response.lost_focus() {
+            events.push(UiEvent::new(&spec.id, UiEventValue::Text(spec.value.clone())));
+        }
+    });
+}
+
+fn render_dropdown(ui: &mut egui::Ui, spec: &mut DropdownSpec, events: &mut Vec<UiEvent>) {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(("wit_dropdown", &spec.id))
+        .selected_text(&spec.selected)
+        .show_ui(ui, |ui| {
+            for option in &spec.options {
+                changed |= ui
+                    .selectable_value(&mut spec.selected, option.clone(), option)
+                    .changed();
+            }
+        });
+    if !spec.label.is_empty() {
+        ui.label(&spec.label);
+    }
+    if changed {
+        events.push(UiEvent::new(&spec.id, UiEventValue::Text(spec.selected.clone())));
+    }
+}
+
+fn render_image(ui: &mut egui::Ui, spec: &mut ImageSpec) {
+    match cached_texture(ui.ctx(), spec) {
+        Ok(texture) => {
+            let available = ui.available_width();
+            let max_width = if spec.max_width > 0.0 {
+                spec.max_width.min(available)
+            } else {
+                available
+            };
+            let size = texture.size_vec2();
+            let scale = (max_width / size.x).min(1.0);
+            ui.add(egui::Image::new((texture.id(), size * scale)));
+        }
+        Err(e) => {
+            ui.colored_label(Color32::from_rgb(230, 80, 80), format!("⚠ Invalid image: {}", e));
+        }
+    }
+}
+
+fn render_table(ui: &mut egui::Ui, spec: &TableSpec) {
+    let columns = spec
+        .rows
+        .iter()
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0)
+        .max(spec.headers.len());
+    if columns == 0 {
+        return;
+    }
+    let max_height = if spec.max_height > 0.0 {
+        spec.max_height
+    } else {
+        DEFAULT_TABLE_HEIGHT
+    };
+
+    let id = ui.next_auto_id();
+    egui::ScrollArea::both()
+        .id_salt(id)
+        .max_height(max_height)
+        .auto_shrink([false, true])
+        .show(ui, |ui| {
+            egui::Grid::new(id).striped(true).show(ui, |ui| {
+                if !spec.headers.is_empty() {
+                    for column in 0..columns {
+                        ui.strong(spec.headers.get(column).map(String::as_str).unwrap_or(""));
+                    }
+                    ui.end_row();
+                }
+                for row in &spec.rows {
+                    for column in 0..columns {
+                        ui.label(row.get(column).map(String::as_str).unwrap_or(""));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+fn render_chart(ui: &mut egui::Ui, spec: &ChartSpec) {
+    if !spec.label.is_empty() {
+        ui.label(egui::RichText::new(&spec.label).small());
+    }
+    let height = if spec.height > 0.0 {
+        spec.height
+    } else {
+        DEFAULT_CHART_HEIGHT
+    };
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), height), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+    let values: Vec<f32> = spec.values.iter().copied().filter(|v| v.is_finite()).collect();
+    let Some((mut min, mut max)) = value_range(&values) else {
+        return;
+    };
+    // Bars grow from zero
+    if spec.kind == ChartKind::Bar {
+        min = min.min(0.0);
+        max = max.max(0.0);
+    }
+    let span = (max - min).max(f32::EPSILON);
+    let rect = rect.shrink(2.0);
+    let y = |value: f32| rect.bottom() - (value - min) / span * rect.height();
+    let color = Color32::from_rgb(100, 200, 255);
+
+    match spec.kind {
+        ChartKind::Line if values.len() == 1 => {
+            painter.circle_filled(egui::pos2(rect.center().x, y(values[0])), 2.0, color);
+        }
+        ChartKind::Line => {
+            let step = rect.width() / (values.len() - 1) as f32;
+            let points = values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| egui::pos2(rect.left() + i as f32 * step, y(*value)))
+                .collect();
+            painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+        }
+        ChartKind::Bar => {
+            let width = rect.width() / values.len() as f32;
+            let gap = (width * 0.15).min(2.0);
+            for (i, value) in values.iter().enumerate() {
+                let left = rect.left() + i as f32 * width;
+                let bar = egui::Rect::from_two_pos(
+                    egui::pos2(left + gap, y(*value)),
+                    egui::pos2(left + width - gap, y(0.0)),
+                );
+                painter.rect_filled(bar, 0.0, color);
+            }
+        }
+    }
+
+    if let Some(last) = values.last() {
+        response.on_hover_text(format!(
+            "min {:.3} · max {:.3} · last {:.3} ({} values)",
+            min,
+            max,
+            last,
+            values.len()
+        ));
+    }
+}
+
+fn render_progress_bar(ui: &mut egui::Ui, spec: &ProgressBarSpec) {
+    let fraction = if spec.fraction.is_nan() {
+        0.0
+    } else {
+        spec.fraction.clamp(0.0, 1.0)
+    };
+    let bar = egui::ProgressBar::new(fraction);
+    let bar = if spec.label.is_empty() {
+        bar.show_percentage()
+    } else {
+        bar.text(&spec.label)
+    };
+    ui.add(bar);
+}
+
+/// Smallest and largest value
+fn value_range(values: &[f32]) -> Option<(f32, f32)> {
+    values.iter().fold(None, |range, &value| match range {
+        None => Some((value, value)),
+        Some((min, max)) => Some((min.min(value), max.max(value))),
+    })
+}
+
+/// Decoded image textures shared by all footers, least recently used dropped first
+#[derive(Clone, Default)]
+struct TextureCache {
+    /// Lookup counter used to order entries by last use
+    clock: u64,
+    entries: HashMap<u64, (Result<egui::TextureHandle, String>, u64)>,
+}
+
+/// Texture for an image element, decoding and uploading it on first use
+fn cached_texture(ctx: &egui::Context, spec: &mut ImageSpec) -> Result<egui::TextureHandle, String> {
+    let key = spec.cache_key();
+    let cache_id = egui::Id::new("wit_ui_texture_cache");
+
+    let cached = ctx.memory_mut(|memory| {
+        let cache = memory.data.get_temp_mut_or_default::<TextureCache>(cache_id);
+        cache.clock += 1;
+        let clock = cache.clock;
+        cache.entries.get_mut(&key).map(|(texture, last_used)| {
+            *last_used = clock;
+            texture.clone()
+        })
+    });
+    if let Some(texture) = cached {
+        return texture;
+    }
+
+    // Invalid images are cached too so they are not decoded again every frame
+    let texture = decode_image(&spec.data)
+        .map(|image| ctx.load_texture(format!("wit_image_{:016x}", key), image, Default::default()));
+
+    ctx.memory_mut(|memory| {
+        let cache = memory.data.get_temp_mut_or_default::<TextureCache>(cache_id);
+        if cache.entries.len() >= MAX_CACHED_TEXTURES {
+            let oldest = cache
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                cache.entries.remove(&oldest);
+            }
+        }
+        cache.entries.insert(key, (texture.clone(), cache.clock));
+    });
+    texture
+}
+
+/// Decode image data into pixels
+fn decode_image(data: &ImageData) -> Result<egui::ColorImage, String> {
+    let (width, height, pixels) = match data {
+        ImageData::Png(bytes) => {
+            // Oversized images are refused from the header, before any pixels are decoded
+            let mut limits = image::Limits::default();
+            limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+            limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+            let mut reader = image::ImageReader::with_format(
+                std::io::Cursor::new(bytes),
+                image::ImageFormat::Png,
+            );
+            reader.limits(limits);
+            let image = reader.decode().map_err(|e| e.to_string())?.to_rgba8();
+            (image.width(), image.height(), image.into_raw())
+        }
+        ImageData::Rgba {
+            width,
+            height,
+            pixels,
+        } => {
+            let expected = *width as usize * *height as usize * 4;
+            if pixels.len() != expected {
+                return Err(format!(
+                    "{}x{} RGBA needs {} bytes, got {}",
+                    width,
+                    height,
+                    expected,
+                    pixels.len()
+                ));
+            }
+            (*width, *height, pixels.clone())
+        }
+    };
+
+    if width == 0 || height == 0 {
+        return Err("image is empty".to_string());
+    }
+    if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+        return Err(format!(
+            "{}x{} exceeds the {} pixel limit",
+            width, height, MAX_IMAGE_DIMENSION
+        ));
+    }
+    Ok(egui::ColorImage::from_rgba_unmultiplied(
+        [width as usize, height as usize],
+        &pixels,
+    ))
+}
+
 /// Adapter to make FooterView compatible with ComponentFooterView trait
 ///
 /// This allows WASM component footer views to be used through the same
@@ -225,9 +918,12 @@ impl crate::ui::component_view::ComponentFooterView for WitFooterViewAdapter {
         node: &mut crate::graph::node::GraphNode,
     ) -> Result<(), String> {
         // Check if node has a cached footer view
-        if let Some(cached_view) = &node.cached_footer_view {
-            // Render the cached view with actual output values
-            render_footer_view(ui, cached_view)
+        if let Some(cached_view) = &mut node.cached_footer_view {
+            // Render the cached view with actual output values; user input is
+            // queued for the app to deliver to the component
+            let events = render_footer_view(ui, cached_view)?;
+            node.pending_ui_events.extend(events);
+            Ok(())
         } else {
             // No cached view yet - node hasn't been executed
             ui.label("⏳ Execute node to see results");
@@ -264,6 +960,153 @@ mod tests {
         assert_eq!(deserialized.elements.len(), 3);
     }
 
+    #[test]
+    fn test_interactive_elements_serialization() {
+        let view = FooterView {
+            elements: vec![
+                UiElement::Checkbox(CheckboxSpec {
+                    id: "verbose".to_string(),
+                    label: "Verbose".to_string(),
+                    checked: true,
+                }),
+                UiElement::Horizontal(HorizontalLayout {
+                    elements: vec![UiElementItem::Button(ButtonSpec {
+                        id: "refresh".to_string(),
+                        label: "Refresh".to_string(),
+                    })],
+                }),
+            ],
+        };
+
+        let json = serde_json::to_string(&view).unwrap();
+        let deserialized: FooterView = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            &deserialized.elements[0],
+            UiElement::Checkbox(spec) if spec.id == "verbose" && spec.checked
+        ));
+    }
+
+    #[test]
+    fn test_decode_rgba_image() {
+        let image = decode_image(&ImageData::Rgba {
+            width: 2,
+            height: 1,
+            pixels: vec![255, 0, 0, 255, 0, 255, 0, 255],
+        })
+        .unwrap();
+        assert_eq!(image.size, [2, 1]);
+
+        let truncated = ImageData::Rgba {
+            width: 2,
+            height: 2,
+            pixels: vec![0; 8],
+        };
+        assert!(decode_image(&truncated).is_err());
+        assert!(decode_image(&ImageData::Png(vec![1, 2, 3])).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_oversized_png() {
+        let mut bytes = Vec::new();
+        image::RgbaImage::new(MAX_IMAGE_DIMENSION + 1, 1)
+            .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .unwrap();
+        assert!(decode_image(&ImageData::Png(bytes)).is_err());
+    }
+
+    #[test]
+    fn test_image_cache_key_follows_content() {
+        let mut a = ImageSpec::new(ImageData::Png(vec![1, 2, 3]), 0.0);
+        let mut b = ImageSpec::new(ImageData::Png(vec![1, 2, 3]), 120.0);
+        let mut c = ImageSpec::new(ImageData::Png(vec![3, 2, 1]), 0.0);
+        assert_eq!(a.cache_key(), b.cache_key());
+        assert_ne!(a.cache_key(), c.cache_key());
+    }
+
+    #[test]
+    fn test_layout_tree_children() {
+        let container = |container| LayoutContent::Container(container);
+        let label = |text: &str| LayoutContent::Element(UiElementItem::Label(text.to_string()));
+        let tree = LayoutTree {
+            nodes: vec![
+                LayoutNode {
+                    parent: None,
+                    content: container(LayoutContainer::Grid {
+                        columns: 2,
+                        striped: false,
+                    }),
+                },
+                LayoutNode {
+                    parent: Some(0),
+                    content: container(LayoutContainer::Vertical),
+                },
+                LayoutNode {
+                    parent: Some(1),
+                    content: label("nested"),
+                },
+                LayoutNode {
+                    parent: Some(0),
+                    content: label("cell"),
+                },
+                LayoutNode {
+                    parent: None,
+                    content: label("footer"),
+                },
+            ],
+        };
+
+        let (roots, children) = tree.children().unwrap();
+        assert_eq!(roots, vec![0, 4]);
+        assert_eq!(children[0], vec![1, 3]);
+        assert_eq!(children[1], vec![2]);
+    }
+
+    #[test]
+    fn test_layout_tree_rejects_bad_parents() {
+        let label = || LayoutContent::Element(UiElementItem::Separator);
+        let forward = LayoutTree {
+            nodes: vec![LayoutNode {
+                parent: Some(0),
+                content: LayoutContent::Container(LayoutContainer::Vertical),
+            }],
+        };
+        assert!(forward.children().is_err());
+
+        let element_parent = LayoutTree {
+            nodes: vec![
+                LayoutNode {
+                    parent: None,
+                    content: label(),
+                },
+                LayoutNode {
+                    parent: Some(0),
+                    content: label(),
+                },
+            ],
+        };
+        assert!(element_parent.children().is_err());
+    }
+
+    #[test]
+    fn test_layout_tree_rejects_deep_nesting() {
+        let chain = |depth: usize| LayoutTree {
+            nodes: (0..=depth)
+                .map(|index| LayoutNode {
+                    parent: index.checked_sub(1).map(|parent| parent as u32),
+                    content: LayoutContent::Container(LayoutContainer::Vertical),
+                })
+                .collect(),
+        };
+        assert!(chain(MAX_LAYOUT_DEPTH).children().is_ok());
+        assert!(chain(MAX_LAYOUT_DEPTH + 1).children().is_err());
+    }
+
+    #[test]
+    fn test_value_range() {
+        assert_eq!(value_range(&[]), None);
+        assert_eq!(value_range(&[2.0, -1.0, 5.0]), Some((-1.0, 5.0)));
+    }
+
     #[test]
     fn test_key_value_pair() {
         let kv = KeyValuePair {
@@ -0,0 +1,39 @@
+//! Contract tests for the components shipped in `components/bin`
+//!
+//! Bundled binaries are rebuilt separately from the host, so every contract
+//! bump must keep them loadable (through the legacy bindings if need be).
+
+use std::path::PathBuf;
+use wasmflow::runtime::contract::ContractVersion;
+use wasmflow::runtime::wasm_host::ComponentManager;
+
+fn bundled_components() -> Vec<PathBuf> {
+    let bin = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("components/bin");
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&bin)
+        .expect("components/bin should exist")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Every bundled component loads through a supported contract version
+#[test]
+fn test_bundled_components_load_with_current_host() {
+    let mut manager = ComponentManager::new().unwrap();
+    let paths = bundled_components();
+    assert!(!paths.is_empty());
+
+    for path in paths {
+        let specs = manager
+            .load_component_sync(&path)
+            .unwrap_or_else(|e| panic!("{} failed to load: {}", path.display(), e));
+        for spec in specs {
+            let version = spec.contract_version.unwrap_or_else(|| {
+                panic!("{} was auto-wrapped instead of loaded as a node", path.display())
+            });
+            assert!(ContractVersion::SUPPORTED.contains(&version));
+        }
+    }
+}
@@ -1,3 +1,4 @@
 mod wasi_http_component_test;
 mod generated_component_test; // T028: Contract tests for generated components
 mod json_parser_test; // JSON parser contract tests (008-json-parser-a)
+mod bundled_components_test; // Bundled binaries load across contract bumps
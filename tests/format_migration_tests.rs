@@ -0,0 +1,140 @@
+//! Golden-file tests for graph format migrations
+//!
+//! Each fixture in `tests/fixtures/formats` is a file as written by the first
+//! release, in the layout of the format version in its name. They are never
+//! regenerated: loading them must keep working as the format moves on.
+
+use std::path::PathBuf;
+use uuid::Uuid;
+use wasmflow::graph::graph::NodeGraph;
+use wasmflow::graph::migration::MigrationReport;
+use wasmflow::graph::node::{ExecutionState, NodeValue};
+use wasmflow::graph::serialization::FORMAT_VERSION;
+use wasmflow::runtime::capabilities::CapabilitySet;
+use wasmflow::SerializationError;
+
+const CONSTANT_NODE: &str = "00000000-0000-4000-8000-0000000000c0";
+const ADD_NODE: &str = "00000000-0000-4000-8000-0000000000a0";
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/formats")
+        .join(name)
+}
+
+fn load_fixture(name: &str) -> (NodeGraph, MigrationReport) {
+    NodeGraph::load_from_file_with_report(fixture_path(name))
+        .unwrap_or_else(|e| panic!("Failed to load {}: {:#}", name, e))
+}
+
+/// Contents shared by every fixture
+fn assert_golden_graph(graph: &NodeGraph) {
+    let constant_id = Uuid::parse_str(CONSTANT_NODE).unwrap();
+    let add_id = Uuid::parse_str(ADD_NODE).unwrap();
+
+    assert_eq!(graph.name, "Golden");
+    assert_eq!(graph.metadata.author, "Golden");
+    assert_eq!(graph.metadata.description, "Golden fixture graph");
+    assert_eq!(graph.version, FORMAT_VERSION);
+    assert_eq!(graph.nodes.len(), 2);
+    assert_eq!(graph.connections.len(), 1);
+
+    let constant = &graph.nodes[&constant_id];
+    assert_eq!(constant.component_id, "builtin:constant:f32");
+    assert_eq!(constant.display_name, "Seven");
+    assert_eq!(constant.position, egui::Pos2::new(10.0, 20.0));
+    assert_eq!(constant.outputs[0].current_value, Some(NodeValue::F32(7.5)));
+    assert_eq!(constant.execution_state, ExecutionState::Completed);
+
+    let add = &graph.nodes[&add_id];
+    assert_eq!(add.component_id, "builtin:math:add");
+    // Nothing is left running after load
+    assert_eq!(add.execution_state, ExecutionState::Idle);
+    assert!(add.dirty);
+
+    let connection = &graph.connections[0];
+    assert_eq!(connection.from_node, constant_id);
+    assert_eq!(connection.to_node, add_id);
+
+    let grant = &graph.capability_grants[&add_id];
+    assert_eq!(grant.scope, "graph");
+    assert!(matches!(grant.capability_set, CapabilitySet::FileRead { .. }));
+}
+
+/// Fields added after v1 start out empty in migrated files
+fn assert_later_fields_empty(graph: &NodeGraph) {
+    assert!(graph.annotations.is_empty());
+    for node in graph.nodes.values() {
+        assert!(node.inputs.iter().all(|port| port.default_value.is_none()));
+        assert!(node.config.is_empty());
+        assert_eq!(node.instance_state, None);
+        assert_eq!(node.component_pin, None);
+    }
+}
+
+#[test]
+fn test_v1_bincode_fixture_migrates() {
+    let (graph, report) = load_fixture("v1_bincode.wfg");
+    assert_golden_graph(&graph);
+    assert_later_fields_empty(&graph);
+
+    assert_eq!(report.source_version, 1);
+    assert_eq!(report.saved_by, None);
+    assert!(!report.legacy_json);
+    assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    assert_eq!(report.steps.len(), 1);
+    assert_eq!((report.steps[0].from, report.steps[0].to), (1, FORMAT_VERSION));
+    assert_eq!(
+        report.steps[0].changes,
+        vec![
+            "Reset 1 node(s) that were saved while running".to_string(),
+            "Frames, groups and notes start out empty".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_v1_json_fixture_migrates() {
+    let (graph, report) = load_fixture("v1_json.wfg");
+    assert_golden_graph(&graph);
+    assert_later_fields_empty(&graph);
+
+    assert_eq!(report.source_version, 1);
+    assert!(report.legacy_json);
+    assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    assert!(report.summary().contains("legacy JSON"));
+}
+
+#[test]
+fn test_migrated_fixture_saves_as_current_version() {
+    let (graph, _) = load_fixture("v1_bincode.wfg");
+    let (reloaded, report) = NodeGraph::from_bytes_with_report(&graph.to_bytes().unwrap()).unwrap();
+
+    assert!(report.is_empty(), "{}", report.summary());
+    assert_eq!(report.source_version, FORMAT_VERSION);
+    assert_eq!(report.saved_by.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+    assert_golden_graph(&reloaded);
+    assert_later_fields_empty(&reloaded);
+}
+
+#[test]
+fn test_checksum_mismatch_only_loads_when_ignored() {
+    let mut bytes = std::fs::read(fixture_path("v1_bincode.wfg")).unwrap();
+    // The checksum is the file's last field
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xFF;
+
+    let err = NodeGraph::from_bytes_with_report(&bytes).unwrap_err();
+    let mismatch = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<SerializationError>());
+    assert!(
+        matches!(mismatch, Some(SerializationError::ChecksumMismatch { .. })),
+        "{:#}",
+        err
+    );
+
+    let (graph, report) = NodeGraph::from_bytes_ignoring_checksum(&bytes).unwrap();
+    assert_golden_graph(&graph);
+    assert_eq!(report.warnings.len(), 1);
+}
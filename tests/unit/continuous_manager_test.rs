@@ -194,3 +194,55 @@ fn test_shutdown_all() {
 
     // All nodes should be stopped (test passes if no panic)
 }
+
+#[test]
+fn test_continuous_component_spec_creates_continuous_node() {
+    let mut spec = wasmflow::graph::node::ComponentSpec::new_user_defined(
+        "user:poller".to_string(),
+        "Poller".to_string(),
+        "Polls a source".to_string(),
+        None,
+        std::path::PathBuf::from("poller.wasm"),
+    );
+    spec.supports_continuous = true;
+
+    let node = spec.create_node(egui::Pos2::ZERO);
+    let config = node.continuous_config.expect("continuous config");
+    assert!(config.supports_continuous);
+    assert!(config.enabled);
+}
+
+#[test]
+fn test_unloaded_wasm_component_reports_start_error() {
+    let mut manager = ContinuousExecutionManager::new();
+    let graph = Arc::new(Mutex::new(NodeGraph::new()));
+    let component_manager = Arc::new(Mutex::new(ComponentManager::new().unwrap()));
+    let (result_tx, result_rx) = channel();
+
+    let mut spec = wasmflow::graph::node::ComponentSpec::new_user_defined(
+        "user:missing".to_string(),
+        "Missing".to_string(),
+        "Not loaded".to_string(),
+        None,
+        std::path::PathBuf::from("missing.wasm"),
+    );
+    spec.supports_continuous = true;
+    let node = spec.create_node(egui::Pos2::ZERO);
+    let node_id = node.id;
+    graph.lock().unwrap().nodes.insert(node_id, node);
+
+    manager
+        .start_node(node_id, graph, component_manager, result_tx)
+        .unwrap();
+
+    let error = result_rx
+        .iter()
+        .find_map(|result| match result {
+            ExecutionResult::Error { error, .. } => Some(error.to_string()),
+            _ => None,
+        })
+        .expect("start error");
+    assert!(error.contains("user:missing"));
+
+    manager.shutdown();
+}
@@ -6,3 +6,5 @@ mod registry_test; // T052: Component registry replacement tests
 mod continuous_manager_test; // T055: Continuous execution manager tests
 mod continuous_state_test; // T056: Continuous execution state management tests
 mod json_parser_unit; // JSON parser unit tests (008-json-parser-a)
+mod port_defaults_test; // Component-declared input defaults
+mod parameters_test; // Component configuration parameters
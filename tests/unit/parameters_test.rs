@@ -0,0 +1,90 @@
+//! Unit tests for component configuration parameters
+//!
+//! Parameters are declared on the ComponentSpec, stored per node in
+//! `GraphNode::config`, and normalized against their declared kind.
+
+use wasmflow::graph::node::{ComponentSpec, DataType, NodeValue, ParameterKind, ParameterSpec};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operation_param() -> ParameterSpec {
+        ParameterSpec {
+            name: "operation".to_string(),
+            label: "Operation".to_string(),
+            description: "Case conversion".to_string(),
+            kind: ParameterKind::Choice(vec![
+                "upper".to_string(),
+                "lower".to_string(),
+                "title".to_string(),
+            ]),
+            default_value: NodeValue::String("upper".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_create_node_uses_parameter_defaults() {
+        let spec = ComponentSpec::new_builtin(
+            "test:string-case".to_string(),
+            "String Case".to_string(),
+            "Change string case".to_string(),
+            None,
+        )
+        .with_input("text".to_string(), DataType::String, "Input text".to_string())
+        .with_parameter(operation_param());
+
+        let node = spec.create_node(egui::Pos2::ZERO);
+
+        // Parameters are not data ports
+        assert_eq!(node.inputs.len(), 1);
+        assert_eq!(
+            node.config.get("operation"),
+            Some(&NodeValue::String("upper".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_normalize_choice_falls_back_to_default() {
+        let param = operation_param();
+
+        assert_eq!(
+            param.normalize(&NodeValue::String("lower".to_string())),
+            NodeValue::String("lower".to_string())
+        );
+        assert_eq!(
+            param.normalize(&NodeValue::String("shout".to_string())),
+            NodeValue::String("upper".to_string())
+        );
+        assert_eq!(
+            param.normalize(&NodeValue::U32(3)),
+            NodeValue::String("upper".to_string())
+        );
+    }
+
+    #[test]
+    fn test_normalize_clamps_ranges() {
+        let precision = ParameterSpec {
+            name: "precision".to_string(),
+            label: "Precision".to_string(),
+            description: "Decimal places".to_string(),
+            kind: ParameterKind::IntRange { min: 0, max: 6 },
+            default_value: NodeValue::I32(2),
+        };
+        assert_eq!(precision.normalize(&NodeValue::I32(9)), NodeValue::I32(6));
+        assert_eq!(precision.normalize(&NodeValue::U32(4)), NodeValue::I32(4));
+
+        let gain = ParameterSpec {
+            name: "gain".to_string(),
+            label: "Gain".to_string(),
+            description: "Multiplier".to_string(),
+            kind: ParameterKind::FloatRange {
+                min: 0.0,
+                max: 1.0,
+                step: 0.1,
+            },
+            default_value: NodeValue::F32(0.5),
+        };
+        assert_eq!(gain.normalize(&NodeValue::F32(-2.0)), NodeValue::F32(0.0));
+    }
+}
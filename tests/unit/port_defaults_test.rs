@@ -0,0 +1,62 @@
+//! Unit tests for component-declared input default values
+//!
+//! Optional inputs may declare a default that is pre-filled on new nodes and
+//! can be overridden inline and reset.
+
+use wasmflow::graph::node::{ComponentSpec, DataType, NodeValue};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case_spec() -> ComponentSpec {
+        ComponentSpec::new_builtin(
+            "test:string-case".to_string(),
+            "String Case".to_string(),
+            "Change string case".to_string(),
+            None,
+        )
+        .with_input("text".to_string(), DataType::String, "Input text".to_string())
+        .with_optional_input(
+            "operation".to_string(),
+            DataType::String,
+            "upper, lower or title".to_string(),
+            Some(NodeValue::String("upper".to_string())),
+        )
+        .with_output("result".to_string(), DataType::String, "Result".to_string())
+    }
+
+    #[test]
+    fn test_create_node_prefills_defaults() {
+        let node = case_spec().create_node(egui::Pos2::ZERO);
+
+        let text = node.get_input("text").unwrap();
+        assert!(!text.optional);
+        assert_eq!(text.current_value, None);
+        assert_eq!(text.default_value, None);
+
+        let operation = node.get_input("operation").unwrap();
+        assert!(operation.optional);
+        assert_eq!(
+            operation.current_value,
+            Some(NodeValue::String("upper".to_string()))
+        );
+        assert!(!operation.is_overridden());
+    }
+
+    #[test]
+    fn test_override_and_reset_default() {
+        let mut node = case_spec().create_node(egui::Pos2::ZERO);
+        let operation = node.get_input_mut("operation").unwrap();
+
+        operation.current_value = Some(NodeValue::String("lower".to_string()));
+        assert!(operation.is_overridden());
+
+        operation.reset_to_default();
+        assert!(!operation.is_overridden());
+        assert_eq!(
+            operation.current_value,
+            Some(NodeValue::String("upper".to_string()))
+        );
+    }
+}